@@ -0,0 +1,57 @@
+#!/usr/bin/env python3
+# SPDX-FileCopyrightText: © 2026 David Bliss
+#
+# SPDX-License-Identifier: GPL-3.0-or-later
+#
+# Generates the gazetteer dataset in data/geonames that is installed with Fotema,
+# so that places can be found without a network connection.
+#
+# Usage: build-aux/geonames.py CITIES_CSV ISO_3166_JSON OUTPUT_DIR
+#
+# CITIES_CSV is the GeoNames cities1000 extract with columns lat,lon,name,admin1,admin2,cc,
+# as packaged by the reverse_geocoder crate (cities.csv).
+# ISO_3166_JSON is iso_3166-1.json from the iso-codes project.
+
+import csv
+import json
+import os
+import sys
+
+
+def main():
+    if len(sys.argv) != 4:
+        sys.exit("Usage: geonames.py CITIES_CSV ISO_3166_JSON OUTPUT_DIR")
+
+    cities_csv, iso_json, output_dir = sys.argv[1:]
+    os.makedirs(output_dir, exist_ok=True)
+
+    with open(cities_csv, newline="", encoding="utf-8") as f:
+        cities = sorted(
+            csv.DictReader(f), key=lambda r: (r["cc"], r["admin1"], r["name"])
+        )
+
+    with open(os.path.join(output_dir, "cities.tsv"), "w", encoding="utf-8") as f:
+        f.write("# Cities from GeoNames (https://www.geonames.org/), CC BY 4.0.\n")
+        f.write("# name\tcountry_code\tregion\tlatitude\tlongitude\n")
+        for r in cities:
+            name = r["name"].strip()
+            if not name:
+                continue
+            f.write(
+                "{}\t{}\t{}\t{:.4f}\t{:.4f}\n".format(
+                    name, r["cc"], r["admin1"].strip(), float(r["lat"]), float(r["lon"])
+                )
+            )
+
+    with open(iso_json, encoding="utf-8") as f:
+        countries = json.load(f)["3166-1"]
+
+    with open(os.path.join(output_dir, "countries.tsv"), "w", encoding="utf-8") as f:
+        f.write("# Country names from iso-codes (https://salsa.debian.org/iso-codes-team/iso-codes).\n")
+        f.write("# country_code\tname\n")
+        for c in sorted(countries, key=lambda c: c["alpha_2"]):
+            f.write("{}\t{}\n".format(c["alpha_2"], c.get("common_name", c["name"])))
+
+
+if __name__ == "__main__":
+    main()
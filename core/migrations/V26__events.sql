@@ -0,0 +1,22 @@
+-- Events and trips found by clustering photos and videos by time and place.
+-- Rebuilt from scratch whenever clustering produces different results.
+CREATE TABLE events (
+        event_id       INTEGER PRIMARY KEY UNIQUE NOT NULL, -- unique ID for event
+        kind           TEXT NOT NULL CHECK (kind IN ('event', 'trip')), -- a single event or multi-day trip
+        trip_id        INTEGER, -- trip this event is part of
+        start_ts       DATETIME NOT NULL, -- ordering timestamp of first item
+        end_ts         DATETIME NOT NULL, -- ordering timestamp of last item
+        local_start_ts DATETIME NOT NULL, -- wall-clock time of first item
+        local_end_ts   DATETIME NOT NULL, -- wall-clock time of last item
+        place_name     TEXT, -- reverse geocoded place name
+        FOREIGN KEY (trip_id) REFERENCES events (event_id) ON DELETE CASCADE
+);
+
+-- Photos and videos in an event or trip.
+CREATE TABLE events_visuals (
+        event_id       INTEGER NOT NULL, -- event containing visual item
+        position       INTEGER NOT NULL, -- order of visual item in event
+        visual_id      TEXT NOT NULL, -- visual item ID from visual view
+        PRIMARY KEY (event_id, position),
+        FOREIGN KEY (event_id) REFERENCES events (event_id) ON DELETE CASCADE
+);
@@ -0,0 +1,8 @@
+-- Where a location came from: embedded metadata, a GPS track log, or a pin
+-- dropped by the user. Locations from track logs and pins must not be
+-- overwritten by metadata enrichment.
+ALTER TABLE pictures_geo ADD COLUMN source TEXT NOT NULL DEFAULT 'embedded'
+  CHECK (source IN ('embedded', 'track', 'manual'));
+
+ALTER TABLE videos_geo ADD COLUMN source TEXT NOT NULL DEFAULT 'embedded'
+  CHECK (source IN ('embedded', 'track', 'manual'));
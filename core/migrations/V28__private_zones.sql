@@ -0,0 +1,14 @@
+-- Sensitive areas, such as home, that are hidden from the Places view and
+-- stripped from exports. A zone is either a circle or a H3 cell.
+CREATE TABLE private_zones (
+        zone_id        INTEGER PRIMARY KEY UNIQUE NOT NULL,
+        name           TEXT NOT NULL, -- user supplied name, such as 'Home'
+        latitude       REAL, -- centre of circle
+        longitude      REAL, -- centre of circle
+        radius_metres  REAL, -- radius of circle
+        h3_cell        INTEGER, -- H3 cell index
+        CHECK (
+          (latitude IS NOT NULL AND longitude IS NOT NULL AND radius_metres IS NOT NULL AND h3_cell IS NULL)
+          OR (latitude IS NULL AND longitude IS NULL AND radius_metres IS NULL AND h3_cell IS NOT NULL)
+        )
+);
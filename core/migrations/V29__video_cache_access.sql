@@ -0,0 +1,4 @@
+-- When a video or motion photo was last viewed. Used to evict the least recently
+-- viewed transcoded and extracted videos when the video cache is over budget.
+ALTER TABLE videos ADD COLUMN accessed_ts DATETIME;
+ALTER TABLE motion_photos ADD COLUMN accessed_ts DATETIME;
@@ -0,0 +1,3 @@
+-- Position in the video of the frame chosen for the thumbnail, so the thumbnail
+-- can be regenerated from the same frame.
+ALTER TABLE videos ADD COLUMN thumbnail_ts_millis INTEGER;
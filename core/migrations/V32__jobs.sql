@@ -0,0 +1,18 @@
+-- Persistent queue of background work, so interrupted runs resume where
+-- they left off, failures are visible and on-screen items can jump the queue.
+
+CREATE TABLE IF NOT EXISTS jobs (
+  job_id       INTEGER PRIMARY KEY UNIQUE NOT NULL, -- unique ID for job
+  kind         TEXT NOT NULL,                       -- type of work, such as PhotoThumbnail
+  target_id    INTEGER NOT NULL,                    -- picture_id or video_id, depending on kind
+  priority     INTEGER NOT NULL DEFAULT 0,          -- higher priority jobs are claimed first
+  state        TEXT NOT NULL DEFAULT 'Queued',      -- Queued, Running or Failed
+  attempts     INTEGER NOT NULL DEFAULT 0,          -- number of failed attempts
+  last_error   TEXT,                                -- error from most recent failed attempt
+  run_after_ts DATETIME,                            -- UTC timestamp before which job must not run
+  claimed_ts   DATETIME,                            -- UTC timestamp job started running
+
+  UNIQUE (kind, target_id)
+);
+
+CREATE INDEX IF NOT EXISTS jobs_claim_idx ON jobs (kind, state, priority DESC, job_id ASC);
@@ -0,0 +1,12 @@
+-- Record why pictures, videos and face scans were marked as broken so the
+-- reasons can be shown to the user and the items retried.
+
+ALTER TABLE pictures ADD COLUMN broken_category TEXT;
+ALTER TABLE pictures ADD COLUMN broken_message TEXT;
+ALTER TABLE pictures ADD COLUMN broken_ts DATETIME;
+
+ALTER TABLE videos ADD COLUMN broken_category TEXT;
+ALTER TABLE videos ADD COLUMN broken_message TEXT;
+ALTER TABLE videos ADD COLUMN broken_ts DATETIME;
+
+ALTER TABLE pictures_face_scans ADD COLUMN broken_message TEXT;
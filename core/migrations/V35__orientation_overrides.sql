@@ -0,0 +1,114 @@
+-- Orientations chosen by the user, for when the orientation in a file is wrong.
+-- An override of a picture replaces its EXIF orientation.
+CREATE TABLE pictures_orientation (
+        picture_id  INTEGER PRIMARY KEY UNIQUE NOT NULL, -- unique ID for picture
+        orientation INTEGER NOT NULL,                    -- EXIF orientation, 1 to 8
+        updated_ts  DATETIME NOT NULL,                   -- UTC timestamp of last change
+        FOREIGN KEY (picture_id) REFERENCES pictures (picture_id) ON DELETE CASCADE
+);
+
+-- An override of a video is turned on top of the rotation of the video stream,
+-- because the rotation of the stream is always applied when playing the video.
+CREATE TABLE videos_orientation (
+        video_id    INTEGER PRIMARY KEY UNIQUE NOT NULL, -- unique ID for video
+        orientation INTEGER NOT NULL,                    -- EXIF orientation, 1 to 8
+        updated_ts  DATETIME NOT NULL,                   -- UTC timestamp of last change
+        FOREIGN KEY (video_id) REFERENCES videos (video_id) ON DELETE CASCADE
+);
+
+DROP VIEW visual;
+
+CREATE VIEW visual AS
+SELECT
+  -- Unique ID
+  COALESCE(pictures.picture_id, 'x') || '_' || COALESCE(videos.video_id, 'x') AS visual_id,
+  COALESCE(pictures.link_path_b64, videos.link_path_b64) AS link_path_b64,
+
+  pictures.picture_id,
+  pictures.picture_path_b64,
+  pictures.picture_path_lossy, -- for debug only. Never read in Fotema.
+  COALESCE(pictures_orientation.orientation, pictures.orientation) AS picture_orientation,
+  pictures_orientation.orientation AS picture_orientation_override,
+  pictures.is_selfie,
+
+  -- Non-destructive edits, such as crops and exposure changes.
+  pictures_edits.edits AS picture_edits,
+
+  videos.video_id,
+  videos.video_path_b64,
+  videos.video_path_lossy, -- for debug only. Never read in Fotema.
+
+  COALESCE(videos.video_codec, motion_photos.video_codec) AS video_codec,
+
+  -- GNOME 48 runtime appears to support HEVC videos without transcoding.
+  false AS is_transcode_required,
+
+  COALESCE(videos.transcoded_path, motion_photos.transcoded_path) AS video_transcoded_path,
+
+  COALESCE(videos.rotation, motion_photos.rotation) AS video_rotation,
+  videos_orientation.orientation AS video_orientation_override,
+
+  -- An iOS live photo is a photo and a video linked with a content ID.
+  -- However, we only really need the video part, and short (<3 seconds)
+  -- videos are possibly live photos that have a missing or misnamed photo.
+  CASE
+        WHEN videos.content_id IS NOT NULL THEN true
+        WHEN videos.duration_millis <= 3000 THEN true
+        WHEN motion_photos.video_path IS NOT NULL THEN true
+        ELSE false
+  END AS is_live_photo,
+
+  COALESCE(videos.duration_millis, motion_photos.duration_millis) as duration_millis,
+
+  motion_photos.video_path AS motion_photo_video_path,
+
+  -- Animated images, such as GIFs, have more than one frame.
+  COALESCE(pictures.frame_count, 1) > 1 AS is_animated,
+  pictures.animation_duration_millis,
+
+  COALESCE(pictures_geo.longitude, videos_geo.longitude) AS longitude,
+  COALESCE(pictures_geo.latitude, videos_geo.latitude) AS latitude,
+
+  -- Reverse geocoded place names.
+  COALESCE(pictures_geo.country_code, videos_geo.country_code) AS country_code,
+  COALESCE(pictures_geo.country_name, videos_geo.country_name) AS country_name,
+  COALESCE(pictures_geo.region_name, videos_geo.region_name) AS region_name,
+  COALESCE(pictures_geo.city_name, videos_geo.city_name) AS city_name,
+
+  -- Wall-clock time at the place of capture.
+  -- Used for grouping by day, month, and year.
+  COALESCE(
+    pictures.local_created_ts,
+    videos.local_created_ts
+  ) AS local_ts,
+
+  -- Timestamp to order visual items by.
+  -- Prefer embedded metadata over file system metadata.
+  -- Timestamps may have different offsets from UTC, so normalise to UTC
+  -- to make them sortable.
+  strftime('%Y-%m-%d %H:%M:%f', COALESCE(
+    pictures.exif_created_ts,
+    videos.stream_created_ts,
+    pictures.exif_modified_ts,
+    pictures.fs_created_ts,
+    videos.fs_created_ts,
+    pictures.fs_modified_ts,
+    videos.fs_modified_ts,
+    pictures.insert_ts,
+    videos.insert_ts,
+    CURRENT_TIMESTAMP
+  )) AS ordering_ts
+FROM
+  pictures
+  FULL OUTER JOIN videos USING (link_path_b64, content_id)
+  FULL OUTER JOIN motion_photos USING (picture_id)
+  FULL OUTER JOIN pictures_geo USING (picture_id)
+  LEFT OUTER JOIN videos_geo ON videos_geo.video_id = videos.video_id
+  LEFT OUTER JOIN pictures_edits ON pictures_edits.picture_id = pictures.picture_id
+  LEFT OUTER JOIN pictures_orientation ON pictures_orientation.picture_id = pictures.picture_id
+  LEFT OUTER JOIN videos_orientation ON videos_orientation.video_id = videos.video_id
+WHERE COALESCE(pictures.is_broken, FALSE) IS FALSE
+AND COALESCE(videos.is_broken, FALSE) IS FALSE
+ORDER BY
+  ordering_ts ASC;
+
@@ -0,0 +1,46 @@
+-- Queue thumbnail jobs when pictures and videos are added and remove jobs with them,
+-- so workers claim work instead of rediscovering it and jobs don't outlive their items.
+
+DELETE FROM jobs
+WHERE kind = 'PhotoThumbnail'
+AND target_id NOT IN (SELECT picture_id FROM pictures);
+
+DELETE FROM jobs
+WHERE kind = 'VideoThumbnail'
+AND target_id NOT IN (SELECT video_id FROM videos);
+
+-- Existing items are queued once. Jobs for items that already have thumbnails
+-- are completed without any work.
+INSERT OR IGNORE INTO jobs (kind, target_id)
+SELECT 'PhotoThumbnail', picture_id
+FROM pictures
+WHERE COALESCE(is_broken, FALSE) IS FALSE;
+
+INSERT OR IGNORE INTO jobs (kind, target_id)
+SELECT 'VideoThumbnail', video_id
+FROM videos
+WHERE COALESCE(is_broken, FALSE) IS FALSE;
+
+CREATE TRIGGER IF NOT EXISTS pictures_queue_thumbnail
+AFTER INSERT ON pictures
+BEGIN
+  INSERT OR IGNORE INTO jobs (kind, target_id) VALUES ('PhotoThumbnail', NEW.picture_id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS videos_queue_thumbnail
+AFTER INSERT ON videos
+BEGIN
+  INSERT OR IGNORE INTO jobs (kind, target_id) VALUES ('VideoThumbnail', NEW.video_id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS pictures_remove_jobs
+AFTER DELETE ON pictures
+BEGIN
+  DELETE FROM jobs WHERE kind = 'PhotoThumbnail' AND target_id = OLD.picture_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS videos_remove_jobs
+AFTER DELETE ON videos
+BEGIN
+  DELETE FROM jobs WHERE kind = 'VideoThumbnail' AND target_id = OLD.video_id;
+END;
@@ -21,3 +21,42 @@ pub fn setup_in_memory() -> Result<Connection> {
     migrations::runner().run(&mut con)?;
     Ok(con)
 }
+
+/// Database fixtures shared by repository tests.
+#[cfg(test)]
+pub(crate) mod test_support {
+    use crate::path_encoding;
+    use crate::photo::PictureId;
+    use rusqlite::{Connection, params};
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+
+    /// Migrated in-memory database.
+    pub(crate) fn setup() -> Arc<Mutex<Connection>> {
+        Arc::new(Mutex::new(super::setup_in_memory().unwrap()))
+    }
+
+    /// Add a picture taken at 2020-01-01 09:30 UTC, with a link path like the scanner's.
+    pub(crate) fn insert_picture(con: &Arc<Mutex<Connection>>, path: &str) -> PictureId {
+        let path = Path::new(path);
+        let link_path = path.with_extension("");
+        let con = con.lock().unwrap();
+        con.execute(
+            "INSERT INTO pictures (
+                picture_path_b64, picture_path_lossy, link_path_b64, link_path_lossy,
+                exif_created_ts, local_created_ts, insert_ts
+            ) VALUES (
+                ?1, ?2, ?3, ?4,
+                '2020-01-01 09:30:00+00:00', '2020-01-01 09:30:00', CURRENT_TIMESTAMP
+            )",
+            params![
+                path_encoding::to_base64(path),
+                path.to_string_lossy(),
+                path_encoding::to_base64(&link_path),
+                link_path.to_string_lossy(),
+            ],
+        )
+        .unwrap();
+        PictureId::new(con.last_insert_rowid())
+    }
+}
@@ -0,0 +1,7 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+pub mod repo;
+
+pub use repo::Repository;
@@ -0,0 +1,131 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::photo::PictureId;
+use crate::video::VideoId;
+
+use anyhow::*;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rusqlite::params;
+use std::result::Result::Ok;
+use std::sync::{Arc, Mutex};
+
+/// Repository of dates chosen by the user.
+/// Repository is backed by a Sqlite database.
+#[derive(Debug, Clone)]
+pub struct Repository {
+    /// Connection to backing Sqlite database.
+    con: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl Repository {
+    pub fn open(con: Arc<Mutex<rusqlite::Connection>>) -> Result<Repository> {
+        Ok(Repository { con })
+    }
+
+    /// Show a picture as taken at a wall-clock time instead of the time in its metadata.
+    /// The UTC timestamp is the same moment, for ordering with other items.
+    pub fn set_picture(
+        &mut self,
+        picture_id: PictureId,
+        local_ts: NaiveDateTime,
+        utc_ts: DateTime<Utc>,
+    ) -> Result<()> {
+        let con = self.con.lock().unwrap();
+        con.execute(
+            "INSERT INTO pictures_date (
+                picture_id,
+                local_ts,
+                utc_ts,
+                updated_ts
+            ) VALUES (
+                ?1, ?2, ?3, CURRENT_TIMESTAMP
+            ) ON CONFLICT (picture_id) DO UPDATE SET
+                local_ts = ?2,
+                utc_ts = ?3,
+                updated_ts = CURRENT_TIMESTAMP
+            ",
+            params![picture_id.id(), local_ts, utc_ts],
+        )?;
+        Ok(())
+    }
+
+    /// Show a video as recorded at a wall-clock time instead of the time in its metadata.
+    /// The UTC timestamp is the same moment, for ordering with other items.
+    pub fn set_video(
+        &mut self,
+        video_id: VideoId,
+        local_ts: NaiveDateTime,
+        utc_ts: DateTime<Utc>,
+    ) -> Result<()> {
+        let con = self.con.lock().unwrap();
+        con.execute(
+            "INSERT INTO videos_date (
+                video_id,
+                local_ts,
+                utc_ts,
+                updated_ts
+            ) VALUES (
+                ?1, ?2, ?3, CURRENT_TIMESTAMP
+            ) ON CONFLICT (video_id) DO UPDATE SET
+                local_ts = ?2,
+                utc_ts = ?3,
+                updated_ts = CURRENT_TIMESTAMP
+            ",
+            params![video_id.id(), local_ts, utc_ts],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{insert_picture, setup};
+    use chrono::NaiveDate;
+
+    /// Local and ordering timestamps of a picture.
+    fn timestamps(
+        con: &Arc<Mutex<rusqlite::Connection>>,
+        picture_id: PictureId,
+    ) -> (NaiveDateTime, NaiveDateTime) {
+        let con = con.lock().unwrap();
+        con.query_row(
+            "SELECT local_ts, ordering_ts FROM visual WHERE picture_id = ?1",
+            params![picture_id.id()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn date_replaces_local_time_of_capture() {
+        let con = setup();
+        let mut repo = Repository::open(con.clone()).unwrap();
+
+        let picture_id = insert_picture(&con, "/pictures/a.jpg");
+        let other_id = insert_picture(&con, "/pictures/b.jpg");
+
+        let chosen = NaiveDate::from_ymd_opt(1999, 12, 31)
+            .unwrap()
+            .and_hms_opt(23, 59, 0)
+            .unwrap();
+        // Taken ten hours ahead of UTC, so ordered by the UTC time and not the local time.
+        let offset = chrono::Duration::hours(10);
+        repo.set_picture(picture_id, chosen, (chosen - offset).and_utc())
+            .unwrap();
+        assert_eq!(timestamps(&con, picture_id), (chosen, chosen - offset));
+
+        let later = chosen + chrono::Duration::days(1);
+        repo.set_picture(picture_id, later, (later - offset).and_utc())
+            .unwrap();
+        assert_eq!(timestamps(&con, picture_id), (later, later - offset));
+
+        let unchanged = NaiveDate::from_ymd_opt(2020, 1, 1)
+            .unwrap()
+            .and_hms_opt(9, 30, 0)
+            .unwrap();
+        assert_eq!(timestamps(&con, other_id).0, unchanged);
+    }
+}
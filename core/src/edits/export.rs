@@ -0,0 +1,30 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use anyhow::*;
+use image::{DynamicImage, ImageFormat, RgbaImage};
+use std::path::Path;
+use tracing::info;
+
+/// Save an edited copy of a picture, in the format named by the file extension,
+/// or as a JPEG if the extension isn't recognised.
+/// The copy is encoded from pixels alone, so it carries none of the metadata of the
+/// original, such as where the picture was taken.
+pub fn save_copy(image: RgbaImage, output_path: &Path) -> Result<()> {
+    let format = ImageFormat::from_path(output_path).unwrap_or(ImageFormat::Jpeg);
+
+    // JPEG has no alpha channel.
+    let image = if format == ImageFormat::Jpeg {
+        DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(image).into_rgb8())
+    } else {
+        DynamicImage::ImageRgba8(image)
+    };
+
+    image
+        .save_with_format(output_path, format)
+        .with_context(|| format!("Failed saving edited copy to {:?}", output_path))?;
+
+    info!("Saved edited copy to {:?}", output_path);
+    Ok(())
+}
@@ -0,0 +1,14 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+pub mod export;
+pub mod model;
+pub mod render;
+pub mod repo;
+pub mod texture;
+
+pub use export::save_copy;
+pub use model::{Adjustments, AspectRatio, Edit, EditStack};
+pub use render::render;
+pub use repo::Repository;
@@ -0,0 +1,360 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::fmt::Display;
+
+/// A single non-destructive change to a picture.
+/// Geometry is relative to the picture as it was after the preceding edits,
+/// so edits must always be applied in order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Edit {
+    /// Keep a rectangle, as fractions of the width and height.
+    Crop {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    },
+
+    /// Rotate by a number of quarter turns clockwise.
+    Rotate(u8),
+
+    /// Rotate by a small angle in degrees clockwise, zooming in so there are no empty corners.
+    Straighten(f32),
+
+    FlipHorizontal,
+
+    FlipVertical,
+
+    /// Brighten or darken by a number of stops.
+    Exposure(f32),
+
+    /// Increase or decrease contrast. From -1.0 to 1.0.
+    Contrast(f32),
+
+    /// Increase or decrease color saturation. From -1.0 (grayscale) to 1.0.
+    Saturation(f32),
+
+    /// Stretch levels so the darkest and lightest pixels use the full range.
+    AutoEnhance,
+}
+
+impl Edit {
+    pub fn parse(value: &str) -> Option<Edit> {
+        let (name, args) = value.split_once(':').unwrap_or((value, ""));
+        let args: Vec<f32> = if args.is_empty() {
+            vec![]
+        } else {
+            args.split(',')
+                .map(|arg| arg.parse::<f32>().ok().filter(|arg| arg.is_finite()))
+                .collect::<Option<_>>()?
+        };
+
+        match (name, args.as_slice()) {
+            ("crop", [x, y, width, height]) => Some(Edit::Crop {
+                x: *x,
+                y: *y,
+                width: *width,
+                height: *height,
+            }),
+            ("rotate", [turns]) => Some(Edit::Rotate((*turns as u8) % 4)),
+            ("straighten", [degrees]) => Some(Edit::Straighten(*degrees)),
+            ("flip-horizontal", []) => Some(Edit::FlipHorizontal),
+            ("flip-vertical", []) => Some(Edit::FlipVertical),
+            ("exposure", [stops]) => Some(Edit::Exposure(*stops)),
+            ("contrast", [amount]) => Some(Edit::Contrast(*amount)),
+            ("saturation", [amount]) => Some(Edit::Saturation(*amount)),
+            ("auto-enhance", []) => Some(Edit::AutoEnhance),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Edit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Edit::Crop {
+                x,
+                y,
+                width,
+                height,
+            } => write!(f, "crop:{},{},{},{}", x, y, width, height),
+            Edit::Rotate(turns) => write!(f, "rotate:{}", turns),
+            Edit::Straighten(degrees) => write!(f, "straighten:{}", degrees),
+            Edit::FlipHorizontal => write!(f, "flip-horizontal"),
+            Edit::FlipVertical => write!(f, "flip-vertical"),
+            Edit::Exposure(stops) => write!(f, "exposure:{}", stops),
+            Edit::Contrast(amount) => write!(f, "contrast:{}", amount),
+            Edit::Saturation(amount) => write!(f, "saturation:{}", amount),
+            Edit::AutoEnhance => write!(f, "auto-enhance"),
+        }
+    }
+}
+
+/// Edits of a picture, applied in order.
+/// Stored in the database as text, which is also part of the thumbnail hash
+/// of an edited picture.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EditStack {
+    pub edits: Vec<Edit>,
+}
+
+impl EditStack {
+    /// Parse edits written by `to_string`. Returns `None` if any edit is not understood,
+    /// because skipping an edit would render something the user didn't ask for.
+    pub fn parse(value: &str) -> Option<EditStack> {
+        let edits = value
+            .split(';')
+            .filter(|edit| !edit.is_empty())
+            .map(Edit::parse)
+            .collect::<Option<Vec<_>>>()?;
+        Some(EditStack { edits })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+}
+
+impl Display for EditStack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, edit) in self.edits.iter().enumerate() {
+            if index > 0 {
+                write!(f, ";")?;
+            }
+            write!(f, "{}", edit)?;
+        }
+        Ok(())
+    }
+}
+
+/// Preset shapes for cropping. Ratios are of the long edge to the short edge,
+/// and follow the orientation of the picture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AspectRatio {
+    /// Don't crop.
+    #[default]
+    Original,
+    Square,
+    FourThree,
+    ThreeTwo,
+    SixteenNine,
+}
+
+impl AspectRatio {
+    pub const ALL: [AspectRatio; 5] = [
+        AspectRatio::Original,
+        AspectRatio::Square,
+        AspectRatio::FourThree,
+        AspectRatio::ThreeTwo,
+        AspectRatio::SixteenNine,
+    ];
+
+    /// Long edge divided by short edge, or `None` to not crop.
+    pub fn ratio(&self) -> Option<f32> {
+        match self {
+            AspectRatio::Original => None,
+            AspectRatio::Square => Some(1.0),
+            AspectRatio::FourThree => Some(4.0 / 3.0),
+            AspectRatio::ThreeTwo => Some(3.0 / 2.0),
+            AspectRatio::SixteenNine => Some(16.0 / 9.0),
+        }
+    }
+
+    /// Largest centered crop of a picture of the given dimensions with this aspect ratio.
+    pub fn centered_crop(&self, width: u32, height: u32) -> Option<Edit> {
+        let ratio = self.ratio()?;
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let (width, height) = (width as f32, height as f32);
+        let target = if width >= height { ratio } else { 1.0 / ratio };
+
+        let (crop_width, crop_height) = if width / height > target {
+            (height * target / width, 1.0)
+        } else {
+            (1.0, width / target / height)
+        };
+
+        Some(Edit::Crop {
+            x: (1.0 - crop_width) / 2.0,
+            y: (1.0 - crop_height) / 2.0,
+            width: crop_width,
+            height: crop_height,
+        })
+    }
+
+    /// Preset closest to a cropped size, within a one percent tolerance.
+    pub fn matching(width: f32, height: f32) -> AspectRatio {
+        let long = f32::max(width, height);
+        let short = f32::min(width, height);
+        if short <= 0.0 {
+            return AspectRatio::Original;
+        }
+
+        let ratio = long / short;
+        AspectRatio::ALL
+            .into_iter()
+            .find(|preset| preset.ratio().is_some_and(|r| (r - ratio).abs() / r < 0.01))
+            .unwrap_or(AspectRatio::Original)
+    }
+}
+
+/// Settings of the edit panel. Converted to and from an edit stack in a fixed order,
+/// so that the same settings always produce the same stack and thumbnail hash.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Adjustments {
+    pub aspect_ratio: AspectRatio,
+
+    /// Quarter turns clockwise.
+    pub quarter_turns: u8,
+
+    /// Degrees clockwise.
+    pub straighten: f32,
+
+    pub flip_horizontal: bool,
+
+    pub flip_vertical: bool,
+
+    /// Stops.
+    pub exposure: f32,
+
+    pub contrast: f32,
+
+    pub saturation: f32,
+
+    pub auto_enhance: bool,
+}
+
+impl Adjustments {
+    /// Read settings from an edit stack. `width` and `height` are of the unedited picture.
+    pub fn from_stack(stack: &EditStack, width: u32, height: u32) -> Adjustments {
+        let mut adjustments = Adjustments::default();
+        let mut crop = None;
+
+        for edit in &stack.edits {
+            match *edit {
+                Edit::Crop { width, height, .. } => crop = Some((width, height)),
+                Edit::Rotate(turns) => {
+                    adjustments.quarter_turns = (adjustments.quarter_turns + turns) % 4
+                }
+                Edit::Straighten(degrees) => adjustments.straighten += degrees,
+                Edit::FlipHorizontal => adjustments.flip_horizontal = !adjustments.flip_horizontal,
+                Edit::FlipVertical => adjustments.flip_vertical = !adjustments.flip_vertical,
+                Edit::Exposure(stops) => adjustments.exposure += stops,
+                Edit::Contrast(amount) => adjustments.contrast += amount,
+                Edit::Saturation(amount) => adjustments.saturation += amount,
+                Edit::AutoEnhance => adjustments.auto_enhance = true,
+            }
+        }
+
+        if let Some((crop_width, crop_height)) = crop {
+            let (width, height) = adjustments.rotated_dimensions(width, height);
+            adjustments.aspect_ratio =
+                AspectRatio::matching(crop_width * width as f32, crop_height * height as f32);
+        }
+
+        adjustments
+    }
+
+    /// Edit stack for these settings. `width` and `height` are of the unedited picture.
+    /// Tone is adjusted before geometry, so auto-enhance sees the whole picture.
+    pub fn to_stack(&self, width: u32, height: u32) -> EditStack {
+        let mut edits = vec![];
+
+        if self.auto_enhance {
+            edits.push(Edit::AutoEnhance);
+        }
+        if self.exposure != 0.0 {
+            edits.push(Edit::Exposure(self.exposure));
+        }
+        if self.contrast != 0.0 {
+            edits.push(Edit::Contrast(self.contrast));
+        }
+        if self.saturation != 0.0 {
+            edits.push(Edit::Saturation(self.saturation));
+        }
+        if self.quarter_turns % 4 != 0 {
+            edits.push(Edit::Rotate(self.quarter_turns % 4));
+        }
+        if self.flip_horizontal {
+            edits.push(Edit::FlipHorizontal);
+        }
+        if self.flip_vertical {
+            edits.push(Edit::FlipVertical);
+        }
+        if self.straighten != 0.0 {
+            edits.push(Edit::Straighten(self.straighten));
+        }
+
+        let (width, height) = self.rotated_dimensions(width, height);
+        if let Some(crop) = self.aspect_ratio.centered_crop(width, height) {
+            edits.push(crop);
+        }
+
+        EditStack { edits }
+    }
+
+    fn rotated_dimensions(&self, width: u32, height: u32) -> (u32, u32) {
+        if self.quarter_turns % 2 == 1 {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stack_round_trips_through_text() {
+        let stack = EditStack {
+            edits: vec![
+                Edit::AutoEnhance,
+                Edit::Exposure(0.5),
+                Edit::Rotate(1),
+                Edit::FlipHorizontal,
+                Edit::Straighten(-2.25),
+                Edit::Crop {
+                    x: 0.125,
+                    y: 0.0,
+                    width: 0.75,
+                    height: 1.0,
+                },
+            ],
+        };
+
+        let text = stack.to_string();
+        assert_eq!(
+            text,
+            "auto-enhance;exposure:0.5;rotate:1;flip-horizontal;straighten:-2.25;crop:0.125,0,0.75,1"
+        );
+        assert_eq!(EditStack::parse(&text), Some(stack));
+        assert_eq!(EditStack::parse(""), Some(EditStack::default()));
+        assert_eq!(EditStack::parse("exposure:0.5;sharpen:1"), None);
+    }
+
+    #[test]
+    fn adjustments_round_trip_through_stack() {
+        let adjustments = Adjustments {
+            aspect_ratio: AspectRatio::SixteenNine,
+            quarter_turns: 1,
+            contrast: 0.25,
+            ..Default::default()
+        };
+
+        // A landscape picture turned to portrait is cropped to 9:16.
+        let stack = adjustments.to_stack(4000, 3000);
+        let Some(Edit::Crop { width, height, .. }) = stack.edits.last() else {
+            panic!("Expected crop");
+        };
+        assert_eq!(*height, 1.0);
+        assert!((width * 3000.0 - 2250.0).abs() < 1.0);
+
+        assert_eq!(Adjustments::from_stack(&stack, 4000, 3000), adjustments);
+    }
+}
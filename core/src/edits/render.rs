@@ -0,0 +1,213 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Applies an edit stack to decoded pixels. The same rendering is used for
+//! thumbnails, the viewer, and exported copies, so they always agree.
+
+use super::model::{Edit, EditStack};
+use image::{Rgba, RgbaImage, imageops};
+
+/// Fraction of pixels ignored at each end of the histogram by auto-enhance,
+/// so a few specks of noise don't prevent the levels from being stretched.
+const AUTO_ENHANCE_CLIP: f32 = 0.005;
+
+/// Render edits onto a picture that already has its EXIF orientation applied.
+pub fn render(mut image: RgbaImage, stack: &EditStack) -> RgbaImage {
+    for edit in &stack.edits {
+        image = match *edit {
+            Edit::Crop {
+                x,
+                y,
+                width,
+                height,
+            } => crop(image, x, y, width, height),
+            Edit::Rotate(1) => imageops::rotate90(&image),
+            Edit::Rotate(2) => imageops::rotate180(&image),
+            Edit::Rotate(3) => imageops::rotate270(&image),
+            Edit::Rotate(_) => image,
+            Edit::Straighten(degrees) => straighten(&image, degrees),
+            Edit::FlipHorizontal => imageops::flip_horizontal(&image),
+            Edit::FlipVertical => imageops::flip_vertical(&image),
+            Edit::Exposure(stops) => {
+                let gain = 2.0f32.powf(stops);
+                apply_lut(image, |v| {
+                    // Scale in approximately linear light, so highlights roll off naturally.
+                    let linear = v.powf(2.2) * gain;
+                    linear.powf(1.0 / 2.2)
+                })
+            }
+            Edit::Contrast(amount) => {
+                let factor = 1.0 + amount.clamp(-1.0, 1.0);
+                apply_lut(image, |v| (v - 0.5) * factor + 0.5)
+            }
+            Edit::Saturation(amount) => saturate(image, 1.0 + amount.clamp(-1.0, 1.0)),
+            Edit::AutoEnhance => auto_enhance(image),
+        };
+    }
+
+    image
+}
+
+fn crop(image: RgbaImage, x: f32, y: f32, width: f32, height: f32) -> RgbaImage {
+    let (image_width, image_height) = image.dimensions();
+
+    let x = (x.clamp(0.0, 1.0) * image_width as f32).round() as u32;
+    let y = (y.clamp(0.0, 1.0) * image_height as f32).round() as u32;
+    let x = x.min(image_width.saturating_sub(1));
+    let y = y.min(image_height.saturating_sub(1));
+
+    let width = (width.clamp(0.0, 1.0) * image_width as f32).round() as u32;
+    let height = (height.clamp(0.0, 1.0) * image_height as f32).round() as u32;
+    let width = width.clamp(1, image_width - x);
+    let height = height.clamp(1, image_height - y);
+
+    imageops::crop_imm(&image, x, y, width, height).to_image()
+}
+
+/// Rotate about the center, keeping the same dimensions and scaling up
+/// just enough that no empty corners are visible.
+fn straighten(image: &RgbaImage, degrees: f32) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return image.clone();
+    }
+
+    let (w, h) = (width as f32, height as f32);
+    let (sin, cos) = degrees.to_radians().sin_cos();
+
+    // Scale of output pixels to source pixels so the rotated output fits inside the source.
+    let scale = f32::min(w / (w * cos + h * sin.abs()), h / (w * sin.abs() + h * cos));
+
+    RgbaImage::from_fn(width, height, |x, y| {
+        let dx = (x as f32 + 0.5 - w / 2.0) * scale;
+        let dy = (y as f32 + 0.5 - h / 2.0) * scale;
+        let sx = dx * cos + dy * sin + w / 2.0 - 0.5;
+        let sy = -dx * sin + dy * cos + h / 2.0 - 0.5;
+        sample_bilinear(image, sx, sy)
+    })
+}
+
+fn sample_bilinear(image: &RgbaImage, x: f32, y: f32) -> Rgba<u8> {
+    let max_x = image.width() - 1;
+    let max_y = image.height() - 1;
+
+    let x = x.clamp(0.0, max_x as f32);
+    let y = y.clamp(0.0, max_y as f32);
+
+    let x0 = x.floor() as u32;
+    let y0 = y.floor() as u32;
+    let x1 = (x0 + 1).min(max_x);
+    let y1 = (y0 + 1).min(max_y);
+    let fx = x - x0 as f32;
+    let fy = y - y0 as f32;
+
+    let p00 = image.get_pixel(x0, y0).0;
+    let p10 = image.get_pixel(x1, y0).0;
+    let p01 = image.get_pixel(x0, y1).0;
+    let p11 = image.get_pixel(x1, y1).0;
+
+    Rgba(std::array::from_fn(|c| {
+        let top = p00[c] as f32 * (1.0 - fx) + p10[c] as f32 * fx;
+        let bottom = p01[c] as f32 * (1.0 - fx) + p11[c] as f32 * fx;
+        (top * (1.0 - fy) + bottom * fy).round() as u8
+    }))
+}
+
+/// Map each color channel through a curve. `curve` takes and returns values from 0.0 to 1.0.
+fn apply_lut(mut image: RgbaImage, curve: impl Fn(f32) -> f32) -> RgbaImage {
+    let lut: Vec<u8> = (0..=255u8)
+        .map(|v| (curve(v as f32 / 255.0).clamp(0.0, 1.0) * 255.0).round() as u8)
+        .collect();
+
+    for pixel in image.pixels_mut() {
+        for channel in pixel.0.iter_mut().take(3) {
+            *channel = lut[*channel as usize];
+        }
+    }
+    image
+}
+
+fn saturate(mut image: RgbaImage, factor: f32) -> RgbaImage {
+    for pixel in image.pixels_mut() {
+        let [r, g, b, _] = pixel.0;
+        let luma = luma(r, g, b);
+        for channel in pixel.0.iter_mut().take(3) {
+            let v = luma + (*channel as f32 - luma) * factor;
+            *channel = v.clamp(0.0, 255.0).round() as u8;
+        }
+    }
+    image
+}
+
+fn auto_enhance(image: RgbaImage) -> RgbaImage {
+    let mut histogram = [0u64; 256];
+    for pixel in image.pixels() {
+        let [r, g, b, _] = pixel.0;
+        histogram[luma(r, g, b).round() as usize] += 1;
+    }
+
+    let total: u64 = histogram.iter().sum();
+    let clip = (total as f32 * AUTO_ENHANCE_CLIP) as u64;
+
+    let low = percentile(&histogram, clip, 0..256) as f32;
+    let high = percentile(&histogram, clip, (0..256).rev()) as f32;
+
+    // Nothing to stretch, or so little range it's probably meant to be flat.
+    if high - low < 16.0 || (low == 0.0 && high == 255.0) {
+        return image;
+    }
+
+    apply_lut(image, |v| (v * 255.0 - low) / (high - low))
+}
+
+/// First level, in the order given, once more than `clip` pixels have been counted.
+fn percentile(histogram: &[u64; 256], clip: u64, levels: impl Iterator<Item = usize>) -> usize {
+    let mut count = 0;
+    for level in levels {
+        count += histogram[level];
+        if count > clip {
+            return level;
+        }
+    }
+    0
+}
+
+fn luma(r: u8, g: u8, b: u8) -> f32 {
+    0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edits::AspectRatio;
+
+    #[test]
+    fn renders_geometry() {
+        let image = RgbaImage::new(400, 300);
+
+        let stack = EditStack {
+            edits: vec![
+                Edit::Rotate(1),
+                Edit::Straighten(3.0),
+                AspectRatio::Square.centered_crop(300, 400).unwrap(),
+            ],
+        };
+
+        let image = render(image, &stack);
+        assert_eq!((image.width(), image.height()), (300, 300));
+    }
+
+    #[test]
+    fn auto_enhance_stretches_levels() {
+        let image = RgbaImage::from_fn(256, 1, |x, _| {
+            let v = 64 + (x / 2) as u8;
+            Rgba([v, v, v, 255])
+        });
+
+        let image = auto_enhance(image);
+        let levels: Vec<u8> = image.pixels().map(|p| p.0[0]).collect();
+        assert_eq!(levels.first(), Some(&0));
+        assert_eq!(levels.last(), Some(&255));
+    }
+}
@@ -0,0 +1,79 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::model::EditStack;
+use crate::photo::PictureId;
+
+use anyhow::*;
+use rusqlite::OptionalExtension;
+use rusqlite::params;
+use std::result::Result::Ok;
+use std::sync::{Arc, Mutex};
+
+/// Repository of non-destructive picture edits.
+/// Repository is backed by a Sqlite database.
+#[derive(Debug, Clone)]
+pub struct Repository {
+    /// Connection to backing Sqlite database.
+    con: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl Repository {
+    pub fn open(con: Arc<Mutex<rusqlite::Connection>>) -> Result<Repository> {
+        Ok(Repository { con })
+    }
+
+    /// Gets edits of a picture, if it has been edited.
+    pub fn get(&self, picture_id: PictureId) -> Result<Option<EditStack>> {
+        let con = self.con.lock().unwrap();
+        let edits: Option<String> = con
+            .query_row(
+                "SELECT edits FROM pictures_edits WHERE picture_id = ?1",
+                params![picture_id.id()],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        let Some(edits) = edits else {
+            return Ok(None);
+        };
+
+        EditStack::parse(&edits)
+            .map(Some)
+            .ok_or_else(|| anyhow!("Unrecognised edits for picture {}: {}", picture_id, edits))
+    }
+
+    /// Replace the edits of a picture. Saving no edits reverts to the original.
+    pub fn save(&mut self, picture_id: PictureId, edits: &EditStack) -> Result<()> {
+        if edits.is_empty() {
+            return self.revert(picture_id);
+        }
+
+        let con = self.con.lock().unwrap();
+        con.execute(
+            "INSERT INTO pictures_edits (
+                picture_id,
+                edits,
+                updated_ts
+            ) VALUES (
+                ?1, ?2, CURRENT_TIMESTAMP
+            ) ON CONFLICT (picture_id) DO UPDATE SET
+                edits = ?2,
+                updated_ts = CURRENT_TIMESTAMP
+            ",
+            params![picture_id.id(), edits.to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// Discard all edits of a picture.
+    pub fn revert(&mut self, picture_id: PictureId) -> Result<()> {
+        let con = self.con.lock().unwrap();
+        con.execute(
+            "DELETE FROM pictures_edits WHERE picture_id = ?1",
+            params![picture_id.id()],
+        )?;
+        Ok(())
+    }
+}
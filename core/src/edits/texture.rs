@@ -0,0 +1,47 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Conversions between GDK textures, as loaded by Glycin and shown by GTK,
+//! and the pixels that edits are rendered onto.
+
+use gdk4::prelude::*;
+use image::RgbaImage;
+
+/// Download the pixels of a texture with straight alpha.
+pub fn to_image(texture: &gdk4::Texture) -> Option<RgbaImage> {
+    let width = texture.width() as u32;
+    let height = texture.height() as u32;
+
+    let mut downloader = gdk4::TextureDownloader::new(texture);
+    downloader.set_format(gdk4::MemoryFormat::R8g8b8a8);
+    let (bytes, stride) = downloader.download_bytes();
+
+    // Rows are normally tightly packed, but if not then drop the padding.
+    let row_len = width as usize * 4;
+    let pixels: Vec<u8> = if stride == row_len {
+        bytes.to_vec()
+    } else {
+        bytes
+            .chunks(stride)
+            .flat_map(|row| &row[..row_len])
+            .copied()
+            .collect()
+    };
+
+    RgbaImage::from_raw(width, height, pixels)
+}
+
+/// Upload pixels to a texture that can be shown by GTK.
+pub fn to_texture(image: RgbaImage) -> gdk4::Texture {
+    let (width, height) = image.dimensions();
+    let bytes = gdk4::glib::Bytes::from_owned(image.into_raw());
+    gdk4::MemoryTexture::new(
+        width as i32,
+        height as i32,
+        gdk4::MemoryFormat::R8g8b8a8,
+        &bytes,
+        width as usize * 4,
+    )
+    .upcast()
+}
@@ -0,0 +1,361 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::model::EventKind;
+use crate::places::Place;
+use crate::visual::{Visual, VisualId};
+
+use chrono::{DateTime, NaiveDateTime, TimeDelta, Utc};
+use h3o::{CellIndex, LatLng, Resolution};
+use std::collections::HashMap;
+
+/// Start a new event if there is a gap of more than this between items.
+const EVENT_MAX_GAP_HOURS: i64 = 3;
+
+/// Start a new event if consecutive items were taken further apart than this.
+const EVENT_MAX_DISTANCE_KM: f64 = 30.0;
+
+/// Events with fewer items than this are too small to be worth showing.
+const EVENT_MIN_SIZE: usize = 3;
+
+/// Events away from home with gaps shorter than this are part of the same trip.
+const TRIP_MAX_GAP_HOURS: i64 = 36;
+
+/// Events this far from home are candidates for being part of a trip.
+const TRIP_MIN_HOME_DISTANCE_KM: f64 = 100.0;
+
+/// Resolution of H3 cells used to find home.
+/// Cells at resolution 5 have an edge length of about 9km.
+const HOME_RESOLUTION: Resolution = Resolution::Five;
+
+/// The parts of a visual item needed for clustering.
+#[derive(Debug, Clone)]
+pub struct Moment {
+    pub visual_id: VisualId,
+    pub ordering_ts: DateTime<Utc>,
+    pub local_ts: NaiveDateTime,
+    pub location: Option<LatLng>,
+    pub place: Option<Place>,
+}
+
+impl From<&Visual> for Moment {
+    fn from(visual: &Visual) -> Self {
+        Self {
+            visual_id: visual.visual_id.clone(),
+            ordering_ts: visual.ordering_ts,
+            local_ts: visual.local_ts,
+            location: visual.location,
+            place: visual.place.clone(),
+        }
+    }
+}
+
+/// An event or trip found by clustering.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cluster {
+    pub kind: EventKind,
+    pub start_ts: DateTime<Utc>,
+    pub end_ts: DateTime<Utc>,
+    pub local_start_ts: NaiveDateTime,
+    pub local_end_ts: NaiveDateTime,
+    pub place_name: Option<String>,
+    pub visual_ids: Vec<VisualId>,
+
+    /// Events that are part of a trip. Always empty for events.
+    pub events: Vec<Cluster>,
+}
+
+impl Cluster {
+    fn event(moments: &[Moment]) -> Self {
+        let place_name = most_common(
+            moments
+                .iter()
+                .filter_map(|m| m.place.as_ref())
+                .map(|p| p.city.as_str()),
+        );
+        Self::build(EventKind::Event, moments, place_name, vec![])
+    }
+
+    fn trip(groups: Vec<Vec<Moment>>) -> Self {
+        let events = groups
+            .iter()
+            .filter(|group| group.len() >= EVENT_MIN_SIZE)
+            .map(|group| Cluster::event(group))
+            .collect();
+
+        let moments: Vec<Moment> = groups.into_iter().flatten().collect();
+        let places: Vec<&Place> = moments.iter().filter_map(|m| m.place.as_ref()).collect();
+
+        // Name a trip after the most specific place all of the trip was in.
+        let cities = distinct(places.iter().map(|p| p.city.as_str()));
+        let regions = distinct(places.iter().filter_map(|p| p.region.as_deref()));
+
+        let place_name = if cities.len() == 1 {
+            cities.into_iter().next()
+        } else if regions.len() == 1 && places.iter().all(|p| p.region.is_some()) {
+            regions.into_iter().next()
+        } else {
+            most_common(places.iter().map(|p| p.country.as_str()))
+        };
+
+        Self::build(EventKind::Trip, &moments, place_name, events)
+    }
+
+    fn build(
+        kind: EventKind,
+        moments: &[Moment],
+        place_name: Option<String>,
+        events: Vec<Cluster>,
+    ) -> Self {
+        let first = moments.first().expect("Clusters can't be empty");
+        let last = moments.last().expect("Clusters can't be empty");
+        Self {
+            kind,
+            start_ts: first.ordering_ts,
+            end_ts: last.ordering_ts,
+            local_start_ts: first.local_ts,
+            local_end_ts: last.local_ts,
+            place_name,
+            visual_ids: moments.iter().map(|m| m.visual_id.clone()).collect(),
+            events,
+        }
+    }
+}
+
+/// Cluster items into events by gaps in time and jumps in location, and then
+/// group events away from home spanning more than one day into trips.
+/// Returns trips and events that aren't part of a trip, in order of time.
+pub fn cluster(mut moments: Vec<Moment>) -> Vec<Cluster> {
+    moments.sort_by_key(|m| m.ordering_ts);
+    let home = home(&moments);
+    let groups = split(moments);
+
+    let trip_max_gap = TimeDelta::hours(TRIP_MAX_GAP_HOURS);
+
+    let mut clusters = Vec::new();
+    let mut run: Vec<Vec<Moment>> = Vec::new();
+
+    for group in groups {
+        let away = is_away(&group, home);
+
+        let continues_run = run.last().is_some_and(|previous| {
+            let gap = group[0].ordering_ts - previous[previous.len() - 1].ordering_ts;
+            gap <= trip_max_gap && away != Some(false)
+        });
+
+        if continues_run {
+            run.push(group);
+            continue;
+        }
+
+        clusters.extend(finish_run(std::mem::take(&mut run)));
+
+        if away == Some(true) {
+            run.push(group);
+        } else if group.len() >= EVENT_MIN_SIZE {
+            clusters.push(Cluster::event(&group));
+        }
+    }
+
+    clusters.extend(finish_run(run));
+    clusters
+}
+
+/// A run of events away from home is a trip if it spans more than one day.
+/// Otherwise it is just a series of events.
+fn finish_run(run: Vec<Vec<Moment>>) -> Vec<Cluster> {
+    let (Some(first), Some(last)) = (run.first(), run.last()) else {
+        return vec![];
+    };
+
+    let first_day = first[0].local_ts.date();
+    let last_day = last[last.len() - 1].local_ts.date();
+
+    if last_day > first_day {
+        vec![Cluster::trip(run)]
+    } else {
+        run.iter()
+            .filter(|group| group.len() >= EVENT_MIN_SIZE)
+            .map(|group| Cluster::event(group))
+            .collect()
+    }
+}
+
+/// Split time-ordered items into groups wherever there is a large gap in time
+/// or a jump in location.
+fn split(moments: Vec<Moment>) -> Vec<Vec<Moment>> {
+    let max_gap = TimeDelta::hours(EVENT_MAX_GAP_HOURS);
+
+    let mut groups = Vec::new();
+    let mut current: Vec<Moment> = Vec::new();
+    let mut last_location: Option<LatLng> = None;
+
+    for moment in moments {
+        if let Some(previous) = current.last() {
+            let gap = moment.ordering_ts - previous.ordering_ts;
+            let moved = match (last_location, moment.location) {
+                (Some(a), Some(b)) => a.distance_km(b) > EVENT_MAX_DISTANCE_KM,
+                _ => false,
+            };
+
+            if gap > max_gap || moved {
+                groups.push(std::mem::take(&mut current));
+                last_location = None;
+            }
+        }
+
+        if moment.location.is_some() {
+            last_location = moment.location;
+        }
+        current.push(moment);
+    }
+
+    if !current.is_empty() {
+        groups.push(current);
+    }
+
+    groups
+}
+
+/// Home is wherever most located items were taken.
+fn home(moments: &[Moment]) -> Option<LatLng> {
+    let mut counts: HashMap<CellIndex, usize> = HashMap::new();
+    for location in moments.iter().filter_map(|m| m.location) {
+        *counts.entry(location.to_cell(HOME_RESOLUTION)).or_default() += 1;
+    }
+
+    counts
+        .into_iter()
+        .max_by_key(|(cell, count)| (*count, std::cmp::Reverse(*cell)))
+        .map(|(cell, _)| LatLng::from(cell))
+}
+
+/// Is a group of items away from home?
+/// None if no items have a location, so it can't be known.
+fn is_away(group: &[Moment], home: Option<LatLng>) -> Option<bool> {
+    let home = home?;
+    let mut locations = group.iter().filter_map(|m| m.location).peekable();
+    locations.peek()?;
+    Some(locations.any(|location| location.distance_km(home) >= TRIP_MIN_HOME_DISTANCE_KM))
+}
+
+/// Most common name, with ties broken alphabetically.
+fn most_common<'a>(names: impl Iterator<Item = &'a str>) -> Option<String> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for name in names {
+        *counts.entry(name).or_default() += 1;
+    }
+
+    counts
+        .into_iter()
+        .max_by_key(|(name, count)| (*count, std::cmp::Reverse(*name)))
+        .map(|(name, _)| name.to_string())
+}
+
+fn distinct<'a>(names: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut names: Vec<String> = names.map(|x| x.to_string()).collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn place(city: &str, country: &str) -> Place {
+        Place {
+            country_code: country[..2].to_uppercase(),
+            country: country.to_string(),
+            region: None,
+            city: city.to_string(),
+        }
+    }
+
+    fn moment(id: &str, day: u32, hour: u32, location: Option<(f64, f64, Place)>) -> Moment {
+        let local_ts = NaiveDate::from_ymd_opt(2025, 6, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap();
+        let (location, place) = match location {
+            Some((lat, lng, place)) => (Some(LatLng::new(lat, lng).unwrap()), Some(place)),
+            None => (None, None),
+        };
+        Moment {
+            visual_id: VisualId::new(id.to_string()),
+            ordering_ts: local_ts.and_utc(),
+            local_ts,
+            location,
+            place,
+        }
+    }
+
+    fn dublin() -> Option<(f64, f64, Place)> {
+        Some((53.35, -6.26, place("Dublin", "Ireland")))
+    }
+
+    fn paris() -> Option<(f64, f64, Place)> {
+        Some((48.86, 2.35, place("Paris", "France")))
+    }
+
+    fn ids(cluster: &Cluster) -> Vec<&str> {
+        cluster
+            .visual_ids
+            .iter()
+            .map(|id| id.id().as_str())
+            .collect()
+    }
+
+    #[test]
+    fn splits_events_by_gap_in_time() {
+        let moments = vec![
+            moment("a", 1, 10, dublin()),
+            moment("b", 1, 11, dublin()),
+            moment("c", 1, 12, None),
+            moment("d", 1, 18, dublin()),
+            moment("e", 1, 19, dublin()),
+            moment("f", 1, 20, dublin()),
+        ];
+
+        let clusters = cluster(moments);
+
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(ids(&clusters[0]), vec!["a", "b", "c"]);
+        assert_eq!(ids(&clusters[1]), vec!["d", "e", "f"]);
+        assert_eq!(clusters[0].kind, EventKind::Event);
+        assert_eq!(clusters[0].place_name, Some("Dublin".to_string()));
+    }
+
+    #[test]
+    fn groups_days_away_from_home_into_trip() {
+        let moments = vec![
+            moment("a", 1, 10, dublin()),
+            moment("b", 1, 11, dublin()),
+            moment("c", 1, 12, dublin()),
+            moment("d", 1, 13, dublin()),
+            moment("x", 2, 10, dublin()),
+            moment("y", 2, 11, dublin()),
+            moment("z", 2, 12, dublin()),
+            moment("e", 5, 10, paris()),
+            moment("f", 5, 11, paris()),
+            moment("g", 5, 12, paris()),
+            moment("h", 6, 10, paris()),
+            moment("i", 6, 11, None),
+            moment("j", 6, 12, paris()),
+        ];
+
+        let clusters = cluster(moments);
+
+        assert_eq!(clusters.len(), 3);
+        assert_eq!(clusters[0].kind, EventKind::Event);
+        assert_eq!(clusters[1].kind, EventKind::Event);
+
+        let trip = &clusters[2];
+        assert_eq!(trip.kind, EventKind::Trip);
+        assert_eq!(trip.place_name, Some("Paris".to_string()));
+        assert_eq!(ids(trip), vec!["e", "f", "g", "h", "i", "j"]);
+        assert_eq!(trip.events.len(), 2);
+    }
+}
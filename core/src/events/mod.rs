@@ -0,0 +1,14 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+pub mod cluster;
+pub mod model;
+pub mod repo;
+
+pub use cluster::Cluster;
+pub use cluster::Moment;
+pub use model::Event;
+pub use model::EventId;
+pub use model::EventKind;
+pub use repo::Repository;
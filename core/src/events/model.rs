@@ -0,0 +1,93 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::visual::VisualId;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::fmt::Display;
+
+/// Database ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EventId(i64);
+
+impl EventId {
+    pub fn new(id: i64) -> Self {
+        Self(id)
+    }
+
+    /// FIXME replace this with a To/From SQL implementation.
+    pub fn id(&self) -> i64 {
+        self.0
+    }
+}
+
+impl Display for EventId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Kind of grouping of photos and videos.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    /// Photos and videos taken close together in time and place, such as
+    /// a birthday party or a day at the beach.
+    Event,
+
+    /// Several days of events away from home.
+    Trip,
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::Event => "event",
+            EventKind::Trip => "trip",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "event" => Some(EventKind::Event),
+            "trip" => Some(EventKind::Trip),
+            _ => None,
+        }
+    }
+}
+
+/// An event or trip
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub event_id: EventId,
+
+    pub kind: EventKind,
+
+    /// Trip this event is part of.
+    pub trip_id: Option<EventId>,
+
+    /// Timestamps of first and last item.
+    pub start_ts: DateTime<Utc>,
+    pub end_ts: DateTime<Utc>,
+
+    /// Wall-clock time at the place of capture of first and last item.
+    pub local_start_ts: NaiveDateTime,
+    pub local_end_ts: NaiveDateTime,
+
+    /// Name of the place where most of the event happened.
+    pub place_name: Option<String>,
+
+    /// Items in the event, in order of capture.
+    pub visual_ids: Vec<VisualId>,
+}
+
+impl Event {
+    /// Does the event span more than one calendar day?
+    pub fn is_multi_day(&self) -> bool {
+        self.local_end_ts.date() > self.local_start_ts.date()
+    }
+
+    /// Item to use as a cover image.
+    pub fn cover(&self) -> Option<&VisualId> {
+        self.visual_ids.first()
+    }
+}
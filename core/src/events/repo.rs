@@ -0,0 +1,203 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::cluster::Cluster;
+use super::model::{Event, EventId, EventKind};
+use crate::visual::VisualId;
+
+use anyhow::*;
+use rusqlite::Row;
+use rusqlite::params;
+use std::collections::HashMap;
+use std::result::Result::Ok;
+use std::sync::{Arc, Mutex};
+
+/// Repository of events and trips.
+/// Repository is backed by a Sqlite database.
+#[derive(Debug, Clone)]
+pub struct Repository {
+    /// Connection to backing Sqlite database.
+    con: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl Repository {
+    pub fn open(con: Arc<Mutex<rusqlite::Connection>>) -> Result<Repository> {
+        Ok(Repository { con })
+    }
+
+    /// Gets all events and trips, ordered by start time.
+    /// Events that are part of a trip have a trip_id.
+    pub fn all(&self) -> Result<Vec<Event>> {
+        let con = self.con.lock().unwrap();
+
+        let mut visual_ids: HashMap<i64, Vec<VisualId>> = HashMap::new();
+        {
+            let mut stmt = con.prepare(
+                "SELECT event_id, visual_id
+                FROM events_visuals
+                ORDER BY event_id, position",
+            )?;
+
+            let rows = stmt.query_map([], |row| {
+                let event_id: i64 = row.get("event_id")?;
+                let visual_id: String = row.get("visual_id")?;
+                Ok((event_id, VisualId::new(visual_id)))
+            })?;
+
+            for (event_id, visual_id) in rows.flatten() {
+                visual_ids.entry(event_id).or_default().push(visual_id);
+            }
+        }
+
+        let mut stmt = con.prepare(
+            "SELECT
+                event_id,
+                kind,
+                trip_id,
+                start_ts,
+                end_ts,
+                local_start_ts,
+                local_end_ts,
+                place_name
+            FROM events
+            ORDER BY start_ts ASC, kind DESC",
+        )?;
+
+        let result = stmt.query_map([], |row| {
+            let mut event = Self::to_event(row)?;
+            event.visual_ids = visual_ids.remove(&event.event_id.id()).unwrap_or_default();
+            Ok(event)
+        })?;
+
+        Ok(result.flatten().collect())
+    }
+
+    /// Replace all events and trips with newly clustered ones.
+    /// Returns false, and leaves the database untouched, if nothing has changed.
+    pub fn replace_all(&mut self, clusters: &[Cluster]) -> Result<bool> {
+        if self.clusters()? == clusters {
+            return Ok(false);
+        }
+
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+
+        tx.execute("DELETE FROM events_visuals", [])?;
+        tx.execute("DELETE FROM events", [])?;
+
+        for trip_or_event in clusters {
+            let event_id = Self::insert(&tx, trip_or_event, None)?;
+            for event in &trip_or_event.events {
+                Self::insert(&tx, event, Some(event_id))?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// Reconstruct stored events as clusters, so they can be compared with new clusters.
+    fn clusters(&self) -> Result<Vec<Cluster>> {
+        let events = self.all()?;
+
+        let mut trip_events: HashMap<EventId, Vec<Cluster>> = HashMap::new();
+        for event in events.iter().filter(|e| e.trip_id.is_some()) {
+            if let Some(trip_id) = event.trip_id {
+                trip_events
+                    .entry(trip_id)
+                    .or_default()
+                    .push(Self::to_cluster(event, vec![]));
+            }
+        }
+
+        let clusters = events
+            .iter()
+            .filter(|e| e.trip_id.is_none())
+            .map(|e| {
+                let events = trip_events.remove(&e.event_id).unwrap_or_default();
+                Self::to_cluster(e, events)
+            })
+            .collect();
+
+        Ok(clusters)
+    }
+
+    fn insert(
+        tx: &rusqlite::Transaction<'_>,
+        cluster: &Cluster,
+        trip_id: Option<EventId>,
+    ) -> Result<EventId> {
+        let mut insert_event = tx.prepare_cached(
+            "INSERT INTO events (
+                kind,
+                trip_id,
+                start_ts,
+                end_ts,
+                local_start_ts,
+                local_end_ts,
+                place_name
+            ) VALUES (
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7
+            )",
+        )?;
+
+        let mut insert_visual = tx.prepare_cached(
+            "INSERT INTO events_visuals (
+                event_id,
+                position,
+                visual_id
+            ) VALUES (
+                ?1, ?2, ?3
+            )",
+        )?;
+
+        insert_event.execute(params![
+            cluster.kind.as_str(),
+            trip_id.map(|id| id.id()),
+            cluster.start_ts,
+            cluster.end_ts,
+            cluster.local_start_ts,
+            cluster.local_end_ts,
+            cluster.place_name,
+        ])?;
+
+        let event_id = tx.last_insert_rowid();
+
+        for (position, visual_id) in cluster.visual_ids.iter().enumerate() {
+            insert_visual.execute(params![event_id, position, visual_id.id()])?;
+        }
+
+        Ok(EventId::new(event_id))
+    }
+
+    fn to_event(row: &Row<'_>) -> rusqlite::Result<Event> {
+        let kind: String = row.get("kind")?;
+        let kind = EventKind::parse(&kind).ok_or(rusqlite::Error::InvalidQuery)?;
+
+        Ok(Event {
+            event_id: row.get("event_id").map(EventId::new)?,
+            kind,
+            trip_id: row.get::<_, Option<i64>>("trip_id")?.map(EventId::new),
+            start_ts: row.get("start_ts")?,
+            end_ts: row.get("end_ts")?,
+            local_start_ts: row.get("local_start_ts")?,
+            local_end_ts: row.get("local_end_ts")?,
+            place_name: row.get("place_name")?,
+            visual_ids: vec![],
+        })
+    }
+
+    fn to_cluster(event: &Event, events: Vec<Cluster>) -> Cluster {
+        Cluster {
+            kind: event.kind,
+            start_ts: event.start_ts,
+            end_ts: event.end_ts,
+            local_start_ts: event.local_start_ts,
+            local_end_ts: event.local_end_ts,
+            place_name: event.place_name.clone(),
+            visual_ids: event.visual_ids.clone(),
+            events,
+        }
+    }
+}
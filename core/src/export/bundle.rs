@@ -0,0 +1,72 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use anyhow::*;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::result::Result::Ok;
+use zip::CompressionMethod;
+use zip::write::SimpleFileOptions;
+
+/// Bundle files into a ZIP archive. The archive is only moved to `zip_path`
+/// once it has been completely written.
+pub fn bundle(files: &[PathBuf], zip_path: &Path) -> Result<()> {
+    let tmp_path = zip_path.with_extension("zip.tmp");
+
+    let result = write(files, &tmp_path);
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+
+    std::fs::rename(&tmp_path, zip_path)?;
+    Ok(())
+}
+
+fn write(files: &[PathBuf], zip_path: &Path) -> Result<()> {
+    let mut archive = zip::ZipWriter::new(BufWriter::new(File::create(zip_path)?));
+
+    // Pictures and videos are already compressed.
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Stored)
+        .large_file(true);
+
+    for path in files {
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        archive.start_file(name.to_string_lossy(), options)?;
+        let mut file = File::open(path).with_context(|| format!("Opening {:?}", path))?;
+        std::io::copy(&mut file, &mut archive)?;
+    }
+
+    archive.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundle_contains_all_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.jpg");
+        let b = dir.path().join("b.mov");
+        std::fs::write(&a, b"picture").unwrap();
+        std::fs::write(&b, b"video").unwrap();
+
+        let zip_path = dir.path().join("export.zip");
+        bundle(&[a, b], &zip_path).unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&zip_path).unwrap()).unwrap();
+        assert_eq!(archive.len(), 2);
+
+        let mut contents = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name("b.mov").unwrap(), &mut contents)
+            .unwrap();
+        assert_eq!(contents, "video");
+    }
+}
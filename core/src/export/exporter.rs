@@ -0,0 +1,526 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Exports copies of pictures and videos out of the library, resized, converted,
+//! and with only the metadata that the user and the privacy policy allow.
+
+use super::bundle::bundle;
+use super::metadata;
+use super::model::{ExportOptions, MetadataPolicy, PictureFormat};
+use super::template;
+use crate::edits;
+use crate::files::ops;
+use crate::orientation;
+use crate::privacy::PrivacyPolicy;
+use crate::video;
+use crate::video::export::ExportFormat;
+use crate::visual::Visual;
+
+use anyhow::*;
+use h3o::LatLng;
+use image::codecs::avif::AvifEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, ImageFormat, RgbaImage};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::result::Result::Ok;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{error, info, warn};
+
+/// Speed of AVIF encoding, from 1 (slowest) to 10 (fastest).
+const AVIF_SPEED: u8 = 6;
+
+/// Formats that pictures can be re-encoded to while keeping their original format.
+/// Anything else, such as HEIC, is re-encoded as JPEG.
+const ENCODABLE_FORMATS: [ImageFormat; 5] = [
+    ImageFormat::Jpeg,
+    ImageFormat::Png,
+    ImageFormat::WebP,
+    ImageFormat::Tiff,
+    ImageFormat::Avif,
+];
+
+/// Files written by an export.
+#[derive(Debug, Default)]
+pub struct Exported {
+    /// Exported files, or the ZIP archive if they were bundled.
+    pub paths: Vec<PathBuf>,
+
+    /// Number of items that couldn't be exported.
+    pub failures: usize,
+}
+
+/// How a picture is exported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PicturePlan {
+    /// Copy the original file as it is.
+    Copy,
+
+    /// Decode, apply orientation and edits, resize, and encode in a format.
+    Render(ImageFormat),
+}
+
+/// Export items to `dir`. If the options ask for a ZIP archive, then the items are
+/// bundled into an archive named `archive_name` in `dir` instead.
+/// Items that fail are skipped and counted.
+pub async fn export_all(
+    visuals: &[Arc<Visual>],
+    options: &ExportOptions,
+    policy: &PrivacyPolicy,
+    dir: &Path,
+    archive_name: &str,
+    stop: &AtomicBool,
+) -> Result<Exported> {
+    if !options.zip {
+        return Ok(export_each(visuals, options, policy, dir, stop).await);
+    }
+
+    // Stage next to the archive, because exports can be too big for a temporary file system.
+    let staging = tempfile::Builder::new()
+        .prefix(".fotema-export")
+        .tempdir_in(dir)?;
+
+    let staged = export_each(visuals, options, policy, staging.path(), stop).await;
+    if staged.paths.is_empty() {
+        return Ok(staged);
+    }
+
+    let archive = Path::new("export.zip");
+    let stem = ops::free_stem(&[archive], dir, archive_name);
+    let zip_path = ops::renamed(archive, dir, &stem);
+    bundle(&staged.paths, &zip_path)?;
+
+    info!("Bundled {} files into {:?}", staged.paths.len(), zip_path);
+
+    Ok(Exported {
+        paths: vec![zip_path],
+        failures: staged.failures,
+    })
+}
+
+async fn export_each(
+    visuals: &[Arc<Visual>],
+    options: &ExportOptions,
+    policy: &PrivacyPolicy,
+    dir: &Path,
+    stop: &AtomicBool,
+) -> Exported {
+    let mut exported = Exported::default();
+
+    for (index, visual) in visuals.iter().enumerate() {
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let stem = template::render(&options.file_name_template, visual, index, visuals.len());
+        match export(visual, options, policy, dir, &stem, stop).await {
+            Ok(paths) => exported.paths.extend(paths),
+            Err(e) => {
+                error!("Failed exporting {}: {:?}", visual.visual_id, e);
+                exported.failures += 1;
+            }
+        }
+    }
+
+    exported
+}
+
+/// Export one item to `stem` in `dir`, without replacing existing files.
+/// A live photo exports both its picture and its video with the same name, so they
+/// stay paired. Returns the paths of the exported files.
+pub async fn export(
+    visual: &Visual,
+    options: &ExportOptions,
+    policy: &PrivacyPolicy,
+    dir: &Path,
+    stem: &str,
+    stop: &AtomicBool,
+) -> Result<Vec<PathBuf>> {
+    let location = match options.metadata {
+        MetadataPolicy::All => policy.export_location(visual.location),
+        MetadataPolicy::NoLocation | MetadataPolicy::None => None,
+    };
+
+    let keep_metadata = keeps_original(options, policy);
+
+    let picture = visual.picture_path.as_ref().map(|path| {
+        let source = path.sandbox_path.as_path();
+        (source, plan_picture(visual, source, options, keep_metadata))
+    });
+
+    let video = visual
+        .video_path
+        .as_ref()
+        .map(|path| path.sandbox_path.as_path());
+
+    // Only extensions matter when finding a free name.
+    let mut extensions: Vec<PathBuf> = vec![];
+    if let Some((source, plan)) = picture {
+        extensions.push(PathBuf::from(format!(
+            "export.{}",
+            picture_extension(source, plan)
+        )));
+    }
+    if let Some(source) = video {
+        extensions.push(PathBuf::from(format!(
+            "export.{}",
+            video_extension(source, keep_metadata)
+        )));
+    }
+
+    let targets: Vec<&Path> = extensions.iter().map(|path| path.as_path()).collect();
+    let stem = ops::free_stem(&targets, dir, stem);
+    let mut outputs = targets.iter().map(|path| ops::renamed(path, dir, &stem));
+
+    let mut written: Vec<PathBuf> = vec![];
+
+    if let Some((source, plan)) = picture {
+        let output = outputs.next().expect("Must have picture output");
+        export_picture(visual, source, plan, options, location, &output).await?;
+        written.push(output);
+    }
+
+    if let Some(source) = video {
+        let output = outputs.next().expect("Must have video output");
+        // Video locations can't be rewritten, so they are removed unless originals are kept.
+        let metadata = match options.metadata {
+            MetadataPolicy::All | MetadataPolicy::NoLocation => MetadataPolicy::NoLocation,
+            MetadataPolicy::None => MetadataPolicy::None,
+        };
+
+        let result = if keep_metadata {
+            ops::copy_file(source, &output)
+        } else {
+            video::export::export(source, &output, ExportFormat::Mp4, metadata, stop)
+        };
+
+        if let Err(e) = result {
+            // Don't leave half of a live photo behind.
+            for path in written {
+                let _ = std::fs::remove_file(path);
+            }
+            return Err(e);
+        }
+        written.push(output);
+    }
+
+    info!("Exported {} to {:?}", visual.visual_id, written);
+    Ok(written)
+}
+
+/// Can original files be copied as they are? Not when any location must be hidden,
+/// because the file may have a location that the library doesn't know about.
+fn keeps_original(options: &ExportOptions, policy: &PrivacyPolicy) -> bool {
+    options.metadata == MetadataPolicy::All && policy.keeps_original_location()
+}
+
+fn plan_picture(
+    visual: &Visual,
+    source: &Path,
+    options: &ExportOptions,
+    keep_metadata: bool,
+) -> PicturePlan {
+    let source_format = ImageFormat::from_path(source).ok();
+
+    let is_heic = source
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("heic") || ext.eq_ignore_ascii_case("heif"));
+
+    let format = match options.format {
+        PictureFormat::Jpeg => Some(ImageFormat::Jpeg),
+        PictureFormat::WebP => Some(ImageFormat::WebP),
+        PictureFormat::Avif => Some(ImageFormat::Avif),
+        PictureFormat::Original if is_heic && options.convert_heic => Some(ImageFormat::Jpeg),
+        PictureFormat::Original => None,
+    };
+
+    // Decoding keeps only the first frame, so animations are copied when their
+    // metadata can be kept. Otherwise a still is better than leaking a location.
+    if visual.is_animated && format.is_none() && keep_metadata {
+        return PicturePlan::Copy;
+    }
+
+    let is_same_format = format.is_none() || format == source_format;
+
+    let fits = options.max_dimension.is_none_or(|max| {
+        image::image_dimensions(source).is_ok_and(|(width, height)| width.max(height) <= max)
+    });
+
+    let is_original = !visual.is_edited() && visual.picture_orientation_override.is_none();
+
+    if is_same_format && fits && is_original && keep_metadata {
+        return PicturePlan::Copy;
+    }
+
+    let format = format
+        .or(source_format.filter(|format| ENCODABLE_FORMATS.contains(format)))
+        .unwrap_or(ImageFormat::Jpeg);
+
+    PicturePlan::Render(format)
+}
+
+fn picture_extension(source: &Path, plan: PicturePlan) -> String {
+    let source_extension = source
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_string());
+
+    match plan {
+        PicturePlan::Copy => source_extension.unwrap_or_default(),
+        PicturePlan::Render(format) if ImageFormat::from_path(source).ok() == Some(format) => {
+            source_extension.unwrap_or_default()
+        }
+        PicturePlan::Render(format) => format.extensions_str()[0].to_string(),
+    }
+}
+
+fn video_extension(source: &Path, keep_metadata: bool) -> String {
+    if keep_metadata {
+        source
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_string())
+            .unwrap_or_default()
+    } else {
+        // Videos without metadata are remuxed as MP4.
+        "mp4".into()
+    }
+}
+
+async fn export_picture(
+    visual: &Visual,
+    source: &Path,
+    plan: PicturePlan,
+    options: &ExportOptions,
+    location: Option<LatLng>,
+    output: &Path,
+) -> Result<()> {
+    let PicturePlan::Render(format) = plan else {
+        return ops::copy_file(source, output);
+    };
+
+    let image = load_upright(visual, source).await?;
+    let image = fit(image, options.max_dimension);
+
+    let tmp_path = output.with_extension(format!("tmp.{}", format.extensions_str()[0]));
+
+    let result = encode(image, format, options.quality, &tmp_path).and_then(|_| {
+        if options.metadata == MetadataPolicy::None {
+            return Ok(());
+        }
+
+        if !orientation::exif::is_writable(&tmp_path) {
+            warn!("Can't write metadata to {:?}", output);
+            return Ok(());
+        }
+
+        // Missing metadata is no reason to fail an export.
+        if let Err(e) = metadata::copy_exif(source, &tmp_path, location) {
+            warn!("Exporting {:?} without metadata: {:?}", output, e);
+        }
+        Ok(())
+    });
+
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+
+    std::fs::rename(&tmp_path, output)?;
+    Ok(())
+}
+
+/// Load a picture as it is shown in Fotema, with its orientation and edits applied.
+async fn load_upright(visual: &Visual, source: &Path) -> Result<RgbaImage> {
+    let orientation = visual.picture_orientation_override;
+
+    let file = gio::File::for_path(source);
+    let mut loader = glycin::Loader::new(file);
+
+    // An orientation chosen by the user replaces the EXIF orientation.
+    loader.apply_transformations(orientation.is_none());
+
+    let image = loader.load().await?;
+    let frame = image.next_frame().await?;
+
+    let image = edits::texture::to_image(&frame.texture())
+        .ok_or_else(|| anyhow!("Unexpected pixel buffer size for {:?}", source))?;
+
+    let image = match orientation {
+        Some(orientation) => orientation::transform(image, orientation),
+        None => image,
+    };
+
+    let image = match visual.picture_edits.as_ref() {
+        Some(edits) if !edits.is_empty() => edits::render(image, edits),
+        _ => image,
+    };
+
+    Ok(image)
+}
+
+/// Scale a picture down so its longest side is at most `max_dimension`.
+fn fit(image: RgbaImage, max_dimension: Option<u32>) -> RgbaImage {
+    let Some(max_dimension) = max_dimension else {
+        return image;
+    };
+
+    let (width, height) = image.dimensions();
+    let Some((width, height)) = fit_dimensions(width, height, max_dimension) else {
+        return image;
+    };
+
+    imageops::resize(&image, width, height, FilterType::Lanczos3)
+}
+
+/// Dimensions of a picture scaled to fit `max_dimension`, or None if it already fits.
+fn fit_dimensions(width: u32, height: u32, max_dimension: u32) -> Option<(u32, u32)> {
+    let longest = width.max(height);
+    if longest <= max_dimension || max_dimension == 0 {
+        return None;
+    }
+
+    let scale = f64::from(max_dimension) / f64::from(longest);
+    let scaled = |side: u32| ((f64::from(side) * scale).round() as u32).max(1);
+    Some((scaled(width), scaled(height)))
+}
+
+fn encode(image: RgbaImage, format: ImageFormat, quality: u8, path: &Path) -> Result<()> {
+    let quality = quality.clamp(1, 100);
+
+    match format {
+        ImageFormat::Jpeg => {
+            // JPEG has no alpha channel.
+            let image = DynamicImage::ImageRgba8(image).into_rgb8();
+            let file = BufWriter::new(File::create(path)?);
+            JpegEncoder::new_with_quality(file, quality).encode_image(&image)?;
+        }
+        ImageFormat::Avif => {
+            let file = BufWriter::new(File::create(path)?);
+            let encoder = AvifEncoder::new_with_speed_quality(file, AVIF_SPEED, quality);
+            DynamicImage::ImageRgba8(image).write_with_encoder(encoder)?;
+        }
+        format => {
+            DynamicImage::ImageRgba8(image).save_with_format(path, format)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FlatpakPathBuf;
+    use crate::privacy::ExportLocation;
+    use crate::visual::VisualId;
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use little_exif::exif_tag::ExifTag;
+    use little_exif::metadata::Metadata;
+    use little_exif::rational::uR64;
+
+    fn picture(path: &Path, is_animated: bool) -> Visual {
+        let local_ts = NaiveDate::from_ymd_opt(2024, 6, 30)
+            .unwrap()
+            .and_hms_opt(14, 5, 9)
+            .unwrap();
+        Visual {
+            visual_id: VisualId::new("1".into()),
+            parent_path: path.parent().unwrap().into(),
+            video_id: None,
+            video_path: None,
+            video_transcoded_path: None,
+            video_duration: None,
+            video_orientation: None,
+            video_orientation_override: None,
+            picture_id: None,
+            picture_path: Some(FlatpakPathBuf::build(path, path)),
+            picture_orientation: None,
+            picture_orientation_override: None,
+            picture_edits: None,
+            motion_photo_video_path: None,
+            is_animated,
+            animation_duration: None,
+            ordering_ts: Utc.from_utc_datetime(&local_ts),
+            local_ts,
+            is_selfie: None,
+            is_live_photo: false,
+            is_transcode_required: None,
+            location: None,
+            place: None,
+        }
+    }
+
+    #[test]
+    fn animations_are_copied_only_with_metadata() {
+        let source = Path::new("/library/party.webp");
+        let visual = picture(source, true);
+        let options = ExportOptions::default();
+
+        assert_eq!(
+            plan_picture(&visual, source, &options, true),
+            PicturePlan::Copy
+        );
+        assert_eq!(
+            plan_picture(&visual, source, &options, false),
+            PicturePlan::Render(ImageFormat::WebP)
+        );
+    }
+
+    #[test]
+    fn hidden_locations_are_removed_from_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("picture.jpg");
+        let rendered = dir.path().join("rendered.jpg");
+        for path in [&source, &rendered] {
+            image::RgbImage::new(4, 2)
+                .save_with_format(path, ImageFormat::Jpeg)
+                .unwrap();
+        }
+
+        // The file has a location, but the library doesn't know it.
+        let mut exif = Metadata::new();
+        exif.set_tag(ExifTag::GPSLatitudeRef("N".into()));
+        exif.set_tag(ExifTag::GPSLatitude(vec![
+            uR64 {
+                nominator: 53,
+                denominator: 1,
+            };
+            3
+        ]));
+        exif.write_to_file(&source).unwrap();
+
+        let visual = picture(&source, false);
+        let options = ExportOptions::default();
+
+        let keep = PrivacyPolicy::new(vec![], ExportLocation::Keep);
+        assert!(keeps_original(&options, &keep));
+        assert_eq!(
+            plan_picture(&visual, &source, &options, true),
+            PicturePlan::Copy
+        );
+
+        let strip = PrivacyPolicy::new(vec![], ExportLocation::Strip);
+        assert!(!keeps_original(&options, &strip));
+        assert_eq!(
+            plan_picture(&visual, &source, &options, false),
+            PicturePlan::Render(ImageFormat::Jpeg)
+        );
+
+        // Rendered pictures take their metadata from the file, without its location.
+        let location = strip.export_location(visual.location);
+        metadata::copy_exif(&source, &rendered, location).unwrap();
+        let exif = Metadata::new_from_path(&rendered).unwrap();
+        assert!(exif.get_tag(&ExifTag::GPSLatitude(vec![])).next().is_none());
+    }
+
+    #[test]
+    fn fit_dimensions_keeps_aspect_ratio() {
+        assert_eq!(fit_dimensions(4000, 3000, 2048), Some((2048, 1536)));
+        assert_eq!(fit_dimensions(3000, 4000, 1024), Some((768, 1024)));
+        assert_eq!(fit_dimensions(800, 600, 1024), None);
+        assert_eq!(fit_dimensions(4000, 1, 1024), Some((1024, 1)));
+    }
+}
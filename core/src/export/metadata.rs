@@ -0,0 +1,147 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Carries EXIF metadata over to exported pictures, which are encoded from pixels alone.
+
+use crate::orientation::Orientation;
+
+use anyhow::*;
+use h3o::LatLng;
+use little_exif::exif_tag::ExifTag;
+use little_exif::ifd::ExifTagGroup;
+use little_exif::metadata::Metadata;
+use little_exif::rational::uR64;
+use std::path::Path;
+use std::result::Result::Ok;
+
+/// Copy the EXIF metadata of a picture to an exported copy of it.
+/// The pixels of exported copies are upright, so the orientation is reset, and the
+/// location is replaced by `location`, which is the location allowed to leave the library.
+pub fn copy_exif(source: &Path, output: &Path, location: Option<LatLng>) -> Result<()> {
+    let mut metadata = Metadata::new_from_path(source)
+        .with_context(|| format!("Failed reading EXIF from {:?}", source))?;
+
+    metadata.set_tag(ExifTag::Orientation(vec![Orientation::North as u16]));
+
+    remove_location(&mut metadata);
+    if let Some(location) = location {
+        set_location(&mut metadata, location);
+    }
+
+    metadata
+        .write_to_file(output)
+        .with_context(|| format!("Failed writing EXIF to {:?}", output))?;
+
+    Ok(())
+}
+
+/// TIFF tag of an XMP packet, which can repeat the location of a picture.
+const XMP_PACKET: u16 = 0x02BC;
+
+fn remove_location(metadata: &mut Metadata) {
+    // Any tag of the GPS IFD, such as its time stamps or direction, helps to place a picture.
+    let gps_tags: Vec<u16> = metadata
+        .get_ifds()
+        .iter()
+        .filter(|ifd| ifd.get_ifd_type() == ExifTagGroup::GPS)
+        .flat_map(|ifd| ifd.get_tags().iter().map(|tag| tag.as_u16()))
+        .collect();
+
+    for tag in gps_tags {
+        metadata.remove_tag_by_hex_group(tag, ExifTagGroup::GPS);
+    }
+
+    metadata.remove_tag_by_hex_group(XMP_PACKET, ExifTagGroup::GENERIC);
+}
+
+fn set_location(metadata: &mut Metadata, location: LatLng) {
+    let latitude_ref = if location.lat() < 0.0 { "S" } else { "N" };
+    let longitude_ref = if location.lng() < 0.0 { "W" } else { "E" };
+
+    metadata.set_tag(ExifTag::GPSLatitudeRef(latitude_ref.into()));
+    metadata.set_tag(ExifTag::GPSLatitude(to_dms(location.lat()).to_vec()));
+    metadata.set_tag(ExifTag::GPSLongitudeRef(longitude_ref.into()));
+    metadata.set_tag(ExifTag::GPSLongitude(to_dms(location.lng()).to_vec()));
+}
+
+/// Degrees, minutes, and seconds, as EXIF stores coordinates.
+/// Seconds are kept to a hundredth, which is well under a metre.
+fn to_dms(degrees: f64) -> [uR64; 3] {
+    let hundredths = (degrees.abs() * 360_000.0).round() as u32;
+    let whole_degrees = hundredths / 360_000;
+    let minutes = (hundredths / 6_000) % 60;
+    let seconds = hundredths % 6_000;
+
+    [
+        uR64 {
+            nominator: whole_degrees,
+            denominator: 1,
+        },
+        uR64 {
+            nominator: minutes,
+            denominator: 1,
+        },
+        uR64 {
+            nominator: seconds,
+            denominator: 100,
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, RgbImage};
+
+    #[test]
+    fn to_dms_splits_degrees() {
+        let dms = to_dms(-6.2603);
+        assert_eq!((dms[0].nominator, dms[0].denominator), (6, 1));
+        assert_eq!((dms[1].nominator, dms[1].denominator), (15, 1));
+        assert_eq!((dms[2].nominator, dms[2].denominator), (3708, 100));
+    }
+
+    #[test]
+    fn copy_exif_removes_location() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source.jpg");
+        let output = dir.path().join("output.jpg");
+        for path in [&source, &output] {
+            RgbImage::new(4, 2)
+                .save_with_format(path, ImageFormat::Jpeg)
+                .unwrap();
+        }
+
+        let mut metadata = Metadata::new();
+        metadata.set_tag(ExifTag::Orientation(vec![Orientation::West as u16]));
+        set_location(&mut metadata, LatLng::new(53.3498, -6.2603).unwrap());
+        metadata.set_tag(ExifTag::GPSDateStamp("2026:10:19".into()));
+        metadata.set_tag(ExifTag::GPSImgDirection(vec![uR64 {
+            nominator: 90,
+            denominator: 1,
+        }]));
+        metadata.set_tag(ExifTag::UnknownUNDEF(
+            b"<x:xmpmeta/>".to_vec(),
+            XMP_PACKET,
+            ExifTagGroup::GENERIC,
+        ));
+        metadata.write_to_file(&source).unwrap();
+
+        copy_exif(&source, &output, None).unwrap();
+
+        let metadata = Metadata::new_from_path(&output).unwrap();
+        let tags: Vec<&ExifTag> = metadata
+            .get_ifds()
+            .iter()
+            .flat_map(|ifd| ifd.get_tags())
+            .collect();
+        assert!(
+            tags.iter()
+                .all(|tag| tag.get_group() != ExifTagGroup::GPS && tag.as_u16() != XMP_PACKET),
+            "{:?}",
+            tags
+        );
+        assert!(tags.contains(&&ExifTag::Orientation(vec![Orientation::North as u16])));
+    }
+}
@@ -0,0 +1,12 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+pub mod bundle;
+pub mod exporter;
+pub mod metadata;
+pub mod model;
+pub mod template;
+
+pub use exporter::{Exported, export_all};
+pub use model::{ExportOptions, MetadataPolicy, PictureFormat};
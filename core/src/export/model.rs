@@ -0,0 +1,113 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+/// Format to save exported pictures in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PictureFormat {
+    /// Keep the format of the original file.
+    #[default]
+    Original,
+
+    Jpeg,
+
+    /// Lossless WebP. The quality setting doesn't apply.
+    WebP,
+
+    Avif,
+}
+
+impl PictureFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PictureFormat::Original => "Original",
+            PictureFormat::Jpeg => "Jpeg",
+            PictureFormat::WebP => "WebP",
+            PictureFormat::Avif => "Avif",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "Original" => Some(PictureFormat::Original),
+            "Jpeg" => Some(PictureFormat::Jpeg),
+            "WebP" => Some(PictureFormat::WebP),
+            "Avif" => Some(PictureFormat::Avif),
+            _ => None,
+        }
+    }
+}
+
+/// Metadata to keep in exported pictures and videos.
+/// Locations are always subject to the privacy policy as well.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MetadataPolicy {
+    /// Keep all metadata.
+    #[default]
+    All,
+
+    /// Keep metadata except for the location.
+    NoLocation,
+
+    /// Remove all metadata.
+    None,
+}
+
+impl MetadataPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetadataPolicy::All => "All",
+            MetadataPolicy::NoLocation => "NoLocation",
+            MetadataPolicy::None => "None",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "All" => Some(MetadataPolicy::All),
+            "NoLocation" => Some(MetadataPolicy::NoLocation),
+            "None" => Some(MetadataPolicy::None),
+            _ => None,
+        }
+    }
+}
+
+/// How pictures and videos are exported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// Longest side of exported pictures in pixels, or None to keep their size.
+    pub max_dimension: Option<u32>,
+
+    /// Format of exported pictures.
+    pub format: PictureFormat,
+
+    /// Quality of JPEG and AVIF pictures, from 1 to 100.
+    pub quality: u8,
+
+    /// Convert HEIC pictures to JPEG when keeping the original format,
+    /// because many apps and services can't open HEIC.
+    pub convert_heic: bool,
+
+    /// Metadata to keep.
+    pub metadata: MetadataPolicy,
+
+    /// Names of exported files, as described in [`super::template`].
+    pub file_name_template: String,
+
+    /// Bundle exported files into one ZIP archive.
+    pub zip: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            max_dimension: None,
+            format: PictureFormat::Original,
+            quality: 90,
+            convert_heic: true,
+            metadata: MetadataPolicy::All,
+            file_name_template: super::template::DEFAULT_TEMPLATE.into(),
+            zip: false,
+        }
+    }
+}
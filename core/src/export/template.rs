@@ -0,0 +1,110 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Names of exported files are made from a template, such as `{date} {name}`.
+//!
+//! Placeholders are:
+//! * `{name}` - name of the original file, without extension.
+//! * `{date}` - day the item was taken, as `2024-06-30`.
+//! * `{time}` - time of day the item was taken, as `14-05-09`.
+//! * `{year}`, `{month}`, `{day}` - parts of the date.
+//! * `{n}` - position of the item in the export, starting from one.
+//!
+//! Anything else is kept as written.
+
+use crate::visual::Visual;
+
+/// Template that keeps the names of the original files.
+pub const DEFAULT_TEMPLATE: &str = "{name}";
+
+/// Name, without extension, of an exported item.
+/// `index` is the position of the item in the export, out of `count` items.
+pub fn render(template: &str, visual: &Visual, index: usize, count: usize) -> String {
+    let name = visual
+        .sandbox_path()
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    // Pad so that names sort in order.
+    let width = count.max(1).to_string().len();
+
+    let ts = visual.local_ts;
+    let stem = template
+        .replace("{name}", &name)
+        .replace("{date}", &ts.format("%Y-%m-%d").to_string())
+        .replace("{time}", &ts.format("%H-%M-%S").to_string())
+        .replace("{year}", &ts.format("%Y").to_string())
+        .replace("{month}", &ts.format("%m").to_string())
+        .replace("{day}", &ts.format("%d").to_string())
+        .replace("{n}", &format!("{:0width$}", index + 1));
+
+    // Names must not reach outside of the export folder.
+    let stem = stem.replace(['/', '\0'], "_");
+    let stem = stem.trim();
+
+    if stem.is_empty() || stem == "." || stem == ".." {
+        name
+    } else {
+        stem.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FlatpakPathBuf;
+    use crate::visual::VisualId;
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use std::path::PathBuf;
+
+    fn visual() -> Visual {
+        let local_ts = NaiveDate::from_ymd_opt(2024, 6, 30)
+            .unwrap()
+            .and_hms_opt(14, 5, 9)
+            .unwrap();
+        let path = PathBuf::from("/library/2024/IMG_0001.HEIC");
+        Visual {
+            visual_id: VisualId::new("1".into()),
+            parent_path: PathBuf::from("/library/2024"),
+            video_id: None,
+            video_path: None,
+            video_transcoded_path: None,
+            video_duration: None,
+            video_orientation: None,
+            video_orientation_override: None,
+            picture_id: None,
+            picture_path: Some(FlatpakPathBuf::build(&path, &path)),
+            picture_orientation: None,
+            picture_orientation_override: None,
+            picture_edits: None,
+            motion_photo_video_path: None,
+            is_animated: false,
+            animation_duration: None,
+            ordering_ts: Utc.from_utc_datetime(&local_ts),
+            local_ts,
+            is_selfie: None,
+            is_live_photo: false,
+            is_transcode_required: None,
+            location: None,
+            place: None,
+        }
+    }
+
+    #[test]
+    fn render_replaces_placeholders() {
+        let visual = visual();
+        assert_eq!(render(DEFAULT_TEMPLATE, &visual, 0, 1), "IMG_0001");
+        assert_eq!(
+            render("{date} {time} {name}", &visual, 0, 1),
+            "2024-06-30 14-05-09 IMG_0001"
+        );
+        assert_eq!(render("{year}/{month}/{day}", &visual, 0, 1), "2024_06_30");
+        assert_eq!(render("Trip {n}", &visual, 4, 120), "Trip 005");
+        assert_eq!(render("{unknown}", &visual, 0, 1), "{unknown}");
+
+        // Templates that render to an empty or unsafe name fall back to the file name.
+        assert_eq!(render(" .. ", &visual, 0, 1), "IMG_0001");
+    }
+}
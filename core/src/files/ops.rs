@@ -0,0 +1,249 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::repo::Repository;
+use crate::FlatpakPathBuf;
+use crate::visual::Visual;
+
+use anyhow::*;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::result::Result::Ok;
+use tracing::{error, info, warn};
+
+/// Files of an item that are in the library. A live photo has both a picture
+/// and a video file, which must be kept side by side with the same name.
+pub fn files_of(visual: &Visual) -> Vec<&Path> {
+    [visual.picture_path.as_ref(), visual.video_path.as_ref()]
+        .into_iter()
+        .flatten()
+        .map(|path| path.sandbox_path.as_path())
+        .collect()
+}
+
+/// Check that a new name for an item is a plain file name without an extension.
+pub fn validate_stem(stem: &str) -> Result<()> {
+    if stem.trim().is_empty() {
+        bail!("Name is empty");
+    }
+    if stem == "." || stem == ".." || stem.contains('/') || stem.contains('\0') {
+        bail!("Name is not a valid file name: {:?}", stem);
+    }
+    Ok(())
+}
+
+/// Path of a file after renaming it to `stem` in `dir`, keeping its extension.
+pub fn renamed(path: &Path, dir: &Path, stem: &str) -> PathBuf {
+    let mut file_name = OsString::from(stem);
+    if let Some(extension) = path.extension() {
+        file_name.push(".");
+        file_name.push(extension);
+    }
+    dir.join(file_name)
+}
+
+/// A name that none of the files would collide with in `dir`.
+/// Adds a counter, such as "IMG_0001 (2)", if `stem` is already taken.
+/// Files don't collide with themselves, so renaming a file to its own name is allowed.
+pub fn free_stem(files: &[&Path], dir: &Path, stem: &str) -> String {
+    let is_free = |stem: &str| {
+        files.iter().all(|path| {
+            let target = renamed(path, dir, stem);
+            target == *path || !target.exists()
+        })
+    };
+
+    if is_free(stem) {
+        return stem.to_string();
+    }
+
+    (2..)
+        .map(|n| format!("{} ({})", stem, n))
+        .find(|stem| is_free(stem))
+        .expect("Must find a free name")
+}
+
+/// Move a file without replacing an existing file.
+/// Falls back to copying and removing for moves across file systems.
+pub fn move_file(from: &Path, to: &Path) -> Result<()> {
+    if from == to {
+        return Ok(());
+    }
+    if to.exists() {
+        bail!("File already exists: {:?}", to);
+    }
+    if std::fs::rename(from, to).is_err() {
+        std::fs::copy(from, to).with_context(|| format!("Copying {:?} to {:?}", from, to))?;
+        std::fs::remove_file(from).with_context(|| format!("Removing {:?}", from))?;
+    }
+    Ok(())
+}
+
+/// Copy a file without replacing an existing file.
+pub fn copy_file(from: &Path, to: &Path) -> Result<()> {
+    if to.exists() {
+        bail!("File already exists: {:?}", to);
+    }
+    std::fs::copy(from, to).with_context(|| format!("Copying {:?} to {:?}", from, to))?;
+    Ok(())
+}
+
+/// Rename or move the files of an item to `stem` in `dir`, which must be in the library.
+/// The database rows are updated in place, so faces, motion photo extracts, and transcodes
+/// of the item are kept. Returns the item with its new paths.
+pub fn relocate(
+    repo: &mut Repository,
+    library_base_dir: &FlatpakPathBuf,
+    visual: &Visual,
+    dir: &Path,
+    stem: &str,
+) -> Result<Visual> {
+    validate_stem(stem)?;
+
+    let relative_dir = dir
+        .strip_prefix(&library_base_dir.sandbox_path)
+        .with_context(|| format!("{:?} is not in the library", dir))?;
+
+    let mut moved: Vec<(PathBuf, PathBuf)> = vec![];
+    for from in files_of(visual) {
+        let to = renamed(from, dir, stem);
+        if let Err(e) = move_file(from, &to) {
+            // Don't split up a live photo.
+            move_back(&moved);
+            return Err(e);
+        }
+        moved.push((from.to_path_buf(), to));
+    }
+
+    let build = |path: &FlatpakPathBuf| {
+        let relative_path = renamed(&path.sandbox_path, relative_dir, stem);
+        let moved_path = FlatpakPathBuf::build(
+            library_base_dir.host_path.join(&relative_path),
+            library_base_dir.sandbox_path.join(&relative_path),
+        );
+        (relative_path, moved_path)
+    };
+
+    let mut visual = visual.clone();
+
+    let result = (|| {
+        if let (Some(picture_id), Some(path)) = (visual.picture_id, visual.picture_path.as_ref()) {
+            let (relative_path, moved_path) = build(path);
+            repo.set_picture_path(picture_id, &relative_path)?;
+            visual.picture_path = Some(moved_path);
+        }
+
+        if let (Some(video_id), Some(path)) = (visual.video_id, visual.video_path.as_ref()) {
+            let (relative_path, moved_path) = build(path);
+            repo.set_video_path(video_id, &relative_path)?;
+            visual.video_path = Some(moved_path);
+        }
+        Ok::<(), Error>(())
+    })();
+
+    if let Err(e) = result {
+        // Files must stay where the database says they are.
+        move_back(&moved);
+        return Err(e);
+    }
+
+    visual.parent_path = library_base_dir.host_path.join(relative_dir);
+
+    info!("Moved {} to {:?}", visual.visual_id, visual.host_path());
+    Ok(visual)
+}
+
+/// Undo moves of files, most recent first.
+fn move_back(moved: &[(PathBuf, PathBuf)]) {
+    for (from, to) in moved.iter().rev() {
+        if let Err(e) = move_file(to, from) {
+            error!("Failed moving {:?} back to {:?}: {:?}", to, from, e);
+        }
+    }
+}
+
+/// Copy the files of an item to `stem` in `dir`.
+/// The copy is added to the library by the next library scan.
+pub fn copy(visual: &Visual, dir: &Path, stem: &str) -> Result<()> {
+    validate_stem(stem)?;
+
+    let mut copied: Vec<PathBuf> = vec![];
+    for from in files_of(visual) {
+        let to = renamed(from, dir, stem);
+        if let Err(e) = copy_file(from, &to) {
+            for path in copied.iter() {
+                if let Err(e) = std::fs::remove_file(path) {
+                    error!("Failed removing partial copy {:?}: {:?}", path, e);
+                }
+            }
+            return Err(e);
+        }
+        copied.push(to);
+    }
+    Ok(())
+}
+
+/// Move the files of an item to the trash of the desktop.
+/// Files that are already gone are skipped.
+pub fn trash(visual: &Visual) -> Result<()> {
+    for path in files_of(visual) {
+        if !path.exists() {
+            warn!("Not trashing missing file {:?}", path);
+            continue;
+        }
+        gio::File::for_path(path)
+            .trash(None::<&gio::Cancellable>)
+            .with_context(|| format!("Moving {:?} to trash", path))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_stem_rejects_paths() {
+        assert!(validate_stem("beach").is_ok());
+        assert!(validate_stem("beach.day").is_ok());
+        assert!(validate_stem("").is_err());
+        assert!(validate_stem("  ").is_err());
+        assert!(validate_stem("..").is_err());
+        assert!(validate_stem("../beach").is_err());
+    }
+
+    #[test]
+    fn free_stem_avoids_collisions_for_all_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let picture = dir.path().join("IMG_0001.HEIC");
+        let video = dir.path().join("IMG_0001.MOV");
+        std::fs::write(&picture, b"picture").unwrap();
+        std::fs::write(&video, b"video").unwrap();
+
+        // Only the video name of the live photo is taken.
+        std::fs::write(dir.path().join("beach.MOV"), b"other").unwrap();
+
+        let files = [picture.as_path(), video.as_path()];
+        assert_eq!(free_stem(&files, dir.path(), "IMG_0001"), "IMG_0001");
+        assert_eq!(free_stem(&files, dir.path(), "beach"), "beach (2)");
+        assert_eq!(free_stem(&files, dir.path(), "sunset"), "sunset");
+    }
+
+    #[test]
+    fn move_file_does_not_replace() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("a.jpg");
+        let to = dir.path().join("b.jpg");
+        std::fs::write(&from, b"a").unwrap();
+        std::fs::write(&to, b"b").unwrap();
+
+        assert!(move_file(&from, &to).is_err());
+        assert!(from.exists());
+
+        std::fs::remove_file(&to).unwrap();
+        move_file(&from, &to).unwrap();
+        assert!(!from.exists());
+        assert_eq!(std::fs::read(&to).unwrap(), b"a");
+    }
+}
@@ -0,0 +1,206 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::path_encoding;
+use crate::photo::PictureId;
+use crate::video::VideoId;
+
+use anyhow::*;
+use rusqlite::params;
+use std::path::{Path, PathBuf};
+use std::result::Result::Ok;
+use std::sync::{Arc, Mutex};
+
+/// Repository of changes to files made from within Fotema.
+/// Rows are updated in place so that faces, people, motion photo extracts,
+/// and transcodes of an item survive a rename or move.
+/// Repository is backed by a Sqlite database.
+#[derive(Debug, Clone)]
+pub struct Repository {
+    /// Connection to backing Sqlite database.
+    con: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl Repository {
+    pub fn open(con: Arc<Mutex<rusqlite::Connection>>) -> Result<Repository> {
+        Ok(Repository { con })
+    }
+
+    /// Set the path, relative to the library, of a picture that has been renamed or moved.
+    pub fn set_picture_path(&mut self, picture_id: PictureId, relative_path: &Path) -> Result<()> {
+        let link_path = link_path(relative_path)?;
+        let con = self.con.lock().unwrap();
+        con.execute(
+            "UPDATE pictures
+            SET
+                picture_path_b64 = ?2,
+                picture_path_lossy = ?3,
+                link_path_b64 = ?4,
+                link_path_lossy = ?5
+            WHERE picture_id = ?1",
+            params![
+                picture_id.id(),
+                path_encoding::to_base64(relative_path),
+                relative_path.to_string_lossy(),
+                path_encoding::to_base64(&link_path),
+                link_path.to_string_lossy(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Set the path, relative to the library, of a video that has been renamed or moved.
+    pub fn set_video_path(&mut self, video_id: VideoId, relative_path: &Path) -> Result<()> {
+        let link_path = link_path(relative_path)?;
+        let con = self.con.lock().unwrap();
+        con.execute(
+            "UPDATE videos
+            SET
+                video_path_b64 = ?2,
+                video_path_lossy = ?3,
+                link_path_b64 = ?4,
+                link_path_lossy = ?5
+            WHERE video_id = ?1",
+            params![
+                video_id.id(),
+                path_encoding::to_base64(relative_path),
+                relative_path.to_string_lossy(),
+                path_encoding::to_base64(&link_path),
+                link_path.to_string_lossy(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Hide an item that is being moved to the trash.
+    pub fn mark_trashed(
+        &mut self,
+        picture_id: Option<PictureId>,
+        video_id: Option<VideoId>,
+    ) -> Result<()> {
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+        if let Some(picture_id) = picture_id {
+            tx.execute(
+                "INSERT INTO pictures_trash (picture_id, trashed_ts)
+                VALUES (?1, CURRENT_TIMESTAMP)
+                ON CONFLICT (picture_id) DO NOTHING",
+                params![picture_id.id()],
+            )?;
+        }
+        if let Some(video_id) = video_id {
+            tx.execute(
+                "INSERT INTO videos_trash (video_id, trashed_ts)
+                VALUES (?1, CURRENT_TIMESTAMP)
+                ON CONFLICT (video_id) DO NOTHING",
+                params![video_id.id()],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Show an item again after moving it to the trash was undone,
+    /// or after its files could not be moved to the trash.
+    pub fn unmark_trashed(
+        &mut self,
+        picture_id: Option<PictureId>,
+        video_id: Option<VideoId>,
+    ) -> Result<()> {
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+        if let Some(picture_id) = picture_id {
+            tx.execute(
+                "DELETE FROM pictures_trash WHERE picture_id = ?1",
+                params![picture_id.id()],
+            )?;
+        }
+        if let Some(video_id) = video_id {
+            tx.execute(
+                "DELETE FROM videos_trash WHERE video_id = ?1",
+                params![video_id.id()],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Show all hidden items again.
+    /// Items are only left hidden if Fotema exited before moving their files to the trash,
+    /// in which case the move to the trash is undone.
+    pub fn unmark_all_trashed(&mut self) -> Result<usize> {
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+        let pictures = tx.execute("DELETE FROM pictures_trash", [])?;
+        let videos = tx.execute("DELETE FROM videos_trash", [])?;
+        tx.commit()?;
+        Ok(pictures + videos)
+    }
+}
+
+/// Path without suffix so sibling pictures and videos can be related.
+fn link_path(relative_path: &Path) -> Result<PathBuf> {
+    let Some(stem) = relative_path.file_stem() else {
+        bail!("No file name: {:?}", relative_path);
+    };
+    Ok(relative_path.with_file_name(stem))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{insert_picture, setup};
+
+    fn count_visuals(con: &Arc<Mutex<rusqlite::Connection>>) -> i64 {
+        let con = con.lock().unwrap();
+        con.query_row("SELECT COUNT(*) FROM visual", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn renamed_picture_keeps_id_and_gets_new_link_path() {
+        let con = setup();
+        let mut repo = Repository::open(con.clone()).unwrap();
+        let picture_id = insert_picture(&con, "2024/IMG_0001.JPG");
+
+        repo.set_picture_path(picture_id, Path::new("Trips/beach.JPG"))
+            .unwrap();
+
+        let con = con.lock().unwrap();
+        let (path_b64, link_path_b64): (String, String) = con
+            .query_row(
+                "SELECT picture_path_b64, link_path_b64 FROM pictures WHERE picture_id = ?1",
+                params![picture_id.id()],
+                |row| std::result::Result::Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+
+        assert_eq!(
+            path_encoding::from_base64(&path_b64).unwrap(),
+            PathBuf::from("Trips/beach.JPG")
+        );
+        assert_eq!(
+            path_encoding::from_base64(&link_path_b64).unwrap(),
+            PathBuf::from("Trips/beach")
+        );
+    }
+
+    #[test]
+    fn trashed_items_are_hidden_until_unmarked() {
+        let con = setup();
+        let mut repo = Repository::open(con.clone()).unwrap();
+        let picture_id = insert_picture(&con, "IMG_0001.JPG");
+        insert_picture(&con, "IMG_0002.JPG");
+
+        repo.mark_trashed(Some(picture_id), None).unwrap();
+        assert_eq!(count_visuals(&con), 1);
+
+        repo.unmark_trashed(Some(picture_id), None).unwrap();
+        assert_eq!(count_visuals(&con), 2);
+
+        repo.mark_trashed(Some(picture_id), None).unwrap();
+        assert_eq!(repo.unmark_all_trashed().unwrap(), 1);
+        assert_eq!(count_visuals(&con), 2);
+    }
+}
@@ -0,0 +1,113 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::track::TrackPoint;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use h3o::LatLng;
+
+/// Matches capture timestamps to locations on a GPS track log.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Correlator {
+    /// Maximum gap between a capture timestamp and a track point for them to match.
+    pub tolerance: TimeDelta,
+
+    /// Difference between the GPS clock and the camera clock. Added to capture
+    /// timestamps before matching, so a camera running two minutes slow
+    /// has an offset of +2 minutes.
+    pub clock_offset: TimeDelta,
+}
+
+impl Default for Correlator {
+    fn default() -> Self {
+        Self {
+            tolerance: TimeDelta::minutes(5),
+            clock_offset: TimeDelta::zero(),
+        }
+    }
+}
+
+impl Correlator {
+    /// Find the location for a capture timestamp. Track points must be ordered by timestamp.
+    /// If there are track points within tolerance either side of the timestamp, then the
+    /// location is interpolated between them. Otherwise the nearest point within tolerance is used.
+    pub fn locate(&self, track: &[TrackPoint], ts: DateTime<Utc>) -> Option<LatLng> {
+        let ts = ts + self.clock_offset;
+
+        let index = track.partition_point(|p| p.ts < ts);
+        let before = index.checked_sub(1).and_then(|i| track.get(i));
+        let after = track.get(index);
+
+        let within = |p: &&TrackPoint| (p.ts - ts).abs() <= self.tolerance;
+        let before = before.filter(within);
+        let after = after.filter(within);
+
+        match (before, after) {
+            (Some(before), Some(after)) => Some(interpolate(before, after, ts)),
+            (Some(before), None) => Some(before.location),
+            (None, Some(after)) => Some(after.location),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Linear interpolation between two track points. Track points are usually only
+/// seconds apart so there is no need to follow a great circle.
+fn interpolate(before: &TrackPoint, after: &TrackPoint, ts: DateTime<Utc>) -> LatLng {
+    let span = (after.ts - before.ts).num_milliseconds();
+    if span <= 0 {
+        return after.location;
+    }
+
+    let fraction = (ts - before.ts).num_milliseconds() as f64 / span as f64;
+    let lat = before.location.lat() + (after.location.lat() - before.location.lat()) * fraction;
+    let lng = before.location.lng() + (after.location.lng() - before.location.lng()) * fraction;
+
+    LatLng::new(lat, lng).unwrap_or(before.location)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn point(minute: u32, lat: f64, lng: f64) -> TrackPoint {
+        TrackPoint {
+            ts: Utc.with_ymd_and_hms(2025, 6, 1, 10, minute, 0).unwrap(),
+            location: LatLng::new(lat, lng).unwrap(),
+        }
+    }
+
+    fn ts(minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 6, 1, 10, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn interpolates_between_points() {
+        let track = vec![point(0, 50.0, 10.0), point(4, 52.0, 12.0)];
+        let correlator = Correlator::default();
+
+        let location = correlator.locate(&track, ts(1)).unwrap();
+
+        assert!((location.lat() - 50.5).abs() < 1e-9);
+        assert!((location.lng() - 10.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn uses_nearest_point_within_tolerance() {
+        let track = vec![point(0, 50.0, 10.0), point(30, 52.0, 12.0)];
+        let correlator = Correlator::default();
+
+        assert_eq!(correlator.locate(&track, ts(3)), Some(track[0].location));
+        assert_eq!(correlator.locate(&track, ts(15)), None);
+        assert_eq!(correlator.locate(&track, ts(40)), None);
+
+        // A camera clock running behind the track is corrected by the offset.
+        let correlator = Correlator {
+            clock_offset: TimeDelta::minutes(28),
+            ..Correlator::default()
+        };
+        assert_eq!(correlator.locate(&track, ts(0)), Some(track[1].location));
+    }
+}
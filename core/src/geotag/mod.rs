@@ -0,0 +1,13 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+pub mod correlate;
+pub mod model;
+pub mod repo;
+pub mod track;
+
+pub use correlate::Correlator;
+pub use model::GeoSource;
+pub use repo::Repository;
+pub use track::TrackPoint;
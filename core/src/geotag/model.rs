@@ -0,0 +1,26 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+/// Where a location for a picture or video came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeoSource {
+    /// Embedded EXIF or video container metadata.
+    Embedded,
+
+    /// Matched to a GPS track log by timestamp.
+    Track,
+
+    /// Pinned to a map by the user.
+    Manual,
+}
+
+impl GeoSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GeoSource::Embedded => "embedded",
+            GeoSource::Track => "track",
+            GeoSource::Manual => "manual",
+        }
+    }
+}
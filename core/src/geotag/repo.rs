@@ -0,0 +1,124 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::model::GeoSource;
+use crate::photo::PictureId;
+use crate::places::GeoTarget;
+use crate::video::VideoId;
+
+use anyhow::*;
+use chrono::{DateTime, Utc};
+use h3o::LatLng;
+use rusqlite::params;
+use std::result::Result::Ok;
+use std::sync::{Arc, Mutex};
+
+/// Repository for adding locations to pictures and videos that don't have one
+/// embedded in their metadata.
+/// Repository is backed by a Sqlite database.
+#[derive(Debug, Clone)]
+pub struct Repository {
+    /// Connection to backing Sqlite database.
+    con: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl Repository {
+    pub fn open(con: Arc<Mutex<rusqlite::Connection>>) -> Result<Repository> {
+        Ok(Repository { con })
+    }
+
+    /// Find pictures and videos without a location, along with the timestamp
+    /// to match against a track log.
+    /// Live photos are returned as the picture part.
+    pub fn find_without_location(&self) -> Result<Vec<(GeoTarget, DateTime<Utc>)>> {
+        let con = self.con.lock().unwrap();
+        let mut stmt = con.prepare(
+            "SELECT picture_id, video_id, ordering_ts
+            FROM visual
+            WHERE latitude IS NULL
+            ORDER BY ordering_ts ASC",
+        )?;
+
+        let result = stmt.query_map([], |row| {
+            let picture_id: Option<i64> = row.get("picture_id")?;
+            let video_id: Option<i64> = row.get("video_id")?;
+            let ordering_ts: DateTime<Utc> = row.get("ordering_ts")?;
+
+            let target = match (picture_id, video_id) {
+                (Some(id), _) => GeoTarget::Picture(PictureId::new(id)),
+                (None, Some(id)) => GeoTarget::Video(VideoId::new(id)),
+                (None, None) => return Err(rusqlite::Error::InvalidQuery),
+            };
+
+            Ok((target, ordering_ts))
+        })?;
+
+        Ok(result.flatten().collect())
+    }
+
+    /// Save locations that didn't come from embedded metadata.
+    /// Locations are reverse geocoded again on the next geocoding pass.
+    pub fn add_locations(
+        &mut self,
+        locations: Vec<(GeoTarget, LatLng)>,
+        source: GeoSource,
+    ) -> Result<()> {
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+
+        {
+            let mut upsert_picture = tx.prepare_cached(
+                "INSERT INTO pictures_geo (
+                    picture_id,
+                    latitude,
+                    longitude,
+                    source
+                ) VALUES (
+                    ?1, ?2, ?3, ?4
+                ) ON CONFLICT (picture_id) DO UPDATE SET
+                    latitude = ?2,
+                    longitude = ?3,
+                    source = ?4,
+                    geocoder_version = 0
+                ",
+            )?;
+
+            let mut upsert_video = tx.prepare_cached(
+                "INSERT INTO videos_geo (
+                    video_id,
+                    latitude,
+                    longitude,
+                    source
+                ) VALUES (
+                    ?1, ?2, ?3, ?4
+                ) ON CONFLICT (video_id) DO UPDATE SET
+                    latitude = ?2,
+                    longitude = ?3,
+                    source = ?4,
+                    geocoder_version = 0
+                ",
+            )?;
+
+            for (target, location) in locations {
+                match target {
+                    GeoTarget::Picture(id) => upsert_picture.execute(params![
+                        id.id(),
+                        location.lat(),
+                        location.lng(),
+                        source.as_str(),
+                    ])?,
+                    GeoTarget::Video(id) => upsert_video.execute(params![
+                        id.id(),
+                        location.lat(),
+                        location.lng(),
+                        source.as_str(),
+                    ])?,
+                };
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,344 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use anyhow::*;
+use chrono::{DateTime, Utc};
+use h3o::LatLng;
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use serde_json::Value;
+use std::path::Path;
+use std::result::Result::Ok;
+
+/// A timestamped location from a GPS track log.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackPoint {
+    pub ts: DateTime<Utc>,
+    pub location: LatLng,
+}
+
+impl TrackPoint {
+    fn build(ts: &str, latitude: f64, longitude: f64) -> Option<Self> {
+        let ts = DateTime::parse_from_rfc3339(ts.trim()).ok()?.to_utc();
+        let location = LatLng::new(latitude, longitude).ok()?;
+        Some(Self { ts, location })
+    }
+}
+
+/// Load track points from a GPX, KML, or GeoJSON file.
+/// Points are ordered by timestamp.
+pub fn load(path: &Path) -> Result<Vec<TrackPoint>> {
+    let extension = path
+        .extension()
+        .and_then(|x| x.to_str())
+        .map(|x| x.to_lowercase());
+
+    let data = std::fs::read_to_string(path)?;
+
+    let mut points = match extension.as_deref() {
+        Some("gpx") => parse_gpx(&data)?,
+        Some("kml") => parse_kml(&data)?,
+        Some("geojson") | Some("json") => parse_geojson(&data)?,
+        _ => bail!("Unsupported track log format: {:?}", path),
+    };
+
+    points.sort_by_key(|p| p.ts);
+    points.dedup_by_key(|p| p.ts);
+    Ok(points)
+}
+
+/// Parse track points, route points, and waypoints with timestamps from GPX.
+pub fn parse_gpx(data: &str) -> Result<Vec<TrackPoint>> {
+    let mut reader = Reader::from_str(data);
+    reader.config_mut().trim_text(true);
+
+    let mut points = Vec::new();
+
+    // Location of point currently being read
+    let mut location: Option<(f64, f64)> = None;
+    let mut ts: Option<String> = None;
+    let mut in_time = false;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"trkpt" | b"rtept" | b"wpt" => {
+                    let lat = e.try_get_attribute("lat")?;
+                    let lon = e.try_get_attribute("lon")?;
+                    location = match (lat, lon) {
+                        (Some(lat), Some(lon)) => {
+                            let lat = lat.unescape_value()?.parse::<f64>().ok();
+                            let lon = lon.unescape_value()?.parse::<f64>().ok();
+                            lat.zip(lon)
+                        }
+                        _ => None,
+                    };
+                    ts = None;
+                }
+                b"time" => in_time = location.is_some(),
+                _ => {}
+            },
+            Event::Text(t) if in_time => {
+                ts = Some(t.unescape()?.to_string());
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"trkpt" | b"rtept" | b"wpt" => {
+                    if let (Some((lat, lon)), Some(ts)) = (location.take(), ts.take()) {
+                        points.extend(TrackPoint::build(&ts, lat, lon));
+                    }
+                }
+                b"time" => in_time = false,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(points)
+}
+
+/// Parse points from KML. Supports gx:Track elements, as exported by Google Location History
+/// and many GPS loggers, and timestamped point placemarks.
+pub fn parse_kml(data: &str) -> Result<Vec<TrackPoint>> {
+    #[derive(PartialEq)]
+    enum Text {
+        None,
+        When,
+        Coord,
+        Coordinates,
+    }
+
+    let mut reader = Reader::from_str(data);
+    reader.config_mut().trim_text(true);
+
+    let mut points = Vec::new();
+
+    let mut text = Text::None;
+    let mut in_track = false;
+
+    // gx:Track has a list of timestamps followed by a list of coordinates.
+    let mut track_whens: Vec<String> = Vec::new();
+    let mut track_coords: Vec<(f64, f64)> = Vec::new();
+
+    // Placemark with a TimeStamp and a Point.
+    let mut placemark_when: Option<String> = None;
+    let mut placemark_coord: Option<(f64, f64)> = None;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"Track" => {
+                    in_track = true;
+                    track_whens.clear();
+                    track_coords.clear();
+                }
+                b"Placemark" => {
+                    placemark_when = None;
+                    placemark_coord = None;
+                }
+                b"when" => text = Text::When,
+                b"coord" => text = Text::Coord,
+                b"coordinates" => text = Text::Coordinates,
+                _ => {}
+            },
+            Event::Text(t) if text != Text::None => {
+                let value = t.unescape()?;
+                match text {
+                    Text::When if in_track => track_whens.push(value.to_string()),
+                    Text::When => placemark_when = Some(value.to_string()),
+                    Text::Coord => {
+                        // gx:coord is "longitude latitude altitude"
+                        let mut parts = value.split_whitespace().map(|x| x.parse::<f64>().ok());
+                        if let (Some(Some(lon)), Some(Some(lat))) = (parts.next(), parts.next()) {
+                            track_coords.push((lat, lon));
+                        }
+                    }
+                    Text::Coordinates => {
+                        // coordinates is "longitude,latitude,altitude", with more than one
+                        // tuple for lines and polygons, which we don't want.
+                        let mut tuples = value.split_whitespace();
+                        if let (Some(tuple), None) = (tuples.next(), tuples.next()) {
+                            let mut parts = tuple.split(',').map(|x| x.parse::<f64>().ok());
+                            if let (Some(Some(lon)), Some(Some(lat))) = (parts.next(), parts.next())
+                            {
+                                placemark_coord = Some((lat, lon));
+                            }
+                        }
+                    }
+                    Text::None => {}
+                }
+            }
+            Event::End(e) => {
+                match e.local_name().as_ref() {
+                    b"Track" => {
+                        in_track = false;
+                        for (when, (lat, lon)) in track_whens.iter().zip(track_coords.iter()) {
+                            points.extend(TrackPoint::build(when, *lat, *lon));
+                        }
+                    }
+                    b"Placemark" => {
+                        if let (Some(when), Some((lat, lon))) =
+                            (placemark_when.take(), placemark_coord.take())
+                        {
+                            points.extend(TrackPoint::build(&when, lat, lon));
+                        }
+                    }
+                    _ => {}
+                }
+                text = Text::None;
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(points)
+}
+
+/// Parse points from GeoJSON. Supports line strings with a "coordTimes" property,
+/// as produced by togeojson, and points with a "time" or "timestamp" property.
+pub fn parse_geojson(data: &str) -> Result<Vec<TrackPoint>> {
+    let root: Value = serde_json::from_str(data)?;
+
+    let features: Vec<&Value> = match root["type"].as_str() {
+        Some("FeatureCollection") => root["features"]
+            .as_array()
+            .map(|x| x.iter().collect())
+            .unwrap_or_default(),
+        Some("Feature") => vec![&root],
+        _ => bail!("GeoJSON must be a Feature or FeatureCollection"),
+    };
+
+    let mut points = Vec::new();
+
+    for feature in features {
+        let geometry = &feature["geometry"];
+        let properties = &feature["properties"];
+
+        match geometry["type"].as_str() {
+            Some("Point") => {
+                let when = properties["time"]
+                    .as_str()
+                    .or_else(|| properties["timestamp"].as_str());
+                if let (Some(when), Some((lat, lon))) =
+                    (when, geojson_position(&geometry["coordinates"]))
+                {
+                    points.extend(TrackPoint::build(when, lat, lon));
+                }
+            }
+            Some("LineString") => {
+                geojson_line(
+                    &geometry["coordinates"],
+                    &properties["coordTimes"],
+                    &mut points,
+                );
+            }
+            Some("MultiLineString") => {
+                let lines = geometry["coordinates"].as_array();
+                let times = properties["coordTimes"].as_array();
+                if let (Some(lines), Some(times)) = (lines, times) {
+                    for (line, times) in lines.iter().zip(times.iter()) {
+                        geojson_line(line, times, &mut points);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(points)
+}
+
+fn geojson_line(coordinates: &Value, times: &Value, points: &mut Vec<TrackPoint>) {
+    let (Some(coordinates), Some(times)) = (coordinates.as_array(), times.as_array()) else {
+        return;
+    };
+
+    for (position, when) in coordinates.iter().zip(times.iter()) {
+        if let (Some((lat, lon)), Some(when)) = (geojson_position(position), when.as_str()) {
+            points.extend(TrackPoint::build(when, lat, lon));
+        }
+    }
+}
+
+/// GeoJSON positions are [longitude, latitude, altitude].
+fn geojson_position(position: &Value) -> Option<(f64, f64)> {
+    let lon = position.get(0)?.as_f64()?;
+    let lat = position.get(1)?.as_f64()?;
+    Some((lat, lon))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_gpx_track() {
+        let data = r#"<?xml version="1.0"?>
+            <gpx version="1.1" xmlns="http://www.topografix.com/GPX/1/1">
+              <trk><trkseg>
+                <trkpt lat="53.35" lon="-6.26"><ele>10</ele><time>2025-06-01T10:00:00Z</time></trkpt>
+                <trkpt lat="53.36" lon="-6.27"><time>2025-06-01T10:01:00+01:00</time></trkpt>
+                <trkpt lat="53.37" lon="-6.28"></trkpt>
+              </trkseg></trk>
+            </gpx>"#;
+
+        let points = parse_gpx(data).unwrap();
+
+        assert_eq!(points.len(), 2);
+        assert!((points[0].location.lat() - 53.35).abs() < 1e-9);
+        assert_eq!(points[1].ts.to_rfc3339(), "2025-06-01T09:01:00+00:00");
+    }
+
+    #[test]
+    fn parses_kml_track_and_placemark() {
+        let data = r#"<?xml version="1.0"?>
+            <kml xmlns="http://www.opengis.net/kml/2.2" xmlns:gx="http://www.google.com/kml/ext/2.2">
+              <Document>
+                <Placemark>
+                  <gx:Track>
+                    <when>2025-06-01T10:00:00Z</when>
+                    <when>2025-06-01T10:01:00Z</when>
+                    <gx:coord>-6.26 53.35 10</gx:coord>
+                    <gx:coord>-6.27 53.36 10</gx:coord>
+                  </gx:Track>
+                </Placemark>
+                <Placemark>
+                  <TimeStamp><when>2025-06-01T11:00:00Z</when></TimeStamp>
+                  <Point><coordinates>2.35,48.86,0</coordinates></Point>
+                </Placemark>
+              </Document>
+            </kml>"#;
+
+        let points = parse_kml(data).unwrap();
+
+        assert_eq!(points.len(), 3);
+        assert!((points[1].location.lng() - -6.27).abs() < 1e-9);
+        assert!((points[2].location.lat() - 48.86).abs() < 1e-9);
+    }
+
+    #[test]
+    fn parses_geojson_line_string() {
+        let data = r#"{
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "properties": {
+                    "coordTimes": ["2025-06-01T10:00:00Z", "2025-06-01T10:01:00Z"]
+                },
+                "geometry": {
+                    "type": "LineString",
+                    "coordinates": [[-6.26, 53.35, 10], [-6.27, 53.36, 10]]
+                }
+            }]
+        }"#;
+
+        let points = parse_geojson(data).unwrap();
+
+        assert_eq!(points.len(), 2);
+        assert!((points[0].location.lat() - 53.35).abs() < 1e-9);
+        assert!((points[0].location.lng() - -6.26).abs() < 1e-9);
+    }
+}
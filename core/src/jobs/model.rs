@@ -0,0 +1,124 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use chrono::TimeDelta;
+use std::fmt::Display;
+
+/// Number of failed attempts after which a job is given up on.
+pub const MAX_ATTEMPTS: u32 = 5;
+
+/// Database ID of a job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JobId(i64);
+
+impl JobId {
+    pub fn new(id: i64) -> Self {
+        Self(id)
+    }
+
+    pub fn id(&self) -> i64 {
+        self.0
+    }
+}
+
+impl Display for JobId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Type of background work. Determines what the target ID of a job refers to.
+/// Thumbnail jobs are queued by the database when pictures and videos are added.
+/// Enrichment, motion photos, faces, and transcodes still find their work with
+/// `find_need_*` queries and are yet to move onto the queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JobKind {
+    /// Generate thumbnails for a picture_id.
+    PhotoThumbnail,
+
+    /// Generate thumbnail and storyboard for a video_id.
+    VideoThumbnail,
+}
+
+impl JobKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobKind::PhotoThumbnail => "PhotoThumbnail",
+            JobKind::VideoThumbnail => "VideoThumbnail",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<JobKind> {
+        match value {
+            "PhotoThumbnail" => Some(JobKind::PhotoThumbnail),
+            "VideoThumbnail" => Some(JobKind::VideoThumbnail),
+            _ => None,
+        }
+    }
+}
+
+impl Display for JobKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Order in which queued jobs are claimed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    /// Work discovered by a library scan.
+    Normal,
+
+    /// Work for an item the user can currently see.
+    Visible,
+}
+
+impl Priority {
+    pub fn value(&self) -> i64 {
+        match self {
+            Priority::Normal => 0,
+            Priority::Visible => 100,
+        }
+    }
+}
+
+/// A unit of background work for a single picture or video.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Job {
+    pub job_id: JobId,
+
+    pub kind: JobKind,
+
+    /// ID of picture or video, depending on kind.
+    pub target_id: i64,
+
+    pub priority: i64,
+
+    /// Number of previously failed attempts.
+    pub attempts: u32,
+
+    /// Error from the most recent failed attempt.
+    pub last_error: Option<String>,
+}
+
+/// How long to wait before retrying a job that has failed a number of times.
+/// Doubles for each attempt, starting from one minute and capped at one day.
+pub fn backoff(attempts: u32) -> TimeDelta {
+    let minutes = 1_i64 << attempts.saturating_sub(1).min(16);
+    TimeDelta::minutes(minutes).min(TimeDelta::days(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_a_day() {
+        assert_eq!(backoff(1), TimeDelta::minutes(1));
+        assert_eq!(backoff(2), TimeDelta::minutes(2));
+        assert_eq!(backoff(4), TimeDelta::minutes(8));
+        assert_eq!(backoff(20), TimeDelta::days(1));
+        assert_eq!(backoff(u32::MAX), TimeDelta::days(1));
+    }
+}
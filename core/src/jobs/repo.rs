@@ -0,0 +1,319 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::model::{Job, JobId, JobKind, MAX_ATTEMPTS, Priority, backoff};
+
+use anyhow::*;
+use rusqlite::Row;
+use rusqlite::Transaction;
+use rusqlite::params;
+use std::result::Result::Ok;
+use std::sync::{Arc, Mutex};
+
+const QUEUED: &str = "Queued";
+const RUNNING: &str = "Running";
+const FAILED: &str = "Failed";
+
+/// Persistent queue of background jobs.
+/// Repository is backed by a Sqlite database.
+#[derive(Debug, Clone)]
+pub struct Repository {
+    /// Connection to backing Sqlite database.
+    con: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl Repository {
+    pub fn open(con: Arc<Mutex<rusqlite::Connection>>) -> Result<Repository> {
+        Ok(Repository { con })
+    }
+
+    /// Queue jobs for targets. Targets that already have a job of the same kind,
+    /// including failed jobs, are left alone.
+    pub fn enqueue(&mut self, kind: JobKind, target_ids: &[i64], priority: Priority) -> Result<()> {
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT OR IGNORE INTO jobs (kind, target_id, priority)
+                VALUES (?1, ?2, ?3)",
+            )?;
+
+            for target_id in target_ids {
+                stmt.execute(params![kind.as_str(), target_id, priority.value()])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Claim up to `limit` jobs that are ready to run, highest priority first,
+    /// then in the order they were queued.
+    pub fn claim(&mut self, kind: JobKind, limit: usize) -> Result<Vec<Job>> {
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+
+        let jobs: Vec<Job> = {
+            let mut stmt = tx.prepare_cached(
+                "SELECT
+                    job_id,
+                    kind,
+                    target_id,
+                    priority,
+                    attempts,
+                    last_error
+                FROM jobs
+                WHERE kind = ?1
+                AND state = ?2
+                AND (run_after_ts IS NULL OR run_after_ts <= CURRENT_TIMESTAMP)
+                ORDER BY priority DESC, job_id ASC
+                LIMIT ?3",
+            )?;
+
+            let result = stmt.query_map(params![kind.as_str(), QUEUED, limit as i64], |row| {
+                Self::to_job(row)
+            })?;
+            result.flatten().collect()
+        };
+
+        {
+            let mut stmt = tx.prepare_cached(
+                "UPDATE jobs
+                SET
+                    state = ?2,
+                    claimed_ts = CURRENT_TIMESTAMP
+                WHERE job_id = ?1",
+            )?;
+
+            for job in &jobs {
+                stmt.execute(params![job.job_id.id(), RUNNING])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(jobs)
+    }
+
+    /// Job finished successfully, so remove it from the queue.
+    pub fn complete(&mut self, job_id: JobId) -> Result<()> {
+        let con = self.con.lock().unwrap();
+        con.execute("DELETE FROM jobs WHERE job_id = ?1", params![job_id.id()])?;
+        Ok(())
+    }
+
+    /// Job failed. Schedules a retry after a backoff delay, or gives up if the job
+    /// has run out of attempts. Returns true if the job has been given up on.
+    pub fn fail(&mut self, job: &Job, error: &str) -> Result<bool> {
+        let attempts = job.attempts + 1;
+        let is_exhausted = attempts >= MAX_ATTEMPTS;
+        let state = if is_exhausted { FAILED } else { QUEUED };
+        let delay_secs = backoff(attempts).num_seconds();
+
+        let con = self.con.lock().unwrap();
+        con.execute(
+            "UPDATE jobs
+            SET
+                state = ?2,
+                attempts = ?3,
+                last_error = ?4,
+                run_after_ts = datetime('now', printf('+%d seconds', ?5)),
+                claimed_ts = NULL
+            WHERE job_id = ?1",
+            params![job.job_id.id(), state, attempts, error, delay_secs],
+        )?;
+
+        Ok(is_exhausted)
+    }
+
+    /// Return jobs left running by an interrupted run to the queue.
+    pub fn recover(&mut self, kind: JobKind) -> Result<usize> {
+        let con = self.con.lock().unwrap();
+        let count = con.execute(
+            "UPDATE jobs
+            SET
+                state = ?2,
+                claimed_ts = NULL
+            WHERE kind = ?1
+            AND state = ?3",
+            params![kind.as_str(), QUEUED, RUNNING],
+        )?;
+        Ok(count)
+    }
+
+    /// Raise the priority of a queued job, or queue one if the target has none.
+    /// Running and failed jobs are left alone.
+    pub fn prioritise(&mut self, kind: JobKind, target_id: i64, priority: Priority) -> Result<()> {
+        let con = self.con.lock().unwrap();
+        con.execute(
+            "INSERT INTO jobs (kind, target_id, priority)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT (kind, target_id) DO UPDATE
+            SET priority = ?3
+            WHERE state = ?4
+            AND priority < ?3",
+            params![kind.as_str(), target_id, priority.value(), QUEUED],
+        )?;
+        Ok(())
+    }
+
+    /// Number of jobs that could be claimed right now.
+    pub fn count_ready(&self, kind: JobKind) -> Result<usize> {
+        let con = self.con.lock().unwrap();
+        let count: i64 = con.query_row(
+            "SELECT COUNT(*)
+            FROM jobs
+            WHERE kind = ?1
+            AND state = ?2
+            AND (run_after_ts IS NULL OR run_after_ts <= CURRENT_TIMESTAMP)",
+            params![kind.as_str(), QUEUED],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// Jobs that have been given up on, most recent first.
+    pub fn failed(&self) -> Result<Vec<Job>> {
+        let con = self.con.lock().unwrap();
+        let mut stmt = con.prepare(
+            "SELECT
+                job_id,
+                kind,
+                target_id,
+                priority,
+                attempts,
+                last_error
+            FROM jobs
+            WHERE state = ?1
+            ORDER BY job_id DESC",
+        )?;
+
+        let result = stmt.query_map(params![FAILED], Self::to_job)?;
+        Ok(result.flatten().collect())
+    }
+
+    /// Give a failed job a fresh set of attempts.
+    pub fn retry(&mut self, job_id: JobId) -> Result<()> {
+        let con = self.con.lock().unwrap();
+        con.execute(
+            "UPDATE jobs
+            SET
+                state = ?2,
+                attempts = 0,
+                run_after_ts = NULL
+            WHERE job_id = ?1",
+            params![job_id.id(), QUEUED],
+        )?;
+        Ok(())
+    }
+
+    /// Queue a job again with a fresh set of attempts, or queue one if the target has none.
+    /// Takes a transaction so it can be part of clearing a problem.
+    pub(crate) fn requeue(tx: &Transaction<'_>, kind: JobKind, target_id: i64) -> Result<()> {
+        let mut stmt = tx.prepare_cached(
+            "INSERT INTO jobs (kind, target_id)
+            VALUES (?1, ?2)
+            ON CONFLICT (kind, target_id) DO UPDATE
+            SET
+                state = ?3,
+                attempts = 0,
+                last_error = NULL,
+                run_after_ts = NULL,
+                claimed_ts = NULL",
+        )?;
+        stmt.execute(params![kind.as_str(), target_id, QUEUED])?;
+        Ok(())
+    }
+
+    fn to_job(row: &Row<'_>) -> rusqlite::Result<Job> {
+        let kind: String = row.get("kind")?;
+        let kind = JobKind::parse(&kind).ok_or(rusqlite::Error::InvalidQuery)?;
+
+        Ok(Job {
+            job_id: row.get("job_id").map(JobId::new)?,
+            kind,
+            target_id: row.get("target_id")?,
+            priority: row.get("priority")?,
+            attempts: row.get("attempts")?,
+            last_error: row.get("last_error")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{insert_picture, setup};
+
+    fn repo() -> Repository {
+        Repository::open(setup()).unwrap()
+    }
+
+    #[test]
+    fn claims_visible_jobs_first() {
+        let mut repo = repo();
+        repo.enqueue(JobKind::PhotoThumbnail, &[1, 2, 3], Priority::Normal)
+            .unwrap();
+        repo.prioritise(JobKind::PhotoThumbnail, 3, Priority::Visible)
+            .unwrap();
+
+        // A visible item without a job gets one.
+        repo.prioritise(JobKind::PhotoThumbnail, 4, Priority::Visible)
+            .unwrap();
+
+        let jobs = repo.claim(JobKind::PhotoThumbnail, 3).unwrap();
+        let targets: Vec<i64> = jobs.iter().map(|job| job.target_id).collect();
+        assert_eq!(targets, vec![3, 4, 1]);
+
+        // Claimed jobs aren't claimed again until recovered.
+        assert_eq!(repo.count_ready(JobKind::PhotoThumbnail).unwrap(), 1);
+        assert_eq!(repo.recover(JobKind::PhotoThumbnail).unwrap(), 3);
+        assert_eq!(repo.count_ready(JobKind::PhotoThumbnail).unwrap(), 4);
+    }
+
+    #[test]
+    fn jobs_are_queued_and_removed_with_pictures() {
+        let con = setup();
+        let repo = Repository::open(con.clone()).unwrap();
+
+        let picture_id = insert_picture(&con, "a.jpg");
+        assert_eq!(repo.count_ready(JobKind::PhotoThumbnail).unwrap(), 1);
+
+        con.lock()
+            .unwrap()
+            .execute(
+                "DELETE FROM pictures WHERE picture_id = ?1",
+                [picture_id.id()],
+            )
+            .unwrap();
+        assert_eq!(repo.count_ready(JobKind::PhotoThumbnail).unwrap(), 0);
+    }
+
+    #[test]
+    fn failed_jobs_back_off_then_give_up() {
+        let mut repo = repo();
+        repo.enqueue(JobKind::VideoThumbnail, &[1], Priority::Normal)
+            .unwrap();
+
+        let mut job = repo.claim(JobKind::VideoThumbnail, 1).unwrap().remove(0);
+        assert!(!repo.fail(&job, "oops").unwrap());
+
+        // Waiting for backoff to expire
+        assert!(repo.claim(JobKind::VideoThumbnail, 1).unwrap().is_empty());
+
+        job.attempts = MAX_ATTEMPTS - 1;
+        assert!(repo.fail(&job, "oops again").unwrap());
+
+        let failed = repo.failed().unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].last_error.as_deref(), Some("oops again"));
+
+        // Re-enqueuing doesn't resurrect a failed job, but retrying does.
+        repo.enqueue(JobKind::VideoThumbnail, &[1], Priority::Normal)
+            .unwrap();
+        assert_eq!(repo.count_ready(JobKind::VideoThumbnail).unwrap(), 0);
+        repo.retry(failed[0].job_id).unwrap();
+        assert_eq!(repo.count_ready(JobKind::VideoThumbnail).unwrap(), 1);
+    }
+}
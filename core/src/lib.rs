@@ -3,15 +3,27 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 pub mod database;
+pub mod dates;
+pub mod edits;
+pub mod events;
+pub mod export;
 pub mod file_types;
+pub mod files;
 pub mod flatpak_path;
+pub mod geotag;
+pub mod jobs;
 pub mod machine_learning;
+pub mod orientation;
 pub mod path_encoding;
 pub mod people;
 pub mod photo;
+pub mod places;
+pub mod privacy;
+pub mod problems;
 pub mod scanner;
 pub mod thumbnailify;
 pub mod time;
+pub mod timezone;
 pub mod video;
 pub mod visual;
 
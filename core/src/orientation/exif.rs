@@ -0,0 +1,100 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Writes an orientation chosen by the user back to a picture file, so other apps
+//! show the picture the same way as Fotema.
+
+use super::model::Orientation;
+
+use anyhow::*;
+use little_exif::exif_tag::ExifTag;
+use little_exif::metadata::Metadata;
+use std::fs;
+use std::path::Path;
+use std::result::Result::Ok;
+
+/// File types that EXIF can be safely written to.
+/// HEIC is excluded because libheif rotates HEIC pictures while decoding, ignoring EXIF.
+const WRITABLE_SUFFIXES: [&str; 6] = ["jpg", "jpeg", "png", "webp", "tif", "tiff"];
+
+/// Can an orientation be written to this file?
+pub fn is_writable(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| {
+        WRITABLE_SUFFIXES
+            .iter()
+            .any(|suffix| ext.eq_ignore_ascii_case(suffix))
+    })
+}
+
+/// Replace the EXIF orientation of a picture file. Other metadata is kept.
+/// The picture is written to a temporary file next to it, which then replaces it,
+/// so a failed write never leaves the picture half written.
+pub fn write_orientation(path: &Path, orientation: Orientation) -> Result<()> {
+    if !is_writable(path) {
+        bail!("Cannot write EXIF orientation to {:?}", path);
+    }
+
+    let dir = path
+        .parent()
+        .ok_or_else(|| anyhow!("No parent directory for {:?}", path))?;
+
+    // EXIF is written according to the file extension, so the temporary file keeps it.
+    let suffix = path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+
+    let temp = tempfile::Builder::new()
+        .prefix(".fotema-")
+        .suffix(&suffix)
+        .tempfile_in(dir)
+        .with_context(|| format!("Failed creating temporary file in {:?}", dir))?;
+
+    fs::copy(path, temp.path()).with_context(|| format!("Failed copying {:?}", path))?;
+
+    let mut metadata = Metadata::new_from_path(temp.path())
+        .with_context(|| format!("Failed reading EXIF from {:?}", path))?;
+
+    metadata.set_tag(ExifTag::Orientation(vec![orientation as u16]));
+
+    metadata
+        .write_to_file(temp.path())
+        .with_context(|| format!("Failed writing EXIF to {:?}", path))?;
+
+    temp.persist(path)
+        .with_context(|| format!("Failed replacing {:?}", path))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, RgbImage};
+
+    #[test]
+    fn writes_orientation_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("picture.jpg");
+        RgbImage::new(4, 2)
+            .save_with_format(&path, ImageFormat::Jpeg)
+            .unwrap();
+
+        let mut metadata = Metadata::new();
+        metadata.set_tag(ExifTag::Orientation(vec![Orientation::North as u16]));
+        metadata.write_to_file(&path).unwrap();
+
+        write_orientation(&path, Orientation::West).unwrap();
+
+        let metadata = Metadata::new_from_path(&path).unwrap();
+        let tag = metadata
+            .get_tag(&ExifTag::Orientation(vec![]))
+            .next()
+            .unwrap();
+        assert_eq!(tag, &ExifTag::Orientation(vec![Orientation::West as u16]));
+
+        // Only the picture remains, without temporary files.
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
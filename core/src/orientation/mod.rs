@@ -0,0 +1,12 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+pub mod exif;
+pub mod model;
+pub mod repo;
+pub mod transform;
+
+pub use model::{Orientation, Turn};
+pub use repo::Repository;
+pub use transform::transform;
@@ -0,0 +1,153 @@
+// SPDX-FileCopyrightText: © 2024 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use strum::{AsRefStr, EnumIter};
+
+// EXIF data can include an orientation, which is a number from 1 to 8 that describes
+// the rotation/flipping to apply.
+//
+// 1 = 0 degrees: the correct orientation, no adjustment is required.
+// 2 = 0 degrees, mirrored: image has been flipped back-to-front.
+// 3 = 180 degrees: image is upside down.
+// 4 = 180 degrees, mirrored: image has been flipped back-to-front and is upside down.
+// 5 = 90 degrees: image has been flipped back-to-front and is on its side.
+// 6 = 90 degrees, mirrored: image is on its side.
+// 7 = 270 degrees: image has been flipped back-to-front and is on its far side.
+// 8 = 270 degrees, mirrored: image is on its far side.
+//
+// The Orientation enum describes where the top of the image should point and if
+// it should be mirrored (flipped on the X axis).
+//
+// NOTE: these enum names will be used in style.css to apply the rotation and mirroring.
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, AsRefStr, EnumIter)]
+pub enum Orientation {
+    // no rotation, no flip
+    #[default]
+    North = 1,
+
+    // no rotation, flip on X axis
+    NorthMirrored = 2,
+
+    // Rotate 180, no flip
+    South = 3,
+
+    // Rotate 180, flip X axis
+    SouthMirrored = 4,
+
+    // Rotate 270 (90 anti-clockwise), flip X axis,
+    WestMirrored = 5,
+
+    // Rotate 270 clock-wise (90 anti-clockwise), no flip
+    West = 6,
+
+    // Rotate 90 clock-wise, flip X axis
+    EastMirrored = 7,
+
+    // Rotate 90 clock-wise, no flip
+    East = 8,
+}
+
+impl Orientation {
+    pub fn from_degrees(degrees: i32) -> Self {
+        match degrees {
+            0 => Orientation::North,
+            90 | -270 => Orientation::East,
+            180 | -180 => Orientation::South,
+            -90 | 270 => Orientation::West,
+            _ => Self::default(),
+        }
+    }
+
+    /// Orientation that shows the picture turned a quarter turn clockwise.
+    pub fn rotate_clockwise(self) -> Self {
+        match self {
+            Orientation::North => Orientation::West,
+            Orientation::West => Orientation::South,
+            Orientation::South => Orientation::East,
+            Orientation::East => Orientation::North,
+
+            // Mirroring reverses the direction of rotation.
+            Orientation::NorthMirrored => Orientation::EastMirrored,
+            Orientation::EastMirrored => Orientation::SouthMirrored,
+            Orientation::SouthMirrored => Orientation::WestMirrored,
+            Orientation::WestMirrored => Orientation::NorthMirrored,
+        }
+    }
+
+    /// Orientation that shows the picture turned a quarter turn anti-clockwise.
+    pub fn rotate_anticlockwise(self) -> Self {
+        self.rotate_clockwise()
+            .rotate_clockwise()
+            .rotate_clockwise()
+    }
+
+    /// Orientation that shows the picture turned a quarter turn.
+    pub fn turn(self, turn: Turn) -> Self {
+        match turn {
+            Turn::Left => self.rotate_anticlockwise(),
+            Turn::Right => self.rotate_clockwise(),
+        }
+    }
+
+    /// Orientation after also turning by another orientation. Mirroring of the other
+    /// orientation is ignored.
+    pub fn turned_by(self, other: Orientation) -> Self {
+        let quarter_turns = match other {
+            Orientation::North | Orientation::NorthMirrored => 0,
+            Orientation::West | Orientation::WestMirrored => 1,
+            Orientation::South | Orientation::SouthMirrored => 2,
+            Orientation::East | Orientation::EastMirrored => 3,
+        };
+        (0..quarter_turns).fold(self, |orientation, _| orientation.rotate_clockwise())
+    }
+}
+
+/// Direction of a quarter turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Turn {
+    /// Anti-clockwise
+    Left,
+
+    /// Clockwise
+    Right,
+}
+
+impl From<u32> for Orientation {
+    fn from(number: u32) -> Self {
+        match number {
+            1 => Orientation::North,
+            2 => Orientation::NorthMirrored,
+            3 => Orientation::South,
+            4 => Orientation::SouthMirrored,
+            5 => Orientation::WestMirrored,
+            6 => Orientation::West,
+            7 => Orientation::EastMirrored,
+            8 => Orientation::East,
+            _ => Self::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn rotations_are_reversible() {
+        for orientation in Orientation::iter() {
+            let turned = orientation.rotate_clockwise();
+            assert_ne!(turned, orientation);
+            assert_eq!(turned.rotate_anticlockwise(), orientation);
+            assert_eq!(
+                turned
+                    .rotate_clockwise()
+                    .rotate_clockwise()
+                    .rotate_clockwise(),
+                orientation
+            );
+        }
+    }
+}
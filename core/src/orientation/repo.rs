@@ -0,0 +1,102 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::model::Orientation;
+use crate::photo::PictureId;
+use crate::video::VideoId;
+
+use anyhow::*;
+use rusqlite::OptionalExtension;
+use rusqlite::params;
+use std::result::Result::Ok;
+use std::sync::{Arc, Mutex};
+
+/// Repository of orientations chosen by the user.
+/// Repository is backed by a Sqlite database.
+#[derive(Debug, Clone)]
+pub struct Repository {
+    /// Connection to backing Sqlite database.
+    con: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl Repository {
+    pub fn open(con: Arc<Mutex<rusqlite::Connection>>) -> Result<Repository> {
+        Ok(Repository { con })
+    }
+
+    /// Show a picture with an orientation instead of its EXIF orientation.
+    /// Choosing the EXIF orientation removes the override.
+    /// Returns the override that was kept, if any.
+    pub fn set_picture(
+        &mut self,
+        picture_id: PictureId,
+        orientation: Orientation,
+    ) -> Result<Option<Orientation>> {
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+        tx.execute(
+            "INSERT INTO pictures_orientation (
+                picture_id,
+                orientation,
+                updated_ts
+            ) VALUES (
+                ?1, ?2, CURRENT_TIMESTAMP
+            ) ON CONFLICT (picture_id) DO UPDATE SET
+                orientation = ?2,
+                updated_ts = CURRENT_TIMESTAMP
+            ",
+            params![picture_id.id(), orientation as u32],
+        )?;
+        tx.execute(
+            "DELETE FROM pictures_orientation
+            WHERE picture_id = ?1
+            AND orientation = (
+                SELECT COALESCE(orientation, 1) FROM pictures WHERE picture_id = ?1
+            )",
+            params![picture_id.id()],
+        )?;
+        let orientation: Option<u32> = tx
+            .query_row(
+                "SELECT orientation FROM pictures_orientation WHERE picture_id = ?1",
+                params![picture_id.id()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        tx.commit()?;
+        Ok(orientation.map(Orientation::from))
+    }
+
+    /// Turn a video on top of the rotation of its video stream.
+    /// Choosing `Orientation::North` removes the override.
+    /// Returns the override that was kept, if any.
+    pub fn set_video(
+        &mut self,
+        video_id: VideoId,
+        orientation: Orientation,
+    ) -> Result<Option<Orientation>> {
+        let con = self.con.lock().unwrap();
+        if orientation == Orientation::North {
+            con.execute(
+                "DELETE FROM videos_orientation WHERE video_id = ?1",
+                params![video_id.id()],
+            )?;
+            Ok(None)
+        } else {
+            con.execute(
+                "INSERT INTO videos_orientation (
+                    video_id,
+                    orientation,
+                    updated_ts
+                ) VALUES (
+                    ?1, ?2, CURRENT_TIMESTAMP
+                ) ON CONFLICT (video_id) DO UPDATE SET
+                    orientation = ?2,
+                    updated_ts = CURRENT_TIMESTAMP
+                ",
+                params![video_id.id(), orientation as u32],
+            )?;
+            Ok(Some(orientation))
+        }
+    }
+}
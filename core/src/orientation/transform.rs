@@ -0,0 +1,142 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::model::Orientation;
+use image::{ImageBuffer, Pixel, imageops};
+
+/// Rotate and mirror pixels as an EXIF orientation describes, so the picture is upright.
+pub fn transform<P>(
+    image: ImageBuffer<P, Vec<P::Subpixel>>,
+    orientation: Orientation,
+) -> ImageBuffer<P, Vec<P::Subpixel>>
+where
+    P: Pixel + 'static,
+{
+    match orientation {
+        Orientation::North => image,
+        Orientation::NorthMirrored => imageops::flip_horizontal(&image),
+        Orientation::South => imageops::rotate180(&image),
+        Orientation::SouthMirrored => imageops::flip_vertical(&image),
+        Orientation::WestMirrored => imageops::flip_horizontal(&imageops::rotate90(&image)),
+        Orientation::West => imageops::rotate90(&image),
+        Orientation::EastMirrored => imageops::flip_horizontal(&imageops::rotate270(&image)),
+        Orientation::East => imageops::rotate270(&image),
+    }
+}
+
+/// A rectangle of pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Size of a picture after it has been made upright.
+pub fn upright_size(orientation: Orientation, width: u32, height: u32) -> (u32, u32) {
+    match orientation {
+        Orientation::North
+        | Orientation::NorthMirrored
+        | Orientation::South
+        | Orientation::SouthMirrored => (width, height),
+        Orientation::West
+        | Orientation::WestMirrored
+        | Orientation::East
+        | Orientation::EastMirrored => (height, width),
+    }
+}
+
+/// Find the region of the stored pixels that becomes a region of the upright picture.
+/// Width and height are of the stored pixels, before any transformation.
+pub fn source_region(orientation: Orientation, region: Region, width: u32, height: u32) -> Region {
+    let Region {
+        x: u,
+        y: v,
+        width: w,
+        height: h,
+    } = region;
+
+    let (x, y, width, height) = match orientation {
+        Orientation::North => (u, v, w, h),
+        Orientation::NorthMirrored => (width - u - w, v, w, h),
+        Orientation::South => (width - u - w, height - v - h, w, h),
+        Orientation::SouthMirrored => (u, height - v - h, w, h),
+        Orientation::West => (v, height - u - w, h, w),
+        Orientation::WestMirrored => (v, u, h, w),
+        Orientation::East => (width - v - h, u, h, w),
+        Orientation::EastMirrored => (width - v - h, height - u - w, h, w),
+    };
+
+    Region {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn transforms_follow_exif() {
+        // 0 1
+        // 2 3
+        let image = GrayImage::from_fn(2, 2, |x, y| Luma([(y * 2 + x) as u8]));
+        let pixels = |orientation| {
+            transform(image.clone(), orientation)
+                .pixels()
+                .map(|p| p.0[0])
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(pixels(Orientation::North), vec![0, 1, 2, 3]);
+        assert_eq!(pixels(Orientation::West), vec![2, 0, 3, 1]);
+        assert_eq!(pixels(Orientation::East), vec![1, 3, 0, 2]);
+
+        // EXIF 5 is a transpose, and EXIF 7 is a transverse.
+        assert_eq!(pixels(Orientation::WestMirrored), vec![0, 2, 1, 3]);
+        assert_eq!(pixels(Orientation::EastMirrored), vec![3, 1, 2, 0]);
+    }
+
+    #[test]
+    fn source_regions_match_transform() {
+        let image = GrayImage::from_fn(4, 3, |x, y| Luma([(y * 4 + x) as u8]));
+        let region = Region {
+            x: 1,
+            y: 0,
+            width: 2,
+            height: 1,
+        };
+
+        for orientation in Orientation::iter() {
+            let upright = transform(image.clone(), orientation);
+            let expected =
+                imageops::crop_imm(&upright, region.x, region.y, region.width, region.height)
+                    .to_image();
+
+            let source = source_region(orientation, region, 4, 3);
+            let cropped =
+                imageops::crop_imm(&image, source.x, source.y, source.width, source.height)
+                    .to_image();
+
+            assert_eq!(
+                upright_size(orientation, 4, 3),
+                upright.dimensions(),
+                "{:?}",
+                orientation
+            );
+            assert_eq!(
+                transform(cropped, orientation),
+                expected,
+                "{:?}",
+                orientation
+            );
+        }
+    }
+}
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use crate::FlatpakPathBuf;
+use crate::orientation::Orientation;
 use crate::photo::model::PictureId;
 use crate::thumbnailify;
 use chrono::{DateTime, Utc};
@@ -14,11 +15,20 @@ use std::path::PathBuf;
 pub struct FaceDetectionCandidate {
     pub picture_id: PictureId,
     pub path: FlatpakPathBuf,
+
+    /// Orientation chosen by the user, replacing the EXIF orientation.
+    pub orientation_override: Option<Orientation>,
 }
 
 impl FaceDetectionCandidate {
+    /// Faces are detected in the unedited thumbnail, so that faces don't have to be
+    /// detected again after every edit.
     pub fn thumbnail_hash(&self) -> String {
-        thumbnailify::compute_hash_for_path(&self.path.host_path)
+        thumbnailify::compute_hash_for_edited_path(
+            &self.path.host_path,
+            self.orientation_override,
+            None,
+        )
     }
 }
 
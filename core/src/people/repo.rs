@@ -367,7 +367,7 @@ impl Repository {
         Ok(())
     }
 
-    pub fn mark_face_scan_broken(&mut self, picture_id: &PictureId) -> Result<()> {
+    pub fn mark_face_scan_broken(&mut self, picture_id: &PictureId, message: &str) -> Result<()> {
         let mut con = self.con.lock().unwrap();
         let tx = con.transaction()?;
 
@@ -377,17 +377,19 @@ impl Repository {
                     picture_id,
                     is_broken,
                     face_count,
-                    scan_ts
+                    scan_ts,
+                    broken_message
                 ) VALUES (
-                    ?1, TRUE, 0, CURRENT_TIMESTAMP
+                    ?1, TRUE, 0, CURRENT_TIMESTAMP, ?2
                 ) ON CONFLICT (picture_id) DO UPDATE SET
                     is_broken = true,
                     face_count = 0,
-                    scan_ts = CURRENT_TIMESTAMP
+                    scan_ts = CURRENT_TIMESTAMP,
+                    broken_message = ?2
                 ",
             )?;
 
-            stmt.execute(params![picture_id.id(),])?;
+            stmt.execute(params![picture_id.id(), message])?;
         }
 
         tx.commit()?;
@@ -415,7 +417,8 @@ impl Repository {
                 ) ON CONFLICT (picture_id) DO UPDATE SET
                     is_broken = ?2,
                     face_count = ?3,
-                    scan_ts = CURRENT_TIMESTAMP
+                    scan_ts = CURRENT_TIMESTAMP,
+                    broken_message = NULL
                 ",
             )?;
 
@@ -563,6 +566,43 @@ impl Repository {
         Ok(())
     }
 
+    /// User is marking the only unconfirmed face in each of some pictures as a person.
+    /// Pictures with no unconfirmed faces, or with more than one, are skipped because
+    /// which face is the person isn't known.
+    /// Returns the number of faces marked.
+    pub fn mark_only_faces_as_person(
+        &mut self,
+        picture_ids: &[PictureId],
+        person_id: PersonId,
+    ) -> Result<usize> {
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+        let mut count = 0;
+
+        {
+            let mut stmt = tx.prepare_cached(
+                "UPDATE pictures_faces
+                SET
+                    person_id = ?2,
+                    is_confirmed = TRUE
+                WHERE picture_id = ?1
+                AND is_ignored = FALSE
+                AND is_confirmed = FALSE
+                AND (
+                    SELECT COUNT(*) FROM pictures_faces
+                    WHERE picture_id = ?1 AND is_ignored = FALSE AND is_confirmed = FALSE
+                ) = 1",
+            )?;
+
+            for picture_id in picture_ids {
+                count += stmt.execute(params![picture_id.id(), person_id.id(),])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(count)
+    }
+
     /// Face recognition is automatically marking a face as a person
     pub fn mark_as_person_unconfirmed(
         &mut self,
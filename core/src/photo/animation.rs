@@ -0,0 +1,381 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::model::Animation;
+
+use anyhow::*;
+use chrono::TimeDelta;
+use std::fs;
+use std::io::{BufReader, ErrorKind, Read, Seek};
+use std::path::Path;
+use std::result::Result::Ok;
+
+use ffmpeg::format::input;
+use ffmpeg::media::Type;
+use ffmpeg_next as ffmpeg;
+
+/// Detect if an image is animated, and if so, how many frames it has and for how long it plays.
+/// Returns None for still images, including formats that can't be animated.
+///
+/// Frames are counted from the container without being decoded, so this is cheap
+/// even for large animations.
+pub fn from_path(path: &Path) -> Result<Option<Animation>> {
+    let ext = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_lowercase());
+
+    let animation = match ext.as_deref() {
+        Some("gif") => from_gif(BufReader::new(fs::File::open(path)?))?,
+        Some("webp") => from_webp(BufReader::new(fs::File::open(path)?))?,
+        Some("png") => from_apng(BufReader::new(fs::File::open(path)?))?,
+        // No simple container to walk for these formats, but FFmpeg can demux them.
+        Some("avif") | Some("jxl") => from_packets(path)?,
+        _ => return Ok(None),
+    };
+
+    Ok(animation.filter(|x| x.frame_count > 1))
+}
+
+/// Walks the GIF block structure, counting image descriptors and summing the
+/// delays from graphic control extensions.
+fn from_gif<R: Read + Seek>(mut r: R) -> Result<Option<Animation>> {
+    let header: [u8; 6] = read_array(&mut r)?;
+    if &header[0..3] != b"GIF" {
+        bail!("Not a GIF");
+    }
+
+    // Logical screen descriptor
+    let screen: [u8; 7] = read_array(&mut r)?;
+    skip_color_table(&mut r, screen[4])?;
+
+    let mut frame_count = 0;
+    let mut delay_hundredths: i64 = 0;
+
+    loop {
+        // Truncated GIFs are common and browsers play what they can, so
+        // running out of data ends the animation rather than failing.
+        let introducer = match read_array::<1, _>(&mut r) {
+            Ok([b]) => b,
+            Err(e) if is_eof(&e) => break,
+            Err(e) => return Err(e),
+        };
+
+        match introducer {
+            // Extension
+            0x21 => {
+                let [label] = read_array(&mut r)?;
+                if label == 0xF9 {
+                    // Graphic control extension: block size, packed fields,
+                    // delay time in hundredths of a second, transparent color index.
+                    let gce: [u8; 5] = read_array(&mut r)?;
+                    delay_hundredths += i64::from(u16::from_le_bytes([gce[2], gce[3]]));
+                }
+                skip_sub_blocks(&mut r)?;
+            }
+            // Image descriptor
+            0x2C => {
+                let descriptor: [u8; 9] = read_array(&mut r)?;
+                skip_color_table(&mut r, descriptor[8])?;
+                let _lzw_minimum_code_size: [u8; 1] = read_array(&mut r)?;
+                skip_sub_blocks(&mut r)?;
+                frame_count += 1;
+            }
+            // Trailer
+            0x3B => break,
+            _ => bail!("Unexpected GIF block {:#04x}", introducer),
+        }
+    }
+
+    Ok(Some(Animation {
+        frame_count,
+        duration: TimeDelta::milliseconds(delay_hundredths * 10),
+    }))
+}
+
+/// Skips a global or local color table, if the packed fields say one is present.
+fn skip_color_table<R: Read + Seek>(r: &mut R, packed: u8) -> Result<()> {
+    if packed & 0x80 != 0 {
+        let len = 3 * (1 << ((packed & 0x07) + 1));
+        r.seek_relative(len)?;
+    }
+    Ok(())
+}
+
+/// Skips GIF data sub-blocks up to and including the block terminator.
+fn skip_sub_blocks<R: Read + Seek>(r: &mut R) -> Result<()> {
+    loop {
+        let [len] = read_array(r)?;
+        if len == 0 {
+            return Ok(());
+        }
+        r.seek_relative(len.into())?;
+    }
+}
+
+/// Walks the RIFF chunks of a WebP, counting ANMF chunks and summing their durations.
+fn from_webp<R: Read + Seek>(mut r: R) -> Result<Option<Animation>> {
+    let header: [u8; 12] = read_array(&mut r)?;
+    if &header[0..4] != b"RIFF" || &header[8..12] != b"WEBP" {
+        bail!("Not a WebP");
+    }
+
+    let mut frame_count = 0;
+    let mut duration_ms: i64 = 0;
+    let mut is_first_chunk = true;
+
+    loop {
+        let chunk_header: [u8; 8] = match read_array(&mut r) {
+            Ok(h) => h,
+            Err(e) if is_eof(&e) => break,
+            Err(e) => return Err(e),
+        };
+        let fourcc = &chunk_header[0..4];
+        let size = u32::from_le_bytes(chunk_header[4..8].try_into()?);
+        // Chunks are padded to an even length
+        let padded_size = i64::from(size) + i64::from(size & 1);
+
+        if is_first_chunk {
+            is_first_chunk = false;
+            // Only the extended format can be animated
+            if fourcc != b"VP8X" {
+                return Ok(None);
+            }
+            let [flags] = read_array(&mut r)?;
+            if flags & 0x02 == 0 {
+                return Ok(None);
+            }
+            r.seek_relative(padded_size - 1)?;
+        } else if fourcc == b"ANMF" {
+            // Frame X, Y, width and height (24 bits each), then a 24-bit duration in milliseconds.
+            let frame: [u8; 15] = read_array(&mut r)?;
+            duration_ms += i64::from(u32::from_le_bytes([frame[12], frame[13], frame[14], 0]));
+            frame_count += 1;
+            r.seek_relative(padded_size - 15)?;
+        } else {
+            r.seek_relative(padded_size)?;
+        }
+    }
+
+    Ok(Some(Animation {
+        frame_count,
+        duration: TimeDelta::milliseconds(duration_ms),
+    }))
+}
+
+/// Walks the chunks of a PNG, counting fcTL chunks and summing their delays.
+/// A PNG is only an APNG if the acTL chunk comes before the image data.
+fn from_apng<R: Read + Seek>(mut r: R) -> Result<Option<Animation>> {
+    let signature: [u8; 8] = read_array(&mut r)?;
+    if &signature != b"\x89PNG\r\n\x1a\n" {
+        bail!("Not a PNG");
+    }
+
+    let mut is_apng = false;
+    let mut frame_count = 0;
+    let mut duration_ms: i64 = 0;
+
+    loop {
+        let chunk_header: [u8; 8] = read_array(&mut r)?;
+        let size = i64::from(u32::from_be_bytes(chunk_header[0..4].try_into()?));
+        let chunk_type = &chunk_header[4..8];
+
+        match chunk_type {
+            b"acTL" => is_apng = true,
+            b"IDAT" if !is_apng => return Ok(None),
+            b"IEND" => break,
+            _ => {}
+        }
+
+        if chunk_type == b"fcTL" {
+            // Sequence number, width, height, X and Y offsets (32 bits each),
+            // then the delay as a fraction of a second.
+            let fctl: [u8; 24] = read_array(&mut r)?;
+            let numerator = i64::from(u16::from_be_bytes([fctl[20], fctl[21]]));
+            let denominator = match u16::from_be_bytes([fctl[22], fctl[23]]) {
+                0 => 100, // Zero means hundredths of a second
+                d => i64::from(d),
+            };
+            duration_ms += numerator * 1000 / denominator;
+            frame_count += 1;
+            // Rest of chunk data, plus CRC
+            r.seek_relative(size - 24 + 4)?;
+        } else {
+            r.seek_relative(size + 4)?;
+        }
+    }
+
+    if !is_apng {
+        return Ok(None);
+    }
+
+    Ok(Some(Animation {
+        frame_count,
+        duration: TimeDelta::milliseconds(duration_ms),
+    }))
+}
+
+fn read_array<const N: usize, R: Read>(r: &mut R) -> Result<[u8; N]> {
+    let mut buf = [0; N];
+    r.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn is_eof(e: &Error) -> bool {
+    e.downcast_ref::<std::io::Error>()
+        .is_some_and(|e| e.kind() == ErrorKind::UnexpectedEof)
+}
+
+/// Counts frames without decoding them.
+fn from_packets(path: &Path) -> Result<Option<Animation>> {
+    let mut ictx = input(path.as_os_str())?;
+
+    let Some(stream) = ictx.streams().best(Type::Video) else {
+        return Ok(None);
+    };
+    let stream_index = stream.index();
+    let time_base = f64::from(stream.time_base());
+
+    let mut frame_count = 0;
+    let mut duration = 0;
+
+    for (stream, packet) in ictx.packets() {
+        if stream.index() == stream_index {
+            frame_count += 1;
+            duration += packet.duration();
+        }
+    }
+
+    let duration = TimeDelta::milliseconds((duration as f64 * time_base * 1000.0) as i64);
+
+    Ok(Some(Animation {
+        frame_count,
+        duration,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// A GIF with a two entry global color table and a frame for each delay.
+    fn gif(delays: &[u16]) -> Vec<u8> {
+        let mut gif = b"GIF89a".to_vec();
+        gif.extend([1, 0, 1, 0, 0x80, 0, 0]);
+        gif.extend([0; 6]);
+        // Application extension, as written for looping animations
+        gif.extend([0x21, 0xFF, 11]);
+        gif.extend(b"NETSCAPE2.0");
+        gif.extend([3, 1, 0, 0, 0]);
+        for delay in delays {
+            let [lo, hi] = delay.to_le_bytes();
+            gif.extend([0x21, 0xF9, 4, 0, lo, hi, 0, 0]);
+            // Image descriptor with a two entry local color table
+            gif.extend([0x2C, 0, 0, 0, 0, 1, 0, 1, 0, 0x80]);
+            gif.extend([0; 6]);
+            gif.extend([2, 2, 0x4C, 0x01, 0]);
+        }
+        gif.push(0x3B);
+        gif
+    }
+
+    fn webp_chunk(fourcc: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut chunk = fourcc.to_vec();
+        chunk.extend((payload.len() as u32).to_le_bytes());
+        chunk.extend(payload);
+        if payload.len() % 2 == 1 {
+            chunk.push(0);
+        }
+        chunk
+    }
+
+    fn webp(flags: u8, durations: &[u32]) -> Vec<u8> {
+        let mut chunks = webp_chunk(b"VP8X", &[flags, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        chunks.extend(webp_chunk(b"ANIM", &[0, 0, 0, 0, 0, 0]));
+        for duration in durations {
+            let mut frame = vec![0; 12];
+            frame.extend(&duration.to_le_bytes()[0..3]);
+            frame.push(0);
+            // Odd length frame data to exercise padding
+            frame.extend(webp_chunk(b"VP8L", &[0x2F, 0, 0, 0, 0]));
+            chunks.extend(webp_chunk(b"ANMF", &frame));
+        }
+        let mut webp = b"RIFF".to_vec();
+        webp.extend((chunks.len() as u32 + 4).to_le_bytes());
+        webp.extend(b"WEBP");
+        webp.extend(chunks);
+        webp
+    }
+
+    fn png_chunk(chunk_type: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+        chunk.extend(chunk_type);
+        chunk.extend(data);
+        chunk.extend([0; 4]); // CRC isn't checked
+        chunk
+    }
+
+    fn fctl(delay_num: u16, delay_den: u16) -> Vec<u8> {
+        let mut data = vec![0; 20];
+        data.extend(delay_num.to_be_bytes());
+        data.extend(delay_den.to_be_bytes());
+        data.extend([0, 0]);
+        data
+    }
+
+    fn png(actl_before_idat: bool, delays: &[(u16, u16)]) -> Vec<u8> {
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png.extend(png_chunk(b"IHDR", &[0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0]));
+        let actl = png_chunk(b"acTL", &[0, 0, 0, delays.len() as u8, 0, 0, 0, 0]);
+        if actl_before_idat {
+            png.extend(&actl);
+        }
+        for (i, (num, den)) in delays.iter().enumerate() {
+            png.extend(png_chunk(b"fcTL", &fctl(*num, *den)));
+            if i == 0 {
+                png.extend(png_chunk(b"IDAT", &[0; 10]));
+            } else {
+                png.extend(png_chunk(b"fdAT", &[0; 14]));
+            }
+        }
+        if !actl_before_idat {
+            png.extend(&actl);
+        }
+        png.extend(png_chunk(b"IEND", &[]));
+        png
+    }
+
+    #[test]
+    fn gif_frames_and_delays() {
+        let animation = from_gif(Cursor::new(gif(&[10, 20, 5]))).unwrap().unwrap();
+        assert_eq!(animation.frame_count, 3);
+        assert_eq!(animation.duration, TimeDelta::milliseconds(350));
+    }
+
+    #[test]
+    fn webp_frames_and_durations() {
+        let animation = from_webp(Cursor::new(webp(0x02, &[100, 250])))
+            .unwrap()
+            .unwrap();
+        assert_eq!(animation.frame_count, 2);
+        assert_eq!(animation.duration, TimeDelta::milliseconds(350));
+
+        // Without the animation flag
+        assert!(from_webp(Cursor::new(webp(0, &[]))).unwrap().is_none());
+    }
+
+    #[test]
+    fn apng_frames_and_delays() {
+        let animation = from_apng(Cursor::new(png(true, &[(1, 10), (50, 0), (0, 0)])))
+            .unwrap()
+            .unwrap();
+        assert_eq!(animation.frame_count, 3);
+        assert_eq!(animation.duration, TimeDelta::milliseconds(600));
+
+        // APNG requires acTL before the image data, so this is a still PNG
+        let late_actl = png(false, &[(1, 10), (1, 10)]);
+        assert!(from_apng(Cursor::new(late_actl)).unwrap().is_none());
+    }
+}
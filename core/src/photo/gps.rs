@@ -87,7 +87,57 @@ impl GPSCoord {
     }
 }
 
+impl GPSCoord {
+    fn from_decimal(decimal: f64) -> Self {
+        Self {
+            sing: decimal.is_sign_positive(),
+            deg: decimal.abs(),
+            min: None,
+            sec: None,
+        }
+    }
+}
+
 impl GPSLocation {
+    /// Location from decimal degrees.
+    pub fn from_decimal(latitude: f64, longitude: f64) -> Option<Self> {
+        if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+            return None;
+        }
+
+        let location = Self {
+            latitude: GPSCoord::from_decimal(latitude),
+            longitude: GPSCoord::from_decimal(longitude),
+        };
+
+        if location.latitude.to_f64_safe().is_none() || location.longitude.to_f64_safe().is_none() {
+            return None;
+        }
+
+        Some(location)
+    }
+
+    /// Parse an ISO 6709 location string, as found in QuickTime and MP4 metadata.
+    /// For example, "+51.5072-000.1276+010.000/". Any altitude is ignored.
+    pub fn for_iso6709(value: &str) -> Option<Self> {
+        let value = value.trim().trim_end_matches('/');
+
+        // Split into signed components, such as ["+51.5072", "-000.1276", "+010.000"]
+        let mut parts: Vec<&str> = Vec::with_capacity(3);
+        let mut start = 0;
+        for (i, c) in value.char_indices().skip(1) {
+            if c == '+' || c == '-' {
+                parts.push(&value[start..i]);
+                start = i;
+            }
+        }
+        parts.push(&value[start..]);
+
+        let latitude = parts.first()?.parse::<f64>().ok()?;
+        let longitude = parts.get(1)?.parse::<f64>().ok()?;
+        Self::from_decimal(latitude, longitude)
+    }
+
     pub fn for_exif(
         latitude: &[exif::Rational],
         latitude_ref: &[Vec<u8>],
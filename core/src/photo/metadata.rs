@@ -3,11 +3,13 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use super::Metadata;
+use super::animation;
 use super::gps::GPSLocation;
 use super::model::Orientation;
+use crate::timezone;
 use anyhow::*;
 use chrono::prelude::*;
-use chrono::{DateTime, FixedOffset};
+use chrono::{DateTime, FixedOffset, NaiveDateTime};
 use exif;
 use exif::Exif;
 use std::fs;
@@ -25,7 +27,9 @@ use std::result::Result::Ok;
 /// 1. Orientation.
 /// 2. Motion photos.
 /// 3. GPS coordinates.
-pub const VERSION: u32 = 3;
+/// 4. Local time of capture and time zone aware creation timestamps.
+/// 5. Frame count and duration of animated images.
+pub const VERSION: u32 = 5;
 
 /// Extract EXIF metadata from file
 pub fn from_path(path: &Path) -> Result<Metadata> {
@@ -63,6 +67,8 @@ pub fn from_path(path: &Path) -> Result<Metadata> {
         metadata.orientation = None;
     }
 
+    metadata.animation = animation::from_path(path).ok().flatten();
+
     Ok(metadata)
 }
 
@@ -82,10 +88,12 @@ pub fn from_raw(data: Vec<u8>) -> Result<Metadata> {
 }
 
 fn from_exif(exif_data: Exif) -> Result<Metadata> {
+    /// Parse an EXIF date time, which is the wall-clock time of the camera,
+    /// and the optional offset from UTC of that wall-clock time.
     fn parse_date_time(
         date_time_field: Option<&exif::Field>,
         time_offset_field: Option<&exif::Field>,
-    ) -> Option<DateTime<FixedOffset>> {
+    ) -> Option<(NaiveDateTime, Option<FixedOffset>)> {
         let date_time_field = date_time_field?;
 
         let mut date_time = match date_time_field.value {
@@ -104,8 +112,10 @@ fn from_exif(exif_data: Exif) -> Result<Metadata> {
             };
         }
 
-        let offset = date_time.offset.unwrap_or(0); // offset in minutes
-        let offset = FixedOffset::east_opt((offset as i32) * 60)?;
+        // offset in minutes
+        let offset = date_time
+            .offset
+            .and_then(|offset| FixedOffset::east_opt((offset as i32) * 60));
 
         let date = NaiveDate::from_ymd_opt(
             date_time.year.into(),
@@ -119,19 +129,34 @@ fn from_exif(exif_data: Exif) -> Result<Metadata> {
             date_time.second.into(),
         )?;
 
-        let naive_date_time = date.and_time(time);
-        Some(offset.from_utc_datetime(&naive_date_time))
+        Some((date.and_time(time), offset))
     }
 
+    let location = gps_location(&exif_data);
+
+    // Work out the offset from UTC for a wall-clock time.
+    // Prefer the offset the camera recorded, then the time zone of where the
+    // photo was taken, and finally the time zone of this computer.
+    let to_date_time = |(local, offset): (NaiveDateTime, Option<FixedOffset>)| {
+        let offset = offset
+            .or_else(|| location.and_then(|loc| timezone::offset_at(&loc, &local)))
+            .unwrap_or_else(|| timezone::system_offset(&local));
+        offset.from_local_datetime(&local).single()
+    };
+
     let exif_created_at = parse_date_time(
         exif_data.get_field(exif::Tag::DateTimeOriginal, exif::In::PRIMARY),
         exif_data.get_field(exif::Tag::OffsetTimeOriginal, exif::In::PRIMARY),
     );
 
+    let local_created_at = exif_created_at.map(|(local, _)| local);
+    let exif_created_at = exif_created_at.and_then(to_date_time);
+
     let exif_modified_at = parse_date_time(
         exif_data.get_field(exif::Tag::DateTime, exif::In::PRIMARY),
         exif_data.get_field(exif::Tag::OffsetTime, exif::In::PRIMARY),
-    );
+    )
+    .and_then(to_date_time);
 
     let lens_model = exif_data
         .get_field(exif::Tag::LensModel, exif::In::PRIMARY)
@@ -147,17 +172,17 @@ fn from_exif(exif_data: Exif) -> Result<Metadata> {
 
     let content_id = ios_content_id(&exif_data);
 
-    let location = gps_location(&exif_data);
-
     let metadata = Metadata {
         fs_created_at: None,
         fs_modified_at: None,
         exif_created_at,
         exif_modified_at,
+        local_created_at,
         lens_model,
         orientation,
         content_id,
         location,
+        animation: None,
     };
 
     Ok(metadata)
@@ -2,10 +2,12 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+pub mod animation;
 pub mod gps;
 pub mod metadata;
 pub mod model;
 pub mod motion_photo;
+mod motion_photo_container;
 pub mod repo;
 pub mod thumbnailer;
 
@@ -4,11 +4,13 @@
 
 use super::gps::GPSLocation;
 use crate::FlatpakPathBuf;
+use crate::edits::EditStack;
+pub use crate::orientation::Orientation;
+use crate::thumbnailify;
 
-use chrono::{DateTime, FixedOffset, TimeDelta, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeDelta, Utc};
 use std::fmt::Display;
 use std::path::PathBuf;
-use strum::{AsRefStr, EnumIter};
 
 /// Database ID of picture
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -45,11 +47,37 @@ pub struct Picture {
 
     /// Was picture taken with front camera?
     pub is_selfie: Option<bool>,
+
+    /// Non-destructive edits, if any.
+    pub edits: Option<EditStack>,
+
+    /// Orientation chosen by the user, replacing the EXIF orientation.
+    pub orientation_override: Option<Orientation>,
 }
 
 impl Picture {
     pub fn thumbnail_hash(&self) -> String {
-        self.path.thumbnail_hash()
+        thumbnailify::compute_hash_for_edited_path(
+            &self.path.host_path,
+            self.orientation_override,
+            self.edits.as_ref(),
+        )
+    }
+
+    /// Hash of thumbnails without edits, but with the orientation chosen by the user.
+    /// Faces are detected in these thumbnails, and they are the starting point for editing.
+    pub fn unedited_thumbnail_hash(&self) -> String {
+        thumbnailify::compute_hash_for_edited_path(
+            &self.path.host_path,
+            self.orientation_override,
+            None,
+        )
+    }
+
+    /// Does this picture have thumbnails of its own, instead of the thumbnails of its file?
+    pub fn has_own_thumbnails(&self) -> bool {
+        self.orientation_override.is_some()
+            || self.edits.as_ref().is_some_and(|edits| !edits.is_empty())
     }
 
     pub fn host_path(&self) -> &PathBuf {
@@ -63,81 +91,6 @@ impl Picture {
 
 /// Extra (non-filesystem) metadata for videos
 
-// EXIF data can include an orientation, which is a number from 1 to 8 that describes
-// the rotation/flipping to apply.
-//
-// 1 = 0 degrees: the correct orientation, no adjustment is required.
-// 2 = 0 degrees, mirrored: image has been flipped back-to-front.
-// 3 = 180 degrees: image is upside down.
-// 4 = 180 degrees, mirrored: image has been flipped back-to-front and is upside down.
-// 5 = 90 degrees: image has been flipped back-to-front and is on its side.
-// 6 = 90 degrees, mirrored: image is on its side.
-// 7 = 270 degrees: image has been flipped back-to-front and is on its far side.
-// 8 = 270 degrees, mirrored: image is on its far side.
-//
-// The Orientation enum describes where the top of the image should point and if
-// it should be mirrored (flipped on the X axis).
-//
-// NOTE: these enum names will be used in style.css to apply the rotation and mirroring.
-//
-// TODO this is also used by videos so move to a common place.
-
-#[derive(Debug, Default, Clone, Copy, AsRefStr, EnumIter)]
-pub enum Orientation {
-    // no rotation, no flip
-    #[default]
-    North = 1,
-
-    // no rotation, flip on X axis
-    NorthMirrored = 2,
-
-    // Rotate 180, no flip
-    South = 3,
-
-    // Rotate 180, flip X axis
-    SouthMirrored = 4,
-
-    // Rotate 270 (90 anti-clockwise), flip X axis,
-    WestMirrored = 5,
-
-    // Rotate 270 clock-wise (90 anti-clockwise), no flip
-    West = 6,
-
-    // Rotate 90 clock-wise, flip X axis
-    EastMirrored = 7,
-
-    // Rotate 90 clock-wise, no flip
-    East = 8,
-}
-
-impl Orientation {
-    pub fn from_degrees(degrees: i32) -> Self {
-        match degrees {
-            0 => Orientation::North,
-            90 | -270 => Orientation::East,
-            180 | -180 => Orientation::South,
-            -90 | 270 => Orientation::West,
-            _ => Self::default(),
-        }
-    }
-}
-
-impl From<u32> for Orientation {
-    fn from(number: u32) -> Self {
-        match number {
-            1 => Orientation::North,
-            2 => Orientation::NorthMirrored,
-            3 => Orientation::South,
-            4 => Orientation::SouthMirrored,
-            5 => Orientation::WestMirrored,
-            6 => Orientation::West,
-            7 => Orientation::EastMirrored,
-            8 => Orientation::East,
-            _ => Self::default(),
-        }
-    }
-}
-
 #[derive(Debug, Default, Clone)]
 pub struct Metadata {
     pub fs_created_at: Option<DateTime<Utc>>,
@@ -148,6 +101,9 @@ pub struct Metadata {
 
     pub exif_modified_at: Option<DateTime<FixedOffset>>,
 
+    /// Wall-clock time at the place the photo was taken.
+    pub local_created_at: Option<NaiveDateTime>,
+
     /// On iPhone the lens model tells you if it was the front or back camera.
     pub lens_model: Option<String>,
 
@@ -161,6 +117,9 @@ pub struct Metadata {
 
     // GPS location
     pub location: Option<GPSLocation>,
+
+    // Frames of an animated image, such as a GIF.
+    pub animation: Option<Animation>,
 }
 
 impl Metadata {
@@ -171,6 +130,15 @@ impl Metadata {
     }
 }
 
+/// Frames of an animated image.
+#[derive(Debug, Clone, Copy)]
+pub struct Animation {
+    pub frame_count: u32,
+
+    // Total time to play all frames once.
+    pub duration: TimeDelta,
+}
+
 /// A video extracted from a motion photo
 #[derive(Debug, Clone)]
 pub struct MotionPhotoVideo {
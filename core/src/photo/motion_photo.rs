@@ -6,13 +6,17 @@ use crate::photo::model::PictureId;
 use anyhow::*;
 
 use super::model::MotionPhotoVideo;
+use super::motion_photo_container;
 use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 use std::result::Result::Ok;
+use std::sync::atomic::AtomicBool;
 use tracing::debug;
 
 use sm_motion_photo::SmMotion;
 
+use crate::video::TranscodeProfile;
 use crate::video::metadata as video_metadata;
 use crate::video::transcode;
 
@@ -20,8 +24,12 @@ use crate::video::transcode;
 /// a bug fix or feature addition that changes the motion photo data produced.
 /// Each photo will be saved with a motion photo extraction version which will allow for
 /// easy selection of photos when their motion photo can be updated.
-
-pub const VERSION: u32 = 1;
+///
+/// History:
+/// 1. Samsung motion photos.
+/// 2. Google motion photos and Samsung HEIC motion photos. Only pictures without a
+///    motion photo video are inspected again, because version 1 found none in these.
+pub const VERSION: u32 = 2;
 
 /// Motion photos are an image followed by an embedded MP4 video.
 #[derive(Debug, Clone)]
@@ -37,20 +45,31 @@ impl MotionPhotoExtractor {
         Ok(MotionPhotoExtractor { base_path })
     }
 
-    /// Extract motion photo video if it exists.
+    /// Extract motion photo video if it exists. HEVC videos are transcoded with the profile.
     pub fn extract(
         &self,
         picture_id: &PictureId,
         picture_path: &Path,
+        profile: TranscodeProfile,
     ) -> Result<Option<MotionPhotoVideo>> {
-        let photo_file = File::open(picture_path)?;
-        let Some(sm) = SmMotion::with(&photo_file) else {
-            return Ok(None); // would be nice if API returned a result instead of an option.
-        };
+        let mut photo_file = File::open(picture_path)?;
 
-        if !sm.has_video() {
-            return Ok(None);
-        }
+        // Prefer the native extractor and fall back to sm_motion_photo for
+        // Samsung JPEG motion photos.
+        let range = motion_photo_container::find_video(&mut photo_file)?;
+
+        let sm = if range.is_none() {
+            let Some(sm) = SmMotion::with(&photo_file) else {
+                return Ok(None); // would be nice if API returned a result instead of an option.
+            };
+
+            if !sm.has_video() {
+                return Ok(None);
+            }
+            Some(sm)
+        } else {
+            None
+        };
 
         debug!("Photo {:?} has an embedded motion video.", picture_path);
 
@@ -67,8 +86,14 @@ impl MotionPhotoExtractor {
                 let _ = std::fs::create_dir_all(p);
             }
 
-            let mut video_file = File::create(&video_path).unwrap();
-            sm.dump_video_file(&mut video_file).unwrap();
+            let mut video_file = File::create(&video_path)?;
+            if let Some(range) = range {
+                photo_file.seek(SeekFrom::Start(range.offset))?;
+                std::io::copy(&mut (&photo_file).take(range.length), &mut video_file)?;
+            } else if let Some(sm) = sm {
+                sm.dump_video_file(&mut video_file)
+                    .map_err(|e| anyhow!("Failed dumping Samsung motion photo video: {:?}", e))?;
+            }
         }
 
         let mut mpv = MotionPhotoVideo {
@@ -85,8 +110,7 @@ impl MotionPhotoExtractor {
             mpv.duration = meta.duration;
         } else {
             // If we have extracted the video but can't get any metadata, then the motion photo
-            // format for this file probably isn't supported by the sm_motion_photo library and
-            // we have duff data.
+            // format for this file probably isn't supported and we have duff data.
             return Ok(None);
         }
 
@@ -99,11 +123,14 @@ impl MotionPhotoExtractor {
                 // Create a directory per 1000 motion photos
                 let partition = (picture_id.id() / 1000) as i32;
                 let partition = format!("{:0>4}", partition);
-                let file_name = format!("{}_transcoded.mkv", picture_id);
+                let file_name = profile.file_name(&format!("{}_transcoded", picture_id));
                 self.base_path.join(partition).join(file_name)
             };
 
-            transcode::transcode(&video_path, &transcoded_path)?;
+            // Motion photo videos are only a few seconds long, so don't bother with
+            // progress or stopping.
+            let stop = AtomicBool::new(false);
+            transcode::transcode(&video_path, &transcoded_path, profile, &stop, |_| {})?;
 
             mpv.transcoded_path = Some(transcoded_path);
         }
@@ -123,6 +150,6 @@ mod tests {
         let path = Path::new("/var/home/david/Pictures/Test/Motion Photos/photo.jpg");
 
         let mp = MotionPhotoExtractor::build(&Path::new(".")).unwrap();
-        mp.extract(&PictureId::new(123), &path);
+        mp.extract(&PictureId::new(123), &path, TranscodeProfile::default());
     }
 }
@@ -0,0 +1,319 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Locate the video embedded in a motion photo without any help from the
+//! `sm_motion_photo` library. Two layouts are understood:
+//!
+//! 1. Google motion photos, where XMP metadata describes the video appended to the image.
+//!    Older Pixel phones use `GCamera:MicroVideoOffset`, which is the length of the video
+//!    at the end of the file. Newer phones describe each appended item in a
+//!    `Container:Directory`, with the video having an `Item:Semantic` of `MotionPhoto`.
+//! 2. Newer Samsung HEIC motion photos, where the video is in a top-level `mpvd` box.
+
+use anyhow::*;
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+use std::io::{Read, Seek, SeekFrom};
+use std::result::Result::Ok;
+
+/// XMP is near the start of both JPEG and HEIC files, so only this much of a file is
+/// searched for it.
+const XMP_SEARCH_LENGTH: u64 = 1024 * 1024;
+
+const XMP_START: &[u8] = b"<x:xmpmeta";
+const XMP_END: &[u8] = b"</x:xmpmeta>";
+
+/// Byte range of an embedded video within a motion photo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VideoRange {
+    pub offset: u64,
+    pub length: u64,
+}
+
+/// Find the embedded video in a motion photo. Only the start of the file and the
+/// headers of its boxes are read, not the whole file.
+pub fn find_video<R: Read + Seek>(file: &mut R) -> Result<Option<VideoRange>> {
+    let len = file.seek(SeekFrom::End(0))?;
+
+    let mut head = Vec::new();
+    file.seek(SeekFrom::Start(0))?;
+    file.by_ref()
+        .take(XMP_SEARCH_LENGTH)
+        .read_to_end(&mut head)?;
+
+    let range = find_xmp(&head)
+        .and_then(|xmp| video_length_from_xmp(xmp).ok().flatten())
+        .and_then(|(length, trailer)| {
+            let end = len.checked_sub(trailer)?;
+            let offset = end.checked_sub(length)?;
+            Some(VideoRange { offset, length })
+        });
+
+    let range = match range {
+        Some(range) => range,
+        None => match find_mpvd_box(file, len)? {
+            Some(range) => range,
+            None => return Ok(None),
+        },
+    };
+
+    Ok(is_mp4(file, len, range)?.then_some(range))
+}
+
+/// XMP is stored as plain text in both JPEG and HEIC files, so find it without
+/// parsing the image container.
+fn find_xmp(data: &[u8]) -> Option<&str> {
+    let start = find(data, XMP_START)?;
+    let end = find(&data[start..], XMP_END)? + start + XMP_END.len();
+    std::str::from_utf8(&data[start..end]).ok()
+}
+
+/// Length of the embedded video and the number of bytes that follow it.
+fn video_length_from_xmp(xmp: &str) -> Result<Option<(u64, u64)>> {
+    #[derive(Default)]
+    struct Item {
+        semantic: Option<String>,
+        length: u64,
+        padding: u64,
+    }
+
+    let mut micro_video_offset: Option<u64> = None;
+    let mut items: Vec<Item> = Vec::new();
+
+    // Properties can be attributes or elements, so track the current element
+    // to associate text with it.
+    let mut element: Option<String> = None;
+
+    let mut set = |name: &str, value: &str, items: &mut Vec<Item>| {
+        let value = value.trim();
+        match local_name(name) {
+            "MicroVideoOffset" => micro_video_offset = value.parse().ok(),
+            "Semantic" => {
+                if let Some(item) = items.last_mut() {
+                    item.semantic = Some(value.to_string());
+                }
+            }
+            "Length" => {
+                if let Some(item) = items.last_mut() {
+                    item.length = value.parse().unwrap_or(0);
+                }
+            }
+            "Padding" => {
+                if let Some(item) = items.last_mut() {
+                    item.padding = value.parse().unwrap_or(0);
+                }
+            }
+            _ => {}
+        }
+    };
+
+    let mut reader = Reader::from_str(xmp);
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) => {
+                let name = element_name(&e);
+                if local_name(&name) == "Item" && !name.starts_with("rdf:") {
+                    items.push(Item::default());
+                }
+                for attribute in e.attributes().flatten() {
+                    let key = String::from_utf8_lossy(attribute.key.as_ref()).to_string();
+                    let value = attribute.unescape_value()?;
+                    set(&key, &value, &mut items);
+                }
+                element = Some(name);
+            }
+            Event::Text(e) => {
+                if let Some(ref name) = element {
+                    let text = e.unescape()?;
+                    set(name, &text, &mut items);
+                }
+            }
+            Event::End(_) => element = None,
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if let Some(index) = items
+        .iter()
+        .position(|x| x.semantic.as_deref() == Some("MotionPhoto"))
+    {
+        let item = &items[index];
+        if item.length > 0 {
+            // Lengths are untrusted, so a trailer too long to count isn't a motion photo.
+            let trailer = items[index + 1..].iter().try_fold(item.padding, |sum, x| {
+                sum.checked_add(x.length)?.checked_add(x.padding)
+            });
+            return Ok(trailer.map(|trailer| (item.length, trailer)));
+        }
+    }
+
+    Ok(micro_video_offset
+        .filter(|x| *x > 0)
+        .map(|length| (length, 0)))
+}
+
+/// Samsung HEIC motion photos put the video in a top-level `mpvd` box.
+/// Box sizes are untrusted, so any size that overflows or runs past the end of the file
+/// means this isn't a motion photo.
+fn find_mpvd_box<R: Read + Seek>(file: &mut R, len: u64) -> Result<Option<VideoRange>> {
+    let mut offset: u64 = 0;
+    let mut header = [0u8; 16];
+
+    while offset.checked_add(8).is_some_and(|end| end <= len) {
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut header[..8])?;
+
+        let size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+
+        let (header_size, size) = match size {
+            0 => (8, len - offset), // box extends to end of file
+            1 => {
+                // 64-bit size follows the type
+                if offset.checked_add(16).is_none_or(|end| end > len) {
+                    return Ok(None);
+                }
+                file.read_exact(&mut header[8..])?;
+                let mut size = [0u8; 8];
+                size.copy_from_slice(&header[8..]);
+                (16, u64::from_be_bytes(size))
+            }
+            size => (8, size),
+        };
+
+        let Some(end) = offset.checked_add(size).filter(|end| *end <= len) else {
+            return Ok(None);
+        };
+
+        if size < header_size {
+            return Ok(None);
+        }
+
+        if &header[4..8] == b"mpvd" {
+            return Ok(Some(VideoRange {
+                offset: offset + header_size,
+                length: size - header_size,
+            }));
+        }
+
+        offset = end;
+    }
+
+    Ok(None)
+}
+
+/// Sanity check the video range points at an MP4 file type box.
+fn is_mp4<R: Read + Seek>(file: &mut R, len: u64, range: VideoRange) -> Result<bool> {
+    let is_in_file = range
+        .offset
+        .checked_add(range.length)
+        .is_some_and(|end| end <= len);
+
+    if range.length < 8 || !is_in_file {
+        return Ok(false);
+    }
+
+    let mut box_type = [0u8; 4];
+    file.seek(SeekFrom::Start(range.offset + 4))?;
+    file.read_exact(&mut box_type)?;
+    Ok(&box_type == b"ftyp")
+}
+
+fn element_name(e: &BytesStart<'_>) -> String {
+    String::from_utf8_lossy(e.name().as_ref()).to_string()
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const MP4: &[u8] = b"\0\0\0\x10ftypisom\0\0\0\0";
+
+    fn motion_photo(xmp: &str, trailer: &[u8]) -> Vec<u8> {
+        let mut data = b"\xff\xd8image".to_vec();
+        data.extend_from_slice(xmp.as_bytes());
+        data.extend_from_slice(b"\xff\xd9");
+        data.extend_from_slice(MP4);
+        data.extend_from_slice(trailer);
+        data
+    }
+
+    fn video_in(data: &[u8]) -> Option<VideoRange> {
+        find_video(&mut Cursor::new(data)).unwrap()
+    }
+
+    #[test]
+    fn finds_micro_video_offset() {
+        let xmp = format!(
+            r#"<x:xmpmeta xmlns:x="adobe:ns:meta/"><rdf:RDF><rdf:Description
+                GCamera:MicroVideo="1" GCamera:MicroVideoOffset="{}"/></rdf:RDF></x:xmpmeta>"#,
+            MP4.len()
+        );
+        let data = motion_photo(&xmp, b"");
+        let range = video_in(&data).unwrap();
+        assert_eq!(range.length, MP4.len() as u64);
+        assert_eq!(range.offset, (data.len() - MP4.len()) as u64);
+
+        // Offset doesn't point at an MP4
+        let mut data = xmp.into_bytes();
+        data.extend_from_slice(&[0; 16]);
+        assert_eq!(video_in(&data), None);
+    }
+
+    #[test]
+    fn finds_container_directory_item() {
+        let xmp = format!(
+            r#"<x:xmpmeta xmlns:x="adobe:ns:meta/"><rdf:RDF><rdf:Description GCamera:MotionPhoto="1">
+                <Container:Directory><rdf:Seq>
+                <rdf:li rdf:parseType="Resource"><Container:Item Item:Mime="image/jpeg" Item:Semantic="Primary" Item:Length="0" Item:Padding="0"/></rdf:li>
+                <rdf:li rdf:parseType="Resource"><Container:Item Item:Mime="video/mp4" Item:Semantic="MotionPhoto" Item:Length="{}"/></rdf:li>
+                <rdf:li rdf:parseType="Resource"><Container:Item><Item:Semantic>Other</Item:Semantic><Item:Length>3</Item:Length></Container:Item></rdf:li>
+                </rdf:Seq></Container:Directory></rdf:Description></rdf:RDF></x:xmpmeta>"#,
+            MP4.len()
+        );
+        let data = motion_photo(&xmp, b"abc");
+        let range = video_in(&data).unwrap();
+        assert_eq!(range.length, MP4.len() as u64);
+        assert_eq!(range.offset, (data.len() - MP4.len() - 3) as u64);
+    }
+
+    #[test]
+    fn finds_mpvd_box() {
+        let mut data = b"\0\0\0\x10ftypheic\0\0\0\0".to_vec();
+        data.extend_from_slice(&((MP4.len() + 8) as u32).to_be_bytes());
+        data.extend_from_slice(b"mpvd");
+        data.extend_from_slice(MP4);
+        let range = video_in(&data).unwrap();
+        assert_eq!(range.offset, 24);
+        assert_eq!(range.length, MP4.len() as u64);
+    }
+
+    #[test]
+    fn rejects_overflowing_sizes() {
+        let mut data = b"\0\0\0\x08free\0\0\0\x01mpvd".to_vec();
+        data.extend_from_slice(&u64::MAX.to_be_bytes());
+        data.extend_from_slice(MP4);
+        assert_eq!(video_in(&data), None);
+
+        let xmp = format!(
+            r#"<x:xmpmeta><Container:Item Item:Semantic="MotionPhoto" Item:Length="16" Item:Padding="{}"/>
+                <Container:Item Item:Semantic="Other" Item:Length="{}"/></x:xmpmeta>"#,
+            u64::MAX,
+            u64::MAX
+        );
+        assert_eq!(video_in(&motion_photo(&xmp, b"")), None);
+    }
+}
@@ -4,9 +4,12 @@
 
 use crate::FlatpakPathBuf;
 use crate::ScannedFile;
+use crate::edits::EditStack;
+use crate::orientation::Orientation;
 use crate::path_encoding;
 use crate::people::model::{DetectedFace, FaceDetectionCandidate, FaceId, Rect};
 use crate::photo::model::{Picture, PictureId};
+use crate::problems::ProblemCategory;
 
 use super::Metadata;
 use super::metadata;
@@ -74,7 +77,10 @@ impl Repository {
                     content_id = ?6,
                     orientation = ?7,
                     fs_created_ts = ?8,
-                    fs_modified_ts = ?9
+                    fs_modified_ts = ?9,
+                    local_created_ts = ?10,
+                    frame_count = ?11,
+                    animation_duration_millis = ?12
                 WHERE picture_id = ?1",
             )?;
 
@@ -87,7 +93,9 @@ impl Repository {
                     ?1, ?2, ?3
                 ) ON CONFLICT (picture_id) DO UPDATE SET
                     latitude = ?2,
-                    longitude = ?3
+                    longitude = ?3,
+                    geocoder_version = 0
+                WHERE pictures_geo.source = 'embedded'
                 ",
             )?;
 
@@ -102,6 +110,9 @@ impl Repository {
                     metadata.orientation.map(|x| x as u8),
                     metadata.fs_created_at,
                     metadata.fs_modified_at,
+                    metadata.local_created_at,
+                    metadata.animation.map(|x| x.frame_count),
+                    metadata.animation.map(|x| x.duration.num_milliseconds()),
                 ])?;
 
                 if let Some(location) = metadata.location {
@@ -121,7 +132,13 @@ impl Repository {
         Ok(())
     }
 
-    pub fn mark_broken(&mut self, picture_id: &PictureId) -> Result<()> {
+    /// Hide a picture that can't be processed, recording why so it can be shown to the user.
+    pub fn mark_broken(
+        &mut self,
+        picture_id: &PictureId,
+        category: ProblemCategory,
+        message: &str,
+    ) -> Result<()> {
         let mut con = self.con.lock().unwrap();
         let tx = con.transaction()?;
 
@@ -129,11 +146,14 @@ impl Repository {
             let mut stmt = tx.prepare_cached(
                 "UPDATE pictures
                 SET
-                    is_broken = TRUE
+                    is_broken = TRUE,
+                    broken_category = ?2,
+                    broken_message = ?3,
+                    broken_ts = CURRENT_TIMESTAMP
                 WHERE picture_id = ?1",
             )?;
 
-            stmt.execute(params![picture_id.id(),])?;
+            stmt.execute(params![picture_id.id(), category.as_str(), message])?;
         }
 
         tx.commit()?;
@@ -190,7 +210,8 @@ impl Repository {
         Ok(())
     }
 
-    /// Gets all pictures in the repository, in ascending order of modification timestamp.
+    /// Gets all pictures in the repository, with their edits and orientation overrides,
+    /// in ascending order of modification timestamp.
     pub fn all(&self) -> Result<Vec<Picture>> {
         let con = self.con.lock().unwrap();
         let mut stmt = con.prepare(
@@ -204,9 +225,13 @@ impl Repository {
                         pictures.fs_modified_ts,
                         pictures.insert_ts
                       ) AS ordering_ts,
-                    pictures.is_selfie
+                    pictures.is_selfie,
+                    pictures_edits.edits,
+                    pictures_orientation.orientation AS orientation_override
                 FROM pictures
-                WHERE COALESCE(is_broken, FALSE) IS FALSE
+                LEFT OUTER JOIN pictures_edits USING (picture_id)
+                LEFT OUTER JOIN pictures_orientation USING (picture_id)
+                WHERE COALESCE(pictures.is_broken, FALSE) IS FALSE
                 ORDER BY ordering_ts ASC",
         )?;
 
@@ -218,6 +243,29 @@ impl Repository {
         Ok(result)
     }
 
+    /// Gets paths of all pictures in the repository, including broken pictures.
+    pub fn all_paths(&self) -> Result<Vec<FlatpakPathBuf>> {
+        let con = self.con.lock().unwrap();
+        let mut stmt = con.prepare("SELECT picture_path_b64 FROM pictures")?;
+
+        let result = stmt
+            .query_map([], |row| {
+                let relative_path: String = row.get(0)?;
+                path_encoding::from_base64(&relative_path)
+                    .map_err(|_| rusqlite::Error::InvalidQuery)
+            })?
+            .flatten()
+            .map(|relative_path| {
+                FlatpakPathBuf::build(
+                    self.library_base_dir.host_path.join(&relative_path),
+                    self.library_base_dir.sandbox_path.join(&relative_path),
+                )
+            })
+            .collect();
+
+        Ok(result)
+    }
+
     /// Gets all pictures that haven't had their metadata extracted.
     /// Will return all pictures that are not broken and have a metadata version
     /// lower than the current metadata scanner.
@@ -250,6 +298,7 @@ impl Repository {
     }
 
     /// Gets all pictures that haven't been inspected for containing a motion photo.
+    /// Pictures that already have a motion photo video are not inspected again.
     pub fn find_need_motion_photo_extract(&self) -> Result<Vec<Picture>> {
         let con = self.con.lock().unwrap();
         let mut stmt = con.prepare(
@@ -267,6 +316,7 @@ impl Repository {
                 FROM pictures
                 FULL OUTER JOIN motion_photos USING (picture_id)
                 WHERE COALESCE(motion_photos.extract_version, 0) < ?1
+                AND motion_photos.video_path IS NULL
                 AND COALESCE(is_broken, FALSE) IS FALSE",
         )?;
 
@@ -381,11 +431,24 @@ impl Repository {
         let ordering_ts = row.get("ordering_ts").expect("must have ordering_ts");
         let is_selfie = row.get("is_selfie").ok();
 
+        // Only some queries select edits and orientation overrides.
+        let edits = row
+            .get("edits")
+            .ok()
+            .and_then(|x: String| EditStack::parse(&x));
+
+        let orientation_override = row
+            .get("orientation_override")
+            .ok()
+            .map(|x: u32| Orientation::from(x));
+
         std::result::Result::Ok(Picture {
             picture_id,
             path: FlatpakPathBuf::build(host_path, sandbox_path),
             ordering_ts,
             is_selfie,
+            edits,
+            orientation_override,
         })
     }
 
@@ -552,9 +615,11 @@ impl Repository {
                         pictures.fs_created_ts,
                         pictures.fs_modified_ts,
                         pictures.insert_ts
-                    ) AS ordering_ts
+                    ) AS ordering_ts,
+                    pictures_orientation.orientation AS orientation_override
                 FROM pictures
                 LEFT OUTER JOIN pictures_face_scans USING (picture_id)
+                LEFT OUTER JOIN pictures_orientation USING (picture_id)
                 WHERE pictures_face_scans.picture_id IS NULL
                 AND COALESCE(pictures.is_broken, FALSE) IS FALSE
                 ORDER BY ordering_ts DESC",
@@ -580,8 +645,10 @@ impl Repository {
         let mut stmt = con.prepare(
             "SELECT
                     pictures.picture_id,
-                    pictures.picture_path_b64
+                    pictures.picture_path_b64,
+                    pictures_orientation.orientation AS orientation_override
                 FROM pictures
+                LEFT OUTER JOIN pictures_orientation USING (picture_id)
                 WHERE pictures.picture_id = ?1",
         )?;
 
@@ -613,8 +680,14 @@ impl Repository {
 
         let sandbox_path = relative_path.map(|x| self.library_base_dir.sandbox_path.join(x));
 
+        let orientation_override = row
+            .get("orientation_override")
+            .ok()
+            .map(|x: u32| Orientation::from(x));
+
         Ok(FaceDetectionCandidate {
             picture_id,
+            orientation_override,
             path: FlatpakPathBuf::build(
                 host_path.expect("Must have host path"),
                 sandbox_path.expect("Must have sandbox path"),
@@ -4,14 +4,17 @@
 
 use anyhow::*;
 
-use image::ImageReader;
-
+use fast_image_resize::PixelType;
+use fast_image_resize::images::ImageRef;
 use gdk4::prelude::TextureExt;
 use glycin;
-use std::io::Cursor;
+use image::RgbaImage;
+use std::borrow::Cow;
 use tracing::error;
 
 use crate::FlatpakPathBuf;
+use crate::edits::{self, EditStack};
+use crate::orientation::{self, Orientation};
 use crate::thumbnailify;
 
 /// Thumbnail operations for photos.
@@ -27,20 +30,38 @@ impl PhotoThumbnailer {
 
     /// Computes a preview square for an image that has been inserted
     /// into the Repository. Preview image will be written to file system and path returned.
-    pub async fn thumbnail(&self, path: &FlatpakPathBuf) -> Result<()> {
-        if self.thumbnailer.is_failed(&path.host_path) {
+    /// An orientation chosen by the user replaces the EXIF orientation, and edited
+    /// pictures are thumbnailed with their edits applied.
+    pub async fn thumbnail(
+        &self,
+        path: &FlatpakPathBuf,
+        orientation: Option<Orientation>,
+        edits: Option<&EditStack>,
+    ) -> Result<()> {
+        if self.thumbnailer.is_failed(path) {
             anyhow::bail!("Failed thumbnail marker exists for {:?}", path.host_path);
         }
 
-        self.thumbnail_internal(path).await.map_err(|err| {
-            let _ = self.thumbnailer.write_failed_thumbnail(path);
-            err
-        })
+        self.thumbnail_internal(path, orientation, edits)
+            .await
+            .map_err(|err| {
+                let _ = self.thumbnailer.write_failed_thumbnail(path);
+                err
+            })
     }
 
-    async fn thumbnail_internal(&self, path: &FlatpakPathBuf) -> Result<()> {
+    async fn thumbnail_internal(
+        &self,
+        path: &FlatpakPathBuf,
+        orientation: Option<Orientation>,
+        edits: Option<&EditStack>,
+    ) -> Result<()> {
         let file = gio::File::for_path(&path.sandbox_path);
-        let loader = glycin::Loader::new(file);
+        let mut loader = glycin::Loader::new(file);
+
+        // An orientation chosen by the user replaces the EXIF orientation.
+        loader.apply_transformations(orientation.is_none());
+
         let image = loader.load().await.map_err(|err| {
             error!("Glycin failed to load file at {:?}", path.sandbox_path);
             err
@@ -54,18 +75,72 @@ impl PhotoThumbnailer {
             err
         })?;
 
-        let bytes = frame.texture().save_to_png_bytes();
-
-        let src_image =
-            ImageReader::with_format(Cursor::new(bytes), image::ImageFormat::Png).decode()?;
-        /*
-                let _ = self.thumbnailer.generate_thumbnail(
-                    path,
-                    thumbnailify::ThumbnailSize::Large,
-                    src_image.clone(),
-                )?;
-        */
-        let _ = self.thumbnailer.generate_all_thumbnails(path, src_image)?;
+        let texture = frame.texture();
+        let width = texture.width() as u32;
+        let height = texture.height() as u32;
+
+        // Straight alpha is what PNG thumbnails expect.
+        let mut downloader = gdk4::TextureDownloader::new(&texture);
+        downloader.set_format(gdk4::MemoryFormat::R8g8b8a8);
+        let (bytes, stride) = downloader.download_bytes();
+
+        // Rows are normally tightly packed, but if not then drop the padding.
+        let row_len = width as usize * 4;
+        let pixels: Cow<'_, [u8]> = if stride == row_len {
+            Cow::Borrowed(&bytes[..])
+        } else {
+            Cow::Owned(
+                bytes
+                    .chunks(stride)
+                    .flat_map(|row| &row[..row_len])
+                    .copied()
+                    .collect(),
+            )
+        };
+
+        let edits = edits.filter(|edits| !edits.is_empty());
+        if orientation.is_none() && edits.is_none() {
+            let src_image = ImageRef::new(width, height, &pixels, PixelType::U8x4)?;
+            self.thumbnailer.generate_all_thumbnails(path, &src_image)?;
+            return Ok(());
+        }
+
+        let image = RgbaImage::from_raw(width, height, pixels.into_owned())
+            .ok_or_else(|| anyhow!("Unexpected pixel buffer size for {:?}", path.host_path))?;
+
+        let image = match orientation {
+            Some(orientation) => orientation::transform(image, orientation),
+            None => image,
+        };
+
+        // Unedited thumbnails are always needed, because faces are detected in them
+        // and they are the starting point for editing.
+        {
+            let src_image = ImageRef::new(
+                image.width(),
+                image.height(),
+                image.as_raw(),
+                PixelType::U8x4,
+            )?;
+            self.thumbnailer
+                .generate_edited_thumbnails(path, orientation, None, &src_image)?;
+        }
+
+        if let Some(edits) = edits {
+            let image = edits::render(image, edits);
+            let src_image = ImageRef::new(
+                image.width(),
+                image.height(),
+                image.as_raw(),
+                PixelType::U8x4,
+            )?;
+            self.thumbnailer.generate_edited_thumbnails(
+                path,
+                orientation,
+                Some(edits),
+                &src_image,
+            )?;
+        }
 
         Ok(())
     }
@@ -0,0 +1,234 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::model::Place;
+
+use anyhow::{Context, Result};
+use h3o::{CellIndex, LatLng, Resolution};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use tracing::{debug, info};
+
+/// This version number should be incremented each time reverse geocoding has
+/// a bug fix or dataset change that changes the places produced.
+/// Each location will be saved with a geocoder version which will allow for
+/// easy selection of locations when their place can be updated.
+///
+/// History:
+/// 1. Bundled GeoNames cities with a population over 1,000.
+pub const VERSION: u32 = 1;
+
+/// Resolution of the H3 cells used to index cities.
+/// Cells at resolution 5 have an edge length of about 9km.
+const INDEX_RESOLUTION: Resolution = Resolution::Five;
+
+/// Number of rings of neighbouring cells to search for the nearest city.
+/// At resolution 5 this is a radius of roughly 40 to 70km.
+const SEARCH_RINGS: u32 = 5;
+
+/// Tab separated name, country code, region, latitude, and longitude of each city.
+const CITIES_FILE: &str = "cities.tsv";
+
+/// Tab separated country code and name of each country.
+const COUNTRIES_FILE: &str = "countries.tsv";
+
+#[derive(Debug)]
+struct City {
+    name: String,
+    country_code: String,
+    region: Option<String>,
+    location: LatLng,
+}
+
+/// Offline reverse geocoder backed by the GeoNames cities dataset,
+/// which is installed with Fotema so all lookups are done locally.
+pub struct Gazetteer {
+    cities: Vec<City>,
+
+    /// Index into cities by H3 cell.
+    index: HashMap<CellIndex, Vec<usize>>,
+
+    /// Country code to country name.
+    countries: HashMap<String, String>,
+}
+
+impl Gazetteer {
+    /// Load the dataset installed in `data_dir`.
+    pub fn load(data_dir: &Path) -> Result<Self> {
+        let open = |file_name: &str| {
+            let path = data_dir.join(file_name);
+            File::open(&path)
+                .map(BufReader::new)
+                .with_context(|| format!("Opening gazetteer data {:?}", path))
+        };
+
+        let gazetteer = Self::from_readers(open(CITIES_FILE)?, open(COUNTRIES_FILE)?)?;
+
+        info!(
+            "Gazetteer loaded with {} cities in {} cells",
+            gazetteer.cities.len(),
+            gazetteer.index.len()
+        );
+
+        Ok(gazetteer)
+    }
+
+    fn from_readers(cities: impl BufRead, countries: impl BufRead) -> Result<Self> {
+        let cities = Self::parse_cities(cities)?;
+
+        let mut index: HashMap<CellIndex, Vec<usize>> = HashMap::new();
+        for (i, city) in cities.iter().enumerate() {
+            index
+                .entry(city.location.to_cell(INDEX_RESOLUTION))
+                .or_default()
+                .push(i);
+        }
+
+        Ok(Self {
+            cities,
+            index,
+            countries: Self::parse_countries(countries)?,
+        })
+    }
+
+    /// Find the place nearest to a location.
+    /// Returns None if there is no city nearby, such as in the middle of the ocean.
+    pub fn lookup(&self, location: LatLng) -> Option<Place> {
+        let cell = location.to_cell(INDEX_RESOLUTION);
+
+        let city = cell
+            .grid_disk::<Vec<_>>(SEARCH_RINGS)
+            .into_iter()
+            .filter_map(|cell| self.index.get(&cell))
+            .flatten()
+            .map(|i| &self.cities[*i])
+            .min_by(|a, b| {
+                let a = a.location.distance_km(location);
+                let b = b.location.distance_km(location);
+                a.total_cmp(&b)
+            })?;
+
+        let country = self
+            .countries
+            .get(&city.country_code)
+            .cloned()
+            .unwrap_or_else(|| city.country_code.clone());
+
+        Some(Place {
+            country_code: city.country_code.clone(),
+            country,
+            region: city.region.clone(),
+            city: city.name.clone(),
+        })
+    }
+
+    fn parse_cities(reader: impl BufRead) -> Result<Vec<City>> {
+        let mut cities = Vec::new();
+
+        for line in reader.lines() {
+            let line = line?;
+            if line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [name, country_code, region, lat, lng] = fields[..] else {
+                continue;
+            };
+
+            let (Ok(lat), Ok(lng)) = (lat.parse::<f64>(), lng.parse::<f64>()) else {
+                debug!("Skipping city with bad coordinates: {}", name);
+                continue;
+            };
+
+            let Ok(location) = LatLng::new(lat, lng) else {
+                continue;
+            };
+
+            cities.push(City {
+                name: name.to_string(),
+                country_code: country_code.to_string(),
+                region: Some(region.to_string()).filter(|region| !region.is_empty()),
+                location,
+            });
+        }
+
+        Ok(cities)
+    }
+
+    fn parse_countries(reader: impl BufRead) -> Result<HashMap<String, String>> {
+        let mut countries = HashMap::new();
+
+        for line in reader.lines() {
+            let line = line?;
+            if line.starts_with('#') {
+                continue;
+            }
+            if let Some((code, name)) = line.split_once('\t') {
+                countries.insert(code.to_string(), name.to_string());
+            }
+        }
+
+        Ok(countries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CITIES: &str = "\
+# name\tcountry_code\tregion\tlatitude\tlongitude
+Dublin\tIE\tLeinster\t53.3331\t-6.2489
+Cork\tIE\tMunster\t51.8980\t-8.4706
+Galway\tIE\tConnacht\t53.2719\t-9.0489
+Nowhere\tIE\tLeinster\tnot-a-number\t-6.0
+Too Few Fields\tIE
+Vaduz\tLI\t\t47.1410\t9.5209
+";
+
+    const COUNTRIES: &str = "\
+# country_code\tname
+IE\tIreland
+";
+
+    fn gazetteer() -> Gazetteer {
+        Gazetteer::from_readers(CITIES.as_bytes(), COUNTRIES.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn parse_cities_skips_bad_lines() {
+        let cities = Gazetteer::parse_cities(CITIES.as_bytes()).unwrap();
+        let names: Vec<&str> = cities.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Dublin", "Cork", "Galway", "Vaduz"]);
+        assert_eq!(cities[0].region.as_deref(), Some("Leinster"));
+        assert_eq!(cities[3].region, None);
+    }
+
+    #[test]
+    fn lookup_finds_nearest_city() {
+        // Dún Laoghaire, just south of Dublin.
+        let location = LatLng::new(53.2940, -6.1340).unwrap();
+        let place = gazetteer().lookup(location).unwrap();
+        assert_eq!(
+            place,
+            Place {
+                country_code: "IE".into(),
+                country: "Ireland".into(),
+                region: Some("Leinster".into()),
+                city: "Dublin".into(),
+            }
+        );
+
+        // Kinsale, south of Cork.
+        let location = LatLng::new(51.7059, -8.5222).unwrap();
+        assert_eq!(gazetteer().lookup(location).unwrap().city, "Cork");
+
+        // Middle of the Atlantic Ocean.
+        let location = LatLng::new(45.0, -30.0).unwrap();
+        assert_eq!(gazetteer().lookup(location), None);
+    }
+}
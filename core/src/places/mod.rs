@@ -0,0 +1,12 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+pub mod gazetteer;
+pub mod model;
+pub mod repo;
+
+pub use gazetteer::Gazetteer;
+pub use model::GeoTarget;
+pub use model::Place;
+pub use repo::Repository;
@@ -0,0 +1,97 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::photo::PictureId;
+use crate::video::VideoId;
+use std::fmt::Display;
+
+/// A named place where a photo or video was taken.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Place {
+    /// ISO 3166 two letter country code, such as "IE".
+    pub country_code: String,
+
+    /// English name of country, such as "Ireland".
+    pub country: String,
+
+    /// First-level administrative division, such as a state, province, or county.
+    pub region: Option<String>,
+
+    /// Nearest city or town.
+    pub city: String,
+}
+
+impl Place {
+    /// Names that this place can be searched by.
+    pub fn terms(&self) -> Vec<&str> {
+        let mut terms = vec![
+            self.city.as_str(),
+            self.country.as_str(),
+            self.country_code.as_str(),
+        ];
+        if let Some(ref region) = self.region {
+            terms.push(region.as_str());
+        }
+        terms
+    }
+
+    /// Does any name of this place contain the search text, ignoring case?
+    pub fn matches(&self, text: &str) -> bool {
+        let text = text.trim().to_lowercase();
+        text.is_empty()
+            || self
+                .terms()
+                .iter()
+                .any(|term| term.to_lowercase().contains(&text))
+    }
+}
+
+impl Display for Place {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.region {
+            Some(ref region) if *region != self.city => {
+                write!(f, "{}, {}, {}", self.city, region, self.country)
+            }
+            _ => write!(f, "{}, {}", self.city, self.country),
+        }
+    }
+}
+
+/// A picture or video with a GPS location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeoTarget {
+    Picture(PictureId),
+    Video(VideoId),
+}
+
+impl Display for GeoTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GeoTarget::Picture(id) => write!(f, "picture {}", id),
+            GeoTarget::Video(id) => write!(f, "video {}", id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_any_term_ignoring_case() {
+        let place = Place {
+            country_code: "IE".into(),
+            country: "Ireland".into(),
+            region: Some("Munster".into()),
+            city: "Cork".into(),
+        };
+
+        assert!(place.matches(""));
+        assert!(place.matches("cork"));
+        assert!(place.matches(" IRE "));
+        assert!(place.matches("munst"));
+        assert!(place.matches("ie"));
+        assert!(!place.matches("Dublin"));
+    }
+}
@@ -0,0 +1,128 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::gazetteer;
+use super::model::{GeoTarget, Place};
+use crate::photo::PictureId;
+use crate::video::VideoId;
+
+use anyhow::*;
+use h3o::LatLng;
+use rusqlite::params;
+use std::result::Result::Ok;
+use std::sync::{Arc, Mutex};
+
+/// Repository of place names for GPS locations.
+/// Repository is backed by a Sqlite database.
+#[derive(Debug, Clone)]
+pub struct Repository {
+    /// Connection to backing Sqlite database.
+    con: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl Repository {
+    pub fn open(con: Arc<Mutex<rusqlite::Connection>>) -> Result<Repository> {
+        Ok(Repository { con })
+    }
+
+    /// Find pictures and videos with a location that hasn't been reverse geocoded
+    /// with the current gazetteer version.
+    pub fn find_need_geocode(&self) -> Result<Vec<(GeoTarget, LatLng)>> {
+        let con = self.con.lock().unwrap();
+        let mut stmt = con.prepare(
+            "SELECT picture_id, NULL AS video_id, latitude, longitude
+            FROM pictures_geo
+            WHERE geocoder_version < ?1
+            UNION ALL
+            SELECT NULL AS picture_id, video_id, latitude, longitude
+            FROM videos_geo
+            WHERE geocoder_version < ?1",
+        )?;
+
+        let result = stmt.query_map([gazetteer::VERSION], |row| {
+            let picture_id: Option<i64> = row.get("picture_id")?;
+            let video_id: Option<i64> = row.get("video_id")?;
+            let latitude: f64 = row.get("latitude")?;
+            let longitude: f64 = row.get("longitude")?;
+
+            let target = match (picture_id, video_id) {
+                (Some(id), _) => GeoTarget::Picture(PictureId::new(id)),
+                (None, Some(id)) => GeoTarget::Video(VideoId::new(id)),
+                (None, None) => return Err(rusqlite::Error::InvalidQuery),
+            };
+
+            let location =
+                LatLng::new(latitude, longitude).map_err(|_| rusqlite::Error::InvalidQuery)?;
+
+            Ok((target, location))
+        })?;
+
+        Ok(result.flatten().collect())
+    }
+
+    /// Save places for locations. A location without a place is still marked as
+    /// geocoded so it isn't looked up again.
+    pub fn add_places(&mut self, places: Vec<(GeoTarget, Option<Place>)>) -> Result<()> {
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+
+        {
+            let mut update_picture = tx.prepare_cached(
+                "UPDATE pictures_geo
+                SET
+                    geocoder_version = ?2,
+                    country_code = ?3,
+                    country_name = ?4,
+                    region_name = ?5,
+                    city_name = ?6
+                WHERE picture_id = ?1",
+            )?;
+
+            let mut update_video = tx.prepare_cached(
+                "UPDATE videos_geo
+                SET
+                    geocoder_version = ?2,
+                    country_code = ?3,
+                    country_name = ?4,
+                    region_name = ?5,
+                    city_name = ?6
+                WHERE video_id = ?1",
+            )?;
+
+            for (target, place) in places {
+                let (country_code, country, region, city) = match place {
+                    Some(place) => (
+                        Some(place.country_code),
+                        Some(place.country),
+                        place.region,
+                        Some(place.city),
+                    ),
+                    None => (None, None, None, None),
+                };
+
+                let stmt = match target {
+                    GeoTarget::Picture(_) => &mut update_picture,
+                    GeoTarget::Video(_) => &mut update_video,
+                };
+
+                let id = match target {
+                    GeoTarget::Picture(id) => id.id(),
+                    GeoTarget::Video(id) => id.id(),
+                };
+
+                stmt.execute(params![
+                    id,
+                    gazetteer::VERSION,
+                    country_code,
+                    country,
+                    region,
+                    city,
+                ])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,11 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+pub mod model;
+pub mod policy;
+pub mod repo;
+
+pub use model::{ExportLocation, PrivateZone, ZoneArea, ZoneId};
+pub use policy::PrivacyPolicy;
+pub use repo::Repository;
@@ -0,0 +1,89 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use h3o::{CellIndex, LatLng};
+use std::fmt::Display;
+
+/// Database ID of a private zone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ZoneId(i64);
+
+impl ZoneId {
+    pub fn new(id: i64) -> Self {
+        Self(id)
+    }
+
+    pub fn id(&self) -> i64 {
+        self.0
+    }
+}
+
+impl Display for ZoneId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Area covered by a private zone.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ZoneArea {
+    /// Everywhere within a distance of a centre point.
+    Circle { centre: LatLng, radius_metres: f64 },
+
+    /// Everywhere within a H3 cell.
+    Cell(CellIndex),
+}
+
+impl ZoneArea {
+    pub fn contains(&self, location: LatLng) -> bool {
+        match self {
+            ZoneArea::Circle {
+                centre,
+                radius_metres,
+            } => centre.distance_m(location) <= *radius_metres,
+            ZoneArea::Cell(cell) => location.to_cell(cell.resolution()) == *cell,
+        }
+    }
+}
+
+/// A sensitive area, such as home, that should never be revealed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrivateZone {
+    pub zone_id: ZoneId,
+    pub name: String,
+    pub area: ZoneArea,
+}
+
+/// What to do with a GPS location when a picture or video leaves the library.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportLocation {
+    /// Export precise location.
+    Keep,
+
+    /// Snap location to the centre of a large H3 cell.
+    Coarse,
+
+    /// Remove location.
+    #[default]
+    Strip,
+}
+
+impl ExportLocation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportLocation::Keep => "Keep",
+            ExportLocation::Coarse => "Coarse",
+            ExportLocation::Strip => "Strip",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "Keep" => Some(ExportLocation::Keep),
+            "Coarse" => Some(ExportLocation::Coarse),
+            "Strip" => Some(ExportLocation::Strip),
+            _ => None,
+        }
+    }
+}
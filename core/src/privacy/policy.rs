@@ -0,0 +1,121 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::model::{ExportLocation, PrivateZone};
+
+use h3o::{LatLng, Resolution};
+
+/// Resolution of H3 cells that coarse locations are snapped to.
+/// Cells at resolution 5 have an edge length of about 9km, which is
+/// about the size of a town.
+pub const COARSE_RESOLUTION: Resolution = Resolution::Five;
+
+/// Decides which locations can be shown and what is revealed when
+/// pictures and videos are exported.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PrivacyPolicy {
+    pub zones: Vec<PrivateZone>,
+    pub export: ExportLocation,
+}
+
+impl PrivacyPolicy {
+    pub fn new(zones: Vec<PrivateZone>, export: ExportLocation) -> Self {
+        Self { zones, export }
+    }
+
+    /// Is a location inside any private zone?
+    pub fn is_private(&self, location: LatLng) -> bool {
+        self.zones.iter().any(|zone| zone.area.contains(location))
+    }
+
+    /// Location that may be shown in the app, or None if it is private.
+    pub fn visible_location(&self, location: Option<LatLng>) -> Option<LatLng> {
+        location.filter(|location| !self.is_private(*location))
+    }
+
+    /// Can original files be exported as they are, with whatever location they carry?
+    /// Only when locations are kept and none are private, because a missing location in
+    /// the library doesn't mean the file has none.
+    pub fn keeps_original_location(&self) -> bool {
+        self.export == ExportLocation::Keep && self.zones.is_empty()
+    }
+
+    /// Location to embed in an exported picture or video, or None if it must
+    /// be stripped. Locations in private zones are always stripped.
+    pub fn export_location(&self, location: Option<LatLng>) -> Option<LatLng> {
+        let location = self.visible_location(location)?;
+        match self.export {
+            ExportLocation::Keep => Some(location),
+            ExportLocation::Coarse => Some(LatLng::from(location.to_cell(COARSE_RESOLUTION))),
+            ExportLocation::Strip => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::privacy::model::{ZoneArea, ZoneId};
+
+    fn home() -> LatLng {
+        LatLng::new(53.35, -6.26).unwrap()
+    }
+
+    fn policy(export: ExportLocation) -> PrivacyPolicy {
+        let zone = PrivateZone {
+            zone_id: ZoneId::new(1),
+            name: "Home".into(),
+            area: ZoneArea::Circle {
+                centre: home(),
+                radius_metres: 500.0,
+            },
+        };
+        PrivacyPolicy::new(vec![zone], export)
+    }
+
+    #[test]
+    fn hides_locations_in_private_zones() {
+        let policy = policy(ExportLocation::Keep);
+        let nearby = LatLng::new(53.352, -6.26).unwrap();
+        let elsewhere = LatLng::new(48.86, 2.35).unwrap();
+
+        assert_eq!(policy.visible_location(Some(nearby)), None);
+        assert_eq!(policy.visible_location(Some(elsewhere)), Some(elsewhere));
+        assert_eq!(policy.export_location(Some(home())), None);
+        assert!(!policy.keeps_original_location());
+        assert!(PrivacyPolicy::new(vec![], ExportLocation::Keep).keeps_original_location());
+    }
+
+    #[test]
+    fn hides_locations_in_private_cells() {
+        let cell = home().to_cell(Resolution::Seven);
+        let policy = PrivacyPolicy::new(
+            vec![PrivateZone {
+                zone_id: ZoneId::new(1),
+                name: "Home".into(),
+                area: ZoneArea::Cell(cell),
+            }],
+            ExportLocation::Keep,
+        );
+
+        assert!(policy.is_private(home()));
+        assert!(!policy.is_private(LatLng::new(48.86, 2.35).unwrap()));
+    }
+
+    #[test]
+    fn coarsens_or_strips_exported_locations() {
+        let paris = LatLng::new(48.8566, 2.3522).unwrap();
+
+        let coarse = policy(ExportLocation::Coarse)
+            .export_location(Some(paris))
+            .unwrap();
+        assert_ne!(coarse, paris);
+        assert!(coarse.distance_km(paris) < 20.0);
+
+        assert_eq!(
+            policy(ExportLocation::Strip).export_location(Some(paris)),
+            None
+        );
+    }
+}
@@ -0,0 +1,109 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::model::{PrivateZone, ZoneArea, ZoneId};
+
+use anyhow::*;
+use h3o::{CellIndex, LatLng};
+use rusqlite::Row;
+use rusqlite::params;
+use std::result::Result::Ok;
+use std::sync::{Arc, Mutex};
+
+/// Repository of private zones.
+/// Repository is backed by a Sqlite database.
+#[derive(Debug, Clone)]
+pub struct Repository {
+    /// Connection to backing Sqlite database.
+    con: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl Repository {
+    pub fn open(con: Arc<Mutex<rusqlite::Connection>>) -> Result<Repository> {
+        Ok(Repository { con })
+    }
+
+    /// Gets all private zones, ordered by name.
+    pub fn all(&self) -> Result<Vec<PrivateZone>> {
+        let con = self.con.lock().unwrap();
+        let mut stmt = con.prepare(
+            "SELECT
+                zone_id,
+                name,
+                latitude,
+                longitude,
+                radius_metres,
+                h3_cell
+            FROM private_zones
+            ORDER BY name COLLATE NOCASE ASC",
+        )?;
+
+        let result = stmt.query_map([], Self::to_zone)?;
+        Ok(result.flatten().collect())
+    }
+
+    pub fn add(&mut self, name: &str, area: ZoneArea) -> Result<ZoneId> {
+        let con = self.con.lock().unwrap();
+
+        match area {
+            ZoneArea::Circle {
+                centre,
+                radius_metres,
+            } => {
+                con.execute(
+                    "INSERT INTO private_zones (name, latitude, longitude, radius_metres)
+                    VALUES (?1, ?2, ?3, ?4)",
+                    params![name, centre.lat(), centre.lng(), radius_metres],
+                )?;
+            }
+            ZoneArea::Cell(cell) => {
+                con.execute(
+                    "INSERT INTO private_zones (name, h3_cell) VALUES (?1, ?2)",
+                    params![name, u64::from(cell) as i64],
+                )?;
+            }
+        }
+
+        Ok(ZoneId::new(con.last_insert_rowid()))
+    }
+
+    pub fn remove(&mut self, zone_id: ZoneId) -> Result<()> {
+        let con = self.con.lock().unwrap();
+        con.execute(
+            "DELETE FROM private_zones WHERE zone_id = ?1",
+            params![zone_id.id()],
+        )?;
+        Ok(())
+    }
+
+    fn to_zone(row: &Row<'_>) -> rusqlite::Result<PrivateZone> {
+        let latitude: Option<f64> = row.get("latitude")?;
+        let longitude: Option<f64> = row.get("longitude")?;
+        let radius_metres: Option<f64> = row.get("radius_metres")?;
+        let h3_cell: Option<i64> = row.get("h3_cell")?;
+
+        let area = match (latitude, longitude, radius_metres, h3_cell) {
+            (Some(latitude), Some(longitude), Some(radius_metres), _) => {
+                let centre =
+                    LatLng::new(latitude, longitude).map_err(|_| rusqlite::Error::InvalidQuery)?;
+                ZoneArea::Circle {
+                    centre,
+                    radius_metres,
+                }
+            }
+            (_, _, _, Some(h3_cell)) => {
+                let cell = CellIndex::try_from(h3_cell as u64)
+                    .map_err(|_| rusqlite::Error::InvalidQuery)?;
+                ZoneArea::Cell(cell)
+            }
+            _ => return Err(rusqlite::Error::InvalidQuery),
+        };
+
+        Ok(PrivateZone {
+            zone_id: row.get("zone_id").map(ZoneId::new)?,
+            name: row.get("name")?,
+            area,
+        })
+    }
+}
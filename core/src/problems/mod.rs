@@ -0,0 +1,9 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+pub mod model;
+pub mod repo;
+
+pub use model::{Problem, ProblemCategory, ProblemTarget};
+pub use repo::Repository;
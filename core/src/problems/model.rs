@@ -0,0 +1,72 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::photo::PictureId;
+use crate::video::VideoId;
+use std::path::PathBuf;
+
+/// Stage of processing at which an item was found to be broken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProblemCategory {
+    /// Couldn't generate a thumbnail.
+    Thumbnail,
+
+    /// Couldn't extract the video from a motion photo.
+    MotionPhoto,
+
+    /// Couldn't transcode a video into a playable format.
+    Transcode,
+
+    /// Couldn't scan a picture for faces.
+    FaceScan,
+}
+
+impl ProblemCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProblemCategory::Thumbnail => "Thumbnail",
+            ProblemCategory::MotionPhoto => "MotionPhoto",
+            ProblemCategory::Transcode => "Transcode",
+            ProblemCategory::FaceScan => "FaceScan",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<ProblemCategory> {
+        match value {
+            "Thumbnail" => Some(ProblemCategory::Thumbnail),
+            "MotionPhoto" => Some(ProblemCategory::MotionPhoto),
+            "Transcode" => Some(ProblemCategory::Transcode),
+            "FaceScan" => Some(ProblemCategory::FaceScan),
+            _ => None,
+        }
+    }
+}
+
+/// What is broken. A picture can be readable but still fail to be scanned for faces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProblemTarget {
+    /// Picture is hidden from the library.
+    Picture(PictureId),
+
+    /// Video is hidden from the library.
+    Video(VideoId),
+
+    /// Picture is in the library, but has no faces.
+    FaceScan(PictureId),
+}
+
+/// An item that couldn't be processed, and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub target: ProblemTarget,
+
+    /// Path relative to library base directory.
+    pub path: PathBuf,
+
+    /// Items marked as broken by older versions of Fotema have no category.
+    pub category: Option<ProblemCategory>,
+
+    /// Error message, if known.
+    pub message: Option<String>,
+}
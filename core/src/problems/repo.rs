@@ -0,0 +1,312 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::model::{Problem, ProblemCategory, ProblemTarget};
+use crate::jobs::{self, JobKind};
+use crate::path_encoding;
+use crate::photo::PictureId;
+use crate::video::VideoId;
+
+use anyhow::*;
+use rusqlite::Row;
+use rusqlite::Transaction;
+use rusqlite::params;
+use std::result::Result::Ok;
+use std::sync::{Arc, Mutex};
+
+/// Repository of pictures, videos and face scans that couldn't be processed.
+/// Repository is backed by a Sqlite database.
+#[derive(Debug, Clone)]
+pub struct Repository {
+    /// Connection to backing Sqlite database.
+    con: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl Repository {
+    pub fn open(con: Arc<Mutex<rusqlite::Connection>>) -> Result<Repository> {
+        Ok(Repository { con })
+    }
+
+    /// Gets all problems, ordered by path.
+    pub fn all(&self) -> Result<Vec<Problem>> {
+        let con = self.con.lock().unwrap();
+        let mut stmt = con.prepare(
+            "SELECT
+                'Picture' AS target,
+                picture_id AS target_id,
+                picture_path_b64 AS path_b64,
+                broken_category,
+                broken_message
+            FROM pictures
+            WHERE COALESCE(is_broken, FALSE) IS TRUE
+
+            UNION ALL
+
+            SELECT
+                'Video' AS target,
+                video_id AS target_id,
+                video_path_b64 AS path_b64,
+                broken_category,
+                broken_message
+            FROM videos
+            WHERE COALESCE(is_broken, FALSE) IS TRUE
+
+            UNION ALL
+
+            SELECT
+                'FaceScan' AS target,
+                pictures.picture_id AS target_id,
+                pictures.picture_path_b64 AS path_b64,
+                'FaceScan' AS broken_category,
+                pictures_face_scans.broken_message
+            FROM pictures_face_scans
+            INNER JOIN pictures USING (picture_id)
+            WHERE pictures_face_scans.is_broken IS TRUE
+            AND COALESCE(pictures.is_broken, FALSE) IS FALSE",
+        )?;
+
+        let result = stmt.query_map([], Self::to_problem)?;
+        let mut problems: Vec<Problem> = result.flatten().collect();
+        problems.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(problems)
+    }
+
+    /// Clear a broken mark so the item is processed again by the next background run.
+    pub fn retry(&mut self, target: ProblemTarget) -> Result<()> {
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+        Self::retry_target(&tx, target)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Clear all broken marks.
+    pub fn retry_all(&mut self) -> Result<usize> {
+        let problems = self.all()?;
+
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+        for problem in &problems {
+            Self::retry_target(&tx, problem.target)?;
+        }
+        tx.commit()?;
+
+        Ok(problems.len())
+    }
+
+    /// Clear broken marks of one category. Returns the number of items that will be retried.
+    pub fn retry_category(&mut self, category: ProblemCategory) -> Result<usize> {
+        let problems: Vec<Problem> = self
+            .all()?
+            .into_iter()
+            .filter(|problem| problem.category == Some(category))
+            .collect();
+
+        let mut con = self.con.lock().unwrap();
+        let tx = con.transaction()?;
+        for problem in &problems {
+            Self::retry_target(&tx, problem.target)?;
+        }
+        tx.commit()?;
+
+        Ok(problems.len())
+    }
+
+    fn retry_target(tx: &Transaction<'_>, target: ProblemTarget) -> Result<()> {
+        match target {
+            ProblemTarget::Picture(picture_id) => {
+                let mut stmt = tx.prepare_cached(
+                    "UPDATE pictures
+                    SET
+                        is_broken = FALSE,
+                        broken_category = NULL,
+                        broken_message = NULL,
+                        broken_ts = NULL
+                    WHERE picture_id = ?1",
+                )?;
+                stmt.execute(params![picture_id.id()])?;
+                jobs::Repository::requeue(tx, JobKind::PhotoThumbnail, picture_id.id())?;
+            }
+            ProblemTarget::Video(video_id) => {
+                let mut stmt = tx.prepare_cached(
+                    "UPDATE videos
+                    SET
+                        is_broken = FALSE,
+                        broken_category = NULL,
+                        broken_message = NULL,
+                        broken_ts = NULL
+                    WHERE video_id = ?1",
+                )?;
+                stmt.execute(params![video_id.id()])?;
+                jobs::Repository::requeue(tx, JobKind::VideoThumbnail, video_id.id())?;
+            }
+            ProblemTarget::FaceScan(picture_id) => {
+                // Pictures without a face scan are candidates for face detection.
+                let mut stmt = tx.prepare_cached(
+                    "DELETE FROM pictures_face_scans
+                    WHERE picture_id = ?1
+                    AND is_broken IS TRUE",
+                )?;
+                stmt.execute(params![picture_id.id()])?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn to_problem(row: &Row<'_>) -> rusqlite::Result<Problem> {
+        let target: String = row.get("target")?;
+        let target_id: i64 = row.get("target_id")?;
+        let target = match target.as_str() {
+            "Picture" => ProblemTarget::Picture(PictureId::new(target_id)),
+            "Video" => ProblemTarget::Video(VideoId::new(target_id)),
+            _ => ProblemTarget::FaceScan(PictureId::new(target_id)),
+        };
+
+        let path: String = row.get("path_b64")?;
+        let path = path_encoding::from_base64(&path).map_err(|_| rusqlite::Error::InvalidQuery)?;
+
+        let category: Option<String> = row.get("broken_category")?;
+        let category = category.and_then(|x| ProblemCategory::parse(&x));
+
+        Ok(Problem {
+            target,
+            path,
+            category,
+            message: row.get("broken_message")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{self, insert_picture};
+    use std::path::PathBuf;
+
+    fn setup() -> (Arc<Mutex<rusqlite::Connection>>, Repository) {
+        let con = test_support::setup();
+        let repo = Repository::open(con.clone()).unwrap();
+        (con, repo)
+    }
+
+    fn insert_broken_picture(
+        con: &Arc<Mutex<rusqlite::Connection>>,
+        path: &str,
+        category: ProblemCategory,
+    ) -> PictureId {
+        let picture_id = insert_picture(con, path);
+        let con = con.lock().unwrap();
+        con.execute(
+            "UPDATE pictures
+            SET is_broken = TRUE, broken_category = ?2, broken_message = 'oops'
+            WHERE picture_id = ?1",
+            params![picture_id.id(), category.as_str()],
+        )
+        .unwrap();
+        picture_id
+    }
+
+    fn insert_video(
+        con: &Arc<Mutex<rusqlite::Connection>>,
+        path: &str,
+        category: ProblemCategory,
+    ) -> VideoId {
+        let path_b64 = path_encoding::to_base64(std::path::Path::new(path));
+        let con = con.lock().unwrap();
+        con.execute(
+            "INSERT INTO videos (
+                video_path_b64, video_path_lossy, link_path_b64, link_path_lossy,
+                is_broken, broken_category, broken_message
+            ) VALUES (?1, ?2, ?1, ?2, TRUE, ?3, 'oops')",
+            params![path_b64, path, category.as_str()],
+        )
+        .unwrap();
+        VideoId::new(con.last_insert_rowid())
+    }
+
+    fn insert_broken_face_scan(con: &Arc<Mutex<rusqlite::Connection>>, picture_id: PictureId) {
+        let con = con.lock().unwrap();
+        con.execute(
+            "INSERT INTO pictures_face_scans (picture_id, is_broken, scan_ts, face_count)
+            VALUES (?1, TRUE, CURRENT_TIMESTAMP, 0)",
+            params![picture_id.id()],
+        )
+        .unwrap();
+    }
+
+    fn fail_job(con: &Arc<Mutex<rusqlite::Connection>>, kind: JobKind, target_id: i64) {
+        let con = con.lock().unwrap();
+        con.execute(
+            "UPDATE jobs SET state = 'Failed' WHERE kind = ?1 AND target_id = ?2",
+            params![kind.as_str(), target_id],
+        )
+        .unwrap();
+    }
+
+    fn count_ready(con: &Arc<Mutex<rusqlite::Connection>>, kind: JobKind) -> usize {
+        let jobs_repo = jobs::Repository::open(con.clone()).unwrap();
+        jobs_repo.count_ready(kind).unwrap()
+    }
+
+    fn targets(repo: &Repository) -> Vec<ProblemTarget> {
+        repo.all()
+            .unwrap()
+            .into_iter()
+            .map(|problem| problem.target)
+            .collect()
+    }
+
+    #[test]
+    fn lists_problems_by_path() {
+        let (con, repo) = setup();
+        let thumbnail = insert_broken_picture(&con, "b.jpg", ProblemCategory::Thumbnail);
+        let transcode = insert_video(&con, "a.mov", ProblemCategory::Transcode);
+        let face_scan = insert_picture(&con, "c.jpg");
+        insert_broken_face_scan(&con, face_scan);
+        insert_picture(&con, "d.jpg");
+
+        // A broken picture is listed once, even if its face scan failed too.
+        insert_broken_face_scan(&con, thumbnail);
+
+        let problems = repo.all().unwrap();
+        assert_eq!(
+            problems.iter().map(|x| x.target).collect::<Vec<_>>(),
+            vec![
+                ProblemTarget::Video(transcode),
+                ProblemTarget::Picture(thumbnail),
+                ProblemTarget::FaceScan(face_scan),
+            ]
+        );
+        assert_eq!(problems[0].path, PathBuf::from("a.mov"));
+        assert_eq!(problems[0].category, Some(ProblemCategory::Transcode));
+        assert_eq!(problems[0].message.as_deref(), Some("oops"));
+        assert_eq!(problems[2].category, Some(ProblemCategory::FaceScan));
+    }
+
+    #[test]
+    fn retry_category_leaves_other_categories() {
+        let (con, mut repo) = setup();
+        let thumbnail = insert_broken_picture(&con, "a.jpg", ProblemCategory::Thumbnail);
+        fail_job(&con, JobKind::PhotoThumbnail, thumbnail.id());
+        let video_id = insert_video(&con, "b.mov", ProblemCategory::Transcode);
+        let picture_id = insert_picture(&con, "c.jpg");
+        insert_broken_face_scan(&con, picture_id);
+        let ready = count_ready(&con, JobKind::PhotoThumbnail);
+
+        // The failed thumbnail job is queued again.
+        assert_eq!(repo.retry_category(ProblemCategory::Thumbnail).unwrap(), 1);
+        assert_eq!(count_ready(&con, JobKind::PhotoThumbnail), ready + 1);
+        assert_eq!(
+            targets(&repo),
+            vec![
+                ProblemTarget::Video(video_id),
+                ProblemTarget::FaceScan(picture_id),
+            ]
+        );
+
+        assert_eq!(repo.retry_category(ProblemCategory::FaceScan).unwrap(), 1);
+        assert_eq!(targets(&repo), vec![ProblemTarget::Video(video_id)]);
+    }
+}
@@ -30,4 +30,11 @@ pub enum ThumbnailError {
 
     #[error("Image resize error: {0}")]
     ResizeError(#[from] fast_image_resize::ResizeError),
+
+    #[error("Image buffer error: {0}")]
+    ImageBuffer(#[from] fast_image_resize::ImageBufferError),
+
+    /// Thumbnails can only be made from 8-bit RGB or RGBA pixels.
+    #[error("Unsupported pixel type")]
+    UnsupportedPixelType,
 }
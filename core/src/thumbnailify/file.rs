@@ -3,9 +3,11 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use std::os::unix::fs::DirBuilderExt;
 use std::{
     fs,
     fs::File,
+    io,
     io::{BufReader, BufWriter},
     path::{Path, PathBuf},
     time::UNIX_EPOCH,
@@ -18,6 +20,7 @@ use url::Url;
 
 use crate::FlatpakPathBuf;
 use crate::thumbnailify::hash;
+use crate::thumbnailify::thumbnailer::is_thumbnail_up_to_date;
 use crate::thumbnailify::{error::ThumbnailError, sizes::ThumbnailSize};
 
 pub fn get_thumbnail_path(
@@ -30,6 +33,12 @@ pub fn get_thumbnail_path(
     get_thumbnail_hash_output(thumbnails_base_dir, &file_uri_hash, size)
 }
 
+/// Gets the directory holding thumbnails of one size.
+/// Format: `{cache_dir}/thumbnails/{size}`
+pub fn get_thumbnail_dir(thumbnails_base_dir: &Path, size: ThumbnailSize) -> PathBuf {
+    thumbnails_base_dir.join(size.to_string())
+}
+
 /// Gets the thumbnail output path using hash and size.
 /// Format: `{cache_dir}/thumbnails/{size}/{md5_hash}.png`
 pub fn get_thumbnail_hash_output(
@@ -37,7 +46,7 @@ pub fn get_thumbnail_hash_output(
     hash: &str,
     size: ThumbnailSize,
 ) -> PathBuf {
-    let output_dir = thumbnails_base_dir.join(size.to_string());
+    let output_dir = get_thumbnail_dir(thumbnails_base_dir, size);
     let output_file = format!("{}.png", hash);
     let path = output_dir.join(output_file);
 
@@ -48,9 +57,27 @@ pub fn get_thumbnail_hash_output(
     path
 }
 
-pub fn get_failed_thumbnail_output(thumbnails_base_dir: &Path, hash: &str) -> PathBuf {
+/// Gets the video storyboard path using hash.
+/// Storyboards aren't part of the thumbnail specification, so live in their own directory.
+/// Format: `{cache_dir}/thumbnails/storyboards/{md5_hash}.jpg`
+pub fn get_storyboard_hash_output(thumbnails_base_dir: &Path, hash: &str) -> PathBuf {
+    get_storyboard_dir(thumbnails_base_dir).join(format!("{}.jpg", hash))
+}
+
+/// Gets the directory holding video storyboards.
+pub fn get_storyboard_dir(thumbnails_base_dir: &Path) -> PathBuf {
+    thumbnails_base_dir.join("storyboards")
+}
+
+/// Gets the directory holding Fotema's failed thumbnail markers.
+/// Format: `{cache_dir}/thumbnails/fail/{app_id}`
+pub fn get_failed_thumbnail_dir(thumbnails_base_dir: &Path) -> PathBuf {
     // FIXME don't hardcode app-id.
-    let fail_dir = thumbnails_base_dir.join("fail").join("app.fotema.Fotema");
+    thumbnails_base_dir.join("fail").join("app.fotema.Fotema")
+}
+
+pub fn get_failed_thumbnail_output(thumbnails_base_dir: &Path, hash: &str) -> PathBuf {
+    let fail_dir = get_failed_thumbnail_dir(thumbnails_base_dir);
     let output_file = format!("{}.png", hash);
     let path = fail_dir.join(output_file);
 
@@ -61,13 +88,24 @@ pub fn get_failed_thumbnail_output(thumbnails_base_dir: &Path, hash: &str) -> Pa
     path
 }
 
-/// Returns the output path for a failed thumbnail marker.
-/// This uses the fails folder under the thumbnails cache.
-pub fn is_failed(thumbnails_base_dir: &Path, host_path: &Path) -> bool {
-    let file_uri = get_file_uri(&host_path).unwrap();
+/// Checks for a failed thumbnail marker in the fails folder under the thumbnails cache.
+/// Markers for files that have changed since the marker was written are ignored,
+/// so changed files are tried again.
+pub fn is_failed(thumbnails_base_dir: &Path, path: &FlatpakPathBuf) -> bool {
+    let file_uri = get_file_uri(&path.host_path).unwrap();
     let file_uri_hash = hash::compute_hash(&file_uri);
     let failed_path = get_failed_thumbnail_output(thumbnails_base_dir, &file_uri_hash);
-    failed_path.exists()
+    failed_path.exists() && is_thumbnail_up_to_date(&failed_path, path)
+}
+
+/// Creates a thumbnail directory and any missing parents.
+/// The thumbnail specification requires directories to be private to the user.
+/// Existing directories, such as a shared thumbnail cache made by another app, are left alone.
+pub fn create_thumbnail_dir(dir: &Path) -> io::Result<()> {
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
 }
 
 /// Writes a failed thumbnail using an empty (1x1 transparent) DynamicImage.
@@ -84,15 +122,27 @@ pub fn write_failed_thumbnail(
         fail_path, path.host_path
     );
 
-    fail_path.parent().as_ref().map(|p| fs::create_dir_all(p));
+    let fail_dir = fail_path.parent().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::Other,
+            "Fail marker path has no parent directory",
+        )
+    })?;
+
+    create_thumbnail_dir(fail_dir)?;
+
+    // Temporary files are only readable by the user, as the thumbnail specification requires.
+    let named_temp = tempfile::Builder::new()
+        .prefix("fail-")
+        .suffix(".png.tmp")
+        .tempfile_in(fail_dir)?;
 
     let failed_img: DynamicImage =
         DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba([0, 0, 0, 0])));
 
     let failed_img: RgbaImage = failed_img.to_rgba8();
 
-    // FIXME write to temporary file first and then move to final path.
-    let file = File::create(&fail_path)?;
+    let file = named_temp.reopen()?;
     let mut encoder = Encoder::new(BufWriter::new(file), 1, 1);
     encoder.set_color(png::ColorType::Rgba);
     encoder.set_depth(png::BitDepth::Eight);
@@ -120,11 +170,27 @@ pub fn write_failed_thumbnail(
     // Write out the PNG header
     let mut writer = encoder.write_header()?;
     writer.write_image_data(&failed_img.into_raw())?;
+    drop(writer); // flush
+
+    named_temp.persist(&fail_path)?;
 
     debug!("Successfully wrote failure marker file to {:?}", fail_path);
     return Ok(());
 }
 
+/// Reads the `Thumb::URI` of the file a thumbnail was made from.
+/// Returns `None` if the thumbnail can't be read or has no URI.
+pub fn read_thumbnail_uri(thumb_path: &Path) -> Option<String> {
+    let file = File::open(thumb_path).ok()?;
+    let reader = Decoder::new(BufReader::new(file)).read_info().ok()?;
+    reader
+        .info()
+        .uncompressed_latin1_text
+        .iter()
+        .find(|c| c.keyword == "Thumb::URI")
+        .map(|c| c.text.clone())
+}
+
 /// Attempts to convert the file path into a file URI.
 /// `input` must be a host path.
 pub fn get_file_uri(input: &Path) -> Result<String, ThumbnailError> {
@@ -0,0 +1,427 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Housekeeping for the thumbnail cache. Thumbnails of files that have left the
+//! library are swept away, failure markers expire when Fotema is upgraded,
+//! and thumbnails can be removed so they are generated again.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+use url::Url;
+
+use crate::FlatpakPathBuf;
+use crate::thumbnailify::{ThumbnailError, ThumbnailSize, Thumbnailer, file, hash};
+
+/// Records which version of Fotema wrote the failure markers.
+const FAILED_VERSION_FILE: &str = ".version";
+
+/// Part of the thumbnail cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheArea {
+    /// Thumbnails of one size.
+    Thumbnails(ThumbnailSize),
+
+    /// Video storyboards shown when scrubbing.
+    Storyboards,
+
+    /// Markers for files that couldn't be thumbnailed.
+    Failed,
+}
+
+/// Disk space used by part of the thumbnail cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AreaUsage {
+    pub area: CacheArea,
+
+    /// Number of files.
+    pub files: usize,
+
+    /// Total size of files in bytes.
+    pub bytes: u64,
+}
+
+/// Disk usage of each area of the thumbnail cache, largest thumbnails first.
+/// A shared cache also holds thumbnails made by other apps, which are included,
+/// as are thumbnails of edits kept in the private cache.
+pub fn disk_usage(thumbnailer: &Thumbnailer) -> Vec<AreaUsage> {
+    let mut areas: Vec<(CacheArea, Vec<PathBuf>)> = ThumbnailSize::ALL
+        .iter()
+        .rev()
+        .map(|size| {
+            let dirs = thumbnailer
+                .base_paths()
+                .into_iter()
+                .map(|base| file::get_thumbnail_dir(base, *size))
+                .collect();
+            (CacheArea::Thumbnails(*size), dirs)
+        })
+        .collect();
+
+    areas.push((
+        CacheArea::Storyboards,
+        vec![file::get_storyboard_dir(&thumbnailer.private_path)],
+    ));
+
+    areas.push((
+        CacheArea::Failed,
+        vec![file::get_failed_thumbnail_dir(&thumbnailer.thumbnails_path)],
+    ));
+
+    areas
+        .into_iter()
+        .map(|(area, dirs)| {
+            let mut usage = AreaUsage {
+                area,
+                files: 0,
+                bytes: 0,
+            };
+
+            for (_, path) in dirs.iter().flat_map(|dir| list_cached(dir)) {
+                if let Ok(metadata) = fs::metadata(&path) {
+                    usage.files += 1;
+                    usage.bytes += metadata.len();
+                }
+            }
+
+            usage
+        })
+        .collect()
+}
+
+/// Removes thumbnails, storyboards and failure markers of files that are no longer
+/// in the library. `library_paths` must be every picture and video in the library,
+/// including broken ones. `edited_hashes` are the thumbnail hashes of the current
+/// edits and orientation overrides, so thumbnails of earlier or reverted ones are removed.
+///
+/// A shared cache also holds thumbnails made by other apps, such as thumbnails of
+/// documents in the library directory. So only thumbnails of missing files, and of
+/// edits and overrides, under the library directory are removed from a shared cache.
+///
+/// Returns the number of files removed.
+pub fn sweep_orphans(
+    thumbnailer: &Thumbnailer,
+    library_base_dir: &FlatpakPathBuf,
+    library_paths: &[FlatpakPathBuf],
+    edited_hashes: &[String],
+) -> Result<usize, ThumbnailError> {
+    let mut known: HashSet<String> = library_paths
+        .iter()
+        .map(compute_hash)
+        .collect::<Result<_, _>>()?;
+
+    let known_files = known.clone();
+    known.extend(edited_hashes.iter().cloned());
+
+    let is_orphan = |hash: &str, thumb_path: &Path| {
+        if known.contains(hash) {
+            false
+        } else if thumbnailer.is_shared {
+            is_shared_orphan(hash, thumb_path, library_base_dir)
+        } else {
+            true
+        }
+    };
+
+    let mut count = 0;
+
+    for size in ThumbnailSize::ALL {
+        let dir = file::get_thumbnail_dir(&thumbnailer.thumbnails_path, size);
+        count += remove_all(
+            list_cached(&dir)
+                .into_iter()
+                .filter(|(hash, path)| is_orphan(hash, path))
+                .map(|(_, path)| path),
+        );
+
+        // Thumbnails of edits kept out of a shared cache are always Fotema's own.
+        if thumbnailer.is_shared {
+            let dir = file::get_thumbnail_dir(&thumbnailer.private_path, size);
+            count += remove_all(
+                list_cached(&dir)
+                    .into_iter()
+                    .filter(|(hash, _)| !known.contains(hash))
+                    .map(|(_, path)| path),
+            );
+        }
+    }
+
+    // Storyboards and failure markers are always Fotema's own.
+    let private_dirs = [
+        file::get_storyboard_dir(&thumbnailer.private_path),
+        file::get_failed_thumbnail_dir(&thumbnailer.thumbnails_path),
+    ];
+
+    for dir in private_dirs {
+        count += remove_all(
+            list_cached(&dir)
+                .into_iter()
+                .filter(|(hash, _)| !known_files.contains(hash))
+                .map(|(_, path)| path),
+        );
+    }
+
+    info!("Swept {} orphaned files from thumbnail cache", count);
+
+    Ok(count)
+}
+
+/// Removes failure markers written by any other version of Fotema, so files that
+/// couldn't be thumbnailed are tried again after an upgrade.
+/// Returns the number of markers removed.
+pub fn expire_failed(thumbnailer: &Thumbnailer, version: &str) -> Result<usize, ThumbnailError> {
+    let fail_dir = file::get_failed_thumbnail_dir(&thumbnailer.thumbnails_path);
+    let version_path = fail_dir.join(FAILED_VERSION_FILE);
+
+    let previous = fs::read_to_string(&version_path).ok();
+    if previous.as_deref().map(str::trim) == Some(version) {
+        return Ok(0);
+    }
+
+    let count = remove_all(list_cached(&fail_dir).into_iter().map(|(_, path)| path));
+
+    file::create_thumbnail_dir(&fail_dir)?;
+    fs::write(&version_path, version)?;
+
+    info!(
+        "Expired {} failed thumbnail markers written by version {:?}",
+        count, previous
+    );
+
+    Ok(count)
+}
+
+/// Removes the failure marker of a file, so it is tried again.
+pub fn remove_failed(
+    thumbnailer: &Thumbnailer,
+    path: &FlatpakPathBuf,
+) -> Result<(), ThumbnailError> {
+    let hash = compute_hash(path)?;
+    let fail_path = file::get_failed_thumbnail_output(&thumbnailer.thumbnails_path, &hash);
+    remove_all([fail_path]);
+    Ok(())
+}
+
+/// Removes all thumbnails, storyboards and failure markers of files so they are
+/// generated again. Returns the number of files removed.
+pub fn remove_thumbnails(
+    thumbnailer: &Thumbnailer,
+    paths: &[FlatpakPathBuf],
+) -> Result<usize, ThumbnailError> {
+    let mut count = 0;
+
+    for path in paths {
+        let hash = compute_hash(path)?;
+
+        let mut outputs = thumbnail_outputs(thumbnailer, &hash);
+
+        outputs.push(file::get_storyboard_hash_output(
+            &thumbnailer.private_path,
+            &hash,
+        ));
+
+        outputs.push(file::get_failed_thumbnail_output(
+            &thumbnailer.thumbnails_path,
+            &hash,
+        ));
+
+        count += remove_all(outputs);
+    }
+
+    info!("Removed {} thumbnail files for regeneration", count);
+
+    Ok(count)
+}
+
+/// Removes thumbnails of edited and reoriented items so they are generated again.
+/// Returns the number of files removed.
+pub fn remove_edited_thumbnails(thumbnailer: &Thumbnailer, edited_hashes: &[String]) -> usize {
+    let count = remove_all(
+        edited_hashes
+            .iter()
+            .flat_map(|hash| thumbnail_outputs(thumbnailer, hash)),
+    );
+
+    info!("Removed {} edited thumbnail files for regeneration", count);
+
+    count
+}
+
+/// Paths of every size of thumbnail with a hash, in both the shared and private caches.
+fn thumbnail_outputs(thumbnailer: &Thumbnailer, hash: &str) -> Vec<PathBuf> {
+    thumbnailer
+        .base_paths()
+        .into_iter()
+        .flat_map(|base| {
+            ThumbnailSize::ALL
+                .iter()
+                .map(move |size| file::get_thumbnail_hash_output(base, hash, *size))
+        })
+        .collect()
+}
+
+fn compute_hash(path: &FlatpakPathBuf) -> Result<String, ThumbnailError> {
+    let file_uri = file::get_file_uri(&path.host_path)?;
+    Ok(hash::compute_hash(&file_uri))
+}
+
+/// Files in a cache directory, paired with the hash in their file name.
+/// Temporary files and anything else not named after a hash are skipped.
+fn list_cached(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let hash = path.file_stem()?.to_str()?.to_string();
+            let is_hash = hash.len() == 32 && hash.chars().all(|c| c.is_ascii_hexdigit());
+            is_hash.then_some((hash, path))
+        })
+        .collect()
+}
+
+/// Is a thumbnail in a shared cache for a file under the library directory that
+/// no longer exists, or for edits of a file under the library directory?
+fn is_shared_orphan(hash: &str, thumb_path: &Path, library_base_dir: &FlatpakPathBuf) -> bool {
+    let Some(uri) = file::read_thumbnail_uri(thumb_path) else {
+        return false;
+    };
+
+    let Some(host_path) = Url::parse(&uri)
+        .ok()
+        .and_then(|url| url.to_file_path().ok())
+    else {
+        return false;
+    };
+
+    let Ok(relative) = host_path.strip_prefix(&library_base_dir.host_path) else {
+        return false;
+    };
+
+    // Other apps name thumbnails after the URI alone, so a thumbnail with some other
+    // name is of edits that are no longer wanted.
+    if hash::compute_hash(&uri) != hash {
+        return true;
+    }
+
+    // Thumbnail URIs are host paths, but files can only be checked inside the sandbox.
+    !library_base_dir.sandbox_path.join(relative).exists()
+}
+
+/// Removes files, returning how many were removed. Missing files are ignored.
+fn remove_all(files: impl IntoIterator<Item = PathBuf>) -> usize {
+    let mut count = 0;
+    for path in files {
+        match fs::remove_file(&path) {
+            Ok(()) => {
+                debug!("Removed {:?}", path);
+                count += 1;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed removing {:?}: {}", path, e),
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, b"png").unwrap();
+    }
+
+    #[test]
+    fn sweeps_thumbnails_of_files_not_in_library() {
+        let dir = tempfile::tempdir().unwrap();
+        let thumbnailer = Thumbnailer::build(dir.path());
+
+        let kept = FlatpakPathBuf::build("/pictures/kept.jpg", "/pictures/kept.jpg");
+        let gone = FlatpakPathBuf::build("/pictures/gone.jpg", "/pictures/gone.jpg");
+
+        for path in [&kept, &gone] {
+            let hash = compute_hash(path).unwrap();
+            touch(&thumbnailer.get_thumbnail_hash_output(&hash, ThumbnailSize::Normal));
+            touch(&thumbnailer.get_storyboard_path(&hash));
+        }
+
+        // Not a thumbnail, so never removed.
+        touch(&dir.path().join("normal").join("notes.txt"));
+
+        let library_base_dir = FlatpakPathBuf::build("/pictures", "/pictures");
+        let count = sweep_orphans(
+            &thumbnailer,
+            &library_base_dir,
+            std::slice::from_ref(&kept),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(count, 2);
+
+        let usage = disk_usage(&thumbnailer);
+        let normal = usage
+            .iter()
+            .find(|u| u.area == CacheArea::Thumbnails(ThumbnailSize::Normal))
+            .unwrap();
+        assert_eq!(normal.files, 1);
+        assert_eq!(normal.bytes, 3);
+
+        assert_eq!(remove_thumbnails(&thumbnailer, &[kept]).unwrap(), 2);
+    }
+
+    #[test]
+    fn sweeps_edited_thumbnails_from_private_cache() {
+        let shared_dir = tempfile::tempdir().unwrap();
+        let private_dir = tempfile::tempdir().unwrap();
+        let thumbnailer = Thumbnailer::build_shared(shared_dir.path(), private_dir.path());
+
+        let kept = hash::compute_hash("file:///pictures/a.jpg#orientation=6");
+        let reverted = hash::compute_hash("file:///pictures/a.jpg#orientation=3");
+
+        // Edited thumbnails aren't in the shared cache, so are found in the private one.
+        let kept_path = thumbnailer.get_thumbnail_hash_output(&kept, ThumbnailSize::Small);
+        assert!(kept_path.starts_with(private_dir.path()));
+
+        for hash in [&kept, &reverted] {
+            touch(&thumbnailer.get_thumbnail_hash_output(hash, ThumbnailSize::Small));
+        }
+
+        let library_base_dir = FlatpakPathBuf::build("/pictures", "/pictures");
+        let count = sweep_orphans(
+            &thumbnailer,
+            &library_base_dir,
+            &[],
+            std::slice::from_ref(&kept),
+        )
+        .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(
+            thumbnailer.nearest_thumbnail(&kept, ThumbnailSize::Large),
+            Some(kept_path)
+        );
+
+        assert_eq!(remove_edited_thumbnails(&thumbnailer, &[kept]), 1);
+    }
+
+    #[test]
+    fn expires_failed_markers_from_other_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let thumbnailer = Thumbnailer::build(dir.path());
+        let fail_dir = file::get_failed_thumbnail_dir(dir.path());
+
+        touch(&fail_dir.join(format!("{}.png", hash::compute_hash("file:///a.jpg"))));
+        assert_eq!(expire_failed(&thumbnailer, "1.0").unwrap(), 1);
+
+        touch(&fail_dir.join(format!("{}.png", hash::compute_hash("file:///b.jpg"))));
+        assert_eq!(expire_failed(&thumbnailer, "1.0").unwrap(), 0);
+        assert_eq!(expire_failed(&thumbnailer, "1.1").unwrap(), 1);
+    }
+}
@@ -2,12 +2,14 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use fast_image_resize::IntoImageView;
 use image::DynamicImage;
 use std::path::{Path, PathBuf};
 
 pub mod error;
 pub mod file;
 pub mod hash;
+pub mod maintenance;
 pub mod sizes;
 pub mod thumbnailer;
 
@@ -22,34 +24,124 @@ pub use sizes::ThumbnailSize;
 pub use thumbnailer::generate_thumbnail;
 
 use crate::FlatpakPathBuf;
+use crate::edits::EditStack;
+use crate::orientation::Orientation;
 
 pub fn compute_hash_for_path(host_path: &Path) -> String {
     let file_uri = file::get_file_uri(host_path).unwrap();
     hash::compute_hash(&file_uri)
 }
 
+/// Hash of thumbnails of a picture or video that has an orientation chosen by the user,
+/// or edits. Each distinct orientation and set of edits has its own thumbnails, so
+/// thumbnails never have to be checked for being out of date.
+/// Without an orientation or edits, this is the same as `compute_hash_for_path`.
+pub fn compute_hash_for_edited_path(
+    host_path: &Path,
+    orientation: Option<Orientation>,
+    edits: Option<&EditStack>,
+) -> String {
+    let mut key = file::get_file_uri(host_path).unwrap();
+    if let Some(orientation) = orientation {
+        key.push_str(&format!("#orientation={}", orientation as u32));
+    }
+    if let Some(edits) = edits.filter(|edits| !edits.is_empty()) {
+        key.push_str(&format!("#edits={}", edits));
+    }
+    hash::compute_hash(&key)
+}
+
+/// Sizes generated for each picture and video in a private cache, largest first.
+const PRIVATE_SIZES: &[ThumbnailSize] = &[
+    ThumbnailSize::XLarge,
+    ThumbnailSize::Large,
+    ThumbnailSize::Normal,
+    ThumbnailSize::Small,
+];
+
 #[derive(Clone, Debug)]
 pub struct Thumbnailer {
+    /// Base directory of thumbnails, laid out as the XDG thumbnail specification describes.
     thumbnails_path: PathBuf,
+
+    /// Base directory for previews that aren't part of the thumbnail specification,
+    /// such as video storyboards.
+    private_path: PathBuf,
+
+    /// Is the thumbnails directory shared with other apps?
+    is_shared: bool,
 }
 
 impl Thumbnailer {
+    /// Thumbnails that are private to Fotema.
     pub fn build(thumbnails_path: &Path) -> Thumbnailer {
         Thumbnailer {
             thumbnails_path: thumbnails_path.into(),
+            private_path: thumbnails_path.into(),
+            is_shared: false,
+        }
+    }
+
+    /// Thumbnails that are shared with file managers and other apps using the
+    /// XDG thumbnail cache.
+    pub fn build_shared(thumbnails_path: &Path, private_path: &Path) -> Thumbnailer {
+        Thumbnailer {
+            thumbnails_path: thumbnails_path.into(),
+            private_path: private_path.into(),
+            is_shared: true,
         }
     }
 
-    pub fn is_failed(&self, host_path: &Path) -> bool {
-        file::is_failed(&self.thumbnails_path, host_path)
+    pub fn thumbnails_path(&self) -> &Path {
+        &self.thumbnails_path
+    }
+
+    /// Sizes generated for each picture and video, largest first.
+    /// Small thumbnails aren't part of the specification, so aren't put in a shared cache.
+    pub fn sizes(&self) -> &'static [ThumbnailSize] {
+        if self.is_shared {
+            &[
+                ThumbnailSize::XLarge,
+                ThumbnailSize::Large,
+                ThumbnailSize::Normal,
+            ]
+        } else {
+            PRIVATE_SIZES
+        }
     }
 
-    pub fn is_thumbnail_up_to_date(&self, host_path: &Path) -> bool {
-        thumbnailer::is_thumbnail_up_to_date(&self.thumbnails_path, host_path)
+    pub fn is_failed(&self, path: &FlatpakPathBuf) -> bool {
+        file::is_failed(&self.thumbnails_path, path)
     }
 
+    pub fn is_thumbnail_up_to_date(&self, path: &FlatpakPathBuf, size: ThumbnailSize) -> bool {
+        let thumb_path = get_thumbnail_path(&self.thumbnails_path, &path.host_path, size);
+        thumbnailer::is_thumbnail_up_to_date(&thumb_path, path)
+    }
+
+    /// Path of a thumbnail with the given hash. Thumbnails of edited pictures and videos
+    /// are kept out of a shared cache, so when one isn't in the shared cache this is
+    /// the path in the private cache.
     pub fn get_thumbnail_hash_output(&self, hash: &str, size: ThumbnailSize) -> PathBuf {
-        get_thumbnail_hash_output(&self.thumbnails_path, hash, size)
+        let shared = get_thumbnail_hash_output(&self.thumbnails_path, hash, size);
+        if !self.is_shared || shared.exists() {
+            shared
+        } else {
+            get_thumbnail_hash_output(&self.private_path, hash, size)
+        }
+    }
+
+    /// Base directories that hold thumbnails, the shared cache first.
+    fn base_paths(&self) -> Vec<&Path> {
+        if self.is_shared {
+            vec![&self.thumbnails_path, &self.private_path]
+        } else {
+            vec![&self.thumbnails_path]
+        }
+    }
+
+    pub fn get_storyboard_path(&self, hash: &str) -> PathBuf {
+        file::get_storyboard_hash_output(&self.private_path, hash)
     }
 
     pub fn get_thumbnail_path(&self, host_path: &Path, size: ThumbnailSize) -> PathBuf {
@@ -64,37 +156,54 @@ impl Thumbnailer {
      * If no thumbnails exist, then return preferred path pointing to absent file.
      */
     pub fn nearest_thumbnail(&self, hash: &str, size: ThumbnailSize) -> Option<PathBuf> {
-        let preferred = file::get_thumbnail_hash_output(&self.thumbnails_path, hash, size);
-
-        if preferred.exists() {
-            Some(preferred)
-        } else {
-            let xxlarge = file::get_thumbnail_hash_output(
-                &self.thumbnails_path,
-                hash,
+        let sizes = match size {
+            // TODO figure out if some fallback sizes should be excluded?
+            // Do I want a request for a small thumbnail to return an XXLarge?
+            ThumbnailSize::Small => [
+                ThumbnailSize::Small,
+                ThumbnailSize::Normal,
+                ThumbnailSize::Large,
+                ThumbnailSize::XLarge,
                 ThumbnailSize::XXLarge,
-            );
-            let xlarge =
-                file::get_thumbnail_hash_output(&self.thumbnails_path, hash, ThumbnailSize::XLarge);
-            let large =
-                file::get_thumbnail_hash_output(&self.thumbnails_path, hash, ThumbnailSize::Large);
-            let normal =
-                file::get_thumbnail_hash_output(&self.thumbnails_path, hash, ThumbnailSize::Normal);
-            let small =
-                file::get_thumbnail_hash_output(&self.thumbnails_path, hash, ThumbnailSize::Small);
-
-            let paths = match size {
-                // TODO figure out if some fallback sizes should be excluded?
-                // Do I want a request for a small thumbnail to return an XXLarge?
-                ThumbnailSize::Small => [small, normal, large, xlarge, xxlarge],
-                ThumbnailSize::Normal => [normal, large, xlarge, xxlarge, small],
-                ThumbnailSize::Large => [large, xlarge, xxlarge, normal, small],
-                ThumbnailSize::XLarge => [xlarge, xxlarge, large, normal, small],
-                ThumbnailSize::XXLarge => [xxlarge, xlarge, large, normal, small],
-            };
-
-            paths.iter().find(|path| path.exists()).cloned()
-        }
+            ],
+            ThumbnailSize::Normal => [
+                ThumbnailSize::Normal,
+                ThumbnailSize::Large,
+                ThumbnailSize::XLarge,
+                ThumbnailSize::XXLarge,
+                ThumbnailSize::Small,
+            ],
+            ThumbnailSize::Large => [
+                ThumbnailSize::Large,
+                ThumbnailSize::XLarge,
+                ThumbnailSize::XXLarge,
+                ThumbnailSize::Normal,
+                ThumbnailSize::Small,
+            ],
+            ThumbnailSize::XLarge => [
+                ThumbnailSize::XLarge,
+                ThumbnailSize::XXLarge,
+                ThumbnailSize::Large,
+                ThumbnailSize::Normal,
+                ThumbnailSize::Small,
+            ],
+            ThumbnailSize::XXLarge => [
+                ThumbnailSize::XXLarge,
+                ThumbnailSize::XLarge,
+                ThumbnailSize::Large,
+                ThumbnailSize::Normal,
+                ThumbnailSize::Small,
+            ],
+        };
+
+        sizes
+            .iter()
+            .flat_map(|size| {
+                self.base_paths()
+                    .into_iter()
+                    .map(move |base| file::get_thumbnail_hash_output(base, hash, *size))
+            })
+            .find(|path| path.exists())
     }
 
     pub fn generate_thumbnail(
@@ -106,12 +215,43 @@ impl Thumbnailer {
         thumbnailer::generate_thumbnail(&self.thumbnails_path, path, size, src_image)
     }
 
+    /// Generate all standard thumbnail sizes from decoded RGB or RGBA pixels.
     pub fn generate_all_thumbnails(
         &self,
         path: &FlatpakPathBuf,
-        src_image: DynamicImage,
+        src_image: &impl IntoImageView,
+    ) -> Result<(), ThumbnailError> {
+        let hash = compute_hash_for_path(&path.host_path);
+        thumbnailer::generate_all_thumbnails(
+            &self.thumbnails_path,
+            path,
+            &hash,
+            self.sizes(),
+            src_image,
+        )
+    }
+
+    /// Generate all standard thumbnail sizes of an edited or reoriented picture or video
+    /// from its rendered pixels.
+    pub fn generate_edited_thumbnails(
+        &self,
+        path: &FlatpakPathBuf,
+        orientation: Option<Orientation>,
+        edits: Option<&EditStack>,
+        src_image: &impl IntoImageView,
     ) -> Result<(), ThumbnailError> {
-        thumbnailer::generate_all_thumbnails(&self.thumbnails_path, path, src_image)
+        let hash = compute_hash_for_edited_path(&path.host_path, orientation, edits);
+
+        // Other apps would show thumbnails of edits that Fotema hasn't saved to the file,
+        // so edited thumbnails never go in a shared cache.
+        let is_edited = hash != compute_hash_for_path(&path.host_path);
+        let (base_path, sizes) = if self.is_shared && is_edited {
+            (&self.private_path, PRIVATE_SIZES)
+        } else {
+            (&self.thumbnails_path, self.sizes())
+        };
+
+        thumbnailer::generate_all_thumbnails(base_path, path, &hash, sizes, src_image)
     }
 
     pub fn write_failed_thumbnail(&self, path: &FlatpakPathBuf) -> Result<(), ThumbnailError> {
@@ -4,7 +4,7 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 /// Predefined thumbnail sizes conforming to the XDG thumbnail standard.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ThumbnailSize {
     Small,
     Normal,
@@ -14,6 +14,15 @@ pub enum ThumbnailSize {
 }
 
 impl ThumbnailSize {
+    /// All sizes, smallest first.
+    pub const ALL: [ThumbnailSize; 5] = [
+        ThumbnailSize::Small,
+        ThumbnailSize::Normal,
+        ThumbnailSize::Large,
+        ThumbnailSize::XLarge,
+        ThumbnailSize::XXLarge,
+    ];
+
     /// Converts the thumbnail size into a maximum dimension (in pixels).
     ///
     /// For example:
@@ -6,7 +6,6 @@
 use std::collections::HashMap;
 use std::io::{BufReader, BufWriter};
 use std::{
-    fs,
     fs::File,
     io,
     path::{Path, PathBuf},
@@ -18,7 +17,9 @@ use tracing::{debug, info};
 use crate::FlatpakPathBuf;
 use crate::thumbnailify::{
     error::ThumbnailError,
-    file::{get_failed_thumbnail_output, get_file_uri, get_thumbnail_hash_output},
+    file::{
+        create_thumbnail_dir, get_failed_thumbnail_output, get_file_uri, get_thumbnail_hash_output,
+    },
     hash::compute_hash,
     sizes::ThumbnailSize,
 };
@@ -29,24 +30,26 @@ use image::DynamicImage;
 
 use fast_image_resize as fr;
 use fr::images::Image;
-use fr::{ResizeOptions, Resizer};
+use fr::{IntoImageView, ResizeOptions, Resizer};
 
 use png::Encoder as ExtendedPngEncoder;
 
 use tempfile;
 
 /// Checks whether the thumbnail file at `thumb_path` is up to date with respect
-/// to the source image at `source_path`. It verifies two metadata fields in the PNG:
+/// to the source image at `path`. It verifies three metadata fields in the PNG:
 ///
+/// - "Thumb::URI": the source file's URI, which must match because thumbnails made by
+///   other apps might be for a different file with the same hash.
 /// - "Thumb::MTime": the source file's modification time (in seconds since UNIX_EPOCH)
 /// - "Thumb::Size": the source file's size in bytes (only checked if present)
 ///
-/// Returns true if "Thumb::MTime" is present and matches the source file's modification time,
-/// and if "Thumb::Size" is present it must match the source file's size.
-pub fn is_thumbnail_up_to_date(thumb_path: &Path, host_path: &Path) -> bool {
+/// The URI is derived from the host path, but the modification time and size must be
+/// read from the sandbox path.
+pub fn is_thumbnail_up_to_date(thumb_path: &Path, path: &FlatpakPathBuf) -> bool {
     debug!(
         "Checking if thumbnail at {:?} is up-to-date with source {:?}",
-        thumb_path, host_path
+        thumb_path, path.host_path
     );
 
     let file = match File::open(thumb_path) {
@@ -69,6 +72,25 @@ pub fn is_thumbnail_up_to_date(thumb_path: &Path, host_path: &Path) -> bool {
 
     let texts = &reader.info().uncompressed_latin1_text;
 
+    let Ok(file_uri) = get_file_uri(&path.host_path) else {
+        return false;
+    };
+
+    match texts.iter().find(|c| c.keyword == "Thumb::URI") {
+        Some(c) if c.text == file_uri => {}
+        Some(c) => {
+            debug!(
+                "Thumb::URI mismatch: thumbnail={} source={}",
+                c.text, file_uri
+            );
+            return false;
+        }
+        None => {
+            debug!("Thumbnail missing 'Thumb::URI' metadata chunk.");
+            return false;
+        }
+    }
+
     let thumb_mtime_str = match texts.iter().find(|c| c.keyword == "Thumb::MTime") {
         Some(c) => &c.text,
         None => {
@@ -78,10 +100,13 @@ pub fn is_thumbnail_up_to_date(thumb_path: &Path, host_path: &Path) -> bool {
     };
     let thumb_mtime = thumb_mtime_str.parse::<u64>().unwrap_or(0);
 
-    let source_metadata = match std::fs::metadata(host_path) {
+    let source_metadata = match std::fs::metadata(&path.sandbox_path) {
         Ok(m) => m,
         Err(e) => {
-            debug!("Failed to get metadata of source {:?}: {}", host_path, e);
+            debug!(
+                "Failed to get metadata of source {:?}: {}",
+                path.sandbox_path, e
+            );
             return false;
         }
     };
@@ -91,7 +116,7 @@ pub fn is_thumbnail_up_to_date(thumb_path: &Path, host_path: &Path) -> bool {
         Err(e) => {
             debug!(
                 "Failed to read modified time of source {:?}: {}",
-                host_path, e
+                path.sandbox_path, e
             );
             return false;
         }
@@ -119,21 +144,47 @@ pub fn is_thumbnail_up_to_date(thumb_path: &Path, host_path: &Path) -> bool {
 
     debug!(
         "Thumbnail at {:?} is up-to-date with source {:?}",
-        thumb_path, host_path
+        thumb_path, path.host_path
     );
     true
 }
+
+/// Generate thumbnails, from the largest of `sizes` to the smallest, for a file that
+/// exists outside of the Flatpak sandbox.
+/// Each size is resized from the next larger size rather than from the source image,
+/// which is much faster for large pictures and looks no different.
+/// `hash` - names the thumbnails. Usually the hash of the file URI, but edited pictures
+/// have a hash of their own.
+/// `src_image` - decoded pixels, either RGB or RGBA. Pixels are read in place, so
+/// there is no need to copy or re-encode the decoded image first.
 pub fn generate_all_thumbnails(
     thumbnails_base_dir: &Path,
     path: &FlatpakPathBuf,
-    src_image: DynamicImage,
+    hash: &str,
+    sizes: &[ThumbnailSize],
+    src_image: &impl IntoImageView,
 ) -> Result<(), ThumbnailError> {
-    let mut labels: HashMap<String, String> = HashMap::with_capacity(3);
+    let Some(largest) = sizes.first() else {
+        return Ok(());
+    };
+
+    let file_uri = get_file_uri(&path.host_path)?;
+
+    // Check if the fail marker exists and is up to date.
+    // Markers are for files, so are always named after the hash of the file URI.
+    let fail_path = get_failed_thumbnail_output(thumbnails_base_dir, &compute_hash(&file_uri));
+    if fail_path.exists() && is_thumbnail_up_to_date(&fail_path, path) {
+        info!(
+            "A fail marker exists and is up-to-date, skipping thumbnails for {:?}",
+            path.host_path
+        );
+        return Ok(());
+    }
+
+    let mut labels: HashMap<String, String> = HashMap::with_capacity(4);
     // FIXME hard-coded app-id
     labels.insert("Software".into(), "app.fotema.Fotema".into());
-
-    let uri = get_file_uri(&path.host_path)?;
-    labels.insert("Thumb::URI".into(), uri);
+    labels.insert("Thumb::URI".into(), file_uri);
 
     let metadata = std::fs::metadata(&path.sandbox_path)?;
     let size = metadata.len();
@@ -147,100 +198,111 @@ pub fn generate_all_thumbnails(
 
     labels.insert("Thumb::MTime".into(), mtime_unix.to_string());
 
-    let sizes = &[
-        ThumbnailSize::XLarge,
-        ThumbnailSize::Large,
-        ThumbnailSize::Normal,
-        ThumbnailSize::Small,
-    ];
-
-    let src_image = DynamicImage::from(src_image.into_rgba8());
+    let pixel_type = src_image
+        .pixel_type()
+        .ok_or(ThumbnailError::UnsupportedPixelType)?;
 
-    let dimension = sizes[0].to_dimension() as f32;
+    let (thumbnail_width, thumbnail_height) =
+        scaled_dimensions(src_image.width(), src_image.height(), *largest);
 
-    let src_width: f32 = src_image.width() as f32;
-    let src_height: f32 = src_image.height() as f32;
-    let src_longest_edge = f32::max(src_width, src_height);
-
-    let scale: f32 = f32::min(1.0, dimension / src_longest_edge);
-
-    let thumbnail_width = (src_width * scale) as u32;
-    let thumbnail_height = (src_height * scale) as u32;
+    let mut resizer = Resizer::new();
 
     // An idea borrowed from Glycin.
     // Resize to double thumbnail size using a fast algorithm, and them
     // resize result to final size using high-quality algorithm.
-    // FIXME don't rough scale if smaller that double thumbnail size?
-    let src_image = rough_resize(src_image, thumbnail_width, thumbnail_height)?;
-
-    generate_thumbnail_recursive(thumbnails_base_dir, path, labels, sizes, src_image)
+    // Not worth it if the source image is already small.
+    let src_longest_edge = u32::max(src_image.width(), src_image.height());
+    if src_longest_edge > largest.to_dimension() * 2 {
+        let mut rough_scaled = Image::new(thumbnail_width * 2, thumbnail_height * 2, pixel_type);
+        let resize_options = ResizeOptions::new().resize_alg(fr::ResizeAlg::Nearest);
+        resizer.resize(src_image, &mut rough_scaled, &resize_options)?;
+
+        generate_thumbnail_cascade(
+            thumbnails_base_dir,
+            hash,
+            path,
+            &labels,
+            sizes,
+            &rough_scaled,
+            &mut resizer,
+        )
+    } else {
+        generate_thumbnail_cascade(
+            thumbnails_base_dir,
+            hash,
+            path,
+            &labels,
+            sizes,
+            src_image,
+            &mut resizer,
+        )
+    }
 }
 
-fn generate_thumbnail_recursive(
+/// Writes the first of `sizes` resized from `src_image`, then writes the remaining
+/// sizes resized from that.
+fn generate_thumbnail_cascade(
     thumbnails_base_dir: &Path,
+    hash: &str,
     path: &FlatpakPathBuf,
-    labels: HashMap<String, String>,
+    labels: &HashMap<String, String>,
     sizes: &[ThumbnailSize],
-    src_image: Image<'static>,
+    src_image: &impl IntoImageView,
+    resizer: &mut Resizer,
 ) -> Result<(), ThumbnailError> {
-    let size = if !sizes.is_empty() {
-        sizes[0]
-    } else {
+    let Some((size, smaller_sizes)) = sizes.split_first() else {
         return Ok(());
     };
 
-    // `canonicalize()` will fail if `host_path` does not exist... which means
-    // that it will __never work__ inside the Flatpak sandbox.
-    // let abs_path = host_path.canonicalize()?;
-
-    //let _ = abs_path
-    //    .to_str()
-    //   .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid file path"))?;
-
-    let file_uri = get_file_uri(&path.host_path)?;
-
-    // Compute the MD5 hash from the file URI.
-    let hash = compute_hash(&file_uri);
-
-    // Check if the fail marker exists and is up to date
-    let fail_path = get_failed_thumbnail_output(thumbnails_base_dir, &hash);
-    if fail_path.exists() && is_thumbnail_up_to_date(&fail_path, &path.sandbox_path) {
-        info!(
-            "A fail marker exists and is up-to-date, returning fail marker at {:?}",
-            fail_path
-        );
-
-        return generate_thumbnail_recursive(
-            thumbnails_base_dir,
-            path,
-            labels,
-            &sizes[1..],
-            src_image,
-        );
-    }
-
     // Determine the expected output thumbnail path.
-    let thumb_path = get_thumbnail_hash_output(thumbnails_base_dir, &hash, size);
+    let thumb_path = get_thumbnail_hash_output(thumbnails_base_dir, hash, *size);
 
-    // If the thumbnail already exists and is up to date, return it immediately.
-    if thumb_path.exists() && is_thumbnail_up_to_date(&thumb_path, &path.host_path) {
+    // If the thumbnail already exists and is up to date, skip to the next size.
+    if thumb_path.exists() && is_thumbnail_up_to_date(&thumb_path, path) {
         info!(
-            "Cached thumbnail at {:?} is up-to-date, returning it",
+            "Cached thumbnail at {:?} is up-to-date, skipping it",
             thumb_path
         );
-        return generate_thumbnail_recursive(
+        return generate_thumbnail_cascade(
             thumbnails_base_dir,
+            hash,
             path,
             labels,
-            &sizes[1..],
+            smaller_sizes,
             src_image,
+            resizer,
         );
     }
 
-    let thumbnail = quality_resize(src_image, size)?;
-    write_thumbnail(&thumb_path, &thumbnail, &labels)?;
+    let thumbnail = quality_resize(src_image, *size, resizer)?;
+    write_thumbnail(&thumb_path, &thumbnail, labels)?;
+
+    generate_thumbnail_cascade(
+        thumbnails_base_dir,
+        hash,
+        path,
+        labels,
+        smaller_sizes,
+        &thumbnail,
+        resizer,
+    )
+}
+
+/// Dimensions of a thumbnail that fits in `size`, keeping the aspect ratio.
+/// Images are never scaled up.
+fn scaled_dimensions(width: u32, height: u32, size: ThumbnailSize) -> (u32, u32) {
+    let dimension = size.to_dimension() as f32;
+
+    let src_width: f32 = width as f32;
+    let src_height: f32 = height as f32;
+    let src_longest_edge = f32::max(src_width, src_height);
+
+    let scale: f32 = f32::min(1.0, dimension / src_longest_edge);
 
-    generate_thumbnail_recursive(thumbnails_base_dir, path, labels, &sizes[1..], thumbnail)
+    // Very wide or tall images mustn't round down to nothing.
+    let thumbnail_width = ((src_width * scale) as u32).max(1);
+    let thumbnail_height = ((src_height * scale) as u32).max(1);
+    (thumbnail_width, thumbnail_height)
 }
 
 /// Generate a thumbnail for a file that exists outside of the Flatpak sandbox.
@@ -268,12 +330,11 @@ pub fn generate_thumbnail(
 
     let file_uri = get_file_uri(&path.host_path)?;
 
-    // Compute the MD5 hash from the file URI.
+    // Check if the fail marker exists and is up to date.
+    // Markers are for files, so are always named after the hash of the file URI.
     let hash = compute_hash(&file_uri);
-
-    // Check if the fail marker exists and is up to date
     let fail_path = get_failed_thumbnail_output(thumbnails_base_dir, &hash);
-    if fail_path.exists() && is_thumbnail_up_to_date(&fail_path, &path.sandbox_path) {
+    if fail_path.exists() && is_thumbnail_up_to_date(&fail_path, path) {
         info!(
             "A fail marker exists and is up-to-date, returning fail marker at {:?}",
             fail_path
@@ -285,7 +346,7 @@ pub fn generate_thumbnail(
     let thumb_path = get_thumbnail_hash_output(thumbnails_base_dir, &hash, size);
 
     // If the thumbnail already exists and is up to date, return it immediately.
-    if thumb_path.exists() && is_thumbnail_up_to_date(&thumb_path, &path.host_path) {
+    if thumb_path.exists() && is_thumbnail_up_to_date(&thumb_path, path) {
         info!(
             "Cached thumbnail at {:?} is up-to-date, returning it",
             thumb_path
@@ -302,7 +363,7 @@ pub fn generate_thumbnail(
         )
     })?;
 
-    fs::create_dir_all(thumb_dir)?;
+    create_thumbnail_dir(thumb_dir)?;
 
     let named_temp = tempfile::Builder::new()
         .prefix("thumb-")
@@ -395,57 +456,31 @@ fn resize(
     Ok(final_scaled)
 }
 
-fn rough_resize(
-    src_image: DynamicImage,
-    thumbnail_width: u32,
-    thumbnail_height: u32,
-) -> Result<Image<'static>, ThumbnailError> {
-    // An idea borrowed from Glycin.
-    // Resize to double thumbnail size using a fast algorithm, and them
-    // resize result to final size using high-quality algorithm.
-
-    let mut rough_scaled = Image::new(
-        thumbnail_width * 2,
-        thumbnail_height * 2,
-        fr::PixelType::U8x4,
-    );
-
-    let resize_options = ResizeOptions::new().resize_alg(fast_image_resize::ResizeAlg::Nearest);
-
-    let mut resizer = Resizer::new();
-    resizer.resize(&src_image, &mut rough_scaled, &resize_options)?;
-    Ok(rough_scaled)
-}
-
 fn quality_resize(
-    src_image: Image<'static>,
+    src_image: &impl IntoImageView,
     size: ThumbnailSize,
+    resizer: &mut Resizer,
 ) -> Result<Image<'static>, ThumbnailError> {
-    let dimension = size.to_dimension() as f32;
+    let pixel_type = src_image
+        .pixel_type()
+        .ok_or(ThumbnailError::UnsupportedPixelType)?;
 
-    let src_width: f32 = src_image.width() as f32;
-    let src_height: f32 = src_image.height() as f32;
-    let src_longest_edge = f32::max(src_width, src_height);
-
-    let scale: f32 = f32::min(1.0, dimension / src_longest_edge);
-
-    let thumbnail_width = (src_width * scale) as u32;
-    let thumbnail_height = (src_height * scale) as u32;
+    let (thumbnail_width, thumbnail_height) =
+        scaled_dimensions(src_image.width(), src_image.height(), size);
 
-    let mut thumbnail = Image::new(thumbnail_width, thumbnail_height, fr::PixelType::U8x4);
+    let mut thumbnail = Image::new(thumbnail_width, thumbnail_height, pixel_type);
 
-    let mut resizer = Resizer::new();
     let resize_options = ResizeOptions::new().resize_alg(
         fast_image_resize::ResizeAlg::Convolution(fast_image_resize::FilterType::Lanczos3),
     );
 
-    resizer.resize(&src_image, &mut thumbnail, &resize_options)?;
+    resizer.resize(src_image, &mut thumbnail, &resize_options)?;
     Ok(thumbnail)
 }
 
 fn write_thumbnail(
     thumb_path: &Path,
-    thumbnail: &Image<'static>,
+    thumbnail: &Image<'_>,
     labels: &HashMap<String, String>,
 ) -> Result<(), ThumbnailError> {
     // Prepare a temporary file in the same directory as the final thumbnail.
@@ -458,7 +493,7 @@ fn write_thumbnail(
         )
     })?;
 
-    fs::create_dir_all(thumb_dir)?;
+    create_thumbnail_dir(thumb_dir)?;
 
     let named_temp = tempfile::Builder::new()
         .prefix("thumb-")
@@ -470,8 +505,14 @@ fn write_thumbnail(
     let file = std::fs::File::create(&temp_path)?;
     let file = BufWriter::new(file);
 
+    let color_type = match thumbnail.pixel_type() {
+        fr::PixelType::U8x3 => png::ColorType::Rgb,
+        fr::PixelType::U8x4 => png::ColorType::Rgba,
+        _ => return Err(ThumbnailError::UnsupportedPixelType),
+    };
+
     let mut encoder = ExtendedPngEncoder::new(file, thumbnail.width(), thumbnail.height());
-    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_color(color_type);
     encoder.set_depth(png::BitDepth::Eight);
 
     for (key, value) in labels.iter() {
@@ -486,3 +527,49 @@ fn write_thumbnail(
     named_temp.persist(&thumb_path)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fr::images::ImageRef;
+
+    #[test]
+    fn generates_all_sizes_from_rgb_pixels() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source.jpg");
+        std::fs::write(&source, b"not really a picture").unwrap();
+        let path = FlatpakPathBuf::build(&source, &source);
+
+        let pixels = vec![128u8; 1500 * 1000 * 3];
+        let src_image = ImageRef::new(1500, 1000, &pixels, fr::PixelType::U8x3).unwrap();
+
+        let thumbnails_dir = dir.path().join("thumbnails");
+        let sizes = [
+            ThumbnailSize::XLarge,
+            ThumbnailSize::Large,
+            ThumbnailSize::Normal,
+            ThumbnailSize::Small,
+        ];
+        let hash = compute_hash(&get_file_uri(&source).unwrap());
+        generate_all_thumbnails(&thumbnails_dir, &path, &hash, &sizes, &src_image).unwrap();
+        for (size, width) in [
+            (ThumbnailSize::XLarge, 512),
+            (ThumbnailSize::Large, 256),
+            (ThumbnailSize::Normal, 128),
+            (ThumbnailSize::Small, 64),
+        ] {
+            let thumb_path = get_thumbnail_hash_output(&thumbnails_dir, &hash, size);
+            assert!(is_thumbnail_up_to_date(&thumb_path, &path));
+
+            let decoder = Decoder::new(BufReader::new(File::open(&thumb_path).unwrap()));
+            let reader = decoder.read_info().unwrap();
+            assert_eq!(reader.info().width, width);
+            assert_eq!(reader.info().color_type, png::ColorType::Rgb);
+        }
+
+        // Thumbnails with the right hash but for some other file must not be used.
+        let other = FlatpakPathBuf::build(dir.path().join("other.jpg"), &source);
+        let thumb_path = get_thumbnail_hash_output(&thumbnails_dir, &hash, ThumbnailSize::Large);
+        assert!(!is_thumbnail_up_to_date(&thumb_path, &other));
+    }
+}
@@ -0,0 +1,101 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::photo::gps::GPSLocation;
+use chrono::prelude::*;
+use chrono::{FixedOffset, LocalResult, NaiveDateTime, TimeDelta};
+use chrono_tz::Tz;
+use std::sync::LazyLock;
+use tracing::debug;
+use tzf_rs::DefaultFinder;
+
+/// Finder for time zone boundaries. The boundary data is embedded in the binary,
+/// so no network access is needed, but building the index isn't free so only do it once.
+static FINDER: LazyLock<DefaultFinder> = LazyLock::new(DefaultFinder::new);
+
+/// Time zone at a GPS location, if one can be found.
+pub fn zone_at(location: &GPSLocation) -> Option<Tz> {
+    let latitude = location.latitude.to_f64_safe()?;
+    let longitude = location.longitude.to_f64_safe()?;
+
+    let name = FINDER.get_tz_name(longitude, latitude);
+    let tz = name.parse::<Tz>().ok();
+    if tz.is_none() {
+        debug!("No time zone for '{}' at {},{}", name, latitude, longitude);
+    }
+    tz
+}
+
+/// Offset from UTC for a wall-clock time at a GPS location.
+/// Daylight saving time is taken into account.
+pub fn offset_at(location: &GPSLocation, local: &NaiveDateTime) -> Option<FixedOffset> {
+    let tz = zone_at(location)?;
+    resolve(tz.from_local_datetime(local))
+        // Wall-clock time skipped by a daylight saving transition, so must be a clock
+        // that hadn't sprung forward yet.
+        .or_else(|| resolve(tz.from_local_datetime(&(*local + TimeDelta::hours(1)))))
+        .map(|dt| dt.offset().fix())
+}
+
+/// Wall-clock time at a GPS location for a UTC timestamp.
+pub fn local_at(location: &GPSLocation, utc: &DateTime<Utc>) -> Option<NaiveDateTime> {
+    let tz = zone_at(location)?;
+    Some(utc.with_timezone(&tz).naive_local())
+}
+
+/// Offset from UTC for a wall-clock time when the place of capture isn't known.
+/// Cameras are most likely set to the time zone of wherever the user lives, so
+/// fall back to the time zone of this computer.
+pub fn system_offset(local: &NaiveDateTime) -> FixedOffset {
+    resolve(Local.from_local_datetime(local))
+        .map(|dt| dt.offset().fix())
+        .unwrap_or_else(|| FixedOffset::east_opt(0).expect("UTC offset"))
+}
+
+/// Wall-clock times in a daylight saving transition are ambiguous or don't exist.
+/// Pick the earliest interpretation rather than fail.
+fn resolve<T: TimeZone>(result: LocalResult<DateTime<T>>) -> Option<DateTime<T>> {
+    match result {
+        LocalResult::Single(dt) => Some(dt),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest),
+        LocalResult::None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offset_at_observes_daylight_saving() {
+        let london = GPSLocation::for_iso6709("+51.5072-000.1276/").unwrap();
+
+        let winter = NaiveDate::from_ymd_opt(2024, 1, 15)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        let summer = NaiveDate::from_ymd_opt(2024, 7, 15)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+
+        assert_eq!(
+            Some(0),
+            offset_at(&london, &winter).map(|x| x.local_minus_utc())
+        );
+        assert_eq!(
+            Some(3600),
+            offset_at(&london, &summer).map(|x| x.local_minus_utc())
+        );
+    }
+
+    #[test]
+    fn test_local_at() {
+        let tokyo = GPSLocation::for_iso6709("+35.6764+139.6500+040.000/").unwrap();
+        let utc = Utc.with_ymd_and_hms(2024, 12, 31, 20, 0, 0).unwrap();
+
+        let local = local_at(&tokyo, &utc).unwrap();
+        assert_eq!(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(), local.date());
+    }
+}
@@ -0,0 +1,263 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::photo::PictureId;
+use crate::video::VideoId;
+
+use anyhow::*;
+use chrono::{DateTime, Utc};
+use rusqlite::Row;
+use rusqlite::params;
+use std::path::{Path, PathBuf};
+use std::result::Result::Ok;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info, warn};
+
+/// Bytes in a gigabyte.
+pub const GIGABYTE: u64 = 1_000_000_000;
+
+/// Item that owns files in the video cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheOwner {
+    /// Transcoded video.
+    Video(VideoId),
+
+    /// Extracted, and possibly transcoded, motion photo video.
+    MotionPhoto(PictureId),
+}
+
+/// Files in the video cache belonging to one video or motion photo.
+#[derive(Debug, Clone)]
+pub struct CachedVideo {
+    pub owner: CacheOwner,
+
+    /// Absolute paths of cached files. Files might not exist if they have been evicted.
+    pub paths: Vec<PathBuf>,
+
+    /// When the video or motion photo was last viewed. None if never viewed.
+    pub accessed_ts: Option<DateTime<Utc>>,
+}
+
+/// Repository of transcoded and extracted videos in the cache directory.
+/// Repository is backed by a Sqlite database.
+///
+/// Evicting a video deletes its files but keeps the paths in the database. The paths
+/// are deterministic, so an evicted video can be restored to the same path, and a
+/// path that is set but doesn't exist marks a video as evicted.
+#[derive(Debug, Clone)]
+pub struct Repository {
+    /// Base path for transcoded videos
+    cache_dir_base_path: PathBuf,
+
+    /// Connection to backing Sqlite database.
+    con: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl Repository {
+    pub fn open(
+        cache_dir_base_path: &Path,
+        con: Arc<Mutex<rusqlite::Connection>>,
+    ) -> Result<Repository> {
+        Ok(Repository {
+            cache_dir_base_path: cache_dir_base_path.into(),
+            con,
+        })
+    }
+
+    /// Record that a video or motion photo has been viewed.
+    pub fn touch(&mut self, owner: CacheOwner) -> Result<()> {
+        let con = self.con.lock().unwrap();
+        match owner {
+            CacheOwner::Video(video_id) => {
+                con.execute(
+                    "UPDATE videos SET accessed_ts = CURRENT_TIMESTAMP WHERE video_id = ?1",
+                    params![video_id.id()],
+                )?;
+            }
+            CacheOwner::MotionPhoto(picture_id) => {
+                con.execute(
+                    "UPDATE motion_photos SET accessed_ts = CURRENT_TIMESTAMP WHERE picture_id = ?1",
+                    params![picture_id.id()],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Gets all videos and motion photos with cached files.
+    pub fn find_cached(&self) -> Result<Vec<CachedVideo>> {
+        let con = self.con.lock().unwrap();
+
+        let mut stmt = con.prepare(
+            "SELECT
+                video_id,
+                transcoded_path,
+                accessed_ts
+            FROM videos
+            WHERE transcoded_path IS NOT NULL",
+        )?;
+
+        let videos = stmt
+            .query_map([], |row| {
+                let video_id = row.get("video_id").map(VideoId::new)?;
+                self.to_cached(row, CacheOwner::Video(video_id), &["transcoded_path"])
+            })?
+            .flatten();
+
+        let mut cached: Vec<CachedVideo> = videos.collect();
+
+        let mut stmt = con.prepare(
+            "SELECT
+                picture_id,
+                video_path,
+                transcoded_path,
+                accessed_ts
+            FROM motion_photos
+            WHERE video_path IS NOT NULL",
+        )?;
+
+        let motion_photos = stmt
+            .query_map([], |row| {
+                let picture_id = row.get("picture_id").map(PictureId::new)?;
+                self.to_cached(
+                    row,
+                    CacheOwner::MotionPhoto(picture_id),
+                    &["video_path", "transcoded_path"],
+                )
+            })?
+            .flatten();
+
+        cached.extend(motion_photos);
+
+        Ok(cached)
+    }
+
+    fn to_cached(
+        &self,
+        row: &Row<'_>,
+        owner: CacheOwner,
+        path_columns: &[&str],
+    ) -> rusqlite::Result<CachedVideo> {
+        let mut paths = Vec::with_capacity(path_columns.len());
+        for column in path_columns {
+            let path: Option<String> = row.get(*column)?;
+            if let Some(path) = path {
+                paths.push(self.cache_dir_base_path.join(path));
+            }
+        }
+
+        Ok(CachedVideo {
+            owner,
+            paths,
+            accessed_ts: row.get("accessed_ts")?,
+        })
+    }
+}
+
+/// Bytes used by the files in the video cache.
+pub fn usage(repo: &Repository) -> Result<u64> {
+    Ok(sizes(repo)?.iter().map(|(_, size)| size).sum())
+}
+
+/// Cached videos with the bytes used by their files. Evicted videos are excluded.
+fn sizes(repo: &Repository) -> Result<Vec<(CachedVideo, u64)>> {
+    let cached = repo
+        .find_cached()?
+        .into_iter()
+        .map(|item| {
+            let size = item
+                .paths
+                .iter()
+                .filter_map(|path| std::fs::metadata(path).ok())
+                .map(|metadata| metadata.len())
+                .sum();
+            (item, size)
+        })
+        .filter(|(_, size)| *size > 0)
+        .collect();
+    Ok(cached)
+}
+
+/// Deletes cached videos, least recently viewed first, until the cache is within
+/// the budget. Returns the count of videos and motion photos evicted.
+pub fn evict(repo: &Repository, budget_bytes: u64) -> Result<usize> {
+    let cached = sizes(repo)?;
+
+    let total: u64 = cached.iter().map(|(_, size)| size).sum();
+    info!(
+        "Video cache uses {} bytes of a {} byte budget",
+        total, budget_bytes
+    );
+
+    let victims = select_evictions(cached, budget_bytes);
+
+    for item in victims.iter() {
+        debug!("Evicting {:?} from video cache", item.owner);
+        for path in item.paths.iter() {
+            if let Err(e) = std::fs::remove_file(path) {
+                warn!("Failed evicting {:?}: {}", path, e);
+            }
+        }
+    }
+
+    Ok(victims.len())
+}
+
+/// Least recently viewed items to remove to bring the cache within budget.
+/// Items never viewed are removed first.
+fn select_evictions(mut cached: Vec<(CachedVideo, u64)>, budget_bytes: u64) -> Vec<CachedVideo> {
+    let mut total: u64 = cached.iter().map(|(_, size)| size).sum();
+
+    // None sorts before Some, so never viewed items are evicted first.
+    cached.sort_by_key(|(item, _)| item.accessed_ts);
+
+    let mut victims = vec![];
+    for (item, size) in cached {
+        if total <= budget_bytes {
+            break;
+        }
+        total -= size;
+        victims.push(item);
+    }
+
+    victims
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn cached(id: i64, day: Option<u32>) -> CachedVideo {
+        CachedVideo {
+            owner: CacheOwner::Video(VideoId::new(id)),
+            paths: vec![],
+            accessed_ts: day.map(|day| Utc.with_ymd_and_hms(2026, 1, day, 0, 0, 0).unwrap()),
+        }
+    }
+
+    #[test]
+    fn evicts_least_recently_viewed_first() {
+        let items = vec![
+            (cached(1, Some(3)), 100),
+            (cached(2, Some(1)), 100),
+            (cached(3, None), 100),
+            (cached(4, Some(2)), 100),
+        ];
+
+        let victims = select_evictions(items, 200);
+        let owners: Vec<CacheOwner> = victims.into_iter().map(|v| v.owner).collect();
+
+        assert_eq!(
+            owners,
+            vec![
+                CacheOwner::Video(VideoId::new(3)),
+                CacheOwner::Video(VideoId::new(2))
+            ]
+        );
+
+        let items = vec![(cached(1, None), 100), (cached(2, Some(1)), 100)];
+        assert!(select_evictions(items, 200).is_empty());
+    }
+}
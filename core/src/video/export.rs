@@ -0,0 +1,431 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::*;
+use chrono::TimeDelta;
+use image::codecs::jpeg::JpegEncoder;
+use std::result::Result::Ok;
+
+use ffmpeg::format::Pixel;
+use ffmpeg::media::Type;
+use ffmpeg::{Dictionary, Packet, Rational, codec, encoder, filter, format, frame, picture};
+use ffmpeg_next as ffmpeg;
+
+use crate::export::MetadataPolicy;
+use crate::video::frame::frame_at;
+use crate::video::transcode::{TranscodeError, rotate_filter, stream_rotation, video_filter};
+
+use tracing::{Level, event};
+
+/// Frame rate of animated exports. Enough for smooth motion without huge files.
+const ANIMATION_FPS: i32 = 12;
+
+/// Maximum length of the longer side of animated exports.
+const ANIMATION_MAX_SIZE: u32 = 480;
+
+const JPEG_QUALITY: u8 = 92;
+
+/// Formats the video of a live photo or motion photo can be exported as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// The video as-is, repackaged in an MP4 container.
+    Mp4,
+
+    /// A looping animated GIF.
+    Gif,
+
+    /// A looping animated WebP.
+    WebP,
+
+    /// A still JPEG of the frame at a timestamp.
+    Jpeg(TimeDelta),
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Mp4 => "mp4",
+            ExportFormat::Gif => "gif",
+            ExportFormat::WebP => "webp",
+            ExportFormat::Jpeg(_) => "jpg",
+        }
+    }
+}
+
+/// Exports a video to a new file in the chosen format. The file is only
+/// moved to `output_path` once it has been completely written.
+/// Container metadata is copied to MP4 exports as far as `metadata` allows.
+/// Other formats never carry it.
+pub fn export(
+    video_path: &Path,
+    output_path: &Path,
+    export_format: ExportFormat,
+    metadata: MetadataPolicy,
+    stop: &AtomicBool,
+) -> Result<()> {
+    event!(
+        Level::DEBUG,
+        "Exporting video {:?} as {:?}",
+        video_path,
+        export_format
+    );
+
+    let temporary_path = output_path.with_extension(format!("tmp.{}", export_format.extension()));
+
+    let result = match export_format {
+        ExportFormat::Mp4 => remux(video_path, &temporary_path, metadata, stop),
+        ExportFormat::Gif | ExportFormat::WebP => {
+            animate(video_path, &temporary_path, export_format, stop)
+        }
+        ExportFormat::Jpeg(timestamp) => still(video_path, &temporary_path, timestamp),
+    };
+
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&temporary_path);
+        return Err(e);
+    }
+
+    std::fs::rename(&temporary_path, output_path)?;
+
+    Ok(())
+}
+
+/// Copy video and audio streams into an MP4 container without re-encoding.
+fn remux(
+    video_path: &Path,
+    output_path: &Path,
+    metadata: MetadataPolicy,
+    stop: &AtomicBool,
+) -> Result<()> {
+    let mut ictx = format::input(video_path)?;
+    let mut octx = format::output_as(output_path, "mp4")?;
+
+    // Output stream index and time base for each input stream that is copied.
+    let mut stream_mapping: Vec<Option<(usize, Rational)>> = vec![None; ictx.nb_streams() as usize];
+
+    for ist in ictx.streams() {
+        let medium = ist.parameters().medium();
+        if medium != Type::Video && medium != Type::Audio {
+            continue;
+        }
+
+        // Parameters include the display matrix, so rotation is preserved.
+        let mut ost = octx.add_stream(encoder::find(codec::Id::None))?;
+        ost.set_parameters(ist.parameters());
+
+        // Codec tags are container specific and must be reset when changing container.
+        unsafe {
+            (*ost.parameters().as_mut_ptr()).codec_tag = 0;
+        }
+
+        stream_mapping[ist.index()] = Some((ost.index(), ist.time_base()));
+    }
+
+    match metadata {
+        MetadataPolicy::All => octx.set_metadata(ictx.metadata().to_owned()),
+        MetadataPolicy::NoLocation => {
+            let mut kept = Dictionary::new();
+            for (key, value) in ictx.metadata().iter() {
+                if !is_location_key(key) {
+                    kept.set(key, value);
+                }
+            }
+            octx.set_metadata(kept);
+        }
+        MetadataPolicy::None => {}
+    }
+    octx.write_header()?;
+
+    for (stream, mut packet) in ictx.packets() {
+        if stop.load(Ordering::Relaxed) {
+            return Err(TranscodeError::Cancelled.into());
+        }
+
+        let Some((ost_index, ist_time_base)) = stream_mapping[stream.index()] else {
+            continue;
+        };
+
+        let ost_time_base = octx
+            .stream(ost_index)
+            .ok_or(TranscodeError::NoVideoStream)?
+            .time_base();
+
+        packet.rescale_ts(ist_time_base, ost_time_base);
+        packet.set_position(-1);
+        packet.set_stream(ost_index);
+        packet.write_interleaved(&mut octx)?;
+    }
+
+    octx.write_trailer()?;
+
+    Ok(())
+}
+
+/// Is a container metadata key for where a video was taken? Apple, Android, and
+/// ffmpeg itself name location keys differently, so any key mentioning a location
+/// or GPS counts.
+fn is_location_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key.contains("location") || key.contains("gps") || key.contains("xyz")
+}
+
+/// Write the frame at a timestamp as a JPEG.
+fn still(video_path: &Path, output_path: &Path, timestamp: TimeDelta) -> Result<()> {
+    let mut ictx = format::input(video_path)?;
+    let image = frame_at(&mut ictx, timestamp)?;
+
+    let file = BufWriter::new(File::create(output_path)?);
+    JpegEncoder::new_with_quality(file, JPEG_QUALITY).encode_image(&image)?;
+
+    Ok(())
+}
+
+/// Render the best video stream as a looping animation.
+fn animate(
+    video_path: &Path,
+    output_path: &Path,
+    export_format: ExportFormat,
+    stop: &AtomicBool,
+) -> Result<()> {
+    let mut ictx = format::input(video_path)?;
+
+    let muxer = export_format.extension();
+    let mut octx = format::output_as(output_path, muxer)?;
+
+    let (video_index, mut animator) = {
+        let ist = ictx
+            .streams()
+            .best(Type::Video)
+            .ok_or(TranscodeError::NoVideoStream)?;
+        let animator = Animator::new(&ist, &mut octx, export_format)?;
+        (ist.index(), animator)
+    };
+
+    // Loop forever. Both the GIF and WebP muxers understand this option.
+    let mut options = Dictionary::new();
+    options.set("loop", "0");
+    octx.write_header_with(options)?;
+
+    let ost_time_base = octx
+        .stream(animator.ost_index)
+        .ok_or(TranscodeError::NoVideoStream)?
+        .time_base();
+
+    for (stream, packet) in ictx.packets() {
+        if stop.load(Ordering::Relaxed) {
+            return Err(TranscodeError::Cancelled.into());
+        }
+
+        if stream.index() == video_index {
+            animator.decoder.send_packet(&packet)?;
+            animator.process_decoded_frames(&mut octx, ost_time_base)?;
+        }
+    }
+
+    animator.flush(&mut octx, ost_time_base)?;
+
+    octx.write_trailer()?;
+
+    if animator.frame_count == 0 {
+        return Err(TranscodeError::InvalidOutput("no frames encoded".into()).into());
+    }
+
+    Ok(())
+}
+
+/// Size that fits within a square of `max_size`, keeping the aspect ratio.
+/// Dimensions are even, as required by some pixel formats. Never enlarges.
+fn fit(width: u32, height: u32, max_size: u32) -> (u32, u32) {
+    let longest = width.max(height).max(1);
+    if longest <= max_size {
+        return (width & !1, height & !1);
+    }
+
+    let scale = |x: u32| ((x as u64 * max_size as u64 / longest as u64) as u32 & !1).max(2);
+    (scale(width), scale(height))
+}
+
+/// Decodes, rotates, scales, and encodes frames of a video as an animation.
+struct Animator {
+    ost_index: usize,
+    time_base: Rational,
+    decoder: ffmpeg::decoder::Video,
+    filter: filter::Graph,
+    encoder: encoder::video::Encoder,
+    frame_count: usize,
+}
+
+impl Animator {
+    fn new(
+        ist: &format::stream::Stream,
+        octx: &mut format::context::Output,
+        export_format: ExportFormat,
+    ) -> Result<Self, TranscodeError> {
+        let decoder = codec::context::Context::from_parameters(ist.parameters())?
+            .decoder()
+            .video()?;
+
+        let rotation = stream_rotation(ist);
+        let (width, height) = if rotation == 90 || rotation == -90 {
+            (decoder.height(), decoder.width())
+        } else {
+            (decoder.width(), decoder.height())
+        };
+        let (width, height) = fit(width, height, ANIMATION_MAX_SIZE);
+
+        let rotate = rotate_filter(rotation);
+        let scale = format!("{rotate}fps={ANIMATION_FPS},scale={width}:{height}:flags=lanczos");
+
+        let (codec, pixel, spec) = if export_format == ExportFormat::Gif {
+            // A palette generated from all frames looks much better than
+            // the default palette.
+            let codec = encoder::find(codec::Id::GIF);
+            let spec = format!(
+                "{scale},split[a][b];[a]palettegen=stats_mode=diff[p];[b][p]paletteuse=dither=bayer"
+            );
+            (
+                codec.ok_or(TranscodeError::EncoderNotFound(codec::Id::GIF))?,
+                Pixel::PAL8,
+                spec,
+            )
+        } else {
+            // The plain libwebp encoder can't write animations.
+            let codec = encoder::find_by_name("libwebp_anim");
+            let spec = format!("{scale},format=yuv420p");
+            (
+                codec.ok_or(TranscodeError::EncoderNotFound(codec::Id::WEBP))?,
+                Pixel::YUV420P,
+                spec,
+            )
+        };
+
+        let filter = video_filter(&decoder, ist.time_base(), &spec)?;
+
+        let global_header = octx.format().flags().contains(format::Flags::GLOBAL_HEADER);
+
+        let mut ost = octx.add_stream(codec)?;
+        let ost_index = ost.index();
+
+        // The fps filter outputs a constant frame rate, so frames are numbered
+        // in a time base of one frame.
+        let time_base = Rational::new(1, ANIMATION_FPS);
+
+        let mut encoder = codec::context::Context::new_with_codec(codec)
+            .encoder()
+            .video()?;
+        encoder.set_width(width);
+        encoder.set_height(height);
+        encoder.set_format(pixel);
+        encoder.set_time_base(time_base);
+        encoder.set_frame_rate(Some(Rational::new(ANIMATION_FPS, 1)));
+        if global_header {
+            encoder.set_flags(codec::Flags::GLOBAL_HEADER);
+        }
+
+        let mut options = Dictionary::new();
+        if export_format == ExportFormat::WebP {
+            options.set("lossless", "0");
+            options.set("quality", "75");
+        }
+
+        let encoder = encoder.open_with(options)?;
+        ost.set_parameters(&encoder);
+
+        Ok(Self {
+            ost_index,
+            time_base,
+            decoder,
+            filter,
+            encoder,
+            frame_count: 0,
+        })
+    }
+
+    /// Flush frames buffered in the decoder, filters, and encoder.
+    fn flush(
+        &mut self,
+        octx: &mut format::context::Output,
+        ost_time_base: Rational,
+    ) -> Result<(), ffmpeg::Error> {
+        self.decoder.send_eof()?;
+        self.process_decoded_frames(octx, ost_time_base)?;
+        self.filter.get("in").expect("in filter").source().flush()?;
+        self.process_filtered_frames(octx, ost_time_base)?;
+        self.encoder.send_eof()?;
+        self.process_encoded_packets(octx, ost_time_base)
+    }
+
+    fn process_decoded_frames(
+        &mut self,
+        octx: &mut format::context::Output,
+        ost_time_base: Rational,
+    ) -> Result<(), ffmpeg::Error> {
+        let mut decoded = frame::Video::empty();
+        while self.decoder.receive_frame(&mut decoded).is_ok() {
+            let timestamp = decoded.timestamp();
+            decoded.set_pts(timestamp);
+            self.filter
+                .get("in")
+                .expect("in filter")
+                .source()
+                .add(&decoded)?;
+            self.process_filtered_frames(octx, ost_time_base)?;
+        }
+        Ok(())
+    }
+
+    fn process_filtered_frames(
+        &mut self,
+        octx: &mut format::context::Output,
+        ost_time_base: Rational,
+    ) -> Result<(), ffmpeg::Error> {
+        let mut filtered = frame::Video::empty();
+        while self
+            .filter
+            .get("out")
+            .expect("out filter")
+            .sink()
+            .frame(&mut filtered)
+            .is_ok()
+        {
+            filtered.set_pts(Some(self.frame_count as i64));
+            filtered.set_kind(picture::Type::None);
+            self.encoder.send_frame(&filtered)?;
+            self.frame_count += 1;
+            self.process_encoded_packets(octx, ost_time_base)?;
+        }
+        Ok(())
+    }
+
+    fn process_encoded_packets(
+        &mut self,
+        octx: &mut format::context::Output,
+        ost_time_base: Rational,
+    ) -> Result<(), ffmpeg::Error> {
+        let mut encoded = Packet::empty();
+        while self.encoder.receive_packet(&mut encoded).is_ok() {
+            encoded.set_stream(self.ost_index);
+            encoded.rescale_ts(self.time_base, ost_time_base);
+            encoded.write_interleaved(octx)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_within_max_size() {
+        assert_eq!(fit(1920, 1080, 480), (480, 270));
+        assert_eq!(fit(1080, 1920, 480), (270, 480));
+        assert_eq!(fit(320, 241, 480), (320, 240));
+    }
+}
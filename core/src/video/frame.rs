@@ -0,0 +1,117 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::video::display_matrix::av_display_rotation_get;
+
+use anyhow::*;
+use chrono::TimeDelta;
+use image::imageops;
+use image::{ImageBuffer, RgbImage};
+use std::result::Result::Ok;
+
+use ffmpeg::format::Pixel;
+use ffmpeg::format::context::Input;
+use ffmpeg::media::Type;
+use ffmpeg::software::scaling::{context::Context, flag::Flags};
+use ffmpeg::util::frame::video::Video;
+use ffmpeg_next as ffmpeg;
+use ffmpeg_next::frame::side_data::Type as SideDataType;
+
+/// Decodes the first frame at or after the timestamp.
+pub(crate) fn frame_at(ictx: &mut Input, timestamp: TimeDelta) -> Result<RgbImage> {
+    let (video_stream_index, time_base, mut decoder) = {
+        let stream = ictx
+            .streams()
+            .best(Type::Video)
+            .ok_or(ffmpeg::Error::StreamNotFound)?;
+        let context_decoder =
+            ffmpeg::codec::context::Context::from_parameters(stream.parameters())?;
+        let decoder = context_decoder.decoder().video()?;
+        (stream.index(), stream.time_base(), decoder)
+    };
+
+    let micros = timestamp.num_microseconds().unwrap_or(0);
+
+    // Seek to the key frame at or before the timestamp, then decode forwards.
+    ictx.seek(micros, ..micros)?;
+
+    let target = micros as f64 / 1_000_000.0;
+    let mut last_frame: Option<Video> = None;
+
+    for (stream, packet) in ictx.packets() {
+        if stream.index() != video_stream_index {
+            continue;
+        }
+
+        decoder.send_packet(&packet)?;
+
+        let mut decoded = Video::empty();
+        while decoder.receive_frame(&mut decoded).is_ok() {
+            let position = decoded.timestamp().unwrap_or(0) as f64 * f64::from(time_base);
+            let is_reached = position >= target;
+            last_frame = Some(decoded.clone());
+            if is_reached {
+                return to_rgb(&decoded);
+            }
+        }
+    }
+
+    // Timestamp is beyond the last frame, so use the last frame.
+    decoder.send_eof()?;
+    let mut decoded = Video::empty();
+    while decoder.receive_frame(&mut decoded).is_ok() {
+        last_frame = Some(decoded.clone());
+    }
+
+    let frame = last_frame.ok_or(ffmpeg::Error::StreamNotFound)?;
+    to_rgb(&frame)
+}
+
+/// Converts a decoded frame to RGB and applies rotation.
+pub(crate) fn to_rgb(decoded: &Video) -> Result<RgbImage> {
+    // MatrixData contains rotation.
+    let rotation = decoded
+        .side_data(SideDataType::DisplayMatrix)
+        .map(|display_matrix| av_display_rotation_get(display_matrix.data()))
+        .unwrap_or(f64::NAN);
+
+    let mut scaler = Context::get(
+        decoded.format(),
+        decoded.width(),
+        decoded.height(),
+        Pixel::RGB24,
+        decoded.width(),
+        decoded.height(),
+        Flags::BILINEAR,
+    )?;
+
+    let mut rgb_frame = Video::empty();
+    scaler.run(decoded, &mut rgb_frame)?;
+
+    let image_width = rgb_frame.width();
+    let image_height = rgb_frame.height();
+
+    // Rows may be padded, so copy only the pixels of each row.
+    let stride = rgb_frame.stride(0);
+    let row_len = image_width as usize * 3;
+    let frame_bytes: Vec<u8> = rgb_frame
+        .data(0)
+        .chunks(stride)
+        .take(image_height as usize)
+        .flat_map(|row| &row[..row_len])
+        .copied()
+        .collect();
+
+    let buffer: RgbImage = ImageBuffer::from_raw(image_width, image_height, frame_bytes)
+        .ok_or_else(|| anyhow!("Video frame to image"))?;
+
+    let buffer = match rotation {
+        90.0 => imageops::rotate90(&buffer),
+        180.0 | -180.0 => imageops::rotate180(&buffer),
+        -90.0 => imageops::rotate270(&buffer),
+        _ => buffer,
+    };
+
+    Ok(buffer)
+}
@@ -0,0 +1,139 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use image::{GrayImage, RgbImage, imageops};
+
+/// Frames are scored at this width, which is plenty to judge a thumbnail and
+/// makes scoring cheap for 4K videos.
+const SCORE_WIDTH: u32 = 256;
+
+/// Mean luma below this is too dark, such as a black frame or a fade-in.
+const MIN_BRIGHTNESS: f64 = 40.0;
+
+/// Mean luma above this is too bright, such as a white flash or an over-exposed frame.
+const MAX_BRIGHTNESS: f64 = 215.0;
+
+/// How suitable a video frame is as a thumbnail.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameScore {
+    /// Mean luma, 0 to 255.
+    pub brightness: f64,
+
+    /// Standard deviation of luma. Low for flat frames such as a solid colour.
+    pub contrast: f64,
+
+    /// Variance of the Laplacian of luma. Low for blurry frames.
+    pub sharpness: f64,
+}
+
+impl FrameScore {
+    pub fn of(frame: &RgbImage) -> Self {
+        let luma = Self::luma(frame);
+        let pixels = luma.as_raw();
+
+        if pixels.is_empty() {
+            return FrameScore {
+                brightness: 0.0,
+                contrast: 0.0,
+                sharpness: 0.0,
+            };
+        }
+
+        let count = pixels.len() as f64;
+        let brightness = pixels.iter().map(|&p| p as f64).sum::<f64>() / count;
+        let contrast = (pixels
+            .iter()
+            .map(|&p| (p as f64 - brightness).powi(2))
+            .sum::<f64>()
+            / count)
+            .sqrt();
+
+        FrameScore {
+            brightness,
+            contrast,
+            sharpness: Self::laplacian_variance(&luma),
+        }
+    }
+
+    /// Single value to rank frames. Higher is better.
+    /// Frames that are too dark or too bright are heavily penalised, then frames
+    /// are ranked by contrast and sharpness.
+    pub fn value(&self) -> f64 {
+        let exposure = if self.brightness < MIN_BRIGHTNESS {
+            self.brightness / MIN_BRIGHTNESS
+        } else if self.brightness > MAX_BRIGHTNESS {
+            (255.0 - self.brightness) / (255.0 - MAX_BRIGHTNESS)
+        } else {
+            1.0
+        };
+
+        // Square root of the sharpness puts it on a similar scale to contrast.
+        exposure * (self.contrast + self.sharpness.sqrt())
+    }
+
+    fn luma(frame: &RgbImage) -> GrayImage {
+        let luma = imageops::grayscale(frame);
+        if luma.width() > SCORE_WIDTH {
+            let height = (luma.height() as u64 * SCORE_WIDTH as u64 / luma.width() as u64).max(1);
+            imageops::resize(
+                &luma,
+                SCORE_WIDTH,
+                height as u32,
+                imageops::FilterType::Triangle,
+            )
+        } else {
+            luma
+        }
+    }
+
+    fn laplacian_variance(luma: &GrayImage) -> f64 {
+        let (width, height) = luma.dimensions();
+        if width < 3 || height < 3 {
+            return 0.0;
+        }
+
+        let at = |x: u32, y: u32| luma.get_pixel(x, y)[0] as f64;
+
+        let mut values = Vec::with_capacity(((width - 2) * (height - 2)) as usize);
+        for y in 1..height - 1 {
+            for x in 1..width - 1 {
+                let laplacian =
+                    at(x - 1, y) + at(x + 1, y) + at(x, y - 1) + at(x, y + 1) - 4.0 * at(x, y);
+                values.push(laplacian);
+            }
+        }
+
+        let count = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / count;
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    fn checkerboard(dark: u8, light: u8) -> RgbImage {
+        RgbImage::from_fn(64, 64, |x, y| {
+            if (x / 8 + y / 8) % 2 == 0 {
+                Rgb([dark, dark, dark])
+            } else {
+                Rgb([light, light, light])
+            }
+        })
+    }
+
+    #[test]
+    fn detailed_well_exposed_frame_scores_highest() {
+        let black = RgbImage::from_pixel(64, 64, Rgb([0, 0, 0]));
+        let flat = RgbImage::from_pixel(64, 64, Rgb([128, 128, 128]));
+        let dark = checkerboard(0, 40);
+        let exposed = checkerboard(60, 200);
+
+        assert_eq!(FrameScore::of(&black).value(), 0.0);
+        assert!(FrameScore::of(&exposed).value() > FrameScore::of(&flat).value());
+        assert!(FrameScore::of(&exposed).value() > FrameScore::of(&dark).value());
+    }
+}
@@ -3,6 +3,8 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use super::Metadata;
+use crate::photo::gps::GPSLocation;
+use crate::timezone;
 use anyhow::*;
 use chrono::prelude::*;
 use chrono::{DateTime, TimeDelta};
@@ -24,8 +26,9 @@ use std::fs;
 //
 // 1. ???
 // 2. ???
+// 3. GPS coordinates and local time of capture.
 
-pub const VERSION: u32 = 2;
+pub const VERSION: u32 = 3;
 
 pub fn from_path(path: &Path) -> Result<Metadata> {
     let mut metadata = Metadata::default();
@@ -47,6 +50,18 @@ pub fn from_path(path: &Path) -> Result<Metadata> {
         .get("com.apple.quicktime.content.identifier")
         .map(String::from);
 
+    // iOS writes ISO 6709 to a QuickTime key, Android writes it to "location".
+    metadata.location = context_metadata
+        .get("com.apple.quicktime.location.ISO6709")
+        .or_else(|| context_metadata.get("location"))
+        .and_then(GPSLocation::for_iso6709);
+
+    // iOS also records creation date with the offset from UTC of the device.
+    let local_created_at = context_metadata
+        .get("com.apple.quicktime.creationdate")
+        .and_then(|x| DateTime::parse_from_str(x, "%Y-%m-%dT%H:%M:%S%z").ok())
+        .map(|x| x.naive_local());
+
     metadata.container_format = Some(String::from(context.format().description()));
 
     if let Some(stream) = context.streams().best(ffmpeg::media::Type::Video) {
@@ -88,6 +103,14 @@ pub fn from_path(path: &Path) -> Result<Metadata> {
         }
     }
 
+    // Stream creation time is UTC, so use the time zone of where the video was taken
+    // to work out the local time of capture.
+    metadata.local_created_at = local_created_at.or_else(|| {
+        let created_at = metadata.stream_created_at.as_ref()?;
+        let location = metadata.location.as_ref()?;
+        timezone::local_at(location, created_at)
+    });
+
     if let Some(stream) = context.streams().best(ffmpeg::media::Type::Audio) {
         let codec = ffmpeg::codec::context::Context::from_parameters(stream.parameters())?;
         metadata.audio_codec = Some(String::from(codec.id().name()));
@@ -2,17 +2,25 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+pub mod cache;
 pub mod display_matrix;
+pub mod export;
+mod frame;
+pub mod frame_score;
 
 pub mod metadata;
 pub mod model;
+pub mod profile;
 pub mod repo;
+pub mod storyboard;
 pub mod thumbnailer;
 pub mod transcode;
 
 pub use model::Metadata;
 pub use model::Video;
 pub use model::VideoId;
+pub use profile::TranscodeProfile;
 pub use repo::Repository;
+pub use storyboard::Storyboarder;
 pub use thumbnailer::VideoThumbnailer;
-pub use transcode::Transcoder;
+pub use transcode::{TranscodeError, Transcoder};
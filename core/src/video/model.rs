@@ -3,8 +3,10 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use crate::FlatpakPathBuf;
+use crate::orientation::Orientation;
 use crate::photo::gps::GPSLocation;
-use chrono::{DateTime, TimeDelta, Utc};
+use crate::thumbnailify;
+use chrono::{DateTime, NaiveDateTime, TimeDelta, Utc};
 use std::fmt::Display;
 use std::path::PathBuf;
 
@@ -49,11 +51,21 @@ pub struct Video {
 
     /// Video codec
     pub video_codec: Option<String>,
+
+    /// Position of the frame chosen for the thumbnail.
+    pub thumbnail_ts: Option<TimeDelta>,
+
+    /// Turn chosen by the user, on top of the rotation of the video stream.
+    pub orientation_override: Option<Orientation>,
 }
 
 impl Video {
     pub fn thumbnail_hash(&self) -> String {
-        self.path.thumbnail_hash()
+        thumbnailify::compute_hash_for_edited_path(
+            &self.path.host_path,
+            self.orientation_override,
+            None,
+        )
     }
 
     pub fn host_path(&self) -> &PathBuf {
@@ -69,6 +81,9 @@ impl Video {
 pub struct Metadata {
     pub stream_created_at: Option<DateTime<Utc>>,
 
+    /// Wall-clock time at the place the video was taken.
+    pub local_created_at: Option<NaiveDateTime>,
+
     pub fs_created_at: Option<DateTime<Utc>>,
 
     pub fs_modified_at: Option<DateTime<Utc>>,
@@ -0,0 +1,131 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+/// Video codec for transcoded videos.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoCodec {
+    H264,
+    Vp9,
+}
+
+/// What to do with the audio stream when transcoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioCodec {
+    /// Copy audio without re-encoding.
+    Copy,
+
+    /// Re-encode audio to stereo AAC.
+    Aac,
+
+    /// Re-encode audio to stereo Opus.
+    Opus,
+}
+
+/// Trade-offs between quality, size, and patent encumbrance for transcoded videos.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TranscodeProfile {
+    /// H.264 at up to 1080p with audio copied. Good quality and plays everywhere.
+    #[default]
+    Standard,
+
+    /// H.264 at up to 720p with stereo AAC audio. Uses less disk space.
+    Compact,
+
+    /// VP9 at up to 1080p with stereo Opus audio. For systems without an H.264 encoder.
+    Open,
+}
+
+impl TranscodeProfile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TranscodeProfile::Standard => "Standard",
+            TranscodeProfile::Compact => "Compact",
+            TranscodeProfile::Open => "Open",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "Standard" => Some(TranscodeProfile::Standard),
+            "Compact" => Some(TranscodeProfile::Compact),
+            "Open" => Some(TranscodeProfile::Open),
+            _ => None,
+        }
+    }
+
+    pub fn video_codec(&self) -> VideoCodec {
+        match self {
+            TranscodeProfile::Standard | TranscodeProfile::Compact => VideoCodec::H264,
+            TranscodeProfile::Open => VideoCodec::Vp9,
+        }
+    }
+
+    /// Maximum length of the shorter side of the video, so that portrait
+    /// and landscape videos are limited to the same resolution.
+    pub fn max_size(&self) -> u32 {
+        match self {
+            TranscodeProfile::Standard | TranscodeProfile::Open => 1080,
+            TranscodeProfile::Compact => 720,
+        }
+    }
+
+    /// Constant rate factor. Lower is better quality. Note that the scales
+    /// for H.264 (0-51) and VP9 (0-63) differ.
+    pub fn crf(&self) -> u32 {
+        match self {
+            TranscodeProfile::Standard => 23,
+            TranscodeProfile::Compact => 28,
+            TranscodeProfile::Open => 32,
+        }
+    }
+
+    pub fn audio_codec(&self) -> AudioCodec {
+        match self {
+            TranscodeProfile::Standard => AudioCodec::Copy,
+            TranscodeProfile::Compact => AudioCodec::Aac,
+            TranscodeProfile::Open => AudioCodec::Opus,
+        }
+    }
+
+    /// Name of a file transcoded with this profile, so that switching profiles
+    /// doesn't reuse files transcoded with another profile.
+    pub fn file_name(&self, stem: &str) -> String {
+        format!("{}_{}.mkv", stem, self.as_str().to_lowercase())
+    }
+
+    /// Output size for a video, keeping the aspect ratio and with even dimensions
+    /// because YUV 4:2:0 needs them.
+    pub fn scaled_size(&self, width: u32, height: u32) -> (u32, u32) {
+        let shorter = width.min(height);
+        let max_size = self.max_size();
+
+        let (width, height) = if shorter > max_size {
+            let scale = max_size as f64 / shorter as f64;
+            (
+                (width as f64 * scale).round() as u32,
+                (height as f64 * scale).round() as u32,
+            )
+        } else {
+            (width, height)
+        };
+
+        (width & !1, height & !1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_shorter_side_to_max_size() {
+        let profile = TranscodeProfile::Compact;
+        assert_eq!(profile.scaled_size(3840, 2160), (1280, 720));
+        assert_eq!(profile.scaled_size(2160, 3840), (720, 1280));
+
+        // Small videos keep their size, but encoders need even dimensions.
+        let profile = TranscodeProfile::Standard;
+        assert_eq!(profile.scaled_size(641, 481), (640, 480));
+    }
+}
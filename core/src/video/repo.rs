@@ -7,7 +7,9 @@ use super::metadata;
 use crate::FlatpakPathBuf;
 use crate::ScannedFile;
 use crate::file_types;
+use crate::orientation::Orientation;
 use crate::path_encoding;
+use crate::problems::ProblemCategory;
 use crate::video::model::{Video, VideoId};
 
 use anyhow::*;
@@ -56,7 +58,13 @@ impl Repository {
         Ok(repo)
     }
 
-    pub fn mark_broken(&mut self, video_id: &VideoId) -> Result<()> {
+    /// Hide a video that can't be processed, recording why so it can be shown to the user.
+    pub fn mark_broken(
+        &mut self,
+        video_id: &VideoId,
+        category: ProblemCategory,
+        message: &str,
+    ) -> Result<()> {
         let mut con = self.con.lock().unwrap();
         let tx = con.transaction()?;
 
@@ -64,11 +72,14 @@ impl Repository {
             let mut stmt = tx.prepare(
                 "UPDATE videos
                 SET
-                    is_broken = TRUE
+                    is_broken = TRUE,
+                    broken_category = ?2,
+                    broken_message = ?3,
+                    broken_ts = CURRENT_TIMESTAMP
                 WHERE video_id = ?1",
             )?;
 
-            stmt.execute(params![video_id.id(),])?;
+            stmt.execute(params![video_id.id(), category.as_str(), message])?;
         }
 
         tx.commit()?;
@@ -100,6 +111,23 @@ impl Repository {
         Ok(())
     }
 
+    /// Record the position of the frame chosen for the thumbnail.
+    pub fn add_thumbnail_timestamp(
+        &mut self,
+        video_id: VideoId,
+        timestamp: TimeDelta,
+    ) -> Result<()> {
+        let con = self.con.lock().unwrap();
+        con.execute(
+            "UPDATE videos
+            SET
+                thumbnail_ts_millis = ?2
+            WHERE video_id = ?1",
+            params![video_id.id(), timestamp.num_milliseconds()],
+        )?;
+        Ok(())
+    }
+
     pub fn add_metadata(&mut self, vids: Vec<(VideoId, Metadata)>) -> Result<()> {
         let mut con = self.con.lock().unwrap();
         let tx = con.transaction()?;
@@ -115,7 +143,8 @@ impl Repository {
                     content_id = ?6,
                     rotation = ?7,
                     fs_created_ts = ?8,
-                    fs_modified_ts = ?9
+                    fs_modified_ts = ?9,
+                    local_created_ts = ?10
                 WHERE video_id = ?1",
             )?;
 
@@ -128,7 +157,9 @@ impl Repository {
                     ?1, ?2, ?3
                 ) ON CONFLICT (video_id) DO UPDATE SET
                     latitude = ?2,
-                    longitude = ?3
+                    longitude = ?3,
+                    geocoder_version = 0
+                WHERE videos_geo.source = 'embedded'
                 ",
             )?;
 
@@ -143,6 +174,7 @@ impl Repository {
                     metadata.rotation,
                     metadata.fs_created_at,
                     metadata.fs_modified_at,
+                    metadata.local_created_at,
                 ])?;
 
                 if let Some(location) = metadata.location {
@@ -237,8 +269,11 @@ impl Repository {
                     ) AS ordering_ts,
                     duration_millis,
                     video_codec,
-                    transcoded_path
+                    transcoded_path,
+                    thumbnail_ts_millis,
+                    videos_orientation.orientation AS orientation_override
                 FROM videos
+                LEFT OUTER JOIN videos_orientation USING (video_id)
                 WHERE COALESCE(is_broken, FALSE) IS FALSE
                 ORDER BY ordering_ts ASC",
         )?;
@@ -248,6 +283,29 @@ impl Repository {
         Ok(result)
     }
 
+    /// Gets paths of all videos in the repository, including broken videos.
+    pub fn all_paths(&self) -> Result<Vec<FlatpakPathBuf>> {
+        let con = self.con.lock().unwrap();
+        let mut stmt = con.prepare("SELECT video_path_b64 FROM videos")?;
+
+        let result = stmt
+            .query_map([], |row| {
+                let relative_path: String = row.get(0)?;
+                path_encoding::from_base64(&relative_path)
+                    .map_err(|_| rusqlite::Error::InvalidQuery)
+            })?
+            .flatten()
+            .map(|relative_path| {
+                FlatpakPathBuf::build(
+                    self.library_base_dir.host_path.join(&relative_path),
+                    self.library_base_dir.sandbox_path.join(&relative_path),
+                )
+            })
+            .collect();
+
+        Ok(result)
+    }
+
     /// Gets all videos in the repository, in ascending order of modification timestamp.
     pub fn find_need_metadata_update(&self) -> Result<Vec<Video>> {
         let con = self.con.lock().unwrap();
@@ -263,7 +321,8 @@ impl Repository {
                     ) AS ordering_ts,
                     duration_millis,
                     video_codec,
-                    transcoded_path
+                    transcoded_path,
+                    thumbnail_ts_millis
                 FROM videos
                 WHERE metadata_version < ?1
                 AND COALESCE(is_broken, FALSE) IS FALSE
@@ -312,6 +371,17 @@ impl Repository {
             .map(|p: String| self.cache_dir_base_path.join(p))
             .ok();
 
+        let thumbnail_ts = row
+            .get("thumbnail_ts_millis")
+            .ok()
+            .and_then(|x: i64| TimeDelta::try_milliseconds(x));
+
+        // Only some queries select orientation overrides.
+        let orientation_override = row
+            .get("orientation_override")
+            .ok()
+            .map(|x: u32| Orientation::from(x));
+
         std::result::Result::Ok(Video {
             video_id,
             path: FlatpakPathBuf::build(host_path, sandbox_path),
@@ -319,6 +389,8 @@ impl Repository {
             stream_duration,
             video_codec,
             transcoded_path,
+            thumbnail_ts,
+            orientation_override,
         })
     }
 
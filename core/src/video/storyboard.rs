@@ -0,0 +1,111 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::FlatpakPathBuf;
+use crate::thumbnailify;
+use crate::video::frame;
+
+use anyhow::*;
+use chrono::TimeDelta;
+use image::codecs::jpeg::JpegEncoder;
+use image::{RgbImage, imageops};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::result::Result::Ok;
+
+use ffmpeg::format::input;
+use ffmpeg_next as ffmpeg;
+
+/// Number of frames in a storyboard.
+pub const FRAME_COUNT: u32 = 10;
+
+/// Height of each frame in a storyboard. Matches the normal thumbnail size.
+const FRAME_HEIGHT: u32 = 128;
+
+const JPEG_QUALITY: u8 = 80;
+
+/// Generates storyboards for videos. A storyboard is a sprite sheet of evenly spaced
+/// frames in a single row, used to preview a video by scrubbing through it.
+#[derive(Debug, Clone)]
+pub struct Storyboarder {
+    thumbnailer: thumbnailify::Thumbnailer,
+}
+
+impl Storyboarder {
+    pub fn build(thumbnailer: thumbnailify::Thumbnailer) -> Result<Storyboarder> {
+        Ok(Storyboarder { thumbnailer })
+    }
+
+    /// Path of the storyboard for a video. Might not exist.
+    pub fn storyboard_path(&self, path: &FlatpakPathBuf) -> PathBuf {
+        self.thumbnailer.get_storyboard_path(&path.thumbnail_hash())
+    }
+
+    /// Generates the storyboard for a video and returns its path.
+    pub fn storyboard(&self, path: &FlatpakPathBuf) -> Result<PathBuf> {
+        let storyboard_path = self.storyboard_path(path);
+        if let Some(parent) = storyboard_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut ictx = input(path.sandbox_path.as_os_str())?;
+
+        // Container duration is in AV_TIME_BASE units (microseconds).
+        let duration = ictx.duration();
+        if duration <= 0 {
+            bail!("Unknown duration for {:?}", path.sandbox_path);
+        }
+
+        let mut sheet: Option<RgbImage> = None;
+
+        for index in 0..FRAME_COUNT {
+            // Take the middle of each segment to avoid the first and last frames.
+            let position = (index as f64 + 0.5) / FRAME_COUNT as f64;
+            let timestamp = TimeDelta::microseconds((duration as f64 * position) as i64);
+            let image = frame::frame_at(&mut ictx, timestamp)?;
+
+            let width = (image.width() as u64 * FRAME_HEIGHT as u64 / image.height().max(1) as u64)
+                .max(1) as u32;
+            let image = imageops::thumbnail(&image, width, FRAME_HEIGHT);
+
+            let sheet = sheet
+                .get_or_insert_with(|| RgbImage::new(image.width() * FRAME_COUNT, image.height()));
+            imageops::replace(sheet, &image, (index * image.width()) as i64, 0);
+        }
+
+        let sheet = sheet.ok_or_else(|| anyhow!("No frames for storyboard"))?;
+
+        // Write to a temporary file so a partial storyboard is never shown.
+        let temporary_path = storyboard_path.with_extension("tmp.jpg");
+        {
+            let file = BufWriter::new(File::create(&temporary_path)?);
+            JpegEncoder::new_with_quality(file, JPEG_QUALITY).encode_image(&sheet)?;
+        }
+        std::fs::rename(&temporary_path, &storyboard_path)?;
+
+        Ok(storyboard_path)
+    }
+}
+
+/// Pixel rectangle `(x, y, width, height)` of the storyboard frame to show at a
+/// position, as a fraction of the duration, in a sprite sheet of the given size.
+pub fn frame_rect(sheet_width: i32, sheet_height: i32, position: f64) -> (i32, i32, i32, i32) {
+    let frame_width = sheet_width / FRAME_COUNT as i32;
+    let index = (position.clamp(0.0, 1.0) * FRAME_COUNT as f64) as i32;
+    let index = index.min(FRAME_COUNT as i32 - 1);
+    (index * frame_width, 0, frame_width, sheet_height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_rect_for_positions() {
+        assert_eq!(frame_rect(1000, 90, 0.0), (0, 0, 100, 90));
+        assert_eq!(frame_rect(1000, 90, 0.55), (500, 0, 100, 90));
+        assert_eq!(frame_rect(1000, 90, 1.0), (900, 0, 100, 90));
+    }
+}
@@ -3,22 +3,26 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use crate::FlatpakPathBuf;
+use crate::orientation::{self, Orientation};
 use crate::thumbnailify;
-use crate::video::display_matrix::av_display_rotation_get;
+use crate::video::frame;
+use crate::video::frame_score::FrameScore;
 
 use anyhow::*;
-use image::imageops;
-use image::{ImageBuffer, ImageFormat, ImageReader, RgbImage};
-use std::path::Path;
+use chrono::TimeDelta;
+use fast_image_resize::PixelType;
+use fast_image_resize::images::ImageRef;
+use image::RgbImage;
 use std::result::Result::Ok;
-use tempfile;
+use tracing::debug;
 
-use ffmpeg::format::{Pixel, input};
-use ffmpeg::media::Type;
-use ffmpeg::software::scaling::{context::Context, flag::Flags};
-use ffmpeg::util::frame::video::Video;
+use ffmpeg::format::context::Input;
+use ffmpeg::format::input;
 use ffmpeg_next as ffmpeg;
-use ffmpeg_next::frame::side_data::Type as SideDataType;
+
+/// Positions of candidate thumbnail frames, as fractions of the video duration.
+/// Avoids the first frame, which is often black or a fade-in.
+const CANDIDATE_POSITIONS: [f64; 3] = [0.1, 0.25, 0.5];
 
 /// Thumbnail operations for videos.
 #[derive(Debug, Clone)]
@@ -31,104 +35,100 @@ impl VideoThumbnailer {
         Ok(VideoThumbnailer { thumbnailer })
     }
 
-    /// Computes a preview for a video
-    pub fn thumbnail(&self, path: &FlatpakPathBuf) -> Result<()> {
-        if self.thumbnailer.is_failed(&path.host_path) {
+    /// Computes a preview for a video from the frame at `timestamp`, or from the best
+    /// of several candidate frames if there is no timestamp.
+    /// A turn chosen by the user is applied on top of the rotation of the video stream.
+    /// Returns the timestamp of the frame used, so the preview can be regenerated
+    /// from the same frame.
+    pub fn thumbnail(
+        &self,
+        path: &FlatpakPathBuf,
+        timestamp: Option<TimeDelta>,
+        orientation: Option<Orientation>,
+    ) -> Result<TimeDelta> {
+        if self.thumbnailer.is_failed(path) {
             anyhow::bail!("Failed thumbnail marker exists for {:?}", path.host_path);
         }
 
-        self.thumbnail_internal(path).map_err(|err| {
-            let _ = self.thumbnailer.write_failed_thumbnail(path);
-            err
-        })
+        self.thumbnail_internal(path, timestamp, orientation)
+            .map_err(|err| {
+                let _ = self.thumbnailer.write_failed_thumbnail(path);
+                err
+            })
     }
 
-    pub fn thumbnail_internal(&self, path: &FlatpakPathBuf) -> Result<()> {
-        // Extract first frame of video for thumbnail
-
-        // Temporary output file for frame.
-        let temporary_png_file = tempfile::Builder::new().suffix(".png").tempfile()?;
-
-        // See https://docs.rs/ffmpeg-next/latest/src/dump_frames/dump-frames.rs.html
-        if let Ok(mut ictx) = input(path.sandbox_path.as_os_str()) {
-            let input = ictx
-                .streams()
-                .best(Type::Video)
-                .ok_or(ffmpeg::Error::StreamNotFound)?;
-
-            let video_stream_index = input.index();
-
-            let context_decoder =
-                ffmpeg::codec::context::Context::from_parameters(input.parameters())?;
-            let mut decoder = context_decoder.decoder().video()?;
-
-            let mut scaler = Context::get(
-                decoder.format(),
-                decoder.width(),
-                decoder.height(),
-                Pixel::RGB24,
-                decoder.width(),
-                decoder.height(),
-                Flags::BILINEAR,
-            )?;
-
-            // Lambda for decoding video
-            let mut receive_and_process_decoded_frames =
-                |decoder: &mut ffmpeg::decoder::Video| -> Result<(), ffmpeg::Error> {
-                    let mut decoded = Video::empty();
-                    if decoder.receive_frame(&mut decoded).is_ok() {
-                        // MatrixData contains rotation.
-                        let display_matrix = decoded.side_data(SideDataType::DisplayMatrix);
-                        let rotation = if let Some(display_matrix) = display_matrix {
-                            av_display_rotation_get(display_matrix.data())
-                        } else {
-                            f64::NAN
-                        };
-
-                        let mut rgb_frame = Video::empty();
-                        scaler.run(&decoded, &mut rgb_frame)?;
-                        Self::convert_rgb_to_png(&rgb_frame, rotation, temporary_png_file.path())
-                            .map_err(|_| ffmpeg::Error::Unknown)?;
-                    }
-                    Ok(())
-                };
-
-            for (stream, packet) in ictx.packets() {
-                if stream.index() == video_stream_index {
-                    // Note to self: can also get side data and display matrix
-                    // from packet side data.
-                    decoder.send_packet(&packet)?;
-                    receive_and_process_decoded_frames(&mut decoder)?;
-                    break;
-                }
-            }
-            decoder.send_eof()?;
-            receive_and_process_decoded_frames(&mut decoder)?;
-        }
-
-        let src_image = ImageReader::open(&temporary_png_file)?.decode()?;
+    pub fn thumbnail_internal(
+        &self,
+        path: &FlatpakPathBuf,
+        timestamp: Option<TimeDelta>,
+        orientation: Option<Orientation>,
+    ) -> Result<TimeDelta> {
+        let mut ictx = input(path.sandbox_path.as_os_str())?;
+
+        let (frame, timestamp) = if let Some(timestamp) = timestamp {
+            (frame::frame_at(&mut ictx, timestamp)?, timestamp)
+        } else {
+            Self::best_frame(&mut ictx)?
+        };
 
-        let _ = self.thumbnailer.generate_all_thumbnails(path, src_image)?;
+        let frame = match orientation {
+            Some(orientation) => orientation::transform(frame, orientation),
+            None => frame,
+        };
 
-        Ok(())
+        // Resize the decoded RGB frame in place.
+        let src_image = ImageRef::new(
+            frame.width(),
+            frame.height(),
+            frame.as_raw(),
+            PixelType::U8x3,
+        )?;
+        self.thumbnailer
+            .generate_edited_thumbnails(path, orientation, None, &src_image)?;
+
+        Ok(timestamp)
     }
 
-    fn convert_rgb_to_png(frame: &Video, rotation: f64, png_path: &Path) -> Result<()> {
-        let image_width = frame.width();
-        let image_height = frame.height();
-        let frame_bytes: Vec<u8> = frame.data(0).to_vec();
-
-        let buffer: RgbImage = ImageBuffer::from_raw(image_width, image_height, frame_bytes)
-            .expect("Video frame to image");
+    /// Scores frames at several positions in the video and picks the best.
+    fn best_frame(ictx: &mut Input) -> Result<(RgbImage, TimeDelta)> {
+        // Container duration is in AV_TIME_BASE units (microseconds).
+        // Zero or negative means unknown, so fall back to the first frame.
+        let duration = ictx.duration();
+        if duration <= 0 {
+            let frame = frame::frame_at(ictx, TimeDelta::zero())?;
+            return Ok((frame, TimeDelta::zero()));
+        }
 
-        let buffer = match rotation {
-            90.0 => imageops::rotate90(&buffer),
-            180.0 | -180.0 => imageops::rotate180(&buffer),
-            -90.0 => imageops::rotate270(&buffer),
-            _ => buffer,
-        };
+        let mut best: Option<(RgbImage, TimeDelta, f64)> = None;
+
+        for position in CANDIDATE_POSITIONS {
+            let timestamp = TimeDelta::microseconds((duration as f64 * position) as i64);
+            let Ok(frame) = frame::frame_at(ictx, timestamp) else {
+                continue;
+            };
+
+            let score = FrameScore::of(&frame);
+            debug!(
+                "Frame at {}ms scores {:?}",
+                timestamp.num_milliseconds(),
+                score
+            );
+
+            let value = score.value();
+            if best
+                .as_ref()
+                .is_none_or(|(_, _, best_value)| value > *best_value)
+            {
+                best = Some((frame, timestamp, value));
+            }
+        }
 
-        buffer.save_with_format(png_path, ImageFormat::Png)?;
-        Ok(())
+        match best {
+            Some((frame, timestamp, _)) => Ok((frame, timestamp)),
+            None => {
+                let frame = frame::frame_at(ictx, TimeDelta::zero())?;
+                Ok((frame, TimeDelta::zero()))
+            }
+        }
     }
 }
@@ -2,14 +2,63 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use anyhow::*;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use ffmpeg::channel_layout::ChannelLayout;
+use ffmpeg::format::{Pixel, Sample, sample};
+use ffmpeg::media::Type;
+use ffmpeg::{Dictionary, Packet, Rational, codec, encoder, filter, format, frame, picture};
+use ffmpeg_next as ffmpeg;
+use ffmpeg_next::packet::side_data::Type as SideDataType;
+use thiserror::Error;
 
 use crate::video::VideoId;
+use crate::video::display_matrix::av_display_rotation_get;
+use crate::video::profile::{AudioCodec, TranscodeProfile, VideoCodec};
 
 use tracing::{Level, event};
 
+/// Shortest acceptable transcoded video, as a fraction of the duration of the original.
+const MIN_DURATION_RATIO: f64 = 0.9;
+
+/// Sample rate of re-encoded audio. Supported by both AAC and Opus encoders.
+const AUDIO_SAMPLE_RATE: i32 = 48000;
+
+/// Errors from transcoding a video.
+#[derive(Error, Debug)]
+pub enum TranscodeError {
+    #[error("No video stream")]
+    NoVideoStream,
+
+    #[error("No encoder available for {0:?}")]
+    EncoderNotFound(codec::Id),
+
+    #[error("Transcoding was stopped")]
+    Cancelled,
+
+    #[error("Invalid transcoded video: {0}")]
+    InvalidOutput(String),
+
+    #[error("FFmpeg error: {0}")]
+    Ffmpeg(#[from] ffmpeg::Error),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl TranscodeError {
+    /// Is the original video at fault? If so, it should be marked as broken so
+    /// that it isn't transcoded again. Cancellation and problems with the local
+    /// system, such as a full disk or missing encoder, aren't the video's fault.
+    pub fn is_broken_video(&self) -> bool {
+        !matches!(
+            self,
+            TranscodeError::Cancelled | TranscodeError::EncoderNotFound(_) | TranscodeError::Io(_)
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Transcoder {
     /// Base path for storing transcoded videos
@@ -24,22 +73,46 @@ impl Transcoder {
     }
 
     /// Transcodes the video at 'path' and returns a path to the transcoded video.
-    pub fn transcode(&self, video_id: VideoId, video_path: &Path) -> Result<PathBuf> {
+    /// The progress callback receives the fraction of the video transcoded so far.
+    pub fn transcode<F>(
+        &self,
+        video_id: VideoId,
+        video_path: &Path,
+        profile: TranscodeProfile,
+        stop: &AtomicBool,
+        progress: F,
+    ) -> Result<PathBuf, TranscodeError>
+    where
+        F: FnMut(f64),
+    {
         let transcoded_path = {
             // Create a directory per 1000 videos
             let partition = (video_id.id() / 1000) as i32;
             let partition = format!("{:0>4}", partition);
-            let file_name = format!("{}.mkv", video_id);
+            let file_name = profile.file_name(&video_id.to_string());
             self.base_path.join(partition).join(file_name)
         };
 
-        transcode(video_path, &transcoded_path)?;
+        transcode(video_path, &transcoded_path, profile, stop, progress)?;
 
         Ok(transcoded_path)
     }
 }
 
-pub fn transcode(video_path: &Path, transcoded_path: &Path) -> Result<()> {
+/// Transcodes the best video stream and best audio stream into a Matroska container
+/// using the codecs and size of the profile. Rotation from the display matrix is applied
+/// to the frames. The transcoded video is only moved to `transcoded_path` once it
+/// has been validated.
+pub fn transcode<F>(
+    video_path: &Path,
+    transcoded_path: &Path,
+    profile: TranscodeProfile,
+    stop: &AtomicBool,
+    progress: F,
+) -> Result<(), TranscodeError>
+where
+    F: FnMut(f64),
+{
     if transcoded_path.exists() {
         return Ok(());
     } else if let Some(p) = transcoded_path.parent() {
@@ -50,22 +123,622 @@ pub fn transcode(video_path: &Path, transcoded_path: &Path) -> Result<()> {
 
     let temporary_transcoded_path = transcoded_path.with_extension("tmp.mkv");
 
-    Command::new("ffmpeg")
-        .arg("-y")
-        .arg("-loglevel")
-        .arg("error")
-        .arg("-hwaccel")
-        .arg("auto")
-        .arg("-i")
-        .arg(video_path.as_os_str())
-        .arg("-c:a")
-        .arg("copy")
-        .arg("-c:v")
-        .arg("h264")
-        .arg(temporary_transcoded_path.as_os_str())
-        .status()?;
+    let result = transcode_internal(
+        video_path,
+        &temporary_transcoded_path,
+        profile,
+        stop,
+        progress,
+    )
+    .and_then(|_| validate(video_path, &temporary_transcoded_path, profile));
+
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&temporary_transcoded_path);
+        return Err(e);
+    }
 
     std::fs::rename(&temporary_transcoded_path, transcoded_path)?;
 
     Ok(())
 }
+
+fn transcode_internal<F>(
+    video_path: &Path,
+    output_path: &Path,
+    profile: TranscodeProfile,
+    stop: &AtomicBool,
+    mut progress: F,
+) -> Result<(), TranscodeError>
+where
+    F: FnMut(f64),
+{
+    let mut ictx = format::input(video_path)?;
+    let mut octx = format::output_as(output_path, "matroska")?;
+
+    let (video_index, video_time_base, duration, mut video) = {
+        let ist = ictx
+            .streams()
+            .best(Type::Video)
+            .ok_or(TranscodeError::NoVideoStream)?;
+        let duration = ist.duration() as f64 * f64::from(ist.time_base());
+        let video = VideoTranscoder::new(&ist, &mut octx, profile)?;
+        (ist.index(), ist.time_base(), duration, video)
+    };
+
+    let mut audio = ictx
+        .streams()
+        .best(Type::Audio)
+        .map(|ist| AudioStream::new(&ist, &mut octx, profile.audio_codec()))
+        .transpose()?;
+
+    octx.set_metadata(ictx.metadata().to_owned());
+    octx.write_header()?;
+
+    // Muxer may change time bases when writing header.
+    let video_ost_time_base = octx
+        .stream(video.ost_index)
+        .ok_or(TranscodeError::NoVideoStream)?
+        .time_base();
+
+    for (stream, mut packet) in ictx.packets() {
+        if stop.load(Ordering::Relaxed) {
+            return Err(TranscodeError::Cancelled);
+        }
+
+        if stream.index() == video_index {
+            video.decoder.send_packet(&packet)?;
+            video.process_decoded_frames(&mut octx, video_ost_time_base)?;
+
+            if duration > 0.0 {
+                let position = packet.pts().unwrap_or(0) as f64 * f64::from(video_time_base);
+                progress((position / duration).clamp(0.0, 1.0));
+            }
+        } else if let Some(ref mut audio) = audio
+            && stream.index() == audio.ist_index()
+        {
+            audio.process_packet(&mut octx, &mut packet)?;
+        }
+    }
+
+    video.flush(&mut octx, video_ost_time_base)?;
+
+    if let Some(ref mut audio) = audio {
+        audio.flush(&mut octx)?;
+    }
+
+    octx.write_trailer()?;
+
+    if video.frame_count == 0 {
+        return Err(TranscodeError::InvalidOutput("no frames encoded".into()));
+    }
+
+    progress(1.0);
+
+    Ok(())
+}
+
+/// Check the transcoded video is playable and isn't truncated.
+fn validate(
+    video_path: &Path,
+    transcoded_path: &Path,
+    profile: TranscodeProfile,
+) -> Result<(), TranscodeError> {
+    let size = std::fs::metadata(transcoded_path)?.len();
+    if size == 0 {
+        return Err(TranscodeError::InvalidOutput("empty file".into()));
+    }
+
+    let ictx = format::input(video_path)?;
+    let octx = format::input(transcoded_path)?;
+
+    let Some(stream) = octx.streams().best(Type::Video) else {
+        return Err(TranscodeError::InvalidOutput("no video stream".into()));
+    };
+
+    if stream.parameters().id() != codec_id(profile.video_codec()) {
+        return Err(TranscodeError::InvalidOutput(format!(
+            "unexpected codec {:?}",
+            stream.parameters().id()
+        )));
+    }
+
+    // Container durations are in AV_TIME_BASE units. Zero or negative means unknown.
+    if ictx.duration() > 0 && octx.duration() > 0 {
+        let ratio = octx.duration() as f64 / ictx.duration() as f64;
+        if ratio < MIN_DURATION_RATIO {
+            return Err(TranscodeError::InvalidOutput(format!(
+                "duration is {:.0}% of original",
+                ratio * 100.0
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Decodes, rotates, and re-encodes a video stream.
+struct VideoTranscoder {
+    ost_index: usize,
+    time_base: Rational,
+    decoder: ffmpeg::decoder::Video,
+    filter: filter::Graph,
+    encoder: encoder::video::Encoder,
+    frame_count: usize,
+}
+
+impl VideoTranscoder {
+    fn new(
+        ist: &format::stream::Stream,
+        octx: &mut format::context::Output,
+        profile: TranscodeProfile,
+    ) -> Result<Self, TranscodeError> {
+        let decoder = codec::context::Context::from_parameters(ist.parameters())?
+            .decoder()
+            .video()?;
+
+        let time_base = ist.time_base();
+        let rotation = stream_rotation(ist);
+
+        let (width, height) = if rotation == 90 || rotation == -90 {
+            (decoder.height(), decoder.width())
+        } else {
+            (decoder.width(), decoder.height())
+        };
+        let (width, height) = profile.scaled_size(width, height);
+
+        let filter = Self::filter(&decoder, time_base, rotation, (width, height))?;
+
+        let codec_id = codec_id(profile.video_codec());
+        let codec = encoder::find(codec_id).ok_or(TranscodeError::EncoderNotFound(codec_id))?;
+        let global_header = octx.format().flags().contains(format::Flags::GLOBAL_HEADER);
+
+        let mut ost = octx.add_stream(codec)?;
+        let ost_index = ost.index();
+
+        let mut encoder = codec::context::Context::new_with_codec(codec)
+            .encoder()
+            .video()?;
+        encoder.set_width(width);
+        encoder.set_height(height);
+        encoder.set_aspect_ratio(decoder.aspect_ratio());
+        encoder.set_format(Pixel::YUV420P);
+        encoder.set_time_base(time_base);
+        if ist.avg_frame_rate().numerator() > 0 {
+            encoder.set_frame_rate(Some(ist.avg_frame_rate()));
+        }
+        if global_header {
+            encoder.set_flags(codec::Flags::GLOBAL_HEADER);
+        }
+
+        let crf = profile.crf().to_string();
+        let mut options = Dictionary::new();
+        match profile.video_codec() {
+            VideoCodec::H264 => {
+                options.set("preset", "veryfast");
+                options.set("crf", &crf);
+            }
+            VideoCodec::Vp9 => {
+                // Constant quality mode for libvpx-vp9 needs a zero bit rate.
+                options.set("crf", &crf);
+                options.set("b", "0");
+                options.set("deadline", "good");
+                options.set("cpu-used", "4");
+                options.set("row-mt", "1");
+            }
+        }
+
+        let encoder = encoder.open_with(options)?;
+        ost.set_parameters(&encoder);
+
+        Ok(Self {
+            ost_index,
+            time_base,
+            decoder,
+            filter,
+            encoder,
+            frame_count: 0,
+        })
+    }
+
+    /// Filter graph to apply rotation, scale to the output size, and convert frames
+    /// to a pixel format all H.264 and VP9 encoders accept.
+    fn filter(
+        decoder: &ffmpeg::decoder::Video,
+        time_base: Rational,
+        rotation: i32,
+        (width, height): (u32, u32),
+    ) -> Result<filter::Graph, ffmpeg::Error> {
+        let rotate = rotate_filter(rotation);
+        let spec = format!("{rotate}scale={width}:{height},format=yuv420p");
+        video_filter(decoder, time_base, &spec)
+    }
+
+    /// Flush frames buffered in the decoder, filters, and encoder.
+    fn flush(
+        &mut self,
+        octx: &mut format::context::Output,
+        ost_time_base: Rational,
+    ) -> Result<(), ffmpeg::Error> {
+        self.decoder.send_eof()?;
+        self.process_decoded_frames(octx, ost_time_base)?;
+        self.filter.get("in").expect("in filter").source().flush()?;
+        self.process_filtered_frames(octx, ost_time_base)?;
+        self.encoder.send_eof()?;
+        self.process_encoded_packets(octx, ost_time_base)
+    }
+
+    fn process_decoded_frames(
+        &mut self,
+        octx: &mut format::context::Output,
+        ost_time_base: Rational,
+    ) -> Result<(), ffmpeg::Error> {
+        let mut decoded = frame::Video::empty();
+        while self.decoder.receive_frame(&mut decoded).is_ok() {
+            let timestamp = decoded.timestamp();
+            decoded.set_pts(timestamp);
+            self.filter
+                .get("in")
+                .expect("in filter")
+                .source()
+                .add(&decoded)?;
+            self.process_filtered_frames(octx, ost_time_base)?;
+        }
+        Ok(())
+    }
+
+    fn process_filtered_frames(
+        &mut self,
+        octx: &mut format::context::Output,
+        ost_time_base: Rational,
+    ) -> Result<(), ffmpeg::Error> {
+        let mut filtered = frame::Video::empty();
+        while self
+            .filter
+            .get("out")
+            .expect("out filter")
+            .sink()
+            .frame(&mut filtered)
+            .is_ok()
+        {
+            filtered.set_kind(picture::Type::None);
+            self.encoder.send_frame(&filtered)?;
+            self.frame_count += 1;
+            self.process_encoded_packets(octx, ost_time_base)?;
+        }
+        Ok(())
+    }
+
+    fn process_encoded_packets(
+        &mut self,
+        octx: &mut format::context::Output,
+        ost_time_base: Rational,
+    ) -> Result<(), ffmpeg::Error> {
+        let mut encoded = Packet::empty();
+        while self.encoder.receive_packet(&mut encoded).is_ok() {
+            encoded.set_stream(self.ost_index);
+            encoded.rescale_ts(self.time_base, ost_time_base);
+            encoded.write_interleaved(octx)?;
+        }
+        Ok(())
+    }
+}
+
+/// Filter graph from decoded frames to the filters in `spec`. Frames are
+/// added to the "in" filter and taken from the "out" filter.
+pub(crate) fn video_filter(
+    decoder: &ffmpeg::decoder::Video,
+    time_base: Rational,
+    spec: &str,
+) -> Result<filter::Graph, ffmpeg::Error> {
+    let mut graph = filter::Graph::new();
+
+    let aspect_ratio = decoder.aspect_ratio();
+    let args = format!(
+        "video_size={}x{}:pix_fmt={}:time_base={}/{}:pixel_aspect={}/{}",
+        decoder.width(),
+        decoder.height(),
+        ffmpeg::ffi::AVPixelFormat::from(decoder.format()) as i32,
+        time_base.numerator(),
+        time_base.denominator(),
+        aspect_ratio.numerator().max(1),
+        aspect_ratio.denominator().max(1),
+    );
+
+    let buffer = filter::find("buffer").ok_or(ffmpeg::Error::FilterNotFound)?;
+    let buffersink = filter::find("buffersink").ok_or(ffmpeg::Error::FilterNotFound)?;
+    graph.add(&buffer, "in", &args)?;
+    graph.add(&buffersink, "out", "")?;
+
+    graph.output("in", 0)?.input("out", 0)?.parse(spec)?;
+    graph.validate()?;
+
+    Ok(graph)
+}
+
+/// Rotation in degrees from the stream's display matrix.
+pub(crate) fn stream_rotation(ist: &format::stream::Stream) -> i32 {
+    ist.side_data()
+        .find(|item| item.kind() == SideDataType::DisplayMatrix)
+        .map(|display_matrix| av_display_rotation_get(display_matrix.data()))
+        .filter(|rotation| !rotation.is_nan())
+        .map(|rotation| rotation.round() as i32)
+        .unwrap_or(0)
+}
+
+/// Filters, with a trailing comma, to apply a rotation.
+/// Matches the rotation the video thumbnailer applies.
+pub(crate) fn rotate_filter(rotation: i32) -> &'static str {
+    match rotation {
+        90 => "transpose=clock,",
+        180 | -180 => "hflip,vflip,",
+        -90 => "transpose=cclock,",
+        _ => "",
+    }
+}
+
+fn codec_id(video_codec: VideoCodec) -> codec::Id {
+    match video_codec {
+        VideoCodec::H264 => codec::Id::H264,
+        VideoCodec::Vp9 => codec::Id::VP9,
+    }
+}
+
+/// Audio is either copied as-is or re-encoded to stereo.
+enum AudioStream {
+    Copy {
+        ist_index: usize,
+        ist_time_base: Rational,
+        ost_index: usize,
+    },
+    Transcode(Box<AudioTranscoder>),
+}
+
+impl AudioStream {
+    fn new(
+        ist: &format::stream::Stream,
+        octx: &mut format::context::Output,
+        audio_codec: AudioCodec,
+    ) -> Result<Self, TranscodeError> {
+        if audio_codec == AudioCodec::Copy {
+            let mut ost = octx.add_stream(encoder::find(codec::Id::None))?;
+            ost.set_parameters(ist.parameters());
+
+            // Codec tags are container specific and must be reset when changing container.
+            unsafe {
+                (*ost.parameters().as_mut_ptr()).codec_tag = 0;
+            }
+
+            Ok(AudioStream::Copy {
+                ist_index: ist.index(),
+                ist_time_base: ist.time_base(),
+                ost_index: ost.index(),
+            })
+        } else {
+            let audio = AudioTranscoder::new(ist, octx, audio_codec)?;
+            Ok(AudioStream::Transcode(Box::new(audio)))
+        }
+    }
+
+    fn ist_index(&self) -> usize {
+        match self {
+            AudioStream::Copy { ist_index, .. } => *ist_index,
+            AudioStream::Transcode(audio) => audio.ist_index,
+        }
+    }
+
+    fn process_packet(
+        &mut self,
+        octx: &mut format::context::Output,
+        packet: &mut Packet,
+    ) -> Result<(), ffmpeg::Error> {
+        match self {
+            AudioStream::Copy {
+                ist_time_base,
+                ost_index,
+                ..
+            } => {
+                let ost_time_base = octx
+                    .stream(*ost_index)
+                    .map(|s| s.time_base())
+                    .unwrap_or(*ist_time_base);
+                packet.rescale_ts(*ist_time_base, ost_time_base);
+                packet.set_position(-1);
+                packet.set_stream(*ost_index);
+                packet.write_interleaved(octx)
+            }
+            AudioStream::Transcode(audio) => {
+                audio.decoder.send_packet(packet)?;
+                audio.process_decoded_frames(octx)
+            }
+        }
+    }
+
+    fn flush(&mut self, octx: &mut format::context::Output) -> Result<(), ffmpeg::Error> {
+        match self {
+            AudioStream::Copy { .. } => Ok(()),
+            AudioStream::Transcode(audio) => audio.flush(octx),
+        }
+    }
+}
+
+/// Decodes, resamples to stereo, and re-encodes an audio stream.
+struct AudioTranscoder {
+    ist_index: usize,
+    ost_index: usize,
+    decoder: ffmpeg::decoder::Audio,
+    filter: filter::Graph,
+    encoder: encoder::audio::Encoder,
+}
+
+impl AudioTranscoder {
+    fn new(
+        ist: &format::stream::Stream,
+        octx: &mut format::context::Output,
+        audio_codec: AudioCodec,
+    ) -> Result<Self, TranscodeError> {
+        let decoder = codec::context::Context::from_parameters(ist.parameters())?
+            .decoder()
+            .audio()?;
+
+        let (codec_id, codec, format, bit_rate) = match audio_codec {
+            AudioCodec::Aac => (
+                codec::Id::AAC,
+                encoder::find(codec::Id::AAC),
+                Sample::F32(sample::Type::Planar),
+                128_000,
+            ),
+            AudioCodec::Opus => (
+                codec::Id::OPUS,
+                encoder::find_by_name("libopus"),
+                Sample::F32(sample::Type::Packed),
+                96_000,
+            ),
+            AudioCodec::Copy => unreachable!("copied audio isn't transcoded"),
+        };
+        let codec = codec.ok_or(TranscodeError::EncoderNotFound(codec_id))?;
+
+        let global_header = octx.format().flags().contains(format::Flags::GLOBAL_HEADER);
+
+        let mut ost = octx.add_stream(codec)?;
+        let ost_index = ost.index();
+
+        let mut encoder = codec::context::Context::new_with_codec(codec)
+            .encoder()
+            .audio()?;
+        encoder.set_rate(AUDIO_SAMPLE_RATE);
+        encoder.set_ch_layout(ChannelLayout::STEREO);
+        encoder.set_format(format);
+        encoder.set_bit_rate(bit_rate);
+        encoder.set_time_base((1, AUDIO_SAMPLE_RATE));
+        if global_header {
+            encoder.set_flags(codec::Flags::GLOBAL_HEADER);
+        }
+
+        let encoder = encoder.open_as(codec)?;
+        ost.set_parameters(&encoder);
+        ost.set_time_base((1, AUDIO_SAMPLE_RATE));
+
+        let filter = Self::filter(&decoder, ist.time_base(), &encoder)?;
+
+        Ok(Self {
+            ist_index: ist.index(),
+            ost_index,
+            decoder,
+            filter,
+            encoder,
+        })
+    }
+
+    /// Filter graph to resample to the sample rate, sample format, and channel
+    /// layout the encoder expects.
+    fn filter(
+        decoder: &ffmpeg::decoder::Audio,
+        time_base: Rational,
+        encoder: &encoder::audio::Encoder,
+    ) -> Result<filter::Graph, ffmpeg::Error> {
+        let mut graph = filter::Graph::new();
+
+        let args = format!(
+            "time_base={}/{}:sample_rate={}:sample_fmt={}:channel_layout={}c",
+            time_base.numerator(),
+            time_base.denominator(),
+            decoder.rate(),
+            decoder.format().name(),
+            decoder.ch_layout().channels(),
+        );
+
+        let abuffer = filter::find("abuffer").ok_or(ffmpeg::Error::FilterNotFound)?;
+        let abuffersink = filter::find("abuffersink").ok_or(ffmpeg::Error::FilterNotFound)?;
+        graph.add(&abuffer, "in", &args)?;
+        graph.add(&abuffersink, "out", "")?;
+
+        let spec = format!(
+            "aresample={rate},aformat=sample_fmts={format}:channel_layouts=stereo,asettb=1/{rate}",
+            rate = AUDIO_SAMPLE_RATE,
+            format = encoder.format().name(),
+        );
+
+        graph.output("in", 0)?.input("out", 0)?.parse(&spec)?;
+        graph.validate()?;
+
+        // Encoders with a fixed frame size, such as AAC, need frames of exactly that size.
+        if !encoder.codec().is_some_and(|codec| {
+            codec
+                .capabilities()
+                .contains(codec::Capabilities::VARIABLE_FRAME_SIZE)
+        }) {
+            graph
+                .get("out")
+                .expect("out filter")
+                .sink()
+                .set_frame_size(encoder.frame_size());
+        }
+
+        Ok(graph)
+    }
+
+    /// Flush samples buffered in the decoder, filters, and encoder.
+    fn flush(&mut self, octx: &mut format::context::Output) -> Result<(), ffmpeg::Error> {
+        self.decoder.send_eof()?;
+        self.process_decoded_frames(octx)?;
+        self.filter.get("in").expect("in filter").source().flush()?;
+        self.process_filtered_frames(octx)?;
+        self.encoder.send_eof()?;
+        self.process_encoded_packets(octx)
+    }
+
+    fn process_decoded_frames(
+        &mut self,
+        octx: &mut format::context::Output,
+    ) -> Result<(), ffmpeg::Error> {
+        let mut decoded = frame::Audio::empty();
+        while self.decoder.receive_frame(&mut decoded).is_ok() {
+            let timestamp = decoded.timestamp();
+            decoded.set_pts(timestamp);
+            self.filter
+                .get("in")
+                .expect("in filter")
+                .source()
+                .add(&decoded)?;
+            self.process_filtered_frames(octx)?;
+        }
+        Ok(())
+    }
+
+    fn process_filtered_frames(
+        &mut self,
+        octx: &mut format::context::Output,
+    ) -> Result<(), ffmpeg::Error> {
+        let mut filtered = frame::Audio::empty();
+        while self
+            .filter
+            .get("out")
+            .expect("out filter")
+            .sink()
+            .frame(&mut filtered)
+            .is_ok()
+        {
+            self.encoder.send_frame(&filtered)?;
+            self.process_encoded_packets(octx)?;
+        }
+        Ok(())
+    }
+
+    fn process_encoded_packets(
+        &mut self,
+        octx: &mut format::context::Output,
+    ) -> Result<(), ffmpeg::Error> {
+        let ost_time_base = octx
+            .stream(self.ost_index)
+            .map(|s| s.time_base())
+            .unwrap_or(Rational::new(1, AUDIO_SAMPLE_RATE));
+
+        let mut encoded = Packet::empty();
+        while self.encoder.receive_packet(&mut encoded).is_ok() {
+            encoded.set_stream(self.ost_index);
+            encoded.rescale_ts(Rational::new(1, AUDIO_SAMPLE_RATE), ost_time_base);
+            encoded.write_interleaved(octx)?;
+        }
+        Ok(())
+    }
+}
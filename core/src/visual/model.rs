@@ -3,10 +3,12 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use std::fmt::Display;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::FlatpakPathBuf;
+use crate::edits::EditStack;
 use crate::photo::model::Orientation;
+use crate::places::Place;
 use crate::thumbnailify;
 use crate::{PictureId, VideoId, YearMonth};
 
@@ -55,20 +57,40 @@ pub struct Visual {
     // Duration of video content
     pub video_duration: Option<TimeDelta>,
 
-    // Rotation of video content. Derived from degrees.
+    // Rotation of video content. Derived from degrees, and turned by any override.
     pub video_orientation: Option<Orientation>,
 
+    /// Turn chosen by the user, on top of the rotation of the video stream.
+    pub video_orientation_override: Option<Orientation>,
+
     pub picture_id: Option<PictureId>,
 
     pub picture_path: Option<FlatpakPathBuf>,
 
+    /// Orientation chosen by the user if any, or else the EXIF orientation.
     pub picture_orientation: Option<Orientation>,
 
+    /// Orientation chosen by the user, replacing the EXIF orientation.
+    pub picture_orientation_override: Option<Orientation>,
+
+    /// Non-destructive edits of the picture, if any.
+    pub picture_edits: Option<EditStack>,
+
     pub motion_photo_video_path: Option<PathBuf>,
 
+    // Is this an animated image, such as a GIF?
+    pub is_animated: bool,
+
+    // Time to play all frames of an animated image once.
+    pub animation_duration: Option<TimeDelta>,
+
     /// Best candidate for ordering visual items. With a final fallback of the current timestamp.
     pub ordering_ts: DateTime<Utc>,
 
+    /// Wall-clock time at the place of capture, which is what users expect
+    /// days, months, and years to be grouped by.
+    pub local_ts: NaiveDateTime,
+
     // Is this a selfie?
     pub is_selfie: Option<bool>,
 
@@ -80,6 +102,9 @@ pub struct Visual {
 
     // Where photo was taken
     pub location: Option<LatLng>,
+
+    /// Name of place where photo was taken
+    pub place: Option<Place>,
 }
 
 impl Visual {
@@ -98,8 +123,38 @@ impl Visual {
         &self.path().host_path
     }
 
+    /// Hash of thumbnails to show. Edited and reoriented items have thumbnails of their own,
+    /// so the thumbnails of the original file are left for other apps.
     pub fn thumbnail_hash(&self) -> String {
-        thumbnailify::compute_hash_for_path(self.host_path())
+        thumbnailify::compute_hash_for_edited_path(
+            self.host_path(),
+            self.orientation_override(),
+            self.picture_edits.as_ref(),
+        )
+    }
+
+    /// Hash of thumbnails without edits, but with the orientation chosen by the user.
+    pub fn unedited_thumbnail_hash(&self) -> String {
+        thumbnailify::compute_hash_for_edited_path(
+            self.host_path(),
+            self.orientation_override(),
+            None,
+        )
+    }
+
+    /// Orientation chosen by the user for the picture, or for the video if there is no picture.
+    pub fn orientation_override(&self) -> Option<Orientation> {
+        if self.picture_path.is_some() {
+            self.picture_orientation_override
+        } else {
+            self.video_orientation_override
+        }
+    }
+
+    pub fn is_edited(&self) -> bool {
+        self.picture_edits
+            .as_ref()
+            .is_some_and(|edits| !edits.is_empty())
     }
 
     pub fn is_selfie(&self) -> bool {
@@ -110,6 +165,20 @@ impl Visual {
         self.is_live_photo
     }
 
+    pub fn is_animated(&self) -> bool {
+        self.is_animated
+    }
+
+    /// The video of a live photo or motion photo.
+    pub fn live_video_path(&self) -> Option<&Path> {
+        if !self.is_motion_photo() {
+            return None;
+        }
+        self.motion_photo_video_path
+            .as_deref()
+            .or(self.video_path.as_ref().map(|x| x.sandbox_path.as_path()))
+    }
+
     pub fn is_photo_only(&self) -> bool {
         self.picture_id.is_some() && self.video_id.is_none() && !self.is_live_photo
     }
@@ -118,12 +187,18 @@ impl Visual {
         self.picture_id.is_none() && self.video_id.is_some()
     }
 
+    /// Key to order items by. Items are ordered by local time so that days, months, and
+    /// years are contiguous, with UTC time breaking ties between different time zones.
+    pub fn sort_key(&self) -> (NaiveDateTime, DateTime<Utc>) {
+        (self.local_ts, self.ordering_ts)
+    }
+
     pub fn year(&self) -> u32 {
-        self.ordering_ts.date_naive().year_ce().1
+        self.local_ts.date().year_ce().1
     }
 
     pub fn year_month(&self) -> YearMonth {
-        let date = self.ordering_ts.date_naive();
+        let date = self.local_ts.date();
         let year = date.year();
         let month = date.month();
         let month = chrono::Month::try_from(u8::try_from(month).unwrap()).unwrap();
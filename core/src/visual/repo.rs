@@ -3,7 +3,9 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use crate::FlatpakPathBuf;
+use crate::edits::EditStack;
 use crate::photo::PictureId;
+use crate::places::Place;
 use crate::video::VideoId;
 use crate::visual::model::{PictureOrientation, Visual, VisualId};
 
@@ -58,29 +60,43 @@ impl Repository {
                     picture_id,
                     picture_path_b64,
                     picture_orientation,
+                    picture_orientation_override,
                     is_selfie,
+                    picture_edits,
 
                     video_id,
                     video_path_b64,
 
                     motion_photo_video_path,
+                    is_animated,
+                    animation_duration_millis,
 
                     ordering_ts,
+                    local_ts,
                     is_live_photo,
 
                     video_transcoded_path,
                     is_transcode_required,
                     duration_millis,
                     video_rotation,
+                    video_orientation_override,
 
                     latitude,
-                    longitude
+                    longitude,
+
+                    country_code,
+                    country_name,
+                    region_name,
+                    city_name
                 FROM visual
                 ORDER BY ordering_ts ASC",
         )?;
 
         let result = stmt.query_map([], |row| self.to_visual(row))?;
-        let visuals = result.flatten().collect();
+        let mut visuals: Vec<Visual> = result.flatten().collect();
+
+        // Local time can fall back to UTC time, so it can't be sorted by in SQL.
+        visuals.sort_by_key(|v| v.sort_key());
         Ok(visuals)
     }
 
@@ -122,8 +138,18 @@ impl Repository {
             .map(|x: u32| PictureOrientation::from(x))
             .ok();
 
+        let picture_orientation_override: Option<PictureOrientation> = row
+            .get("picture_orientation_override")
+            .map(|x: u32| PictureOrientation::from(x))
+            .ok();
+
         let is_selfie: Option<bool> = row.get("is_selfie").ok();
 
+        let picture_edits: Option<EditStack> = row
+            .get("picture_edits")
+            .ok()
+            .and_then(|x: String| EditStack::parse(&x));
+
         let video_id: Option<VideoId> = row.get("video_id").map(VideoId::new).ok();
 
         let video_relative_path: Option<PathBuf> = row
@@ -145,19 +171,44 @@ impl Repository {
                 None
             };
 
+        let video_orientation_override: Option<PictureOrientation> = row
+            .get("video_orientation_override")
+            .map(|x: u32| PictureOrientation::from(x))
+            .ok();
+
         let video_orientation: Option<PictureOrientation> = row
             .get("video_rotation")
             .map(|x: i32| PictureOrientation::from_degrees(x))
             .ok();
 
+        let video_orientation = match video_orientation_override {
+            Some(turn) => Some(video_orientation.unwrap_or_default().turned_by(turn)),
+            None => video_orientation,
+        };
+
         let motion_photo_video_path: Option<PathBuf> = row
             .get("motion_photo_video_path")
             .map(|x: String| PathBuf::from(x))
             .map(|x| self.cache_dir_base_path.join(x))
             .ok();
 
+        let is_animated: Option<bool> = row.get("is_animated").ok();
+        let is_animated = is_animated.is_some_and(|x| x);
+
+        let animation_duration: Option<TimeDelta> = row
+            .get("animation_duration_millis")
+            .ok()
+            .and_then(TimeDelta::try_milliseconds);
+
         let ordering_ts: DateTime<Utc> = row.get("ordering_ts").expect("Must have ordering_ts");
 
+        // Without a known place of capture, assume the wall-clock time in this computer's time zone.
+        let local_ts: NaiveDateTime = row
+            .get::<_, Option<NaiveDateTime>>("local_ts")
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| ordering_ts.with_timezone(&Local).naive_local());
+
         let is_live_photo: Option<bool> = row.get("is_live_photo").ok();
 
         let is_live_photo = is_live_photo.is_some_and(|x| x);
@@ -184,23 +235,48 @@ impl Repository {
             None
         };
 
+        let country_code: Option<String> = row.get("country_code").ok().flatten();
+        let country: Option<String> = row.get("country_name").ok().flatten();
+        let region: Option<String> = row.get("region_name").ok().flatten();
+        let city: Option<String> = row.get("city_name").ok().flatten();
+
+        let place = if let (Some(country_code), Some(country), Some(city)) =
+            (country_code, country, city)
+        {
+            Some(Place {
+                country_code,
+                country,
+                region,
+                city,
+            })
+        } else {
+            None
+        };
+
         let v = Visual {
             visual_id,
             parent_path: link_path.parent().map(PathBuf::from).expect("Parent path"),
             picture_id,
             picture_path,
             picture_orientation,
+            picture_orientation_override,
+            picture_edits,
             video_id,
             video_path,
             ordering_ts,
+            local_ts,
             is_selfie,
             is_live_photo,
             video_transcoded_path,
             video_orientation,
+            video_orientation_override,
             is_transcode_required,
             video_duration,
             motion_photo_video_path,
+            is_animated,
+            animation_duration,
             location,
+            place,
         };
         Ok(v)
     }
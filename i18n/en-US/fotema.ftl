@@ -36,6 +36,69 @@ folder-album = Folder
 # Title for places page which shows photos overlayed onto a map.
 places-page = Places
 
+# Title for places page view showing photos on a map.
+places-map = Map
+
+# Title for places page view showing a list of countries and cities.
+places-list = List
+
+# Row for showing all photos and videos taken in a country.
+places-list-all = All
+
+# Count of photos and videos taken in a country.
+places-list-count = { $count ->
+    [one] { $count } item
+   *[other] { $count } items
+}
+
+# Search entry for filtering places by city, region, or country.
+# Attributes:
+#  .placeholder - Placeholder text of the search entry.
+#  .no-results - Status page title shown when no places match the search text.
+places-list-search =
+  .placeholder = Search places
+  .no-results = No Matching Places
+
+# Status page shown when no photos or videos have a named place.
+places-list-empty =
+  .title = No Places
+  .description = Photos and videos with a location will be listed here once their place names have been found.
+
+# Title for events page which shows trips and events found by grouping
+# photos and videos by time and place.
+events-page = Events
+
+# Title of a trip.
+# Variables:
+#   $place - name of city, region, or country of trip.
+events-trip-title = Trip to { $place }
+
+# Title of a trip with no known place.
+events-trip-untitled = Trip
+
+# Row for showing all photos and videos in a trip.
+events-trip-all = All
+
+# Range of dates of a multi-day trip or event.
+# Variables:
+#   $start - date of first photo or video.
+#   $end - date of last photo or video.
+events-date-range = { $start } – { $end }
+
+# Subtitle for trips and events.
+# Variables:
+#   $dates - date or range of dates.
+#   $count - number of photos and videos.
+events-subtitle = { $count ->
+    [one] { $dates } · { $count } item
+   *[other] { $dates } · { $count } items
+}
+
+# Status page shown when no events have been found.
+events-empty =
+  .title = No Events
+  .description = Photos and videos taken close together in time and place will be grouped into events and trips here.
+
 # Title for people page which shows an album of faces.
 people-page = People
 
@@ -90,6 +153,41 @@ about-translator-credits =
 # Tooltip for (i) button to show photo/video information sidebar
 viewer-info-tooltip = Show properties
 
+# Tooltip for button to set the location of a photo/video on a map
+viewer-set-location-tooltip = Set location
+
+# Tooltip for button to crop, rotate, and adjust a photo
+viewer-edit-tooltip = Edit
+
+# Tooltip for button to turn a photo/video a quarter turn anticlockwise
+viewer-rotate-left-tooltip = Rotate left
+
+# Tooltip for button to turn a photo/video a quarter turn clockwise
+viewer-rotate-right-tooltip = Rotate right
+
+# Menu to export the video of a live or motion photo.
+viewer-export-menu =
+  .tooltip = Export
+  .video = Export video (MP4)
+  .gif = Export as animated GIF
+  .webp = Export as animated WebP
+  .still = Export current frame (JPEG)
+
+# Toast shown when exporting the video of a live or motion photo fails.
+viewer-export-failed = Couldn't export video
+
+# Toast shown when writing the orientation of a picture to its file fails.
+viewer-orientation-write-failed = Couldn't save orientation to file
+
+# Menu of operations on the files of the item being viewed.
+viewer-file-menu =
+  .tooltip = File
+  .rename = Rename…
+  .move = Move to Folder…
+  .copy = Copy to Folder…
+  .share = Export or Share…
+  .trash = Move to Trash
+
 viewer-faces-menu =
   .tooltip = Faces menu
   .restore-ignored = Restore all ignored faces
@@ -132,6 +230,19 @@ viewer-skip-backwards-10-seconds =
 viewer-mute =
   .tooltip = Mute/Unmute
 
+viewer-zoom-in =
+  .tooltip = Zoom In
+
+viewer-zoom-out =
+  .tooltip = Zoom Out
+
+# Button showing the current zoom, which switches between fitting the photo
+# to the window and showing it at its actual size.
+# Variables:
+#   $percent - Zoom as a percentage of the actual size of the photo.
+viewer-zoom-toggle = { $percent }%
+  .tooltip = Fit to Window or Actual Size
+
 # Convert all incompatible videos description.
 viewer-convert-all-description = This video must be converted before it can be played. This only needs to happen once, but it takes a while to convert a video.
 
@@ -152,6 +263,230 @@ viewer-error-missing-file = Cannot display file because it is missing:
 # be raised.
 viewer-error-missing-path = File path not present in database
 
+## Slideshow
+
+# Tooltip for button to start a slideshow of the album being viewed.
+slideshow-start-tooltip = Play slideshow
+
+# Position of the item shown in a slideshow.
+# Variables:
+#   $position - Number of the item shown, starting from one.
+#   $count - Number of items in the slideshow.
+slideshow-position = { $position } / { $count }
+
+# Button to switch between shuffled and sorted order in a slideshow.
+# Attributes:
+#  .on - Tooltip when shuffled.
+#  .off - Tooltip when in sort order.
+slideshow-shuffle =
+  .on = Shuffled
+  .off = In order
+
+slideshow-exit =
+  .tooltip = Exit Slideshow
+
+# Reason given to the desktop for keeping the screen on during a slideshow.
+slideshow-inhibit-reason = Playing slideshow
+
+## Selection mode
+
+# Tooltip for button to start selecting items of the album being viewed.
+album-select-tooltip = Select
+
+# Button to stop selecting items.
+album-selection-cancel = Cancel
+
+# Number of items selected.
+# Variables:
+#   $count - Number of selected items.
+album-selection-count = { $count ->
+    [0] No items selected
+    [one] 1 item selected
+   *[other] { $count } items selected
+}
+
+# Menu for adding items to the selection.
+# Attributes:
+#  .tooltip - Tooltip for menu button.
+#  .all - Select all items in the album.
+#  .month - Select all items in the same months as the selected items.
+#  .year - Select all items in the same years as the selected items.
+album-select-menu =
+  .tooltip = Select more
+  .all = Select All
+  .month = Select Whole Months
+  .year = Select Whole Years
+
+# Menu of file operations for the selected items.
+album-selection-file-menu =
+  .tooltip = Files
+  .move = Move to Folder…
+  .copy = Copy to Folder…
+
+# Menu of other operations on the selected items.
+album-selection-more-menu =
+  .tooltip = More
+  .assign-person = Assign Person…
+  .set-date = Set Date…
+
+album-selection-trash-tooltip = Move to Trash
+
+album-selection-share-tooltip = Export or Share
+
+## File operations
+
+files-rename-dialog =
+  .heading = Rename
+  .cancel-button = Cancel
+  .rename-button = Rename
+
+files-rename-invalid = Name is not a valid file name
+
+# Variables:
+#   $name - Name chosen for the item, without an extension.
+files-rename-exists = A file named “{ $name }” already exists
+
+files-rename-failed = Couldn't rename file
+
+# Variables:
+#   $count - Number of items moved.
+#   $folder - Name of the folder the items were moved to.
+files-move-done = { $count ->
+    [one] Moved to { $folder }
+   *[other] Moved { $count } items to { $folder }
+}
+
+# Variables:
+#   $count - Number of items that were not moved.
+files-move-failed = { $count ->
+    [one] Couldn't move one item
+   *[other] Couldn't move { $count } items
+}
+
+# Variables:
+#   $count - Number of items copied.
+#   $folder - Name of the folder the items were copied to.
+files-copy-done = { $count ->
+    [one] Copied to { $folder }
+   *[other] Copied { $count } items to { $folder }
+}
+
+# Variables:
+#   $count - Number of items that were not copied.
+files-copy-failed = { $count ->
+    [one] Couldn't copy one item
+   *[other] Couldn't copy { $count } items
+}
+
+# Toast shown after moving items to the trash, which can be undone.
+# Variables:
+#   $count - Number of items moved to the trash.
+# Attributes:
+#  .undo-button - Button to restore the items.
+files-trash-done = { $count ->
+    [one] Moved to trash
+   *[other] Moved { $count } items to trash
+}
+  .undo-button = Undo
+
+# Variables:
+#   $count - Number of items that were not moved to the trash.
+files-trash-failed = { $count ->
+    [one] Couldn't move one item to trash
+   *[other] Couldn't move { $count } items to trash
+}
+
+files-folder-outside-library = Choose a folder in the picture library
+
+## Export and share
+
+# Title of dialog for exporting or sharing items.
+# Variables:
+#   $count - Number of items to export.
+export-title = { $count ->
+    [one] Export 1 Item
+   *[other] Export { $count } Items
+}
+
+export-pictures-section = Pictures
+
+# Attributes:
+#  .original - Keep the format of each picture.
+export-format = Format
+  .original = Original
+  .jpeg = JPEG
+  .webp = WebP (lossless)
+  .avif = AVIF
+
+export-quality = Quality
+  .subtitle = For JPEG and AVIF pictures.
+
+export-convert-heic = Convert HEIC to JPEG
+  .subtitle = HEIC pictures can't be opened by many apps.
+
+# Attributes:
+#  .original - Keep the size of each picture.
+#  .pixels - Limit the longest side of each picture.
+# Variables:
+#   $pixels - Maximum length of the longest side in pixels.
+export-size = Size
+  .original = Original
+  .pixels = At most { $pixels } pixels
+
+export-metadata-section = Metadata
+  .description = Locations in private zones are never exported.
+
+export-metadata = Keep
+  .all = All metadata
+  .no-location = All except location
+  .none = No metadata
+
+export-files-section = Files
+
+# Placeholders for parts of the file name.
+export-file-name-template = File name
+  .tooltip = Use {"{"}name{"}"}, {"{"}date{"}"}, {"{"}time{"}"}, {"{"}year{"}"}, {"{"}month{"}"}, {"{"}day{"}"}, and {"{"}n{"}"} for a number.
+
+export-zip = Bundle into ZIP archive
+  .subtitle = Always used when sharing several items with an app.
+
+export-button = Export to Folder…
+
+export-share-email-button = Email
+
+export-share-app-button = Open With…
+
+# Name of ZIP archive of exported items, without an extension.
+export-archive-name = Pictures
+
+# Variables:
+#   $count - Number of items being exported.
+export-started = { $count ->
+    [one] Exporting 1 item…
+   *[other] Exporting { $count } items…
+}
+
+# Button on the toast shown while exporting.
+export-cancel = Cancel
+
+export-cancelled = Export cancelled
+
+# Variables:
+#   $count - Number of items exported.
+export-done = { $count ->
+    [one] Exported 1 item
+   *[other] Exported { $count } items
+}
+
+# Variables:
+#   $count - Number of items that were not exported.
+export-failed = { $count ->
+    [one] Couldn't export one item
+   *[other] Couldn't export { $count } items
+}
+
+export-share-failed = Couldn't share items
+
 ## Photo/Video Information Sidebar
 
 # Name of containing folder of photo or video being viewed.
@@ -163,6 +498,9 @@ infobar-folder = Folder
 # File name of photo or video
 infobar-file-name = File Name
 
+# Name of nearest city, region, and country where photo or video was taken.
+infobar-place = Place
+
 # File creation timestamp from file system metadata.
 infobar-file-created = File Created
 
@@ -255,6 +593,31 @@ prefs-processing-face-detection = Face detection
 prefs-processing-motion-photos = Motion photos
   .subtitle = Detect Android motion photos and extract the videos.
 
+prefs-processing-write-orientation = Save rotation to files
+  .subtitle = When rotating a picture, also change the orientation saved in the picture file. HEIC files are never changed.
+
+prefs-slideshow-section = Slideshows
+
+# Seconds to show each picture in a slideshow.
+prefs-slideshow-interval = Time per picture
+  .subtitle = Seconds to show each picture. Videos and live photos always play to the end.
+
+prefs-slideshow-shuffle = Shuffle
+  .subtitle = Play slideshows in a random order.
+
+prefs-background-section = Background processing
+  .description = Limit how much of your computer { -app-name } uses while processing your library.
+
+# Number of threads for background processing.
+prefs-background-threads = Processing threads
+  .subtitle = Number of CPU threads for scanning, thumbnails, and face detection. Zero leaves one CPU core free.
+
+prefs-background-pause-on-battery = Pause on battery
+  .subtitle = Pause processing while your computer is running on battery.
+
+prefs-background-pause-while-active = Pause while in use
+  .subtitle = Pause processing while you are using { -app-name }, and resume a few seconds later.
+
 prefs-library-section =
   .title = Library
   .description = Configure library directory.
@@ -264,6 +627,81 @@ prefs-library-section-pictures-dir =
   .title = Pictures Directory
   .tooltip = Choose pictures directory.
 
+# Preferences for hiding where pictures and videos were taken.
+prefs-videos-section = Videos
+  .description = Incompatible videos and motion photos are converted and stored in a cache.
+
+# Trade-offs between quality, size, and codecs for converted videos.
+prefs-videos-transcode-profile = Conversion quality
+  .subtitle = Quality and size of converted videos.
+  .standard = Standard
+  .compact = Compact
+  .open = Open codecs
+
+prefs-videos-cache-limit = Video cache limit
+  .subtitle = Gigabytes of disk space for converted videos. Least recently viewed videos are deleted first and converted again when viewed. Zero is unlimited.
+
+prefs-thumbnails-section = Thumbnails
+  .description = Thumbnails of pictures and videos removed from your library are deleted automatically.
+
+# Total disk space used by thumbnails. Expands to show each part of the thumbnail cache.
+prefs-thumbnails-usage = Disk usage
+
+# A part of the thumbnail cache.
+# Variables:
+#   $area - Which part: small, normal, large, x-large, xx-large, storyboards, or failed.
+prefs-thumbnails-area = { $area ->
+    [small] Small thumbnails
+    [normal] Normal thumbnails
+    [large] Large thumbnails
+    [x-large] Extra large thumbnails
+    [xx-large] Huge thumbnails
+    [storyboards] Video previews
+   *[failed] Failed thumbnails
+  }
+
+# Number of files in a part of the thumbnail cache.
+# Variables:
+#   $count - Number of files.
+prefs-thumbnails-files = { $count ->
+    [one] One file
+   *[other] { $count } files
+  }
+
+# Delete all thumbnails and generate them again.
+# Attributes:
+#   .confirm-heading - Heading of dialog asking to confirm.
+#   .confirm-body - Body of dialog asking to confirm.
+prefs-thumbnails-regenerate = Regenerate thumbnails
+  .subtitle = Delete the thumbnails of all pictures and videos and generate them again.
+  .tooltip = Regenerate thumbnails.
+  .confirm-heading = Regenerate all thumbnails?
+  .confirm-body = Generating thumbnails for a large library will take a while. Pictures and videos may appear without thumbnails until it finishes.
+  .cancel = Cancel
+  .regenerate = Regenerate
+
+prefs-privacy-section = Location privacy
+  .description = Locations in private zones, such as your home, are hidden from places and are never exported.
+
+# How much location is kept when pictures and videos are exported.
+# Attributes:
+#   .keep - Export the exact location.
+#   .coarse - Export an approximate location, about the size of a town.
+#   .strip - Remove the location.
+prefs-privacy-export-location = Exported location
+  .subtitle = Location to include in exported pictures and videos.
+  .keep = Exact
+  .coarse = Approximate
+  .strip = None
+
+prefs-privacy-private-thumbnails = Private thumbnails
+  .subtitle = Keep thumbnails private to { -app-name } instead of sharing them with your file manager and other apps. Takes effect when { -app-name } restarts.
+
+# A private zone row.
+prefs-privacy-zone =
+  .add-tooltip = Add a private zone.
+  .remove-tooltip = Remove private zone.
+
 ## Progress bar for background tasks
 
 # Extracting details from photo EXIF data
@@ -287,6 +725,12 @@ progress-convert-videos = Converting videos.
 # Extracting motion photo videos
 progress-motion-photo = Processing motion photos.
 
+# Looking up place names for photo and video locations
+progress-geocode = Finding places.
+
+# Matching photos and videos to a GPS track log
+progress-geotag = Matching GPS track log.
+
 # Detect faces from photos
 progress-detect-faces-photos = Detecting faces in photos.
 
@@ -324,6 +768,16 @@ banner-clean-videos = Video database maintenance.
 # Extracting video component from Android motion photos
 banner-extract-motion-photos = Processing motion photos.
 
+# Grouping photos and videos into events and trips.
+banner-events = Finding events and trips.
+
+# Looking up place names for photo and video locations.
+# The place name data is downloaded the first time.
+banner-geocode = Finding place names for photos and videos.
+
+# Setting locations from a GPS track log or a pin dropped on a map.
+banner-geotag = Setting locations for photos and videos.
+
 # Detect and extract faces from photos
 banner-detect-faces-photos = Detecting faces in photos. This will take a while.
 
@@ -333,6 +787,12 @@ banner-recognize-faces-photos = Recognizing people in photos. This will take a w
 # Transcoding videos to a compatible format
 banner-convert-videos = Converting videos.
 
+# Deleting least recently viewed videos from the cache, or converting an evicted video again
+banner-video-cache = Updating video cache.
+
+# Deleting thumbnails so they can be generated again
+banner-thumbnail-cache = Removing thumbnails.
+
 # Generate face thumbnails
 banner-face-thumbnails = Generating face thumbnails
 
@@ -348,12 +808,172 @@ banner-stopping = Stopping tasks...
 
 # The "hamburger" menu on the main app navigation sidebar.
 
+# Menu item to show dialog for importing a GPS track log
+primary-menu-import-track = Import GPS Track…
+
+# Menu item to show pictures and videos that couldn't be processed
+primary-menu-problems = Problems…
+
 # Menu item to show preferences dialog
 primary-menu-preferences = Preferences
 
 # Menu item to show "about" dialog
 primary-menu-about = About {-app-name}
 
+## Import GPS track dialog
+
+# Matches photos and videos without a location to a GPS track log
+# recorded by a phone or GPS logger at the same time.
+
+track-import-title = Import GPS Track
+
+track-import-description = Photos and videos without a location will be placed where the GPS track log was at the time they were taken.
+
+# GPX, KML, and GeoJSON are file formats and should not be translated.
+track-import-file = Track Log
+  .none = No file chosen
+  .tooltip = Choose a GPX, KML, or GeoJSON file
+  .filter = GPS track logs
+
+track-import-tolerance = Time Tolerance
+  .subtitle = Maximum minutes between a photo and the nearest track point
+
+track-import-clock-offset = Camera Clock Offset
+  .subtitle = Minutes to add to the camera time to match the GPS time
+
+track-import-button = Import
+
+## Set location dialog
+
+# Map with a pin in the centre that the user moves the map under.
+
+pin-location-title = Set Location
+
+pin-location-cancel = Cancel
+
+pin-location-save = Set
+
+## Set date dialog
+
+# Calendar for choosing the day items were taken on.
+
+set-date-title = Set Date
+
+set-date-cancel = Cancel
+
+set-date-save = Set
+
+set-date-description = Each item keeps its time of day.
+
+## Assign person dialog
+
+# List of people to choose the person in many items at once.
+
+assign-person-title = Assign Person
+
+assign-person-cancel = Cancel
+
+assign-person-description = Only items with exactly one unnamed face are assigned.
+
+# Toast shown after assigning a person to selected items.
+# Variables:
+#   $count - Number of items assigned.
+#   $total - Number of items selected.
+assign-person-done = { $count ->
+    [0] No items had exactly one unnamed face
+   *[other] Assigned { $count } of { $total } items
+}
+
+## Photo edit dialog
+
+# Edits never change the original file.
+
+photo-edit-title = Edit Photo
+
+photo-edit-cancel = Cancel
+
+photo-edit-save = Save
+
+photo-edit-geometry = Crop and Rotate
+
+photo-edit-crop = Crop
+  .original = Original
+  .square = Square
+  .four-three = 4:3
+  .three-two = 3:2
+  .sixteen-nine = 16:9
+
+photo-edit-rotate = Rotate
+  .left = Rotate left
+  .right = Rotate right
+
+photo-edit-flip = Flip
+  .horizontal = Flip horizontally
+  .vertical = Flip vertically
+
+# Rotate by a few degrees to level a tilted horizon.
+photo-edit-straighten = Straighten
+
+photo-edit-light = Light and Color
+
+photo-edit-auto-enhance = Auto-enhance
+  .subtitle = Stretch the darkest and lightest parts to use the full range
+
+photo-edit-exposure = Exposure
+
+photo-edit-contrast = Contrast
+
+photo-edit-saturation = Saturation
+
+# The copy has no metadata, such as the location the photo was taken at.
+photo-edit-export = Export edited copy
+  .suffix = edited
+
+photo-edit-export-failed = Couldn't export edited copy
+
+photo-edit-revert = Revert to original
+  .confirm-heading = Revert to original?
+  .confirm-body = All edits of this photo will be discarded.
+  .cancel = Cancel
+  .revert = Revert
+
+# Dialog for choosing an area to hide locations from.
+private-zone-title = Add Private Zone
+
+private-zone-cancel = Cancel
+
+private-zone-save = Add
+
+# Name of private zone.
+# Attributes:
+#   .default - Name used when the user doesn't provide one.
+private-zone-name = Name
+  .default = Private zone
+
+private-zone-radius = Radius in metres
+  .subtitle = Distance from the pin that is private.
+
+## Problems dialog
+
+# Lists pictures and videos that couldn't be processed, and why.
+problems-title = Problems
+
+problems-retry-all = Retry All
+
+problems-retry =
+  .tooltip = Try processing this item again.
+
+problems-empty = No Problems
+  .description = All pictures and videos were processed successfully.
+
+# Stage of processing that failed. Shown before the error message.
+problems-category =
+  .thumbnail = Thumbnail
+  .motion-photo = Motion photo
+  .transcode = Video conversion
+  .face-scan = Face detection
+  .unknown = Unknown
+
 ## Person menu
 
 # Menu item to rename a person
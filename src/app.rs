@@ -28,11 +28,23 @@ use fotema_core::FlatpakPathBuf;
 use fotema_core::PictureId;
 use fotema_core::VisualId;
 use fotema_core::database;
+use fotema_core::dates;
+use fotema_core::edits;
+use fotema_core::events;
+use fotema_core::export::{ExportOptions, MetadataPolicy, PictureFormat};
+use fotema_core::files;
+use fotema_core::geotag::Correlator;
+use fotema_core::jobs;
+use fotema_core::orientation::{self, Turn};
 use fotema_core::path_encoding;
 use fotema_core::people;
-use fotema_core::thumbnailify::Thumbnailer;
+use fotema_core::places::GeoTarget;
+use fotema_core::privacy::{self, ExportLocation, PrivacyPolicy, ZoneArea, ZoneId};
+use fotema_core::problems;
+use fotema_core::video::{self, TranscodeProfile};
 
-use h3o::CellIndex;
+use chrono::NaiveDate;
+use h3o::{CellIndex, LatLng};
 
 use std::path::PathBuf;
 use std::rc::Rc;
@@ -48,21 +60,34 @@ use tracing::{error, info, warn};
 mod components;
 
 use crate::host_path;
+use crate::thumbnails;
 
 use self::components::{
     about::AboutDialog,
     albums::{
-        album::{Album, AlbumInput, AlbumOutput},
+        album::{Album, AlbumInput, AlbumOutput, BatchAction},
         album_filter::AlbumFilter,
         album_sort::AlbumSort,
+        events_album::{EventsAlbum, EventsAlbumInput, EventsAlbumOutput},
         folders_album::{FoldersAlbum, FoldersAlbumInput, FoldersAlbumOutput},
         people_album::{PeopleAlbum, PeopleAlbumInput, PeopleAlbumOutput},
         person_album::{PersonAlbum, PersonAlbumInput, PersonAlbumOutput},
+        place_list_album::{PlaceListAlbum, PlaceListAlbumInput, PlaceListAlbumOutput},
         places_album::{PlacesAlbum, PlacesAlbumInput, PlacesAlbumOutput},
     },
+    assign_person::{AssignPersonDialog, AssignPersonInput, AssignPersonOutput},
+    export::{ExportDialog, ExportInput},
+    file_operations::{FileOperations, FileOperationsInput, FileOperationsOutput},
     library::{Library, LibraryInput, LibraryOutput},
     onboard::{Onboard, OnboardOutput},
+    photo_edit::{PhotoEditDialog, PhotoEditInput, PhotoEditOutput},
+    pin_location::{PinLocationDialog, PinLocationInput, PinLocationOutput},
     preferences::{PreferencesDialog, PreferencesInput, PreferencesOutput},
+    private_zone::{PrivateZoneDialog, PrivateZoneInput, PrivateZoneOutput},
+    problems::{ProblemsDialog, ProblemsInput, ProblemsOutput},
+    set_date::{SetDateDialog, SetDateInput, SetDateOutput},
+    slideshow::{Slideshow, SlideshowInput, SlideshowOutput},
+    track_import::{TrackImportDialog, TrackImportInput, TrackImportOutput},
     viewer::view_nav::{ViewNav, ViewNavInput, ViewNavOutput},
 };
 
@@ -71,6 +96,11 @@ mod background;
 use self::background::bootstrap::{
     Bootstrap, BootstrapInput, BootstrapOutput, MediaType, TaskName, ThumbnailType,
 };
+use self::background::orientation_write_task::{
+    OrientationWriteTask, OrientationWriteTaskInput, OrientationWriteTaskOutput,
+};
+use self::background::scheduler::UserActivity;
+use self::background::thumbnail_priority_task::ThumbnailPriorityTask;
 
 use self::components::progress_monitor::ProgressMonitor;
 use self::components::progress_panel::ProgressPanel;
@@ -91,6 +121,7 @@ pub enum ViewName {
     People,
     Person,
     Places,
+    Events,
     Selfies,
 }
 
@@ -113,6 +144,7 @@ impl FromStr for ViewName {
             "People" => ::core::result::Result::Ok(ViewName::People),
             "Person" => ::core::result::Result::Ok(ViewName::Person),
             "Places" => ::core::result::Result::Ok(ViewName::Places),
+            "Events" => ::core::result::Result::Ok(ViewName::Events),
             "Selfies" => ::core::result::Result::Ok(ViewName::Selfies),
             _ => ::core::result::Result::Err(::strum::ParseError::VariantNotFound),
         }
@@ -168,6 +200,39 @@ pub struct Settings {
     /// Base path of pictures directory inside Flatpak sandbox.
     /// Will be under `/run/users/<uid>/docs/<doc-id>/...`
     pub library_base_dir: FlatpakPathBuf,
+
+    /// How much location to reveal when exporting pictures and videos.
+    pub export_location: ExportLocation,
+
+    /// Codecs and size of transcoded videos.
+    pub transcode_profile: TranscodeProfile,
+
+    /// Disk space for transcoded and motion photo videos in gigabytes. Zero is unlimited.
+    pub video_cache_limit_gb: u32,
+
+    /// Threads for background processing. Zero leaves one CPU core free.
+    pub background_threads: u32,
+
+    /// Pause background processing while running on battery.
+    pub pause_on_battery: bool,
+
+    /// Pause background processing while the user is using Fotema.
+    pub pause_while_active: bool,
+
+    /// Keep thumbnails in Fotema's own cache instead of the shared XDG thumbnail cache.
+    pub private_thumbnails: bool,
+
+    /// Also write orientations chosen by the user to the EXIF metadata of picture files.
+    pub write_orientation_to_exif: bool,
+
+    /// Seconds to show each picture in a slideshow.
+    pub slideshow_interval_secs: u32,
+
+    /// Play slideshows in a random order.
+    pub slideshow_shuffle: bool,
+
+    /// Options last used for exporting pictures and videos.
+    pub export_options: ExportOptions,
 }
 
 /// Active settings
@@ -184,14 +249,40 @@ type ActiveView = Arc<relm4::SharedState<ViewName>>;
 // State is loaded by the `load_library` background task.
 type SharedState = Arc<relm4::SharedState<Vec<Arc<fotema_core::Visual>>>>;
 
+/// Private zones and export policy for locations.
+/// Consulted by anything that shows or exports a location.
+type PrivacyState = Arc<relm4::SharedState<PrivacyPolicy>>;
+
 pub(super) struct App {
     adaptive_layout: Arc<adaptive::LayoutState>,
 
     about_dialog: Controller<AboutDialog>,
     preferences_dialog: AsyncController<PreferencesDialog>,
+    track_import_dialog: AsyncController<TrackImportDialog>,
+    pin_location_dialog: Controller<PinLocationDialog>,
+    set_date_dialog: Controller<SetDateDialog>,
+    assign_person_dialog: Controller<AssignPersonDialog>,
+    photo_edit_dialog: AsyncController<PhotoEditDialog>,
+    private_zone_dialog: Controller<PrivateZoneDialog>,
+    problems_dialog: Controller<ProblemsDialog>,
+
+    /// Renames, moves, copies, and trashes files.
+    file_operations: AsyncController<FileOperations>,
+
+    /// Exports copies of items, or shares them with other apps.
+    export_dialog: AsyncController<ExportDialog>,
 
     bootstrap: WorkerController<Bootstrap>,
 
+    /// Writes orientations chosen by the user to picture files.
+    orientation_write_task: WorkerController<OrientationWriteTask>,
+
+    /// Prioritises thumbnails of items scrolled into view. Albums send to it directly,
+    /// so it is only kept here to keep it running.
+    _thumbnail_priority_task: WorkerController<ThumbnailPriorityTask>,
+
+    toast_overlay: adw::ToastOverlay,
+
     // View for first run
     onboard: AsyncController<Onboard>,
     onboard_view: adw::ToolbarView,
@@ -214,12 +305,26 @@ pub(super) struct App {
     /// Album with photos overlayed onto a map
     places_page: Controller<PlacesAlbum>,
 
+    /// List of named places where photos were taken
+    place_list: Controller<PlaceListAlbum>,
+
+    /// Switch between map and list of places
+    places_stack: adw::ViewStack,
+
+    /// Trips and events
+    events_page: Controller<EventsAlbum>,
+
     // Grid of folders of photos
     folders_album: Controller<FoldersAlbum>,
 
     // Folder album currently being viewed
     folder_album: Controller<Album>,
 
+    /// Filter of the folder, place, or event album currently being viewed.
+    folder_album_filter: AlbumFilter,
+
+    slideshow: Controller<Slideshow>,
+
     // Main navigation. Parent of library stack.
     main_navigation: adw::OverlaySplitView,
 
@@ -232,15 +337,36 @@ pub(super) struct App {
     // Window header bar
     header_bar: adw::HeaderBar,
 
+    // Starts a slideshow of the main view. Only shown for views that are albums.
+    slideshow_button: gtk::Button,
+
+    // Starts selecting items of the main view. Only shown for views that are albums.
+    select_button: gtk::Button,
+
     // Activity indicator. Only shown when progress bar is hidden.
     spinner: adw::Spinner,
 
     bootstrap_progress: Controller<ProgressPanel>,
 
+    /// Progress of background I/O tasks, which can run alongside CPU tasks.
+    bootstrap_io_progress: Controller<ProgressPanel>,
+
     // Message banner
     banner: adw::Banner,
 
     settings_state: SettingsState,
+
+    /// All visual items, used to hide items in private zones from place albums.
+    state: SharedState,
+
+    privacy_state: PrivacyState,
+    privacy_repo: privacy::Repository,
+
+    /// Orientations chosen by the user.
+    orientation_repo: orientation::Repository,
+
+    /// Dates chosen by the user.
+    dates_repo: dates::Repository,
 }
 
 #[derive(Debug)]
@@ -268,12 +394,34 @@ pub(super) enum AppMsg {
 
     ViewGeographicArea(CellIndex),
 
+    ViewPlace(AlbumFilter),
+
+    ViewEvent(Vec<VisualId>),
+
     ViewPerson(people::Person),
 
     PersonDeleted,
 
     PersonRenamed,
 
+    // Play a slideshow of the album being viewed.
+    Slideshow,
+
+    // Play a slideshow of an album.
+    StartSlideshow(AlbumFilter),
+
+    // Leave slideshow.
+    CloseSlideshow,
+
+    // Slideshow is dismissed.
+    SlideshowHidden,
+
+    // Start selecting items of the album being viewed.
+    Select,
+
+    // Apply an action to items selected in an album.
+    Batch(BatchAction, Vec<Arc<fotema_core::Visual>>),
+
     // A background task has started.
     TaskStarted(TaskName),
 
@@ -287,6 +435,83 @@ pub(super) enum AppMsg {
 
     ProcessMotionPhotos,
 
+    // Delete least recently viewed videos until the video cache is within its limit
+    EvictVideoCache,
+
+    // Delete and generate again all thumbnails
+    RegenerateThumbnails,
+
+    // Transcode or extract a video again after it was evicted from the video cache
+    RestoreVideoCache(Arc<fotema_core::Visual>),
+
+    // Match items without a location to a GPS track log
+    GeotagTrack(PathBuf, Correlator),
+
+    // Show map to set the location of items
+    SetLocation(Vec<Arc<fotema_core::Visual>>),
+
+    // Set the location of items to a pin dropped on a map
+    PinLocation(Vec<GeoTarget>, LatLng),
+
+    // Set the date items were taken, keeping their time of day
+    SetDate(Vec<Arc<fotema_core::Visual>>, NaiveDate),
+
+    // Faces in items have been assigned to a person.
+    // The first usize is the count of items assigned, the second of items chosen.
+    PersonAssigned(usize, usize),
+
+    // Show editor for a picture
+    EditPicture(Arc<fotema_core::Visual>),
+
+    // Edits of a picture have been saved or reverted
+    PictureEdited(Arc<fotema_core::Visual>),
+
+    // Turn an item a quarter turn, because its orientation is wrong
+    Rotate(Arc<fotema_core::Visual>, Turn),
+
+    // Writing an orientation to a picture file failed
+    OrientationWriteFailed(PathBuf),
+
+    // Rename the files of an item
+    Rename(Arc<fotema_core::Visual>),
+
+    // Move the files of items to another folder in the library
+    MoveToFolder(Vec<Arc<fotema_core::Visual>>),
+
+    // Copy the files of items to another folder in the library
+    CopyToFolder(Vec<Arc<fotema_core::Visual>>),
+
+    // Move the files of items to the trash
+    Trash(Vec<Arc<fotema_core::Visual>>),
+
+    // Export copies of items, or share them with another app
+    Share(Vec<Arc<fotema_core::Visual>>),
+
+    // Files of items have been renamed or moved
+    Relocated(Vec<Arc<fotema_core::Visual>>),
+
+    // Items have been hidden or shown again, so the viewer must filter its album again
+    RefreshViewer,
+
+    // Viewer has no items left to show
+    ViewerEmpty,
+
+    // Files in the library have been changed from within Fotema
+    FilesChanged,
+
+    // Show map to choose a new private zone
+    PresentPrivateZoneDialog,
+
+    // Hide locations in an area
+    AddPrivateZone(String, ZoneArea),
+
+    // Stop hiding locations in an area
+    RemovePrivateZone(ZoneId),
+
+    // Process items that were previously broken again.
+    // bool is true if videos must be transcoded again.
+    RetryProblems(bool),
+
     // Stop all background tasks
     StopBackgroundTasks,
 
@@ -306,6 +531,10 @@ pub(super) enum AppMsg {
 relm4::new_action_group!(pub(super) WindowActionGroup, "win");
 relm4::new_stateless_action!(PreferencesAction, WindowActionGroup, "preferences");
 relm4::new_stateless_action!(AboutAction, WindowActionGroup, "about");
+relm4::new_stateless_action!(ImportTrackAction, WindowActionGroup, "import_track");
+relm4::new_stateless_action!(ProblemsAction, WindowActionGroup, "problems");
+relm4::new_stateless_action!(pub(super) SlideshowAction, WindowActionGroup, "slideshow");
+relm4::new_stateless_action!(SelectAction, WindowActionGroup, "select");
 
 #[relm4::component(pub async)]
 impl SimpleAsyncComponent for App {
@@ -316,6 +545,10 @@ impl SimpleAsyncComponent for App {
 
     menu! {
         primary_menu: {
+            section! {
+                &fl!("primary-menu-import-track") => ImportTrackAction,
+                &fl!("primary-menu-problems") => ProblemsAction,
+            },
             section! {
                 &fl!("primary-menu-preferences") => PreferencesAction,
                 &fl!("primary-menu-about") => AboutAction,
@@ -351,6 +584,7 @@ impl SimpleAsyncComponent for App {
             )) {
                 add_setter: (&header_bar, "show-title", Some(&false.into())),
                 add_setter: (&switcher_bar, "reveal", Some(&true.into())),
+                add_setter: (&places_switcher_bar, "reveal", Some(&true.into())),
                 //add_setter: (&main_navigation, "collapsed", &true.into()),
                 //add_setter: (&main_navigation, "show-sidebar", &false.into()),
                 add_setter: (&spinner, "visible", Some(&true.into())),
@@ -366,9 +600,18 @@ impl SimpleAsyncComponent for App {
             // 1. Navigation view containing stack of pages.
             // 2. Page for displaying a single photo.
             #[local_ref]
-            picture_navigation_view -> adw::NavigationView {
+            toast_overlay -> adw::ToastOverlay {
+            #[wrap(Some)]
+            #[local_ref]
+            set_child = &picture_navigation_view -> adw::NavigationView {
                 set_pop_on_escape: true,
-                connect_popped[sender] => move |_,_| sender.input(AppMsg::ViewHidden),
+                connect_popped[sender] => move |_, page| {
+                    if page.tag().is_some_and(|tag| tag.as_str() == "slideshow") {
+                        sender.input(AppMsg::SlideshowHidden);
+                    } else {
+                        sender.input(AppMsg::ViewHidden);
+                    }
+                },
 
                 // Page for showing main navigation. Such as "Library", "Selfies", etc.
                 adw::NavigationPage {
@@ -395,6 +638,7 @@ impl SimpleAsyncComponent for App {
                                         set_vexpand: true,
                                     },
 
+                                    model.bootstrap_io_progress.widget(),
                                     model.bootstrap_progress.widget(),
                                 }
                             }
@@ -413,6 +657,20 @@ impl SimpleAsyncComponent for App {
 
                                     #[local_ref]
                                     pack_end = &spinner -> adw::Spinner,
+
+                                    #[local_ref]
+                                    pack_end = &slideshow_button -> gtk::Button {
+                                        set_icon_name: "play-symbolic",
+                                        set_tooltip_text: Some(&fl!("slideshow-start-tooltip")),
+                                        set_action_name: Some("win.slideshow"),
+                                    },
+
+                                    #[local_ref]
+                                    pack_end = &select_button -> gtk::Button {
+                                        set_icon_name: "selection-mode-symbolic",
+                                        set_tooltip_text: Some(&fl!("album-select-tooltip")),
+                                        set_action_name: Some("win.select"),
+                                    },
                                 },
 
                                 // NOTE I would like this to be an adw::ViewStack
@@ -478,12 +736,31 @@ impl SimpleAsyncComponent for App {
 
                                         add_child = &gtk::Box {
                                             set_orientation: gtk::Orientation::Vertical,
-                                            container_add: model.places_page.widget(),
+
+                                            #[local_ref]
+                                            places_stack -> adw::ViewStack {
+                                                set_vexpand: true,
+                                                add_titled_with_icon[Some("map"), &fl!("places-map"), "mark-location-symbolic"] = model.places_page.widget(),
+                                                add_titled_with_icon[Some("list"), &fl!("places-list"), "view-list-symbolic"] = model.place_list.widget(),
+                                            },
+
+                                            #[name(places_switcher_bar)]
+                                            adw::ViewSwitcherBar {
+                                                set_stack: Some(&places_stack),
+                                            },
                                         } -> {
                                             set_title: &fl!("places-page"),
                                             set_name: ViewName::Places.as_ref(),
                                         },
 
+                                        add_child = &gtk::Box {
+                                            set_orientation: gtk::Orientation::Vertical,
+                                            container_add: model.events_page.widget(),
+                                        } -> {
+                                            set_title: &fl!("events-page"),
+                                            set_name: ViewName::Events.as_ref(),
+                                        },
+
                                         add_child = &gtk::Box {
                                             set_orientation: gtk::Orientation::Vertical,
                                             container_add: model.selfies_page.widget(),
@@ -524,7 +801,19 @@ impl SimpleAsyncComponent for App {
                             set_title_widget = &gtk::Label {
                                 set_label: &fl!("folder-album"),
                                 add_css_class: "title",
-                            }
+                            },
+
+                            pack_end = &gtk::Button {
+                                set_icon_name: "play-symbolic",
+                                set_tooltip_text: Some(&fl!("slideshow-start-tooltip")),
+                                set_action_name: Some("win.slideshow"),
+                            },
+
+                            pack_end = &gtk::Button {
+                                set_icon_name: "selection-mode-symbolic",
+                                set_tooltip_text: Some(&fl!("album-select-tooltip")),
+                                set_action_name: Some("win.select"),
+                            },
                         },
 
                         #[wrap(Some)]
@@ -542,6 +831,12 @@ impl SimpleAsyncComponent for App {
                     set_tag: Some("picture"),
                     model.view_nav.widget(),
                 },
+
+                adw::NavigationPage {
+                    set_tag: Some("slideshow"),
+                    model.slideshow.widget(),
+                },
+            },
             },
 
             // Hmmm... can the views be refactored so we don't have a separate toolbar view
@@ -575,13 +870,6 @@ impl SimpleAsyncComponent for App {
         let cache_dir = glib::user_cache_dir().join(APP_ID);
         let _ = std::fs::create_dir_all(&cache_dir);
 
-        // WARN duplicate thumbnail path calculation in bootstrap.rs
-        let thumbnail_dir = glib::user_cache_dir()
-            .join(APP_ID) // Remove to use standard XDG thumbnail path
-            .join("thumbnails");
-
-        let thumbnailer = Rc::new(Thumbnailer::build(&thumbnail_dir));
-
         let db_path = data_dir.join("pictures.sqlite");
 
         let con = database::setup(&db_path).expect("Must be able to open database");
@@ -602,10 +890,33 @@ impl SimpleAsyncComponent for App {
             Err(e) => error!("Failed loading settings: {}", e),
         }
 
+        // Changing between shared and private thumbnails takes effect on restart.
+        let thumbnailer = Rc::new(thumbnails::build_thumbnailer(
+            settings_state.read().private_thumbnails,
+        ));
+
         settings_state.subscribe(sender.input_sender(), |settings| {
             AppMsg::SettingsChanged(settings.clone())
         });
 
+        let privacy_repo = privacy::Repository::open(con.clone()).unwrap();
+
+        let jobs_repo = jobs::Repository::open(con.clone()).unwrap();
+        let thumbnail_priority_task = ThumbnailPriorityTask::builder()
+            .detach_worker(jobs_repo)
+            .detach();
+
+        let video_cache_repo = video::cache::Repository::open(&cache_dir, con.clone()).unwrap();
+        let privacy_state = PrivacyState::new(relm4::SharedState::new());
+        match privacy_repo.all() {
+            std::result::Result::Ok(zones) => {
+                info!("Loaded {} private zones", zones.len());
+                let export = settings_state.read().export_location;
+                *privacy_state.write() = PrivacyPolicy::new(zones, export);
+            }
+            Err(e) => error!("Failed loading private zones: {}", e),
+        }
+
         let bootstrap_progress_monitor: Reducer<ProgressMonitor> = Reducer::new();
         let bootstrap_progress_monitor = Arc::new(bootstrap_progress_monitor);
 
@@ -613,12 +924,24 @@ impl SimpleAsyncComponent for App {
             .launch(bootstrap_progress_monitor.clone())
             .detach();
 
+        let bootstrap_io_progress_monitor: Reducer<ProgressMonitor> = Reducer::new();
+        let bootstrap_io_progress_monitor = Arc::new(bootstrap_io_progress_monitor);
+
+        let bootstrap_io_progress = self::components::progress_panel::ProgressPanel::builder()
+            .launch(bootstrap_io_progress_monitor.clone())
+            .detach();
+
+        // Background tasks can pause while the user is interacting with the main window.
+        let user_activity = UserActivity::default();
+
         let bootstrap = Bootstrap::builder()
             .detach_worker((
                 con.clone(),
                 state.clone(),
                 settings_state.clone(),
                 bootstrap_progress_monitor.clone(),
+                bootstrap_io_progress_monitor,
+                user_activity.clone(),
             ))
             .forward(sender.input_sender(), |msg| match msg {
                 BootstrapOutput::TaskStarted(msg) => AppMsg::TaskStarted(msg),
@@ -641,24 +964,48 @@ impl SimpleAsyncComponent for App {
                 active_view.clone(),
                 adaptive_layout.clone(),
                 thumbnailer.clone(),
+                thumbnail_priority_task.sender().clone(),
             ))
             .forward(sender.input_sender(), |msg| match msg {
                 LibraryOutput::View(id) => AppMsg::View(id, AlbumFilter::All),
+                LibraryOutput::Batch(action, visuals) => AppMsg::Batch(action, visuals),
             });
 
         settings_state.subscribe(library.sender(), |settings| {
             LibraryInput::Sort(settings.album_sort)
         });
 
+        let toast_overlay = adw::ToastOverlay::new();
+
+        let orientation_write_task = OrientationWriteTask::builder().detach_worker(()).forward(
+            sender.input_sender(),
+            |msg| match msg {
+                OrientationWriteTaskOutput::Failed(path) => AppMsg::OrientationWriteFailed(path),
+            },
+        );
+
         let view_nav = ViewNav::builder()
             .launch((
                 state.clone(),
                 bootstrap_progress_monitor,
                 adaptive_layout.clone(),
                 people_repo.clone(),
+                video_cache_repo,
+                privacy_state.clone(),
+                toast_overlay.clone(),
             ))
             .forward(sender.input_sender(), |msg| match msg {
                 ViewNavOutput::TranscodeAll => AppMsg::TranscodeAll,
+                ViewNavOutput::SetLocation(visuals) => AppMsg::SetLocation(visuals),
+                ViewNavOutput::RestoreCache(visual) => AppMsg::RestoreVideoCache(visual),
+                ViewNavOutput::Edit(visual) => AppMsg::EditPicture(visual),
+                ViewNavOutput::Rotate(visual, turn) => AppMsg::Rotate(visual, turn),
+                ViewNavOutput::Rename(visual) => AppMsg::Rename(visual),
+                ViewNavOutput::MoveToFolder(visuals) => AppMsg::MoveToFolder(visuals),
+                ViewNavOutput::CopyToFolder(visuals) => AppMsg::CopyToFolder(visuals),
+                ViewNavOutput::Trash(visuals) => AppMsg::Trash(visuals),
+                ViewNavOutput::Share(visuals) => AppMsg::Share(visuals),
+                ViewNavOutput::Empty => AppMsg::ViewerEmpty,
             });
 
         settings_state.subscribe(view_nav.sender(), |settings| {
@@ -672,10 +1019,12 @@ impl SimpleAsyncComponent for App {
                 ViewName::Selfies,
                 AlbumFilter::Selfies,
                 thumbnailer.clone(),
+                thumbnail_priority_task.sender().clone(),
             ))
             .forward(sender.input_sender(), |msg| match msg {
                 AlbumOutput::Selected(id, filter) => AppMsg::View(id, filter),
                 AlbumOutput::ScrollOffset(_) => AppMsg::Ignore,
+                AlbumOutput::Batch(action, visuals) => AppMsg::Batch(action, visuals),
             });
 
         state.subscribe(selfies_page.sender(), |_| AlbumInput::Refresh);
@@ -693,10 +1042,12 @@ impl SimpleAsyncComponent for App {
                 ViewName::Animated,
                 AlbumFilter::Motion,
                 thumbnailer.clone(),
+                thumbnail_priority_task.sender().clone(),
             ))
             .forward(sender.input_sender(), |msg| match msg {
                 AlbumOutput::Selected(id, filter) => AppMsg::View(id, filter),
                 AlbumOutput::ScrollOffset(_) => AppMsg::Ignore,
+                AlbumOutput::Batch(action, visuals) => AppMsg::Batch(action, visuals),
             });
 
         state.subscribe(motion_page.sender(), |_| AlbumInput::Refresh);
@@ -712,10 +1063,12 @@ impl SimpleAsyncComponent for App {
                 ViewName::Videos,
                 AlbumFilter::Videos,
                 thumbnailer.clone(),
+                thumbnail_priority_task.sender().clone(),
             ))
             .forward(sender.input_sender(), |msg| match msg {
                 AlbumOutput::Selected(id, filter) => AppMsg::View(id, filter),
                 AlbumOutput::ScrollOffset(_) => AppMsg::Ignore,
+                AlbumOutput::Batch(action, visuals) => AppMsg::Batch(action, visuals),
             });
 
         state.subscribe(videos_page.sender(), |_| AlbumInput::Refresh);
@@ -745,11 +1098,14 @@ impl SimpleAsyncComponent for App {
                 people_repo.clone(),
                 active_view.clone(),
                 thumbnailer.clone(),
+                thumbnail_priority_task.sender().clone(),
             ))
             .forward(sender.input_sender(), |msg| match msg {
                 PersonAlbumOutput::Selected(id, filter) => AppMsg::View(id, filter),
                 PersonAlbumOutput::Deleted => AppMsg::PersonDeleted,
                 PersonAlbumOutput::Renamed => AppMsg::PersonRenamed,
+                PersonAlbumOutput::Slideshow(filter) => AppMsg::StartSlideshow(filter),
+                PersonAlbumOutput::Batch(action, visuals) => AppMsg::Batch(action, visuals),
             });
 
         state.subscribe(person_album.sender(), |_| PersonAlbumInput::Refresh);
@@ -761,7 +1117,12 @@ impl SimpleAsyncComponent for App {
         });
 
         let places_page = PlacesAlbum::builder()
-            .launch((state.clone(), active_view.clone(), thumbnailer.clone()))
+            .launch((
+                state.clone(),
+                active_view.clone(),
+                privacy_state.clone(),
+                thumbnailer.clone(),
+            ))
             .forward(sender.input_sender(), |msg| match msg {
                 PlacesAlbumOutput::View(visual_id) => {
                     AppMsg::View(visual_id.clone(), AlbumFilter::One(visual_id))
@@ -772,10 +1133,37 @@ impl SimpleAsyncComponent for App {
             });
 
         state.subscribe(places_page.sender(), |_| PlacesAlbumInput::Refresh);
+        privacy_state.subscribe(places_page.sender(), |_| PlacesAlbumInput::Refresh);
         adaptive_layout.subscribe(places_page.sender(), |layout| {
             PlacesAlbumInput::Adapt(*layout)
         });
 
+        let place_list = PlaceListAlbum::builder()
+            .launch((state.clone(), active_view.clone(), privacy_state.clone()))
+            .forward(sender.input_sender(), |msg| match msg {
+                PlaceListAlbumOutput::Selected(filter) => AppMsg::ViewPlace(filter),
+            });
+
+        state.subscribe(place_list.sender(), |_| PlaceListAlbumInput::Refresh);
+        privacy_state.subscribe(place_list.sender(), |_| PlaceListAlbumInput::Refresh);
+
+        let places_stack = adw::ViewStack::new();
+
+        let events_repo = events::Repository::open(con.clone()).unwrap();
+
+        let events_page = EventsAlbum::builder()
+            .launch((
+                state.clone(),
+                active_view.clone(),
+                events_repo,
+                thumbnailer.clone(),
+            ))
+            .forward(sender.input_sender(), |msg| match msg {
+                EventsAlbumOutput::Selected(visual_ids) => AppMsg::ViewEvent(visual_ids),
+            });
+
+        state.subscribe(events_page.sender(), |_| EventsAlbumInput::Refresh);
+
         let folders_album = FoldersAlbum::builder()
             .launch((state.clone(), active_view.clone(), thumbnailer.clone()))
             .forward(sender.input_sender(), |msg| match msg {
@@ -794,10 +1182,12 @@ impl SimpleAsyncComponent for App {
                 ViewName::Folder,
                 AlbumFilter::None,
                 thumbnailer.clone(),
+                thumbnail_priority_task.sender().clone(),
             ))
             .forward(sender.input_sender(), |msg| match msg {
                 AlbumOutput::Selected(id, filter) => AppMsg::View(id, filter),
                 AlbumOutput::ScrollOffset(_) => AppMsg::Ignore,
+                AlbumOutput::Batch(action, visuals) => AppMsg::Batch(action, visuals),
             });
 
         state.subscribe(folder_album.sender(), |_| AlbumInput::Refresh);
@@ -806,15 +1196,123 @@ impl SimpleAsyncComponent for App {
             AlbumInput::Sort(settings.album_sort)
         });
 
+        let slideshow = Slideshow::builder()
+            .launch((state.clone(), settings_state.clone()))
+            .forward(sender.input_sender(), |msg| match msg {
+                SlideshowOutput::Close => AppMsg::CloseSlideshow,
+            });
+
         let about_dialog = AboutDialog::builder().launch(root.clone()).detach();
 
         let preferences_dialog = PreferencesDialog::builder()
-            .launch((settings_state.clone(), root.clone()))
+            .launch((
+                settings_state.clone(),
+                privacy_state.clone(),
+                thumbnailer.as_ref().clone(),
+                root.clone(),
+            ))
             .forward(sender.input_sender(), |msg| match msg {
                 PreferencesOutput::EnableFaceDetection => AppMsg::ScanPicturesForFaces,
                 PreferencesOutput::ProcessMotionPhotos => AppMsg::ProcessMotionPhotos,
+                PreferencesOutput::EvictVideoCache => AppMsg::EvictVideoCache,
+                PreferencesOutput::RegenerateThumbnails => AppMsg::RegenerateThumbnails,
+                PreferencesOutput::AddPrivateZone => AppMsg::PresentPrivateZoneDialog,
+                PreferencesOutput::RemovePrivateZone(zone_id) => AppMsg::RemovePrivateZone(zone_id),
             });
 
+        let track_import_dialog = TrackImportDialog::builder().launch(root.clone()).forward(
+            sender.input_sender(),
+            |msg| match msg {
+                TrackImportOutput::Import(path, correlator) => {
+                    AppMsg::GeotagTrack(path, correlator)
+                }
+            },
+        );
+
+        let pin_location_dialog = PinLocationDialog::builder().launch(root.clone()).forward(
+            sender.input_sender(),
+            |msg| match msg {
+                PinLocationOutput::Pinned(targets, location) => {
+                    AppMsg::PinLocation(targets, location)
+                }
+            },
+        );
+
+        let set_date_dialog =
+            SetDateDialog::builder()
+                .launch(root.clone())
+                .forward(sender.input_sender(), |msg| match msg {
+                    SetDateOutput::Dated(visuals, date) => AppMsg::SetDate(visuals, date),
+                });
+
+        let assign_person_dialog = AssignPersonDialog::builder()
+            .launch((people_repo.clone(), root.clone()))
+            .forward(sender.input_sender(), |msg| match msg {
+                AssignPersonOutput::Assigned(count, total) => AppMsg::PersonAssigned(count, total),
+            });
+
+        let dates_repo = dates::Repository::open(con.clone()).unwrap();
+
+        let edits_repo = edits::Repository::open(con.clone()).unwrap();
+
+        let orientation_repo = orientation::Repository::open(con.clone()).unwrap();
+
+        let photo_edit_dialog = PhotoEditDialog::builder()
+            .launch((
+                edits_repo,
+                thumbnailer.as_ref().clone(),
+                root.clone(),
+                toast_overlay.clone(),
+            ))
+            .forward(sender.input_sender(), |msg| match msg {
+                PhotoEditOutput::Edited(visual) => AppMsg::PictureEdited(visual),
+            });
+
+        let private_zone_dialog = PrivateZoneDialog::builder().launch(root.clone()).forward(
+            sender.input_sender(),
+            |msg| match msg {
+                PrivateZoneOutput::Add(name, area) => AppMsg::AddPrivateZone(name, area),
+            },
+        );
+
+        let problems_repo = problems::Repository::open(con.clone()).unwrap();
+
+        let problems_dialog = ProblemsDialog::builder()
+            .launch((
+                problems_repo,
+                thumbnailer.as_ref().clone(),
+                settings_state.clone(),
+                root.clone(),
+            ))
+            .forward(sender.input_sender(), |msg| match msg {
+                ProblemsOutput::Retry(transcode) => AppMsg::RetryProblems(transcode),
+            });
+
+        let files_repo = files::Repository::open(con.clone()).unwrap();
+
+        let file_operations = FileOperations::builder()
+            .launch((
+                files_repo,
+                settings_state.clone(),
+                state.clone(),
+                toast_overlay.clone(),
+                root.clone(),
+            ))
+            .forward(sender.input_sender(), |msg| match msg {
+                FileOperationsOutput::Relocated(visuals) => AppMsg::Relocated(visuals),
+                FileOperationsOutput::Refresh => AppMsg::RefreshViewer,
+                FileOperationsOutput::FilesChanged => AppMsg::FilesChanged,
+            });
+
+        let export_dialog = ExportDialog::builder()
+            .launch((
+                settings_state.clone(),
+                privacy_state.clone(),
+                toast_overlay.clone(),
+                root.clone(),
+            ))
+            .detach();
+
         let picture_navigation_view = adw::NavigationView::builder().build();
 
         let main_navigation = adw::OverlaySplitView::builder().build();
@@ -823,6 +1321,10 @@ impl SimpleAsyncComponent for App {
 
         let header_bar = adw::HeaderBar::new();
 
+        let slideshow_button = gtk::Button::new();
+
+        let select_button = gtk::Button::new();
+
         let spinner = adw::Spinner::builder().visible(false).build();
 
         let banner = adw::Banner::builder()
@@ -833,9 +1335,21 @@ impl SimpleAsyncComponent for App {
         let model = Self {
             adaptive_layout,
             bootstrap,
+            orientation_write_task,
+            _thumbnail_priority_task: thumbnail_priority_task,
+            toast_overlay: toast_overlay.clone(),
 
             about_dialog,
             preferences_dialog,
+            track_import_dialog,
+            pin_location_dialog,
+            set_date_dialog,
+            assign_person_dialog,
+            photo_edit_dialog,
+            private_zone_dialog,
+            problems_dialog,
+            file_operations,
+            export_dialog,
 
             onboard,
             onboard_view: onboard_view.clone(),
@@ -848,23 +1362,38 @@ impl SimpleAsyncComponent for App {
             people_page,
             person_album,
             places_page,
+            place_list,
+            places_stack: places_stack.clone(),
+            events_page,
             selfies_page,
             show_selfies,
             folders_album,
             folder_album,
+            folder_album_filter: AlbumFilter::None,
+            slideshow,
 
             main_navigation: main_navigation.clone(),
             main_stack: main_stack.clone(),
 
             picture_navigation_view: picture_navigation_view.clone(),
             header_bar: header_bar.clone(),
+            slideshow_button: slideshow_button.clone(),
+            select_button: select_button.clone(),
             spinner: spinner.clone(),
 
             bootstrap_progress,
+            bootstrap_io_progress,
 
             banner: banner.clone(),
 
             settings_state: settings_state.clone(),
+
+            state: state.clone(),
+
+            privacy_state,
+            privacy_repo,
+            orientation_repo,
+            dates_repo,
         };
 
         let widgets = view_output!();
@@ -885,11 +1414,52 @@ impl SimpleAsyncComponent for App {
             })
         };
 
+        let import_track_action = {
+            let sender = model.track_import_dialog.sender().clone();
+            RelmAction::<ImportTrackAction>::new_stateless(move |_| {
+                sender.send(TrackImportInput::Present).unwrap();
+            })
+        };
+
+        let problems_action = {
+            let sender = model.problems_dialog.sender().clone();
+            RelmAction::<ProblemsAction>::new_stateless(move |_| {
+                sender.send(ProblemsInput::Present).unwrap();
+            })
+        };
+
         actions.add_action(about_action);
         actions.add_action(preferences_action);
+        actions.add_action(import_track_action);
+        actions.add_action(problems_action);
+
+        let slideshow_action = {
+            let sender = sender.clone();
+            RelmAction::<SlideshowAction>::new_stateless(move |_| {
+                sender.input(AppMsg::Slideshow);
+            })
+        };
+        actions.add_action(slideshow_action);
+
+        let select_action = {
+            let sender = sender.clone();
+            RelmAction::<SelectAction>::new_stateless(move |_| {
+                sender.input(AppMsg::Select);
+            })
+        };
+        actions.add_action(select_action);
 
         actions.register_for_widget(&widgets.main_window);
 
+        // Observe, but don't consume, key presses and pointer movement.
+        let activity_controller = gtk::EventControllerLegacy::new();
+        activity_controller.set_propagation_phase(gtk::PropagationPhase::Capture);
+        activity_controller.connect_event(move |_, _| {
+            user_activity.record();
+            glib::Propagation::Proceed
+        });
+        widgets.main_window.add_controller(activity_controller);
+
         widgets.load_window_size();
 
         // Get startup window size and propagate so all components have correct narrow/wide layout.
@@ -929,6 +1499,10 @@ impl SimpleAsyncComponent for App {
                 if let Err(e) = App::save_settings(&settings) {
                     error!("Failed to save settings: {}", e);
                 }
+
+                if self.privacy_state.read().export != settings.export_location {
+                    self.privacy_state.write().export = settings.export_location;
+                }
             }
             AppMsg::ToggleSidebar => {
                 let show = self.main_navigation.shows_sidebar();
@@ -954,6 +1528,12 @@ impl SimpleAsyncComponent for App {
                         .policy(adw::ViewSwitcherPolicy::Wide)
                         .build();
                     self.header_bar.set_title_widget(Some(&vs));
+                } else if child_name == ViewName::Places {
+                    let vs = adw::ViewSwitcher::builder()
+                        .stack(&self.places_stack)
+                        .policy(adw::ViewSwitcherPolicy::Wide)
+                        .build();
+                    self.header_bar.set_title_widget(Some(&vs));
                 } else if let Some(child) = child {
                     let page = self.main_stack.page(&child);
                     let title = page.title().map(|x| x.to_string());
@@ -966,6 +1546,11 @@ impl SimpleAsyncComponent for App {
                     self.header_bar.set_title_widget(title.as_ref());
                 }
 
+                self.slideshow_button
+                    .set_visible(Self::slideshow_filter(child_name).is_some());
+                self.select_button
+                    .set_visible(Self::slideshow_filter(child_name).is_some());
+
                 // figure out which view to activate
                 match child_name {
                     ViewName::Library | ViewName::All | ViewName::Month | ViewName::Year => {
@@ -981,7 +1566,11 @@ impl SimpleAsyncComponent for App {
                     ViewName::Folder => self.folder_album.emit(AlbumInput::Activate),
                     ViewName::People => self.people_page.emit(PeopleAlbumInput::Activate),
                     ViewName::Person => self.person_album.emit(PersonAlbumInput::Activate),
-                    ViewName::Places => self.places_page.emit(PlacesAlbumInput::Activate),
+                    ViewName::Places => {
+                        self.places_page.emit(PlacesAlbumInput::Activate);
+                        self.place_list.emit(PlaceListAlbumInput::Activate);
+                    }
+                    ViewName::Events => self.events_page.emit(EventsAlbumInput::Activate),
                     ViewName::Nothing => warn!("Nothing activated... which should not happen"),
                 }
             }
@@ -996,16 +1585,18 @@ impl SimpleAsyncComponent for App {
                 self.view_nav.emit(ViewNavInput::Hidden);
             }
             AppMsg::ViewFolder(path) => {
-                self.folder_album.emit(AlbumInput::Activate);
-                self.folder_album
-                    .emit(AlbumInput::Filter(AlbumFilter::Folder(path)));
-                self.picture_navigation_view.push_by_tag("album");
+                self.view_folder_album(AlbumFilter::Folder(path));
             }
             AppMsg::ViewGeographicArea(cell_index) => {
-                self.folder_album.emit(AlbumInput::Activate);
-                self.folder_album
-                    .emit(AlbumInput::Filter(AlbumFilter::GeographicArea(cell_index)));
-                self.picture_navigation_view.push_by_tag("album");
+                let filter = self.without_private(AlbumFilter::GeographicArea(cell_index));
+                self.view_folder_album(filter);
+            }
+            AppMsg::ViewPlace(filter) => {
+                let filter = self.without_private(filter);
+                self.view_folder_album(filter);
+            }
+            AppMsg::ViewEvent(visual_ids) => {
+                self.view_folder_album(AlbumFilter::Visuals(visual_ids));
             }
             AppMsg::ViewPerson(person) => {
                 //info!("picture_ids = {:?}", picture_ids);
@@ -1021,6 +1612,113 @@ impl SimpleAsyncComponent for App {
             AppMsg::PersonRenamed => {
                 self.people_page.emit(PeopleAlbumInput::Refresh);
             }
+            AppMsg::Slideshow => {
+                let tag = self
+                    .picture_navigation_view
+                    .visible_page()
+                    .and_then(|page| page.tag());
+
+                match tag.as_deref() {
+                    Some("album") => {
+                        sender.input(AppMsg::StartSlideshow(self.folder_album_filter.clone()));
+                    }
+                    Some("person_album") => {
+                        // Person album knows which pictures are of the person.
+                        self.person_album.emit(PersonAlbumInput::Slideshow);
+                    }
+                    Some("picture") | Some("slideshow") => {}
+                    _ => {
+                        let view_name = self
+                            .main_stack
+                            .visible_child_name()
+                            .and_then(|x| ViewName::from_str(x.as_str()).ok())
+                            .unwrap_or(ViewName::Nothing);
+
+                        if let Some(filter) = Self::slideshow_filter(view_name) {
+                            sender.input(AppMsg::StartSlideshow(filter));
+                        }
+                    }
+                }
+            }
+            AppMsg::Select => {
+                let tag = self
+                    .picture_navigation_view
+                    .visible_page()
+                    .and_then(|page| page.tag());
+
+                match tag.as_deref() {
+                    Some("album") => self.folder_album.emit(AlbumInput::SelectionMode(true)),
+                    Some("person_album") => self.person_album.emit(PersonAlbumInput::Select),
+                    Some("picture") | Some("slideshow") => {}
+                    _ => {
+                        let view_name = self
+                            .main_stack
+                            .visible_child_name()
+                            .and_then(|x| ViewName::from_str(x.as_str()).ok())
+                            .unwrap_or(ViewName::Nothing);
+
+                        match view_name {
+                            ViewName::Library
+                            | ViewName::All
+                            | ViewName::Month
+                            | ViewName::Year => self.library.emit(LibraryInput::Select),
+                            ViewName::Videos => {
+                                self.videos_page.emit(AlbumInput::SelectionMode(true))
+                            }
+                            ViewName::Animated => {
+                                self.motion_page.emit(AlbumInput::SelectionMode(true))
+                            }
+                            ViewName::Selfies => {
+                                self.selfies_page.emit(AlbumInput::SelectionMode(true))
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            AppMsg::Batch(action, visuals) => match action {
+                BatchAction::Rotate(turn) => {
+                    info!("Rotate {} items {:?}", visuals.len(), turn);
+                    let mut picture_ids = vec![];
+                    for visual in visuals {
+                        match self.rotate(&visual, turn) {
+                            Ok(visual) => {
+                                picture_ids.extend(visual.picture_id);
+                                self.view_nav.emit(ViewNavInput::Edited(Arc::new(visual)));
+                            }
+                            Err(e) => error!("Failed to rotate {}: {}", visual.visual_id, e),
+                        }
+                    }
+                    self.bootstrap.emit(BootstrapInput::Reoriented(picture_ids));
+                }
+                BatchAction::SetLocation => sender.input(AppMsg::SetLocation(visuals)),
+                BatchAction::SetDate => self.set_date_dialog.emit(SetDateInput::Present(visuals)),
+                BatchAction::AssignPerson => self
+                    .assign_person_dialog
+                    .emit(AssignPersonInput::Present(visuals)),
+                BatchAction::MoveToFolder => sender.input(AppMsg::MoveToFolder(visuals)),
+                BatchAction::CopyToFolder => sender.input(AppMsg::CopyToFolder(visuals)),
+                BatchAction::Trash => sender.input(AppMsg::Trash(visuals)),
+                BatchAction::Share => sender.input(AppMsg::Share(visuals)),
+            },
+            AppMsg::StartSlideshow(filter) => {
+                info!("Starting slideshow for {:?}", filter);
+                self.slideshow.emit(SlideshowInput::Start(filter));
+                self.picture_navigation_view.push_by_tag("slideshow");
+            }
+            AppMsg::CloseSlideshow => {
+                if self
+                    .picture_navigation_view
+                    .visible_page()
+                    .and_then(|page| page.tag())
+                    .is_some_and(|tag| tag.as_str() == "slideshow")
+                {
+                    self.picture_navigation_view.pop();
+                }
+            }
+            AppMsg::SlideshowHidden => {
+                self.slideshow.emit(SlideshowInput::Stop);
+            }
             AppMsg::TaskStarted(task_name) => {
                 self.spinner
                     .set_visible(!self.main_navigation.shows_sidebar());
@@ -1044,6 +1742,15 @@ impl SimpleAsyncComponent for App {
                     TaskName::MotionPhoto => {
                         self.banner.set_title(&fl!("banner-extract-motion-photos"));
                     }
+                    TaskName::Geocode => {
+                        self.banner.set_title(&fl!("banner-geocode"));
+                    }
+                    TaskName::Geotag => {
+                        self.banner.set_title(&fl!("banner-geotag"));
+                    }
+                    TaskName::Events => {
+                        self.banner.set_title(&fl!("banner-events"));
+                    }
                     TaskName::Thumbnail(ThumbnailType::Photo) => {
                         self.banner.set_title(&fl!("banner-thumbnails-photos"));
                     }
@@ -1068,6 +1775,12 @@ impl SimpleAsyncComponent for App {
                     TaskName::Transcode => {
                         self.banner.set_title(&fl!("banner-convert-videos"));
                     }
+                    TaskName::VideoCache => {
+                        self.banner.set_title(&fl!("banner-video-cache"));
+                    }
+                    TaskName::ThumbnailCache => {
+                        self.banner.set_title(&fl!("banner-thumbnail-cache"));
+                    }
                     TaskName::Tidy => {
                         // Show nothing
                     }
@@ -1098,6 +1811,148 @@ impl SimpleAsyncComponent for App {
                 info!("Process motion photos");
                 self.bootstrap.emit(BootstrapInput::ProcessMotionPhotos);
             }
+            AppMsg::EvictVideoCache => {
+                info!("Evict video cache");
+                self.bootstrap.emit(BootstrapInput::EvictVideoCache);
+            }
+            AppMsg::RegenerateThumbnails => {
+                info!("Regenerate thumbnails");
+                self.bootstrap.emit(BootstrapInput::RegenerateThumbnails);
+            }
+            AppMsg::RestoreVideoCache(visual) => {
+                info!("Restore evicted video for {}", visual.visual_id);
+                self.bootstrap
+                    .emit(BootstrapInput::RestoreVideoCache(visual));
+            }
+            AppMsg::GeotagTrack(path, correlator) => {
+                info!("Geotag from track log: {:?}", path);
+                self.bootstrap
+                    .emit(BootstrapInput::GeotagTrack(path, correlator));
+            }
+            AppMsg::SetLocation(visuals) => {
+                self.pin_location_dialog
+                    .emit(PinLocationInput::Present(visuals));
+            }
+            AppMsg::PinLocation(targets, location) => {
+                info!("Pin {} items to location: {}", targets.len(), location);
+                self.bootstrap
+                    .emit(BootstrapInput::PinLocation(targets, location));
+            }
+            AppMsg::SetDate(visuals, date) => {
+                info!("Set date of {} items to {}", visuals.len(), date);
+                for visual in visuals {
+                    let local_ts = date.and_time(visual.local_ts.time());
+                    // Keep the item's offset from UTC by moving its ordering time as far
+                    // as its wall-clock time.
+                    let utc_ts = visual.ordering_ts + (local_ts - visual.local_ts);
+                    let result = if let Some(picture_id) = visual.picture_id {
+                        self.dates_repo.set_picture(picture_id, local_ts, utc_ts)
+                    } else if let Some(video_id) = visual.video_id {
+                        self.dates_repo.set_video(video_id, local_ts, utc_ts)
+                    } else {
+                        continue;
+                    };
+
+                    if let Err(e) = result {
+                        error!("Failed to set date of {}: {}", visual.visual_id, e);
+                    }
+                }
+                self.bootstrap.emit(BootstrapInput::Redated);
+            }
+            AppMsg::PersonAssigned(count, total) => {
+                info!("Assigned person to {} of {} items", count, total);
+                let toast =
+                    adw::Toast::new(&fl!("assign-person-done", count = count, total = total));
+                self.toast_overlay.add_toast(toast);
+                self.people_page.emit(PeopleAlbumInput::Refresh);
+            }
+            AppMsg::EditPicture(visual) => {
+                self.photo_edit_dialog.emit(PhotoEditInput::Present(visual));
+            }
+            AppMsg::PictureEdited(visual) => {
+                info!("Picture edited: {}", visual.visual_id);
+                self.view_nav.emit(ViewNavInput::Edited(visual));
+                self.bootstrap.emit(BootstrapInput::PictureEdited);
+            }
+            AppMsg::Rotate(visual, turn) => {
+                info!("Rotate {} {:?}", visual.visual_id, turn);
+                match self.rotate(&visual, turn) {
+                    Ok(visual) => {
+                        let picture_ids = visual.picture_id.into_iter().collect();
+                        self.view_nav.emit(ViewNavInput::Edited(Arc::new(visual)));
+                        self.bootstrap.emit(BootstrapInput::Reoriented(picture_ids));
+                    }
+                    Err(e) => error!("Failed to rotate {}: {}", visual.visual_id, e),
+                }
+            }
+            AppMsg::OrientationWriteFailed(path) => {
+                warn!("Orientation not saved to {:?}", path);
+                let toast = adw::Toast::new(&fl!("viewer-orientation-write-failed"));
+                self.toast_overlay.add_toast(toast);
+            }
+            AppMsg::Rename(visual) => {
+                self.file_operations
+                    .emit(FileOperationsInput::Rename(visual));
+            }
+            AppMsg::MoveToFolder(visuals) => {
+                self.file_operations
+                    .emit(FileOperationsInput::MoveToFolder(visuals));
+            }
+            AppMsg::CopyToFolder(visuals) => {
+                self.file_operations
+                    .emit(FileOperationsInput::CopyToFolder(visuals));
+            }
+            AppMsg::Trash(visuals) => {
+                self.file_operations
+                    .emit(FileOperationsInput::Trash(visuals));
+            }
+            AppMsg::Share(visuals) => {
+                self.export_dialog.emit(ExportInput::Present(visuals));
+            }
+            AppMsg::Relocated(visuals) => {
+                for visual in visuals {
+                    self.view_nav.emit(ViewNavInput::Edited(visual));
+                }
+            }
+            AppMsg::RefreshViewer => {
+                self.view_nav.emit(ViewNavInput::Refresh);
+            }
+            AppMsg::ViewerEmpty => {
+                if self
+                    .picture_navigation_view
+                    .visible_page()
+                    .and_then(|page| page.tag())
+                    .is_some_and(|tag| tag.as_str() == "picture")
+                {
+                    self.picture_navigation_view.pop();
+                }
+            }
+            AppMsg::FilesChanged => {
+                info!("Files changed");
+                self.bootstrap.emit(BootstrapInput::FilesChanged);
+            }
+            AppMsg::PresentPrivateZoneDialog => {
+                self.private_zone_dialog.emit(PrivateZoneInput::Present);
+            }
+            AppMsg::AddPrivateZone(name, area) => {
+                info!("Adding private zone: {}", name);
+                if let Err(e) = self.privacy_repo.add(&name, area) {
+                    error!("Failed to add private zone: {}", e);
+                }
+                self.reload_private_zones();
+            }
+            AppMsg::RemovePrivateZone(zone_id) => {
+                info!("Removing private zone: {}", zone_id);
+                if let Err(e) = self.privacy_repo.remove(zone_id) {
+                    error!("Failed to remove private zone: {}", e);
+                }
+                self.reload_private_zones();
+            }
+            AppMsg::RetryProblems(transcode) => {
+                info!("Retry problems");
+                self.bootstrap
+                    .emit(BootstrapInput::RetryProblems(transcode));
+            }
             AppMsg::StopBackgroundTasks => {
                 info!("Stop all background tasks");
                 self.banner.set_button_label(None);
@@ -1146,6 +2001,87 @@ impl SimpleAsyncComponent for App {
 }
 
 impl App {
+    /// Save an orientation override that turns an item a quarter turn.
+    /// Returns the item as it should now be shown.
+    fn rotate(&mut self, visual: &fotema_core::Visual, turn: Turn) -> Result<fotema_core::Visual> {
+        let mut visual = visual.clone();
+
+        if let Some(picture_id) = visual.picture_id {
+            let orientation = visual.picture_orientation.unwrap_or_default().turn(turn);
+            visual.picture_orientation = Some(orientation);
+            visual.picture_orientation_override =
+                self.orientation_repo.set_picture(picture_id, orientation)?;
+
+            if self.settings_state.read().write_orientation_to_exif {
+                let path = visual.sandbox_path().clone();
+                if orientation::exif::is_writable(&path) {
+                    self.orientation_write_task
+                        .emit(OrientationWriteTaskInput::Write(path, orientation));
+                }
+            }
+        } else if let Some(video_id) = visual.video_id {
+            let orientation = visual
+                .video_orientation_override
+                .unwrap_or_default()
+                .turn(turn);
+            visual.video_orientation = visual.video_orientation.map(|x| x.turn(turn));
+            visual.video_orientation_override =
+                self.orientation_repo.set_video(video_id, orientation)?;
+        }
+
+        Ok(visual)
+    }
+
+    /// Reload private zones so that views hiding private locations are refreshed.
+    fn reload_private_zones(&mut self) {
+        match self.privacy_repo.all() {
+            std::result::Result::Ok(zones) => {
+                self.privacy_state.write().zones = zones;
+            }
+            Err(e) => error!("Failed loading private zones: {}", e),
+        }
+    }
+
+    /// Show the album page for a folder, place, or event.
+    fn view_folder_album(&mut self, filter: AlbumFilter) {
+        self.folder_album.emit(AlbumInput::Activate);
+        self.folder_album.emit(AlbumInput::Filter(filter.clone()));
+        self.folder_album_filter = filter;
+        self.picture_navigation_view.push_by_tag("album");
+    }
+
+    /// Filter for a slideshow of a main view, if the view is an album.
+    fn slideshow_filter(view_name: ViewName) -> Option<AlbumFilter> {
+        match view_name {
+            ViewName::Library | ViewName::All | ViewName::Month | ViewName::Year => {
+                Some(AlbumFilter::All)
+            }
+            ViewName::Videos => Some(AlbumFilter::Videos),
+            ViewName::Animated => Some(AlbumFilter::Motion),
+            ViewName::Selfies => Some(AlbumFilter::Selfies),
+            _ => None,
+        }
+    }
+
+    /// An album filter for a place that never shows items in private zones.
+    fn without_private(&self, filter: AlbumFilter) -> AlbumFilter {
+        let privacy = self.privacy_state.read();
+        if privacy.zones.is_empty() {
+            return filter;
+        }
+
+        let visual_ids = self
+            .state
+            .read()
+            .iter()
+            .filter(|v| privacy.visible_location(v.location).is_some())
+            .filter(|v| filter.clone().filter(v))
+            .map(|v| v.visual_id.clone())
+            .collect();
+
+        AlbumFilter::Visuals(visual_ids)
+    }
+
     pub async fn load_settings() -> Result<Settings> {
         info!("Loading settings");
 
@@ -1169,6 +2105,30 @@ impl App {
                 .unwrap_or(AlbumSort::Ascending),
             is_onboarding_complete: gio_settings.boolean("onboarding-complete"),
             library_base_dir,
+            export_location: ExportLocation::parse(&gio_settings.string("export-location"))
+                .unwrap_or_default(),
+            transcode_profile: TranscodeProfile::parse(&gio_settings.string("transcode-profile"))
+                .unwrap_or_default(),
+            video_cache_limit_gb: gio_settings.uint("video-cache-limit-gb"),
+            background_threads: gio_settings.uint("background-threads"),
+            pause_on_battery: gio_settings.boolean("pause-on-battery"),
+            pause_while_active: gio_settings.boolean("pause-while-active"),
+            private_thumbnails: gio_settings.boolean("private-thumbnails"),
+            write_orientation_to_exif: gio_settings.boolean("write-orientation-to-exif"),
+            slideshow_interval_secs: gio_settings.uint("slideshow-interval"),
+            slideshow_shuffle: gio_settings.boolean("slideshow-shuffle"),
+            export_options: ExportOptions {
+                max_dimension: Some(gio_settings.uint("export-max-dimension"))
+                    .filter(|pixels| *pixels > 0),
+                format: PictureFormat::parse(&gio_settings.string("export-format"))
+                    .unwrap_or_default(),
+                quality: gio_settings.uint("export-quality").clamp(1, 100) as u8,
+                convert_heic: gio_settings.boolean("export-convert-heic"),
+                metadata: MetadataPolicy::parse(&gio_settings.string("export-metadata"))
+                    .unwrap_or_default(),
+                file_name_template: gio_settings.string("export-file-name-template").into(),
+                zip: gio_settings.boolean("export-zip"),
+            },
         })
     }
 
@@ -1184,6 +2144,34 @@ impl App {
             "pictures-base-dir-b64",
             &path_encoding::to_base64(&settings.library_base_dir.sandbox_path),
         )?;
+        gio_settings.set_string("export-location", settings.export_location.as_str())?;
+        gio_settings.set_string("transcode-profile", settings.transcode_profile.as_str())?;
+        gio_settings.set_uint("video-cache-limit-gb", settings.video_cache_limit_gb)?;
+        gio_settings.set_uint("background-threads", settings.background_threads)?;
+        gio_settings.set_boolean("pause-on-battery", settings.pause_on_battery)?;
+        gio_settings.set_boolean("pause-while-active", settings.pause_while_active)?;
+        gio_settings.set_boolean("private-thumbnails", settings.private_thumbnails)?;
+        gio_settings.set_boolean(
+            "write-orientation-to-exif",
+            settings.write_orientation_to_exif,
+        )?;
+        gio_settings.set_uint("slideshow-interval", settings.slideshow_interval_secs)?;
+        gio_settings.set_boolean("slideshow-shuffle", settings.slideshow_shuffle)?;
+
+        let export_options = &settings.export_options;
+        gio_settings.set_string("export-format", export_options.format.as_str())?;
+        gio_settings.set_uint("export-quality", u32::from(export_options.quality))?;
+        gio_settings.set_uint(
+            "export-max-dimension",
+            export_options.max_dimension.unwrap_or(0),
+        )?;
+        gio_settings.set_boolean("export-convert-heic", export_options.convert_heic)?;
+        gio_settings.set_string("export-metadata", export_options.metadata.as_str())?;
+        gio_settings.set_string(
+            "export-file-name-template",
+            &export_options.file_name_template,
+        )?;
+        gio_settings.set_boolean("export-zip", export_options.zip)?;
         Ok(())
     }
 }
@@ -7,22 +7,31 @@ use relm4::{
 
 use crate::app::Settings;
 use crate::config::APP_ID;
+use crate::thumbnails;
 use fotema_core::FlatpakPathBuf;
 use fotema_core::PictureId;
 use fotema_core::Scanner;
+use fotema_core::Visual;
 use fotema_core::database;
+use fotema_core::events;
+use fotema_core::geotag::{self, Correlator};
+use fotema_core::jobs;
 use fotema_core::people;
 use fotema_core::people::migrate::Migrate;
 use fotema_core::photo;
-use fotema_core::thumbnailify::Thumbnailer;
+use fotema_core::places;
+use fotema_core::places::GeoTarget;
+use fotema_core::problems;
 use fotema_core::video;
 use fotema_core::visual;
+use h3o::LatLng;
 
 use std::result::Result::Ok;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 use std::collections::VecDeque;
+use std::path::PathBuf;
 use std::time::Instant;
 
 use tracing::{error, info, warn};
@@ -30,6 +39,8 @@ use tracing::{error, info, warn};
 use anyhow;
 
 use super::{
+    event_cluster_task::{EventClusterTask, EventClusterTaskInput, EventClusterTaskOutput},
+    geotag_task::{GeotagTask, GeotagTaskInput, GeotagTaskOutput},
     library_scan_task::{LibraryScanTask, LibraryScanTaskInput, LibraryScanTaskOutput},
     load_library_task::{LoadLibraryTask, LoadLibraryTaskInput, LoadLibraryTaskOutput},
     migrate_task::{MigrateTask, MigrateTaskInput, MigrateTaskOutput},
@@ -48,7 +59,11 @@ use super::{
         PhotoRecognizeFacesTask, PhotoRecognizeFacesTaskInput, PhotoRecognizeFacesTaskOutput,
     },
     photo_thumbnail_task::{PhotoThumbnailTask, PhotoThumbnailTaskInput, PhotoThumbnailTaskOutput},
+    place_geocode_task::{PlaceGeocodeTask, PlaceGeocodeTaskInput, PlaceGeocodeTaskOutput},
+    scheduler::{Scheduler, UserActivity},
+    thumbnail_cache_task::{ThumbnailCacheTask, ThumbnailCacheTaskInput, ThumbnailCacheTaskOutput},
     tidy_task::{TidyTask, TidyTaskInput, TidyTaskOutput},
+    video_cache_task::{VideoCacheTask, VideoCacheTaskInput, VideoCacheTaskOutput},
     video_clean_task::{VideoCleanTask, VideoCleanTaskInput, VideoCleanTaskOutput},
     video_enrich_task::{VideoEnrichTask, VideoEnrichTaskInput, VideoEnrichTaskOutput},
     video_thumbnail_task::{VideoThumbnailTask, VideoThumbnailTaskInput, VideoThumbnailTaskOutput},
@@ -62,13 +77,13 @@ use crate::app::SharedState;
 use crate::app::components::progress_monitor::ProgressMonitor;
 
 /// FIXME copied from progress_monitor. Consolidate?
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MediaType {
     Photo,
     Video,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ThumbnailType {
     Photo,
     Video,
@@ -77,21 +92,76 @@ pub enum ThumbnailType {
 
 /// FIXME very similar (but different) to progress_monitor::TaskName.
 /// Any thoughts about this fact?
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TaskName {
     LoadLibrary,
     Scan,
     Enrich(MediaType),
+    Geocode,
+    Geotag,
+    Events,
     MotionPhoto,
     Thumbnail(ThumbnailType),
+    ThumbnailCache,
     Clean(MediaType),
     DetectFaces,
     RecognizeFaces,
     Transcode,
+    VideoCache,
     Tidy,
     Migrate,
 }
 
+/// Which tasks a queued task can run alongside. Tasks always start in the order
+/// they were queued.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lane {
+    /// Mostly waits on the disk. Can start while a CPU task queued before it is still
+    /// running, because it doesn't need that task's results.
+    Io,
+
+    /// Mostly computes. Waits for all tasks queued before it, because it works on
+    /// what they scanned and enriched.
+    Cpu,
+
+    /// Reads or tidies up after the work of other tasks, so runs alone.
+    Exclusive,
+}
+
+impl TaskName {
+    fn lane(&self) -> Lane {
+        match self {
+            TaskName::Scan
+            | TaskName::Enrich(_)
+            | TaskName::Geocode
+            | TaskName::Geotag
+            | TaskName::Events
+            | TaskName::Clean(_) => Lane::Io,
+            TaskName::Thumbnail(_)
+            | TaskName::MotionPhoto
+            | TaskName::DetectFaces
+            | TaskName::RecognizeFaces
+            | TaskName::Transcode => Lane::Cpu,
+            TaskName::LoadLibrary
+            | TaskName::ThumbnailCache
+            | TaskName::VideoCache
+            | TaskName::Tidy
+            | TaskName::Migrate => Lane::Exclusive,
+        }
+    }
+}
+
+/// Can a task in a lane start while other tasks are running?
+/// At most one I/O task and one CPU task run at once.
+fn can_start(lane: Lane, running: &[TaskName], allows_overlap: bool) -> bool {
+    running.is_empty()
+        || (allows_overlap
+            && lane == Lane::Io
+            && running
+                .iter()
+                .all(|task_name| task_name.lane() == Lane::Cpu))
+}
+
 #[derive(Debug)]
 pub enum BootstrapInput {
     /// Configure the pictures library root and host path
@@ -119,6 +189,39 @@ pub enum BootstrapInput {
     /// Queue task for processing motion photos
     ProcessMotionPhotos,
 
+    /// Queue task for evicting videos until the video cache is within its limit.
+    EvictVideoCache,
+
+    /// Queue task for transcoding or extracting a video evicted from the video cache.
+    RestoreVideoCache(Arc<Visual>),
+
+    /// Queue task for matching items without a location to a GPS track log.
+    GeotagTrack(PathBuf, Correlator),
+
+    /// Queue task for setting the location of items to a pin dropped on a map.
+    PinLocation(Vec<GeoTarget>, LatLng),
+
+    /// Queue tasks for processing items that were previously broken.
+    /// bool is true if videos that couldn't be transcoded should be transcoded again.
+    RetryProblems(bool),
+
+    /// Queue tasks for deleting and generating again all thumbnails.
+    RegenerateThumbnails,
+
+    /// Queue tasks for showing a picture with new edits in the library.
+    PictureEdited,
+
+    /// Queue tasks for showing items with a new orientation in the library.
+    /// Faces of a reoriented picture are detected again.
+    Reoriented(Vec<PictureId>),
+
+    /// Queue tasks for showing items with a new date in the library.
+    Redated,
+
+    /// Queue tasks for updating the library after files were renamed, moved, copied,
+    /// or moved to the trash from within Fotema.
+    FilesChanged,
+
     /// A background task has started.
     TaskStarted(TaskName),
 
@@ -162,12 +265,20 @@ pub struct Controllers {
     photo_enrich_task: Arc<WorkerController<PhotoEnrichTask>>,
     video_enrich_task: Arc<WorkerController<VideoEnrichTask>>,
 
+    place_geocode_task: Arc<WorkerController<PlaceGeocodeTask>>,
+
+    event_cluster_task: Arc<WorkerController<EventClusterTask>>,
+
+    geotag_task: Arc<WorkerController<GeotagTask>>,
+
     photo_clean_task: Arc<WorkerController<PhotoCleanTask>>,
     video_clean_task: Arc<WorkerController<VideoCleanTask>>,
 
     photo_thumbnail_task: Arc<WorkerController<PhotoThumbnailTask>>,
     video_thumbnail_task: Arc<WorkerController<VideoThumbnailTask>>,
 
+    thumbnail_cache_task: Arc<WorkerController<ThumbnailCacheTask>>,
+
     photo_extract_motion_task: Arc<WorkerController<PhotoExtractMotionTask>>,
 
     photo_detect_faces_task: Arc<WorkerController<PhotoDetectFacesTask>>,
@@ -175,16 +286,21 @@ pub struct Controllers {
 
     video_transcode_task: Arc<WorkerController<VideoTranscodeTask>>,
 
+    video_cache_task: Arc<WorkerController<VideoCacheTask>>,
+
     tidy_task: Arc<WorkerController<TidyTask>>,
     migrate_task: Arc<WorkerController<MigrateTask>>,
     person_thumbnail_task: Arc<WorkerController<PersonThumbnailTask>>,
 
+    /// Decides whether an I/O task and a CPU task may run at the same time.
+    scheduler: Scheduler,
+
     /// Pending ordered tasks to process
     /// Wow... figuring out a type signature that would compile was a nightmare.
-    pending_tasks: Arc<Mutex<VecDeque<Box<Task>>>>,
+    pending_tasks: Arc<Mutex<VecDeque<(TaskName, Box<Task>)>>>,
 
-    // Is a task currently running?
-    is_running: bool,
+    /// Tasks currently running.
+    running: Vec<TaskName>,
 }
 
 impl Controllers {
@@ -196,37 +312,137 @@ impl Controllers {
                 info!("Start");
                 self.started_at = Some(Instant::now());
 
-                if let Ok(mut tasks) = self.pending_tasks.lock() {
-                    if let Some(task) = tasks.pop_front() {
-                        self.is_running = true;
-                        task();
-                    } else {
-                        self.is_running = false;
-                        let _ = sender.output(BootstrapOutput::Completed);
-                    }
+                self.run_ready_tasks();
+                if !self.is_running() {
+                    let _ = sender.output(BootstrapOutput::Completed);
                 }
             }
             BootstrapInput::ScanPictureForFaces(picture_id) => {
                 info!("Queueing task to scan picture {} for faces", picture_id);
                 self.add_task_photo_detect_faces_for_one(picture_id);
                 self.add_task_photo_recognize_faces();
-                self.run_if_idle();
+                self.run_ready_tasks();
             }
             BootstrapInput::ScanPicturesForFaces => {
                 info!("Queueing task to scan all pictures for faces");
                 self.add_task_photo_detect_faces();
                 self.add_task_photo_recognize_faces();
-                self.run_if_idle();
+                self.run_ready_tasks();
             }
             BootstrapInput::TranscodeAll => {
                 info!("Queueing task to transcode all incompatible videos");
+                // Evict first so the transcodes that fit within the cache limit are kept.
+                self.add_task_video_cache_evict();
                 self.add_task_video_transcode();
-                self.run_if_idle();
+                self.add_task_load_library(sender.input_sender().clone());
+                self.run_ready_tasks();
             }
             BootstrapInput::ProcessMotionPhotos => {
                 info!("Queueing task to process motion photos");
                 self.add_task_photo_extract_motion();
-                self.run_if_idle();
+                self.add_task_video_cache_evict();
+                self.run_ready_tasks();
+            }
+            BootstrapInput::EvictVideoCache => {
+                info!("Queueing task to evict videos from video cache");
+                self.add_task_video_cache_evict();
+                self.add_task_load_library(sender.input_sender().clone());
+                self.run_ready_tasks();
+            }
+            BootstrapInput::RestoreVideoCache(visual) => {
+                info!(
+                    "Queueing task to restore evicted video for {}",
+                    visual.visual_id
+                );
+                self.add_task_video_cache_restore(visual);
+                self.add_task_load_library(sender.input_sender().clone());
+                self.run_ready_tasks();
+            }
+            BootstrapInput::GeotagTrack(track_path, correlator) => {
+                info!("Queueing task to geotag from track log {:?}", track_path);
+                self.add_task_geotag(GeotagTaskInput::Correlate(track_path, correlator));
+                self.add_task_place_geocode();
+                self.add_task_event_cluster();
+                self.add_task_load_library(sender.input_sender().clone());
+                self.run_ready_tasks();
+            }
+            BootstrapInput::PinLocation(targets, location) => {
+                info!("Queueing task to pin {} items to a location", targets.len());
+                self.add_task_geotag(GeotagTaskInput::Pin(targets, location));
+                self.add_task_place_geocode();
+                self.add_task_event_cluster();
+                self.add_task_load_library(sender.input_sender().clone());
+                self.run_ready_tasks();
+            }
+            BootstrapInput::RetryProblems(transcode) => {
+                info!("Queueing tasks to process previously broken items");
+                // Items broken early on might not have had their metadata extracted.
+                self.add_task_photo_enrich();
+                self.add_task_video_enrich();
+                self.add_task_photo_thumbnail(PhotoThumbnailTaskInput::Start);
+                self.add_task_video_thumbnail(VideoThumbnailTaskInput::Start);
+                if transcode {
+                    // Transcoding only happens when asked for, so only retry it when
+                    // a transcode failed.
+                    self.add_task_video_cache_evict();
+                    self.add_task_video_transcode();
+                }
+                self.add_task_load_library(sender.input_sender().clone());
+                self.add_task_photo_extract_motion();
+                self.add_task_photo_detect_faces();
+                self.add_task_photo_recognize_faces();
+                self.run_ready_tasks();
+            }
+            BootstrapInput::RegenerateThumbnails => {
+                info!("Queueing tasks to regenerate all thumbnails");
+                self.add_task_thumbnail_cache(ThumbnailCacheTaskInput::Regenerate);
+                self.add_task_load_library(sender.input_sender().clone());
+                self.add_task_photo_thumbnail(PhotoThumbnailTaskInput::Start);
+                self.add_task_video_thumbnail(VideoThumbnailTaskInput::Start);
+                self.run_ready_tasks();
+            }
+            BootstrapInput::PictureEdited => {
+                info!("Queueing tasks to show edited picture");
+                self.add_task_photo_thumbnail(PhotoThumbnailTaskInput::Sweep);
+                self.add_task_reload_library();
+                self.run_ready_tasks();
+            }
+            BootstrapInput::Reoriented(picture_ids) => {
+                info!("Queueing tasks to show reoriented items");
+                self.add_task_photo_thumbnail(PhotoThumbnailTaskInput::Sweep);
+                self.add_task_video_thumbnail(VideoThumbnailTaskInput::Sweep);
+                self.add_task_reload_library();
+                if !picture_ids.is_empty() {
+                    for picture_id in picture_ids {
+                        self.add_task_photo_detect_faces_for_one(picture_id);
+                    }
+                    self.add_task_photo_recognize_faces();
+                }
+                self.run_ready_tasks();
+            }
+            BootstrapInput::Redated => {
+                info!("Queueing tasks to show redated items");
+                self.add_task_event_cluster();
+                self.add_task_reload_library();
+                self.run_ready_tasks();
+            }
+            BootstrapInput::FilesChanged => {
+                info!("Queueing tasks to show changed files");
+                // Copies are new files for the scan to find.
+                self.add_task_library_scan();
+                self.add_task_photo_enrich();
+                self.add_task_video_enrich();
+                self.add_task_photo_clean();
+                self.add_task_video_clean();
+
+                // Thumbnails are named after the path of a file, so renamed and
+                // moved files need new thumbnails and the old ones are swept away.
+                self.add_task_photo_thumbnail(PhotoThumbnailTaskInput::Sweep);
+                self.add_task_video_thumbnail(VideoThumbnailTaskInput::Sweep);
+                self.add_task_thumbnail_cache(ThumbnailCacheTaskInput::Sweep);
+                self.add_task_photo_extract_motion();
+                self.add_task_reload_library();
+                self.run_ready_tasks();
             }
             BootstrapInput::TaskStarted(task_name) => {
                 info!("Task started: {:?}", task_name);
@@ -240,18 +456,18 @@ impl Controllers {
                 self.library_stale
                     .fetch_or(updated.is_some_and(|x| x > 0), Ordering::Relaxed);
 
-                if let Ok(mut tasks) = self.pending_tasks.lock() {
-                    if let Some(task) = tasks.pop_front() {
-                        self.is_running = true;
-                        task();
-                    } else {
-                        self.is_running = false;
-                        self.library_stale.store(false, Ordering::Relaxed);
-                        let _ = sender.output(BootstrapOutput::Completed);
-                    }
+                if let Some(index) = self.running.iter().position(|x| *x == task_name) {
+                    self.running.remove(index);
                 }
 
-                if !self.is_running {
+                // With nothing running, the next task can always start, so all tasks
+                // are complete if nothing is running now.
+                self.run_ready_tasks();
+
+                if !self.is_running() {
+                    self.library_stale.store(false, Ordering::Relaxed);
+                    let _ = sender.output(BootstrapOutput::Completed);
+
                     // Note: AtomicBool::swap returns previous value.
                     if self.stop.swap(false, Ordering::Relaxed) {
                         sender.input(BootstrapInput::Stopped);
@@ -260,7 +476,7 @@ impl Controllers {
             }
             BootstrapInput::Stop => {
                 info!("Stopping all background tasks");
-                if self.is_running {
+                if self.is_running() {
                     let _ = sender.output(BootstrapOutput::Stopping);
                     if let Ok(mut tasks) = self.pending_tasks.lock() {
                         tasks.clear();
@@ -278,50 +494,101 @@ impl Controllers {
 
     fn add_task_library_scan(&mut self) {
         let sender = self.library_scan_task.sender().clone();
-        self.enqueue(Box::new(move || sender.emit(LibraryScanTaskInput::Start)));
+        self.enqueue(
+            TaskName::Scan,
+            Box::new(move || sender.emit(LibraryScanTaskInput::Start)),
+        );
     }
 
     fn add_task_photo_enrich(&mut self) {
         let sender = self.photo_enrich_task.sender().clone();
-        self.enqueue(Box::new(move || sender.emit(PhotoEnrichTaskInput::Start)));
+        self.enqueue(
+            TaskName::Enrich(MediaType::Photo),
+            Box::new(move || sender.emit(PhotoEnrichTaskInput::Start)),
+        );
     }
 
     fn add_task_video_enrich(&mut self) {
         let sender = self.video_enrich_task.sender().clone();
-        self.enqueue(Box::new(move || sender.emit(VideoEnrichTaskInput::Start)));
+        self.enqueue(
+            TaskName::Enrich(MediaType::Video),
+            Box::new(move || sender.emit(VideoEnrichTaskInput::Start)),
+        );
+    }
+
+    fn add_task_place_geocode(&mut self) {
+        let sender = self.place_geocode_task.sender().clone();
+        self.enqueue(
+            TaskName::Geocode,
+            Box::new(move || sender.emit(PlaceGeocodeTaskInput::Start)),
+        );
     }
 
-    fn add_task_photo_thumbnail(&mut self) {
+    fn add_task_event_cluster(&mut self) {
+        let sender = self.event_cluster_task.sender().clone();
+        self.enqueue(
+            TaskName::Events,
+            Box::new(move || sender.emit(EventClusterTaskInput::Start)),
+        );
+    }
+
+    fn add_task_geotag(&mut self, input: GeotagTaskInput) {
+        let sender = self.geotag_task.sender().clone();
+        self.enqueue(
+            TaskName::Geotag,
+            Box::new(move || sender.emit(input.clone())),
+        );
+    }
+
+    fn add_task_photo_thumbnail(&mut self, input: PhotoThumbnailTaskInput) {
         let sender = self.photo_thumbnail_task.sender().clone();
-        self.enqueue(Box::new(move || {
-            sender.emit(PhotoThumbnailTaskInput::Start)
-        }));
+        self.enqueue(
+            TaskName::Thumbnail(ThumbnailType::Photo),
+            Box::new(move || sender.emit(input.clone())),
+        );
     }
 
-    fn add_task_video_thumbnail(&mut self) {
+    fn add_task_video_thumbnail(&mut self, input: VideoThumbnailTaskInput) {
         let sender = self.video_thumbnail_task.sender().clone();
-        self.enqueue(Box::new(move || {
-            sender.emit(VideoThumbnailTaskInput::Start)
-        }));
+        self.enqueue(
+            TaskName::Thumbnail(ThumbnailType::Video),
+            Box::new(move || sender.emit(input.clone())),
+        );
+    }
+
+    fn add_task_thumbnail_cache(&mut self, input: ThumbnailCacheTaskInput) {
+        let sender = self.thumbnail_cache_task.sender().clone();
+        self.enqueue(
+            TaskName::ThumbnailCache,
+            Box::new(move || sender.emit(input.clone())),
+        );
     }
 
     fn add_task_photo_clean(&mut self) {
         let sender = self.photo_clean_task.sender().clone();
-        self.enqueue(Box::new(move || sender.emit(PhotoCleanTaskInput::Start)));
+        self.enqueue(
+            TaskName::Clean(MediaType::Photo),
+            Box::new(move || sender.emit(PhotoCleanTaskInput::Start)),
+        );
     }
 
     fn add_task_video_clean(&mut self) {
         let sender = self.video_clean_task.sender().clone();
-        self.enqueue(Box::new(move || sender.emit(VideoCleanTaskInput::Start)));
+        self.enqueue(
+            TaskName::Clean(MediaType::Video),
+            Box::new(move || sender.emit(VideoCleanTaskInput::Start)),
+        );
     }
 
     fn add_task_photo_extract_motion(&mut self) {
         let sender = self.photo_extract_motion_task.sender().clone();
         let enable = self.settings_state.read().process_motion_photos;
+        let profile = self.settings_state.read().transcode_profile;
         if enable {
-            self.enqueue(Box::new(move || {
-                sender.emit(PhotoExtractMotionTaskInput::Start)
-            }));
+            self.enqueue(
+                TaskName::MotionPhoto,
+                Box::new(move || sender.emit(PhotoExtractMotionTaskInput::Start(profile))),
+            );
         }
     }
 
@@ -331,9 +598,10 @@ impl Controllers {
         match mode {
             FaceDetectionMode::Off => {}
             FaceDetectionMode::On => {
-                self.enqueue(Box::new(move || {
-                    sender.emit(PhotoDetectFacesTaskInput::DetectForAllPictures)
-                }));
+                self.enqueue(
+                    TaskName::DetectFaces,
+                    Box::new(move || sender.emit(PhotoDetectFacesTaskInput::DetectForAllPictures)),
+                );
             }
         };
     }
@@ -344,9 +612,12 @@ impl Controllers {
         match mode {
             FaceDetectionMode::Off => {}
             FaceDetectionMode::On => {
-                self.enqueue(Box::new(move || {
-                    sender.emit(PhotoDetectFacesTaskInput::DetectForOnePicture(picture_id))
-                }));
+                self.enqueue(
+                    TaskName::DetectFaces,
+                    Box::new(move || {
+                        sender.emit(PhotoDetectFacesTaskInput::DetectForOnePicture(picture_id))
+                    }),
+                );
             }
         };
     }
@@ -357,9 +628,10 @@ impl Controllers {
         match mode {
             FaceDetectionMode::Off => {}
             FaceDetectionMode::On => {
-                self.enqueue(Box::new(move || {
-                    sender.emit(PhotoRecognizeFacesTaskInput::Start)
-                }));
+                self.enqueue(
+                    TaskName::RecognizeFaces,
+                    Box::new(move || sender.emit(PhotoRecognizeFacesTaskInput::Start)),
+                );
             }
         };
     }
@@ -370,59 +642,110 @@ impl Controllers {
         match mode {
             FaceDetectionMode::Off => {}
             FaceDetectionMode::On => {
-                self.enqueue(Box::new(move || {
-                    sender.emit(PersonThumbnailTaskInput::Start)
-                }));
+                self.enqueue(
+                    TaskName::Thumbnail(ThumbnailType::Face),
+                    Box::new(move || sender.emit(PersonThumbnailTaskInput::Start)),
+                );
             }
         };
     }
 
     fn add_task_video_transcode(&mut self) {
         let sender = self.video_transcode_task.sender().clone();
-        self.enqueue(Box::new(move || {
-            sender.emit(VideoTranscodeTaskInput::Start)
-        }));
+        let (profile, limit_gb) = {
+            let settings = self.settings_state.read();
+            (settings.transcode_profile, settings.video_cache_limit_gb)
+        };
+        self.enqueue(
+            TaskName::Transcode,
+            Box::new(move || sender.emit(VideoTranscodeTaskInput::Start(profile, limit_gb))),
+        );
+    }
+
+    fn add_task_video_cache_evict(&mut self) {
+        let sender = self.video_cache_task.sender().clone();
+        let limit_gb = self.settings_state.read().video_cache_limit_gb;
+        if limit_gb > 0 {
+            self.enqueue(
+                TaskName::VideoCache,
+                Box::new(move || sender.emit(VideoCacheTaskInput::Evict(limit_gb))),
+            );
+        }
+    }
+
+    fn add_task_video_cache_restore(&mut self, visual: Arc<Visual>) {
+        let sender = self.video_cache_task.sender().clone();
+        let profile = self.settings_state.read().transcode_profile;
+        self.enqueue(
+            TaskName::VideoCache,
+            Box::new(move || sender.emit(VideoCacheTaskInput::Restore(visual.clone(), profile))),
+        );
     }
 
     fn add_task_load_library(&mut self, bootstrap_sender: Sender<BootstrapInput>) {
         let sender = self.load_library_task.sender().clone();
         let stale = self.library_stale.clone();
         let library_state = self.shared_state.clone();
-        self.enqueue(Box::new(move || {
-            if stale.load(Ordering::Relaxed) || library_state.read().is_empty() {
-                info!("Library stale or empty so refreshing.");
-                sender.emit(LoadLibraryTaskInput::Refresh);
-            } else {
-                bootstrap_sender.emit(BootstrapInput::TaskCompleted(TaskName::LoadLibrary, None));
-            }
-        }));
+        self.enqueue(
+            TaskName::LoadLibrary,
+            Box::new(move || {
+                if stale.load(Ordering::Relaxed) || library_state.read().is_empty() {
+                    info!("Library stale or empty so refreshing.");
+                    sender.emit(LoadLibraryTaskInput::Refresh);
+                } else {
+                    bootstrap_sender
+                        .emit(BootstrapInput::TaskCompleted(TaskName::LoadLibrary, None));
+                }
+            }),
+        );
     }
 
     fn add_task_tidy(&mut self) {
         let sender = self.tidy_task.sender().clone();
-        self.enqueue(Box::new(move || sender.emit(TidyTaskInput::Start)));
+        self.enqueue(
+            TaskName::Tidy,
+            Box::new(move || sender.emit(TidyTaskInput::Start)),
+        );
     }
 
     fn add_task_migrate(&mut self) {
         let sender = self.migrate_task.sender().clone();
-        self.enqueue(Box::new(move || sender.emit(MigrateTaskInput::Start)));
+        self.enqueue(
+            TaskName::Migrate,
+            Box::new(move || sender.emit(MigrateTaskInput::Start)),
+        );
     }
 
-    fn enqueue(&mut self, task: Box<dyn Fn() + Send + Sync>) {
+    /// Load the library even if no task has marked it as stale, such as
+    /// when edits have changed which thumbnail an item has.
+    fn add_task_reload_library(&mut self) {
+        let sender = self.load_library_task.sender().clone();
+        self.enqueue(
+            TaskName::LoadLibrary,
+            Box::new(move || sender.emit(LoadLibraryTaskInput::Refresh)),
+        );
+    }
+
+    fn enqueue(&mut self, task_name: TaskName, task: Box<dyn Fn() + Send + Sync>) {
         if let Ok(mut vec) = self.pending_tasks.lock() {
-            vec.push_back(task);
+            vec.push_back((task_name, task));
         }
     }
 
-    /// Run next task if no tasks running
-    fn run_if_idle(&mut self) {
-        if self.is_running {
-            return;
-        }
+    fn is_running(&self) -> bool {
+        !self.running.is_empty()
+    }
+
+    /// Start queued tasks, in order, for as long as the running tasks allow.
+    fn run_ready_tasks(&mut self) {
+        let allows_overlap = self.scheduler.allows_overlap();
         if let Ok(mut tasks) = self.pending_tasks.lock() {
-            if let Some(task) = tasks.pop_front() {
-                info!("Running task right now");
-                self.is_running = true;
+            while let Some((task_name, _)) = tasks.front()
+                && can_start(task_name.lane(), &self.running, allows_overlap)
+            {
+                let (task_name, task) = tasks.pop_front().expect("Must have front task");
+                info!("Running task {:?} right now", task_name);
+                self.running.push(task_name);
                 task();
             }
         }
@@ -436,8 +759,15 @@ pub struct Bootstrap {
 
     con: Arc<Mutex<database::Connection>>,
 
+    /// Progress of CPU tasks.
     progress_monitor: Arc<Reducer<ProgressMonitor>>,
 
+    /// Progress of I/O tasks, which can run at the same time as CPU tasks.
+    io_progress_monitor: Arc<Reducer<ProgressMonitor>>,
+
+    /// Thread pools and pause policies shared by background tasks.
+    scheduler: Scheduler,
+
     /// Background task runners. Only present after library path is set.
     controllers: Option<Controllers>,
 
@@ -457,14 +787,8 @@ impl Bootstrap {
         let cache_dir = glib::user_cache_dir().join(APP_ID);
         let _ = std::fs::create_dir_all(&cache_dir);
 
-        // WARN duplicate thumbnail path calculation in app.rs
-        let thumbnail_dir = glib::user_cache_dir()
-            .join(APP_ID) // Remove to use standard XDG thumbnail path
-            .join("thumbnails");
-
-        info!("Thumbnail directory is {:?}", thumbnail_dir);
-
-        let thumbnailer = Thumbnailer::build(&thumbnail_dir);
+        let thumbnailer =
+            thumbnails::build_thumbnailer(self.settings_state.read().private_thumbnails);
 
         let photo_repo =
             photo::Repository::open(library_base_dir, &cache_dir, &data_dir, self.con.clone())?;
@@ -478,12 +802,26 @@ impl Bootstrap {
 
         let video_thumbnailer = video::VideoThumbnailer::build(thumbnailer.clone())?;
 
+        let video_storyboarder = video::Storyboarder::build(thumbnailer.clone())?;
+
         let motion_photo_extractor = photo::MotionPhotoExtractor::build(&cache_dir)?;
 
+        let video_cache_repo = video::cache::Repository::open(&cache_dir, self.con.clone())?;
+
         let visual_repo = visual::Repository::open(library_base_dir, &cache_dir, self.con.clone())?;
 
         let people_repo = people::Repository::open(&cache_dir, &data_dir, self.con.clone())?;
 
+        let places_repo = places::Repository::open(self.con.clone())?;
+
+        let events_repo = events::Repository::open(self.con.clone())?;
+
+        let geotag_repo = geotag::Repository::open(self.con.clone())?;
+
+        let jobs_repo = jobs::Repository::open(self.con.clone())?;
+
+        let problems_repo = problems::Repository::open(self.con.clone())?;
+
         let stop = Arc::new(AtomicBool::new(false));
 
         let load_library_task = LoadLibraryTask::builder()
@@ -504,7 +842,7 @@ impl Bootstrap {
             });
 
         let photo_enrich_task = PhotoEnrichTask::builder()
-            .detach_worker((stop.clone(), photo_repo.clone()))
+            .detach_worker((stop.clone(), self.scheduler.clone(), photo_repo.clone()))
             .forward(sender.input_sender(), |msg| match msg {
                 PhotoEnrichTaskOutput::Started => {
                     BootstrapInput::TaskStarted(TaskName::Enrich(MediaType::Photo))
@@ -517,8 +855,9 @@ impl Bootstrap {
         let video_enrich_task = VideoEnrichTask::builder()
             .detach_worker((
                 stop.clone(),
+                self.scheduler.clone(),
                 video_repo.clone(),
-                self.progress_monitor.clone(),
+                self.io_progress_monitor.clone(),
             ))
             .forward(sender.input_sender(), |msg| match msg {
                 VideoEnrichTaskOutput::Started => {
@@ -529,10 +868,53 @@ impl Bootstrap {
                 }
             });
 
+        let place_geocode_task = PlaceGeocodeTask::builder()
+            .detach_worker((
+                stop.clone(),
+                self.scheduler.clone(),
+                places_repo,
+                self.io_progress_monitor.clone(),
+            ))
+            .forward(sender.input_sender(), |msg| match msg {
+                PlaceGeocodeTaskOutput::Started => BootstrapInput::TaskStarted(TaskName::Geocode),
+                PlaceGeocodeTaskOutput::Completed(count) => {
+                    BootstrapInput::TaskCompleted(TaskName::Geocode, Some(count))
+                }
+            });
+
+        let event_cluster_task = EventClusterTask::builder()
+            .detach_worker((
+                stop.clone(),
+                self.scheduler.clone(),
+                visual_repo.clone(),
+                events_repo,
+            ))
+            .forward(sender.input_sender(), |msg| match msg {
+                EventClusterTaskOutput::Started => BootstrapInput::TaskStarted(TaskName::Events),
+                EventClusterTaskOutput::Completed(count) => {
+                    BootstrapInput::TaskCompleted(TaskName::Events, Some(count))
+                }
+            });
+
+        let geotag_task = GeotagTask::builder()
+            .detach_worker((
+                stop.clone(),
+                self.scheduler.clone(),
+                geotag_repo,
+                self.io_progress_monitor.clone(),
+            ))
+            .forward(sender.input_sender(), |msg| match msg {
+                GeotagTaskOutput::Started => BootstrapInput::TaskStarted(TaskName::Geotag),
+                GeotagTaskOutput::Completed(count) => {
+                    BootstrapInput::TaskCompleted(TaskName::Geotag, Some(count))
+                }
+            });
+
         let photo_extract_motion_task = PhotoExtractMotionTask::builder()
             .detach_worker((
                 stop.clone(),
-                motion_photo_extractor,
+                self.scheduler.clone(),
+                motion_photo_extractor.clone(),
                 photo_repo.clone(),
                 self.progress_monitor.clone(),
             ))
@@ -548,9 +930,11 @@ impl Bootstrap {
         let photo_thumbnail_task = PhotoThumbnailTask::builder()
             .detach_worker((
                 stop.clone(),
-                thumbnail_dir.clone(),
+                self.scheduler.clone(),
+                thumbnailer.clone(),
                 photo_thumbnailer.clone(),
                 photo_repo.clone(),
+                jobs_repo.clone(),
                 self.progress_monitor.clone(),
             ))
             .forward(sender.input_sender(), |msg| match msg {
@@ -566,9 +950,12 @@ impl Bootstrap {
         let video_thumbnail_task = VideoThumbnailTask::builder()
             .detach_worker((
                 stop.clone(),
-                thumbnail_dir.clone(),
+                self.scheduler.clone(),
+                thumbnailer.clone(),
                 video_thumbnailer.clone(),
+                video_storyboarder,
                 video_repo.clone(),
+                jobs_repo.clone(),
                 self.progress_monitor.clone(),
             ))
             .forward(sender.input_sender(), |msg| match msg {
@@ -581,27 +968,65 @@ impl Bootstrap {
                 ),
             });
 
+        let thumbnail_cache_task = ThumbnailCacheTask::builder()
+            .detach_worker((
+                stop.clone(),
+                self.scheduler.clone(),
+                thumbnailer.clone(),
+                library_base_dir.clone(),
+                photo_repo.clone(),
+                video_repo.clone(),
+                problems_repo,
+                jobs_repo.clone(),
+            ))
+            .forward(sender.input_sender(), |msg| match msg {
+                ThumbnailCacheTaskOutput::Started => {
+                    BootstrapInput::TaskStarted(TaskName::ThumbnailCache)
+                }
+                ThumbnailCacheTaskOutput::Completed(count) => {
+                    BootstrapInput::TaskCompleted(TaskName::ThumbnailCache, Some(count))
+                }
+            });
+
         let transcoder = video::Transcoder::new(&cache_dir);
 
         let video_transcode_task = VideoTranscodeTask::builder()
             .detach_worker((
                 stop.clone(),
+                self.scheduler.clone(),
                 self.shared_state.clone(),
                 video_repo.clone(),
-                transcoder,
+                video_cache_repo.clone(),
+                transcoder.clone(),
                 self.progress_monitor.clone(),
             ))
             .forward(sender.input_sender(), |msg| match msg {
                 VideoTranscodeTaskOutput::Started => {
                     BootstrapInput::TaskStarted(TaskName::Transcode)
                 }
-                VideoTranscodeTaskOutput::Completed => {
-                    BootstrapInput::TaskCompleted(TaskName::Transcode, None)
+                VideoTranscodeTaskOutput::Completed(count) => {
+                    BootstrapInput::TaskCompleted(TaskName::Transcode, Some(count))
+                }
+            });
+
+        let video_cache_task = VideoCacheTask::builder()
+            .detach_worker((
+                stop.clone(),
+                video_cache_repo,
+                video_repo.clone(),
+                photo_repo.clone(),
+                transcoder,
+                motion_photo_extractor,
+            ))
+            .forward(sender.input_sender(), |msg| match msg {
+                VideoCacheTaskOutput::Started => BootstrapInput::TaskStarted(TaskName::VideoCache),
+                VideoCacheTaskOutput::Completed(count) => {
+                    BootstrapInput::TaskCompleted(TaskName::VideoCache, Some(count))
                 }
             });
 
         let photo_clean_task = PhotoCleanTask::builder()
-            .detach_worker((stop.clone(), photo_repo.clone()))
+            .detach_worker((stop.clone(), self.scheduler.clone(), photo_repo.clone()))
             .forward(sender.input_sender(), |msg| match msg {
                 PhotoCleanTaskOutput::Started => {
                     BootstrapInput::TaskStarted(TaskName::Clean(MediaType::Photo))
@@ -612,7 +1037,7 @@ impl Bootstrap {
             });
 
         let video_clean_task = VideoCleanTask::builder()
-            .detach_worker((stop.clone(), video_repo.clone()))
+            .detach_worker((stop.clone(), self.scheduler.clone(), video_repo.clone()))
             .forward(sender.input_sender(), |msg| match msg {
                 VideoCleanTaskOutput::Started => {
                     BootstrapInput::TaskStarted(TaskName::Clean(MediaType::Video))
@@ -625,6 +1050,7 @@ impl Bootstrap {
         let photo_detect_faces_task = PhotoDetectFacesTask::builder()
             .detach_worker((
                 stop.clone(),
+                self.scheduler.clone(),
                 data_dir.clone(),
                 thumbnailer.clone(),
                 photo_repo.clone(),
@@ -643,6 +1069,7 @@ impl Bootstrap {
         let photo_recognize_faces_task = PhotoRecognizeFacesTask::builder()
             .detach_worker((
                 stop.clone(),
+                self.scheduler.clone(),
                 cache_dir.clone(),
                 people_repo.clone(),
                 self.progress_monitor.clone(),
@@ -682,6 +1109,7 @@ impl Bootstrap {
         let person_thumbnail_task = PersonThumbnailTask::builder()
             .detach_worker((
                 stop.clone(),
+                self.scheduler.clone(),
                 person_thumbnailer,
                 photo_repo.clone(),
                 self.progress_monitor.clone(),
@@ -705,19 +1133,25 @@ impl Bootstrap {
             library_scan_task: Arc::new(library_scan_task),
             photo_enrich_task: Arc::new(photo_enrich_task),
             video_enrich_task: Arc::new(video_enrich_task),
+            place_geocode_task: Arc::new(place_geocode_task),
+            event_cluster_task: Arc::new(event_cluster_task),
+            geotag_task: Arc::new(geotag_task),
             photo_extract_motion_task: Arc::new(photo_extract_motion_task),
             photo_clean_task: Arc::new(photo_clean_task),
             video_clean_task: Arc::new(video_clean_task),
             photo_thumbnail_task: Arc::new(photo_thumbnail_task),
             video_thumbnail_task: Arc::new(video_thumbnail_task),
+            thumbnail_cache_task: Arc::new(thumbnail_cache_task),
             photo_detect_faces_task: Arc::new(photo_detect_faces_task),
             photo_recognize_faces_task: Arc::new(photo_recognize_faces_task),
             video_transcode_task: Arc::new(video_transcode_task),
+            video_cache_task: Arc::new(video_cache_task),
             tidy_task: Arc::new(tidy_task),
             migrate_task: Arc::new(migrate_task),
             person_thumbnail_task: Arc::new(person_thumbnail_task),
+            scheduler: self.scheduler.clone(),
             pending_tasks: Arc::new(Mutex::new(VecDeque::new())),
-            is_running: false,
+            running: Vec::new(),
             library_stale: Arc::new(AtomicBool::new(true)),
         };
 
@@ -731,17 +1165,26 @@ impl Bootstrap {
         controllers.add_task_library_scan();
         controllers.add_task_photo_enrich();
         controllers.add_task_video_enrich();
+        controllers.add_task_place_geocode();
+        controllers.add_task_event_cluster();
 
         // If loaded library is currently empty, then refresh now that the photo and video scans
         // are complete. Note: should do this after enriching because otherwise Fotema won't
         // have processed the orientation metadata and will display pictures incorrectly.
         controllers.add_task_load_library(sender.input_sender().clone());
 
-        controllers.add_task_photo_thumbnail();
-        controllers.add_task_video_thumbnail();
+        // Before generating thumbnails so files that failed with an older version are tried again.
+        controllers.add_task_thumbnail_cache(ThumbnailCacheTaskInput::ExpireFailed);
+
+        controllers.add_task_photo_thumbnail(PhotoThumbnailTaskInput::Start);
+        controllers.add_task_video_thumbnail(VideoThumbnailTaskInput::Start);
         controllers.add_task_photo_clean();
         controllers.add_task_video_clean();
+
+        // After cleaning so thumbnails of files removed from the library are swept away.
+        controllers.add_task_thumbnail_cache(ThumbnailCacheTaskInput::Sweep);
         controllers.add_task_photo_extract_motion();
+        controllers.add_task_video_cache_evict();
         controllers.add_task_photo_detect_faces();
         controllers.add_task_photo_recognize_faces();
 
@@ -761,22 +1204,35 @@ impl Worker for Bootstrap {
         SharedState,
         SettingsState,
         Arc<Reducer<ProgressMonitor>>,
+        Arc<Reducer<ProgressMonitor>>,
+        UserActivity,
     );
     type Input = BootstrapInput;
     type Output = BootstrapOutput;
 
     fn init(
-        (con, shared_state, settings_state, progress_monitor): Self::Init,
+        (
+            con,
+            shared_state,
+            settings_state,
+            progress_monitor,
+            io_progress_monitor,
+            user_activity,
+        ): Self::Init,
         sender: ComponentSender<Self>,
     ) -> Self {
         settings_state.subscribe(sender.input_sender(), |settings| {
             BootstrapInput::SettingsUpdated(settings.clone())
         });
 
+        let scheduler = Scheduler::new(settings_state.clone(), user_activity);
+
         Self {
             shared_state,
             settings_state,
             progress_monitor,
+            io_progress_monitor,
+            scheduler,
             con,
             controllers: None,
             library_base_dir: None,
@@ -817,7 +1273,7 @@ impl Worker for Bootstrap {
                     if self
                         .controllers
                         .as_ref()
-                        .is_some_and(|controllers| controllers.is_running)
+                        .is_some_and(|controllers| controllers.is_running())
                     {
                         self.library_base_dir = None;
                         sender.input(BootstrapInput::Stop);
@@ -0,0 +1,120 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use anyhow::*;
+use fotema_core::events::{self, Moment};
+use fotema_core::visual;
+use relm4::Worker;
+use relm4::prelude::*;
+use std::result::Result::Ok;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{error, info};
+
+use crate::app::background::scheduler::{Scheduler, Stage};
+
+#[derive(Debug)]
+pub enum EventClusterTaskInput {
+    Start,
+}
+
+#[derive(Debug)]
+pub enum EventClusterTaskOutput {
+    // Finding events has started.
+    Started,
+
+    // Finding events has completed.
+    // usize is count of events and trips if they changed, otherwise zero.
+    Completed(usize),
+}
+
+pub struct EventClusterTask {
+    // Stop flag
+    stop: Arc<AtomicBool>,
+
+    scheduler: Scheduler,
+
+    visual_repo: visual::Repository,
+
+    events_repo: events::Repository,
+}
+
+impl EventClusterTask {
+    fn cluster(
+        stop: Arc<AtomicBool>,
+        visual_repo: visual::Repository,
+        mut events_repo: events::Repository,
+        sender: ComponentSender<Self>,
+    ) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let _ = sender.output(EventClusterTaskOutput::Started);
+
+        let moments: Vec<Moment> = visual_repo.all()?.iter().map(Moment::from).collect();
+
+        let clusters = events::cluster::cluster(moments);
+
+        if stop.load(Ordering::Relaxed) {
+            let _ = sender.output(EventClusterTaskOutput::Completed(0));
+            return Ok(());
+        }
+
+        let updated = events_repo.replace_all(&clusters)?;
+
+        info!(
+            "Found {} events and trips in {} seconds. Changed? {}",
+            clusters.len(),
+            start.elapsed().as_secs(),
+            updated,
+        );
+
+        let count = if updated { clusters.len() } else { 0 };
+        let _ = sender.output(EventClusterTaskOutput::Completed(count));
+
+        Ok(())
+    }
+}
+
+impl Worker for EventClusterTask {
+    type Init = (
+        Arc<AtomicBool>,
+        Scheduler,
+        visual::Repository,
+        events::Repository,
+    );
+    type Input = EventClusterTaskInput;
+    type Output = EventClusterTaskOutput;
+
+    fn init(
+        (stop, scheduler, visual_repo, events_repo): Self::Init,
+        _sender: ComponentSender<Self>,
+    ) -> Self {
+        EventClusterTask {
+            stop,
+            scheduler,
+            visual_repo,
+            events_repo,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        match msg {
+            EventClusterTaskInput::Start => {
+                info!("Finding events...");
+                let stop = self.stop.clone();
+                let visual_repo = self.visual_repo.clone();
+                let events_repo = self.events_repo.clone();
+
+                self.scheduler.spawn(Stage::Io, move || {
+                    if let Err(e) =
+                        EventClusterTask::cluster(stop, visual_repo, events_repo, sender.clone())
+                    {
+                        error!("Failed to find events: {}", e);
+                        let _ = sender.output(EventClusterTaskOutput::Completed(0));
+                    }
+                });
+            }
+        };
+    }
+}
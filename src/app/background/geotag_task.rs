@@ -0,0 +1,184 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use anyhow::*;
+use fotema_core::geotag::{self, Correlator, GeoSource};
+use fotema_core::places::GeoTarget;
+use h3o::LatLng;
+use relm4::Reducer;
+use relm4::Worker;
+use relm4::prelude::*;
+use std::path::PathBuf;
+use std::result::Result::Ok;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{error, info};
+
+use crate::app::background::scheduler::{Scheduler, Stage};
+use crate::app::components::progress_monitor::{ProgressMonitor, ProgressMonitorInput, TaskName};
+
+#[derive(Debug, Clone)]
+pub enum GeotagTaskInput {
+    /// Match pictures and videos without a location to points on a GPS track log.
+    Correlate(PathBuf, Correlator),
+
+    /// Set the location of pictures and videos to a pin dropped by the user.
+    Pin(Vec<GeoTarget>, LatLng),
+}
+
+#[derive(Debug)]
+pub enum GeotagTaskOutput {
+    // Geotagging has started.
+    Started,
+
+    // Geotagging has completed.
+    // usize is count of items given a location.
+    Completed(usize),
+}
+
+pub struct GeotagTask {
+    // Stop flag
+    stop: Arc<AtomicBool>,
+
+    scheduler: Scheduler,
+
+    repo: geotag::Repository,
+
+    progress_monitor: Arc<Reducer<ProgressMonitor>>,
+}
+
+impl GeotagTask {
+    fn correlate(
+        stop: Arc<AtomicBool>,
+        track_path: PathBuf,
+        correlator: Correlator,
+        mut repo: geotag::Repository,
+        progress_monitor: Arc<Reducer<ProgressMonitor>>,
+        sender: ComponentSender<Self>,
+    ) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let _ = sender.output(GeotagTaskOutput::Started);
+
+        let track = geotag::track::load(&track_path)?;
+        info!("Loaded {} track points from {:?}", track.len(), track_path);
+
+        let unlocated = repo.find_without_location()?;
+        let count = unlocated.len();
+        info!("Found {} items without a location", count);
+
+        if track.is_empty() || count == 0 {
+            let _ = sender.output(GeotagTaskOutput::Completed(0));
+            return Ok(());
+        }
+
+        progress_monitor.emit(ProgressMonitorInput::Start(TaskName::Geotag, count));
+
+        let locations: Vec<(GeoTarget, LatLng)> = unlocated
+            .into_iter()
+            .take_while(|_| !stop.load(Ordering::Relaxed))
+            .filter_map(|(target, ts)| {
+                progress_monitor.emit(ProgressMonitorInput::Advance);
+                correlator
+                    .locate(&track, ts)
+                    .map(|location| (target, location))
+            })
+            .collect();
+
+        let located = locations.len();
+
+        repo.add_locations(locations, GeoSource::Track)?;
+
+        info!(
+            "Matched {} of {} items to track log in {} seconds.",
+            located,
+            count,
+            start.elapsed().as_secs()
+        );
+
+        progress_monitor.emit(ProgressMonitorInput::Complete);
+
+        let _ = sender.output(GeotagTaskOutput::Completed(located));
+
+        Ok(())
+    }
+
+    fn pin(
+        targets: Vec<GeoTarget>,
+        location: LatLng,
+        mut repo: geotag::Repository,
+        sender: ComponentSender<Self>,
+    ) -> Result<()> {
+        let _ = sender.output(GeotagTaskOutput::Started);
+
+        let count = targets.len();
+        let locations = targets.into_iter().map(|t| (t, location)).collect();
+        repo.add_locations(locations, GeoSource::Manual)?;
+
+        info!("Pinned {} items to {}", count, location);
+
+        let _ = sender.output(GeotagTaskOutput::Completed(count));
+
+        Ok(())
+    }
+}
+
+impl Worker for GeotagTask {
+    type Init = (
+        Arc<AtomicBool>,
+        Scheduler,
+        geotag::Repository,
+        Arc<Reducer<ProgressMonitor>>,
+    );
+    type Input = GeotagTaskInput;
+    type Output = GeotagTaskOutput;
+
+    fn init(
+        (stop, scheduler, repo, progress_monitor): Self::Init,
+        _sender: ComponentSender<Self>,
+    ) -> Self {
+        GeotagTask {
+            stop,
+            scheduler,
+            repo,
+            progress_monitor,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        let repo = self.repo.clone();
+
+        match msg {
+            GeotagTaskInput::Correlate(track_path, correlator) => {
+                info!("Geotagging from track log {:?}...", track_path);
+                let stop = self.stop.clone();
+                let progress_monitor = self.progress_monitor.clone();
+
+                self.scheduler.spawn(Stage::Io, move || {
+                    if let Err(e) = GeotagTask::correlate(
+                        stop,
+                        track_path,
+                        correlator,
+                        repo,
+                        progress_monitor,
+                        sender.clone(),
+                    ) {
+                        error!("Failed to geotag from track log: {}", e);
+                        let _ = sender.output(GeotagTaskOutput::Completed(0));
+                    }
+                });
+            }
+            GeotagTaskInput::Pin(targets, location) => {
+                info!("Pinning {} items to a location...", targets.len());
+
+                self.scheduler.spawn(Stage::Io, move || {
+                    if let Err(e) = GeotagTask::pin(targets, location, repo, sender.clone()) {
+                        error!("Failed to pin location: {}", e);
+                        let _ = sender.output(GeotagTaskOutput::Completed(0));
+                    }
+                });
+            }
+        };
+    }
+}
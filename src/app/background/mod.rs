@@ -4,9 +4,15 @@
 
 pub mod bootstrap;
 
+pub mod event_cluster_task;
+
+pub mod geotag_task;
+
 pub mod library_scan_task;
 pub mod load_library_task;
 
+pub mod orientation_write_task;
+
 pub mod person_thumbnail_task;
 
 pub mod photo_clean_task;
@@ -16,6 +22,14 @@ pub mod photo_extract_motion_task;
 pub mod photo_recognize_faces_task;
 pub mod photo_thumbnail_task;
 
+pub mod place_geocode_task;
+
+pub mod scheduler;
+
+pub mod thumbnail_cache_task;
+pub mod thumbnail_priority_task;
+
+pub mod video_cache_task;
 pub mod video_clean_task;
 pub mod video_enrich_task;
 pub mod video_thumbnail_task;
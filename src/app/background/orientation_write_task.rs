@@ -0,0 +1,48 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use relm4::Worker;
+use relm4::prelude::*;
+use std::path::PathBuf;
+use tracing::{error, info};
+
+use fotema_core::orientation::{self, Orientation};
+
+#[derive(Debug)]
+pub enum OrientationWriteTaskInput {
+    /// Write an orientation chosen by the user to the EXIF of a picture file.
+    Write(PathBuf, Orientation),
+}
+
+#[derive(Debug)]
+pub enum OrientationWriteTaskOutput {
+    /// Writing an orientation to a picture file failed.
+    Failed(PathBuf),
+}
+
+/// Writes orientations to picture files. A worker handles one message at a time,
+/// so writes are never interleaved and the last orientation chosen is the one kept.
+pub struct OrientationWriteTask;
+
+impl Worker for OrientationWriteTask {
+    type Init = ();
+    type Input = OrientationWriteTaskInput;
+    type Output = OrientationWriteTaskOutput;
+
+    fn init(_: Self::Init, _sender: ComponentSender<Self>) -> Self {
+        Self
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        match msg {
+            OrientationWriteTaskInput::Write(path, orientation) => {
+                info!("Writing orientation {:?} to {:?}", orientation, path);
+                if let Err(e) = orientation::exif::write_orientation(&path, orientation) {
+                    error!("Failed writing orientation to file: {:#}", e);
+                    let _ = sender.output(OrientationWriteTaskOutput::Failed(path));
+                }
+            }
+        }
+    }
+}
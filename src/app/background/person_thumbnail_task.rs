@@ -17,6 +17,7 @@ use tracing::{error, info};
 
 use fotema_core::people::PersonThumbnailer;
 
+use crate::app::background::scheduler::{Scheduler, Stage};
 use crate::app::components::progress_monitor::{
     ProgressMonitor, ProgressMonitorInput, TaskName, ThumbnailType,
 };
@@ -39,6 +40,8 @@ pub struct PersonThumbnailTask {
     // Stop flag
     stop: Arc<AtomicBool>,
 
+    scheduler: Scheduler,
+
     thumbnailer: fotema_core::people::PersonThumbnailer,
 
     // FIXME use people repo
@@ -124,6 +127,7 @@ impl PersonThumbnailTask {
 impl Worker for PersonThumbnailTask {
     type Init = (
         Arc<AtomicBool>,
+        Scheduler,
         PersonThumbnailer,
         fotema_core::photo::Repository,
         Arc<Reducer<ProgressMonitor>>,
@@ -132,11 +136,12 @@ impl Worker for PersonThumbnailTask {
     type Output = PersonThumbnailTaskOutput;
 
     fn init(
-        (stop, thumbnailer, repo, progress_monitor): Self::Init,
+        (stop, scheduler, thumbnailer, repo, progress_monitor): Self::Init,
         _sender: ComponentSender<Self>,
     ) -> Self {
         PersonThumbnailTask {
             stop,
+            scheduler,
             thumbnailer,
             repo,
             progress_monitor,
@@ -153,7 +158,7 @@ impl Worker for PersonThumbnailTask {
                 let progress_monitor = self.progress_monitor.clone();
 
                 // Avoid runtime panic from calling block_on
-                rayon::spawn(move || {
+                self.scheduler.spawn(Stage::Cpu, move || {
                     if let Err(e) = PersonThumbnailTask::enrich(
                         stop,
                         repo,
@@ -23,6 +23,7 @@ use fotema_core::photo;
 use fotema_core::photo::PictureId;
 use fotema_core::thumbnailify::Thumbnailer;
 
+use crate::app::background::scheduler::{Scheduler, Stage};
 use crate::app::components::progress_monitor::{ProgressMonitor, ProgressMonitorInput, TaskName};
 use deadpool::managed;
 
@@ -76,6 +77,8 @@ pub struct PhotoDetectFacesTask {
     // Stop flag
     stop: Arc<AtomicBool>,
 
+    scheduler: Scheduler,
+
     /// Base directory for storing photo faces
     faces_base_dir: PathBuf,
     thumbnailer: Thumbnailer,
@@ -154,6 +157,8 @@ impl PhotoDetectFacesTask {
             .par_iter()
             .take_any_while(|_| !self.stop.load(Ordering::Relaxed))
             .for_each(|candidate| {
+                self.scheduler.wait_while_paused(&self.stop);
+
                 let mut repo = self.people_repo.clone();
 
                 // Careful! panic::catch_unwind returns Ok(Err) if the evaluated expression returns
@@ -165,12 +170,12 @@ impl PhotoDetectFacesTask {
                 })
                 .and_then(|faces| repo.clone().add_face_scans(&candidate.picture_id, &faces));
 
-                if result.is_err() {
+                if let Err(e) = result {
                     error!(
                         "Failed detecting faces: Photo path: {:?}. Error: {:?}",
-                        candidate.path.sandbox_path, result
+                        candidate.path.sandbox_path, e
                     );
-                    let _ = repo.mark_face_scan_broken(&candidate.picture_id);
+                    let _ = repo.mark_face_scan_broken(&candidate.picture_id, &format!("{:#}", e));
                 }
 
                 self.progress_monitor.emit(ProgressMonitorInput::Advance);
@@ -193,6 +198,7 @@ impl PhotoDetectFacesTask {
 impl Worker for PhotoDetectFacesTask {
     type Init = (
         Arc<AtomicBool>,
+        Scheduler,
         PathBuf,
         Thumbnailer,
         photo::Repository,
@@ -203,11 +209,12 @@ impl Worker for PhotoDetectFacesTask {
     type Output = PhotoDetectFacesTaskOutput;
 
     fn init(
-        (stop, faces_base_dir, thumbnailer, photo_repo, people_repo, progress_monitor): Self::Init,
+        (stop, scheduler, faces_base_dir, thumbnailer, photo_repo, people_repo, progress_monitor): Self::Init,
         _sender: ComponentSender<Self>,
     ) -> Self {
         PhotoDetectFacesTask {
             stop,
+            scheduler,
             faces_base_dir,
             thumbnailer,
             photo_repo,
@@ -223,7 +230,7 @@ impl Worker for PhotoDetectFacesTask {
                 let this = self.clone();
 
                 // Avoid runtime panic from calling block_on
-                rayon::spawn(move || {
+                self.scheduler.spawn(Stage::Cpu, move || {
                     if let Err(e) = this.detect_for_all(sender) {
                         error!("Failed to extract photo faces: {}", e);
                     }
@@ -235,7 +242,7 @@ impl Worker for PhotoDetectFacesTask {
                 let this = self.clone();
 
                 // Avoid runtime panic from calling block_on
-                rayon::spawn(move || {
+                self.scheduler.spawn(Stage::Cpu, move || {
                     if let Err(e) = this.detect_for_one(sender.clone(), picture_id) {
                         error!("Failed to extract photo faces: {}", e);
                         let _ = sender.output(PhotoDetectFacesTaskOutput::Completed);
@@ -13,6 +13,8 @@ use std::sync::atomic::{AtomicBool, Ordering};
 
 use tracing::{error, info};
 
+use crate::app::background::scheduler::{Scheduler, Stage};
+
 #[derive(Debug)]
 pub enum PhotoEnrichTaskInput {
     Start,
@@ -31,6 +33,8 @@ pub struct PhotoEnrichTask {
     // Stop flag
     stop: Arc<AtomicBool>,
 
+    scheduler: Scheduler,
+
     // Danger! Don't hold the repo mutex for too long as it blocks viewing images.
     repo: fotema_core::photo::Repository,
 }
@@ -38,6 +42,7 @@ pub struct PhotoEnrichTask {
 impl PhotoEnrichTask {
     fn enrich(
         stop: Arc<AtomicBool>,
+        scheduler: Scheduler,
         mut repo: fotema_core::photo::Repository,
         sender: &ComponentSender<PhotoEnrichTask>,
     ) -> Result<()> {
@@ -61,6 +66,8 @@ impl PhotoEnrichTask {
             .par_iter()
             .take_any_while(|_| !stop.load(Ordering::Relaxed))
             .flat_map(|pic| {
+                scheduler.wait_while_paused(&stop);
+
                 let result = metadata::from_path(&pic.sandbox_path());
                 result.map(|m| (pic.picture_id, m))
             })
@@ -83,12 +90,16 @@ impl PhotoEnrichTask {
 }
 
 impl Worker for PhotoEnrichTask {
-    type Init = (Arc<AtomicBool>, fotema_core::photo::Repository);
+    type Init = (Arc<AtomicBool>, Scheduler, fotema_core::photo::Repository);
     type Input = PhotoEnrichTaskInput;
     type Output = PhotoEnrichTaskOutput;
 
-    fn init((stop, repo): Self::Init, _sender: ComponentSender<Self>) -> Self {
-        PhotoEnrichTask { stop, repo }
+    fn init((stop, scheduler, repo): Self::Init, _sender: ComponentSender<Self>) -> Self {
+        PhotoEnrichTask {
+            stop,
+            scheduler,
+            repo,
+        }
     }
 
     fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
@@ -97,10 +108,11 @@ impl Worker for PhotoEnrichTask {
                 info!("Enriching photos...");
                 let repo = self.repo.clone();
                 let stop = self.stop.clone();
+                let scheduler = self.scheduler.clone();
 
                 // Avoid runtime panic from calling block_on
-                rayon::spawn(move || {
-                    if let Err(e) = PhotoEnrichTask::enrich(stop, repo, &sender) {
+                self.scheduler.spawn(Stage::Io, move || {
+                    if let Err(e) = PhotoEnrichTask::enrich(stop, scheduler, repo, &sender) {
                         error!("Failed to update previews: {}", e);
                     }
                 });
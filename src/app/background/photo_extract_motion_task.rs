@@ -12,11 +12,16 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use tracing::{error, info};
 
+use fotema_core::problems::ProblemCategory;
+use fotema_core::video::TranscodeProfile;
+
+use crate::app::background::scheduler::{Scheduler, Stage};
 use crate::app::components::progress_monitor::{ProgressMonitor, ProgressMonitorInput, TaskName};
 
 #[derive(Debug)]
 pub enum PhotoExtractMotionTaskInput {
-    Start,
+    /// Extract motion photo videos and transcode HEVC videos with a profile.
+    Start(TranscodeProfile),
 }
 
 #[derive(Debug)]
@@ -32,6 +37,8 @@ pub struct PhotoExtractMotionTask {
     // Stop flag
     stop: Arc<AtomicBool>,
 
+    scheduler: Scheduler,
+
     extractor: fotema_core::photo::MotionPhotoExtractor,
 
     // Danger! Don't hold the repo mutex for too long as it blocks viewing images.
@@ -43,8 +50,10 @@ pub struct PhotoExtractMotionTask {
 impl PhotoExtractMotionTask {
     fn extract(
         stop: Arc<AtomicBool>,
+        scheduler: Scheduler,
         repo: fotema_core::photo::Repository,
         extractor: fotema_core::photo::MotionPhotoExtractor,
+        profile: TranscodeProfile,
         progress_monitor: Arc<Reducer<ProgressMonitor>>,
         sender: ComponentSender<Self>,
     ) -> Result<()> {
@@ -73,14 +82,14 @@ impl PhotoExtractMotionTask {
 
         progress_monitor.emit(ProgressMonitorInput::Start(TaskName::MotionPhoto, count));
 
-        // One thread per CPU core... makes my laptop sluggish and hot... also likes memory.
-        // Might need to consider constraining number of CPUs to use less memory or to
-        // keep the computer more response while thumbnail generation is going on.
+        // Runs on the CPU pool, so the number of threads is limited by the thread budget.
         unprocessed
             .par_iter()
             .take_any_while(|_| !stop.load(Ordering::Relaxed))
             .for_each(|photo| {
-                let result = extractor.extract(&photo.picture_id, photo.sandbox_path());
+                scheduler.wait_while_paused(&stop);
+
+                let result = extractor.extract(&photo.picture_id, photo.sandbox_path(), profile);
 
                 let result = match result {
                     Ok(opt_video) => repo
@@ -91,7 +100,11 @@ impl PhotoExtractMotionTask {
                             "Failed extracting motion photo: {:?}: Photo path: {:?}",
                             e, photo.path
                         );
-                        repo.clone().mark_broken(&photo.picture_id)
+                        repo.clone().mark_broken(
+                            &photo.picture_id,
+                            ProblemCategory::MotionPhoto,
+                            &format!("{:#}", e),
+                        )
                     }
                 };
 
@@ -122,6 +135,7 @@ impl PhotoExtractMotionTask {
 impl Worker for PhotoExtractMotionTask {
     type Init = (
         Arc<AtomicBool>,
+        Scheduler,
         fotema_core::photo::MotionPhotoExtractor,
         fotema_core::photo::Repository,
         Arc<Reducer<ProgressMonitor>>,
@@ -130,11 +144,12 @@ impl Worker for PhotoExtractMotionTask {
     type Output = PhotoExtractMotionTaskOutput;
 
     fn init(
-        (stop, extractor, repo, progress_monitor): Self::Init,
+        (stop, scheduler, extractor, repo, progress_monitor): Self::Init,
         _sender: ComponentSender<Self>,
     ) -> Self {
         PhotoExtractMotionTask {
             stop,
+            scheduler,
             extractor,
             repo,
             progress_monitor,
@@ -143,18 +158,21 @@ impl Worker for PhotoExtractMotionTask {
 
     fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
         match msg {
-            PhotoExtractMotionTaskInput::Start => {
+            PhotoExtractMotionTaskInput::Start(profile) => {
                 info!("Extracting motion photos...");
                 let stop = self.stop.clone();
+                let scheduler = self.scheduler.clone();
                 let repo = self.repo.clone();
                 let extractor = self.extractor.clone();
                 let progress_monitor = self.progress_monitor.clone();
 
-                rayon::spawn(move || {
+                self.scheduler.spawn(Stage::Cpu, move || {
                     if let Err(e) = PhotoExtractMotionTask::extract(
                         stop,
+                        scheduler,
                         repo,
                         extractor,
+                        profile,
                         progress_monitor,
                         sender,
                     ) {
@@ -17,6 +17,7 @@ use fotema_core::machine_learning::face_recognizer::FaceRecognizer;
 use fotema_core::people;
 use fotema_core::people::model::{DetectedFace, PersonForRecognition};
 
+use crate::app::background::scheduler::{Scheduler, Stage};
 use crate::app::components::progress_monitor::{ProgressMonitor, ProgressMonitorInput, TaskName};
 
 #[derive(Debug)]
@@ -38,6 +39,8 @@ pub struct PhotoRecognizeFacesTask {
     // Stop flag
     stop: Arc<AtomicBool>,
 
+    scheduler: Scheduler,
+
     // Danger! Don't hold the repo mutex for too long as it blocks viewing images.
     repo: people::Repository,
 
@@ -140,6 +143,7 @@ impl PhotoRecognizeFacesTask {
 impl Worker for PhotoRecognizeFacesTask {
     type Init = (
         Arc<AtomicBool>,
+        Scheduler,
         PathBuf,
         people::Repository,
         Arc<Reducer<ProgressMonitor>>,
@@ -148,11 +152,12 @@ impl Worker for PhotoRecognizeFacesTask {
     type Output = PhotoRecognizeFacesTaskOutput;
 
     fn init(
-        (stop, cache_dir, repo, progress_monitor): Self::Init,
+        (stop, scheduler, cache_dir, repo, progress_monitor): Self::Init,
         _sender: ComponentSender<Self>,
     ) -> Self {
         PhotoRecognizeFacesTask {
             stop,
+            scheduler,
             cache_dir,
             repo,
             progress_monitor,
@@ -166,7 +171,7 @@ impl Worker for PhotoRecognizeFacesTask {
                 let this = self.clone();
 
                 // Avoid runtime panic from calling block_on
-                rayon::spawn(move || {
+                self.scheduler.spawn(Stage::Cpu, move || {
                     if let Err(e) = this.recognize(sender.clone()) {
                         error!("Failed to recognize photo faces: {}", e);
                         let _ = sender.output(PhotoRecognizeFacesTaskOutput::Completed);
@@ -8,7 +8,7 @@ use rayon::prelude::*;
 use relm4::Reducer;
 use relm4::Worker;
 use relm4::prelude::*;
-use std::path::{Path, PathBuf};
+use std::collections::HashMap;
 use std::result::Result::Ok;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -16,17 +16,28 @@ use tracing::{error, info};
 
 use std::panic;
 
+use fotema_core::jobs::{self, JobKind, Priority};
+use fotema_core::photo::model::Picture;
 use fotema_core::photo::thumbnailer::PhotoThumbnailer;
-use fotema_core::thumbnailify;
-use fotema_core::thumbnailify::ThumbnailSize;
+use fotema_core::problems::ProblemCategory;
+use fotema_core::thumbnailify::{ThumbnailSize, Thumbnailer};
 
+use crate::app::background::scheduler::{Scheduler, Stage};
 use crate::app::components::progress_monitor::{
     ProgressMonitor, ProgressMonitorInput, TaskName, ThumbnailType,
 };
 
-#[derive(Debug)]
+/// Number of jobs to claim at a time.
+const BATCH_SIZE: usize = 64;
+
+#[derive(Debug, Clone)]
 pub enum PhotoThumbnailTaskInput {
+    /// Generate thumbnails for queued jobs.
     Start,
+
+    /// Queue jobs for all pictures missing a thumbnail, then generate them.
+    /// For when thumbnails have changed without knowing which pictures are affected.
+    Sweep,
 }
 
 #[derive(Debug)]
@@ -42,45 +53,53 @@ pub struct PhotoThumbnailTask {
     // Stop flag
     stop: Arc<AtomicBool>,
 
-    thumbnails_path: PathBuf,
+    scheduler: Scheduler,
+
+    thumbnail_cache: Thumbnailer,
     thumbnailer: fotema_core::photo::PhotoThumbnailer,
 
     // Danger! Don't hold the repo mutex for too long as it blocks viewing images.
     repo: fotema_core::photo::Repository,
 
+    jobs_repo: jobs::Repository,
+
     progress_monitor: Arc<Reducer<ProgressMonitor>>,
 }
 
 impl PhotoThumbnailTask {
     fn enrich(
         stop: Arc<AtomicBool>,
+        scheduler: Scheduler,
         repo: fotema_core::photo::Repository,
-        thumbnails_path: &Path,
+        mut jobs_repo: jobs::Repository,
+        thumbnail_cache: &Thumbnailer,
         thumbnailer: PhotoThumbnailer,
         progress_monitor: Arc<Reducer<ProgressMonitor>>,
         sender: ComponentSender<Self>,
+        sweep: bool,
     ) -> Result<()> {
         let start = std::time::Instant::now();
 
-        let mut unprocessed: Vec<fotema_core::photo::model::Picture> = repo
-            .all()?
-            .into_iter()
-            .filter(|pic| pic.path.exists())
-            .filter(|pic| {
-                let thumb_hash = pic.thumbnail_hash();
-                let large_path = thumbnailify::get_thumbnail_hash_output(
-                    thumbnails_path,
-                    &thumb_hash,
-                    ThumbnailSize::XLarge,
-                );
-                !large_path.exists()
-            })
-            .collect();
+        // Jobs left running when Fotema last quit must be resumed.
+        let recovered = jobs_repo.recover(JobKind::PhotoThumbnail)?;
+        if recovered > 0 {
+            info!("Resuming {} interrupted photo thumbnail jobs", recovered);
+        }
+
+        if sweep {
+            // should be ascending time order from database, so reverse to queue newest items first
+            let unprocessed: Vec<i64> = repo
+                .all()?
+                .into_iter()
+                .rev()
+                .filter(|pic| pic.path.exists() && !Self::has_thumbnail(thumbnail_cache, pic))
+                .map(|pic| pic.picture_id.id())
+                .collect();
 
-        // should be ascending time order from database, so reverse to process newest items first
-        unprocessed.reverse();
+            jobs_repo.enqueue(JobKind::PhotoThumbnail, &unprocessed, Priority::Normal)?;
+        }
 
-        let count = unprocessed.len();
+        let count = jobs_repo.count_ready(JobKind::PhotoThumbnail)?;
         info!("Found {} photos to generate thumbnails for", count);
 
         // Short-circuit before sending progress messages to stop
@@ -92,43 +111,93 @@ impl PhotoThumbnailTask {
 
         let _ = sender.output(PhotoThumbnailTaskOutput::Started);
 
+        let pictures: HashMap<i64, Picture> = repo
+            .all()?
+            .into_iter()
+            .map(|pic| (pic.picture_id.id(), pic))
+            .collect();
+
         progress_monitor.emit(ProgressMonitorInput::Start(
             TaskName::Thumbnail(ThumbnailType::Photo),
             count,
         ));
 
-        // One thread per CPU core... makes my laptop sluggish and hot... also likes memory.
-        // Might need to consider constraining number of CPUs to use less memory or to
-        // keep the computer more response while thumbnail generation is going on.
-        unprocessed
-            .par_iter()
-            .take_any_while(|_| !stop.load(Ordering::Relaxed))
-            .for_each(|pic| {
-                // Careful! panic::catch_unwind returns Ok(Err) if the evaluated expression returns
-                // an error but doesn't panic.
-                let result = panic::catch_unwind(|| {
-                    block_on(async { thumbnailer.thumbnail(&pic.path).await })
-                });
+        // Claim jobs in batches so items that become visible while we work
+        // are picked up by the next batch.
+        let mut count = 0;
+        while !stop.load(Ordering::Relaxed) {
+            let batch = jobs_repo.claim(JobKind::PhotoThumbnail, BATCH_SIZE)?;
+            if batch.is_empty() {
+                break;
+            }
+
+            count += batch.len();
+
+            // Runs on the CPU pool, so the number of threads is limited by the thread budget.
+            batch
+                .par_iter()
+                .take_any_while(|_| !stop.load(Ordering::Relaxed))
+                .for_each(|job| {
+                    scheduler.wait_while_paused(&stop);
+
+                    let mut jobs_repo = jobs_repo.clone();
+
+                    // Picture removed from library, or thumbnail generated elsewhere.
+                    let Some(pic) = pictures.get(&job.target_id).filter(|pic| {
+                        pic.path.exists() && !Self::has_thumbnail(thumbnail_cache, pic)
+                    }) else {
+                        let _ = jobs_repo.complete(job.job_id);
+                        progress_monitor.emit(ProgressMonitorInput::Advance);
+                        return;
+                    };
+
+                    // Careful! panic::catch_unwind returns Ok(Err) if the evaluated expression returns
+                    // an error but doesn't panic.
+                    let result = panic::catch_unwind(|| {
+                        block_on(async {
+                            thumbnailer
+                                .thumbnail(&pic.path, pic.orientation_override, pic.edits.as_ref())
+                                .await
+                        })
+                    });
 
-                // If we got an err, then there was a panic.
-                // If we got Ok(Err(e)) there wasn't a panic, but we still failed.
-                if let Ok(Err(e)) = result {
-                    error!(
-                        "Failed generate or add thumbnail: {:?}: Photo path: {:?}",
-                        e.root_cause(),
-                        pic.path
-                    );
-                    let _ = repo.clone().mark_broken(&pic.picture_id);
-                } else if result.is_err() {
-                    error!(
-                        "Panicked generate or add thumbnail: Photo path: {:?}",
-                        pic.path
-                    );
-                    let _ = repo.clone().mark_broken(&pic.picture_id);
-                }
-
-                progress_monitor.emit(ProgressMonitorInput::Advance);
-            });
+                    // If we got an err, then there was a panic.
+                    // If we got Ok(Err(e)) there wasn't a panic, but we still failed.
+                    let error = match result {
+                        Ok(Ok(_)) => None,
+                        Ok(Err(e)) => {
+                            error!(
+                                "Failed generate or add thumbnail: {:?}: Photo path: {:?}",
+                                e.root_cause(),
+                                pic.path
+                            );
+                            Some(format!("{:#}", e))
+                        }
+                        Err(_) => {
+                            error!(
+                                "Panicked generate or add thumbnail: Photo path: {:?}",
+                                pic.path
+                            );
+                            Some(String::from("Panicked generating thumbnail"))
+                        }
+                    };
+
+                    if let Some(error) = error {
+                        // Only give up on the picture once it has run out of retries.
+                        if let Ok(true) = jobs_repo.fail(job, &error) {
+                            let _ = repo.clone().mark_broken(
+                                &pic.picture_id,
+                                ProblemCategory::Thumbnail,
+                                &error,
+                            );
+                        }
+                    } else {
+                        let _ = jobs_repo.complete(job.job_id);
+                    }
+
+                    progress_monitor.emit(ProgressMonitorInput::Advance);
+                });
+        }
 
         info!(
             "Generated {} photo thumbnails in {} seconds.",
@@ -142,51 +211,68 @@ impl PhotoThumbnailTask {
 
         Ok(())
     }
+
+    fn has_thumbnail(thumbnail_cache: &Thumbnailer, pic: &Picture) -> bool {
+        let thumb_hash = pic.thumbnail_hash();
+        let large_path =
+            thumbnail_cache.get_thumbnail_hash_output(&thumb_hash, ThumbnailSize::XLarge);
+        large_path.exists()
+    }
 }
 
 impl Worker for PhotoThumbnailTask {
     type Init = (
         Arc<AtomicBool>,
-        PathBuf,
+        Scheduler,
+        Thumbnailer,
         PhotoThumbnailer,
         fotema_core::photo::Repository,
+        jobs::Repository,
         Arc<Reducer<ProgressMonitor>>,
     );
     type Input = PhotoThumbnailTaskInput;
     type Output = PhotoThumbnailTaskOutput;
 
     fn init(
-        (stop, thumbnails_path, thumbnailer, repo, progress_monitor): Self::Init,
+        (stop, scheduler, thumbnail_cache, thumbnailer, repo, jobs_repo, progress_monitor): Self::Init,
         _sender: ComponentSender<Self>,
     ) -> Self {
         PhotoThumbnailTask {
             stop,
-            thumbnails_path: thumbnails_path.into(),
+            scheduler,
+            thumbnail_cache,
             thumbnailer,
             repo,
+            jobs_repo,
             progress_monitor,
         }
     }
 
     fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
         match msg {
-            PhotoThumbnailTaskInput::Start => {
+            PhotoThumbnailTaskInput::Start | PhotoThumbnailTaskInput::Sweep => {
                 info!("Generating photo thumbnails...");
+                let sweep = matches!(msg, PhotoThumbnailTaskInput::Sweep);
                 let stop = self.stop.clone();
+                let scheduler = self.scheduler.clone();
                 let repo = self.repo.clone();
-                let thumbnails_path = self.thumbnails_path.clone();
+                let jobs_repo = self.jobs_repo.clone();
+                let thumbnail_cache = self.thumbnail_cache.clone();
                 let thumbnailer = self.thumbnailer.clone();
                 let progress_monitor = self.progress_monitor.clone();
 
                 // Avoid runtime panic from calling block_on
-                rayon::spawn(move || {
+                self.scheduler.spawn(Stage::Cpu, move || {
                     if let Err(e) = PhotoThumbnailTask::enrich(
                         stop,
+                        scheduler,
                         repo,
-                        &thumbnails_path,
+                        jobs_repo,
+                        &thumbnail_cache,
                         thumbnailer,
                         progress_monitor,
                         sender,
+                        sweep,
                     ) {
                         error!("Failed to update previews: {}", e);
                     }
@@ -0,0 +1,142 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use anyhow::*;
+use fotema_core::places::Gazetteer;
+use rayon::prelude::*;
+use relm4::Reducer;
+use relm4::Worker;
+use relm4::prelude::*;
+use std::path::Path;
+use std::result::Result::Ok;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{error, info};
+
+use crate::app::background::scheduler::{Scheduler, Stage};
+use crate::app::components::progress_monitor::{ProgressMonitor, ProgressMonitorInput, TaskName};
+use crate::config::PKGDATADIR;
+
+#[derive(Debug)]
+pub enum PlaceGeocodeTaskInput {
+    Start,
+}
+
+#[derive(Debug)]
+pub enum PlaceGeocodeTaskOutput {
+    // Reverse geocoding has started.
+    Started,
+
+    // Reverse geocoding has completed
+    Completed(usize),
+}
+
+pub struct PlaceGeocodeTask {
+    // Stop flag
+    stop: Arc<AtomicBool>,
+
+    scheduler: Scheduler,
+
+    repo: fotema_core::places::Repository,
+
+    progress_monitor: Arc<Reducer<ProgressMonitor>>,
+}
+
+impl PlaceGeocodeTask {
+    fn geocode(
+        stop: Arc<AtomicBool>,
+        mut repo: fotema_core::places::Repository,
+        progress_monitor: Arc<Reducer<ProgressMonitor>>,
+        sender: ComponentSender<Self>,
+    ) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let unprocessed = repo.find_need_geocode()?;
+
+        let count = unprocessed.len();
+        info!("Found {} locations as candidates for geocoding", count);
+
+        // Short-circuit before sending progress messages to stop
+        // banner from appearing and disappearing.
+        if count == 0 {
+            let _ = sender.output(PlaceGeocodeTaskOutput::Completed(count));
+            return Ok(());
+        }
+
+        let _ = sender.output(PlaceGeocodeTaskOutput::Started);
+
+        // Loading the gazetteer takes a moment, so only do it when there is
+        // something to geocode.
+        let gazetteer = Gazetteer::load(&Path::new(PKGDATADIR).join("geonames"))?;
+
+        progress_monitor.emit(ProgressMonitorInput::Start(TaskName::Geocode, count));
+
+        let places = unprocessed
+            .par_iter()
+            .take_any_while(|_| !stop.load(Ordering::Relaxed))
+            .map(|(target, location)| {
+                let place = gazetteer.lookup(*location);
+                progress_monitor.emit(ProgressMonitorInput::Advance);
+                (*target, place)
+            })
+            .collect();
+
+        repo.add_places(places)?;
+
+        info!(
+            "Geocoded {} locations in {} seconds.",
+            count,
+            start.elapsed().as_secs()
+        );
+
+        progress_monitor.emit(ProgressMonitorInput::Complete);
+
+        let _ = sender.output(PlaceGeocodeTaskOutput::Completed(count));
+
+        Ok(())
+    }
+}
+
+impl Worker for PlaceGeocodeTask {
+    type Init = (
+        Arc<AtomicBool>,
+        Scheduler,
+        fotema_core::places::Repository,
+        Arc<Reducer<ProgressMonitor>>,
+    );
+    type Input = PlaceGeocodeTaskInput;
+    type Output = PlaceGeocodeTaskOutput;
+
+    fn init(
+        (stop, scheduler, repo, progress_monitor): Self::Init,
+        _sender: ComponentSender<Self>,
+    ) -> Self {
+        PlaceGeocodeTask {
+            stop,
+            scheduler,
+            repo,
+            progress_monitor,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        match msg {
+            PlaceGeocodeTaskInput::Start => {
+                info!("Geocoding places...");
+                let stop = self.stop.clone();
+                let repo = self.repo.clone();
+                let progress_monitor = self.progress_monitor.clone();
+
+                self.scheduler.spawn(Stage::Io, move || {
+                    if let Err(e) =
+                        PlaceGeocodeTask::geocode(stop, repo, progress_monitor, sender.clone())
+                    {
+                        error!("Failed to geocode places: {}", e);
+                        let _ = sender.output(PlaceGeocodeTaskOutput::Completed(0));
+                    }
+                });
+            }
+        };
+    }
+}
@@ -0,0 +1,340 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{error, info};
+
+use crate::app::SettingsState;
+
+/// Scanning and enriching spend most of their time waiting for the disk, so don't
+/// benefit from many threads.
+const MAX_IO_THREADS: usize = 4;
+
+/// How long after the last key press or pointer movement the user is considered active.
+const ACTIVE_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// How often to check if paused work can resume.
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long to trust a power supply reading.
+const BATTERY_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+/// Kind of work a background task does, which determines the threads it runs on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Mostly waits on the disk or network, such as scanning, enriching and geocoding.
+    Io,
+
+    /// Mostly computes, such as generating thumbnails and detecting faces.
+    Cpu,
+}
+
+/// When the user last interacted with Fotema.
+/// Recorded by the main window and consulted by background tasks.
+#[derive(Debug, Clone, Default)]
+pub struct UserActivity(Arc<AtomicU64>);
+
+impl UserActivity {
+    pub fn record(&self) {
+        self.0.store(now_millis(), Ordering::Relaxed);
+    }
+
+    fn idle_for(&self) -> Duration {
+        let last = self.0.load(Ordering::Relaxed);
+        Duration::from_millis(now_millis().saturating_sub(last))
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+struct Pools {
+    /// Thread budget the pools were built for.
+    threads: usize,
+    io: Arc<ThreadPool>,
+    cpu: Arc<ThreadPool>,
+}
+
+impl Pools {
+    fn build(threads: usize) -> Option<Pools> {
+        let (io_threads, cpu_threads) = split_budget(threads);
+
+        let io = ThreadPoolBuilder::new()
+            .num_threads(io_threads)
+            .thread_name(|i| format!("fotema-io-{}", i))
+            .build()
+            .map(Arc::new);
+
+        // Without threads of its own, CPU work shares the I/O pool.
+        let cpu = match io {
+            Ok(ref io) if cpu_threads == 0 => Ok(io.clone()),
+            _ => ThreadPoolBuilder::new()
+                .num_threads(cpu_threads)
+                .thread_name(|i| format!("fotema-cpu-{}", i))
+                .build()
+                .map(Arc::new),
+        };
+
+        match (io, cpu) {
+            (Ok(io), Ok(cpu)) => Some(Pools { threads, io, cpu }),
+            (Err(e), _) | (_, Err(e)) => {
+                error!("Failed building thread pools: {}", e);
+                None
+            }
+        }
+    }
+}
+
+/// Runs background tasks on thread pools sized to the user's thread budget,
+/// and pauses them according to the user's power and activity settings.
+#[derive(Clone)]
+pub struct Scheduler {
+    settings_state: SettingsState,
+    user_activity: UserActivity,
+    pools: Arc<Mutex<Option<Pools>>>,
+
+    /// Last time the power supply was checked, and whether running on battery.
+    battery: Arc<Mutex<Option<(Instant, bool)>>>,
+}
+
+impl std::fmt::Debug for Scheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Scheduler").finish_non_exhaustive()
+    }
+}
+
+impl Scheduler {
+    pub fn new(settings_state: SettingsState, user_activity: UserActivity) -> Self {
+        Self {
+            settings_state,
+            user_activity,
+            pools: Arc::new(Mutex::new(None)),
+            battery: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Run a task on the thread pool for a stage. Parallel iterators used by the
+    /// task will stay within the same pool.
+    pub fn spawn<F>(&self, stage: Stage, task: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        match self.pool(stage) {
+            Some(pool) => pool.spawn(task),
+            None => rayon::spawn(task),
+        }
+    }
+
+    /// Run a task on the thread pool for a stage, blocking until it completes.
+    pub fn install<F, R>(&self, stage: Stage, task: F) -> R
+    where
+        F: FnOnce() -> R + Send,
+        R: Send,
+    {
+        match self.pool(stage) {
+            Some(pool) => pool.install(task),
+            None => task(),
+        }
+    }
+
+    /// Can an I/O task run at the same time as a CPU task? Only if the thread budget
+    /// can give each of them a thread.
+    pub fn allows_overlap(&self) -> bool {
+        thread_budget(self.settings_state.read().background_threads) > 1
+    }
+
+    /// Block while background work should be paused, or until stopped.
+    /// Call between items so work can pause part way through a task.
+    pub fn wait_while_paused(&self, stop: &AtomicBool) {
+        let mut is_paused = false;
+        while !stop.load(Ordering::Relaxed) {
+            let Some(reason) = self.pause_reason() else {
+                break;
+            };
+            if !is_paused {
+                info!("Pausing background work: {}", reason);
+                is_paused = true;
+            }
+            thread::sleep(PAUSE_POLL_INTERVAL);
+        }
+
+        if is_paused {
+            info!("Resuming background work");
+        }
+    }
+
+    fn pause_reason(&self) -> Option<&'static str> {
+        let (pause_on_battery, pause_while_active) = {
+            let settings = self.settings_state.read();
+            (settings.pause_on_battery, settings.pause_while_active)
+        };
+
+        if pause_while_active && self.user_activity.idle_for() < ACTIVE_GRACE_PERIOD {
+            Some("user is active")
+        } else if pause_on_battery && self.is_on_battery() {
+            Some("running on battery")
+        } else {
+            None
+        }
+    }
+
+    fn is_on_battery(&self) -> bool {
+        let mut battery = self.battery.lock().unwrap();
+        match *battery {
+            Some((checked_at, on_battery)) if checked_at.elapsed() < BATTERY_CHECK_INTERVAL => {
+                on_battery
+            }
+            _ => {
+                let on_battery = read_on_battery(Path::new(POWER_SUPPLY_DIR));
+                *battery = Some((Instant::now(), on_battery));
+                on_battery
+            }
+        }
+    }
+
+    /// Pools are rebuilt when the thread budget changes. Tasks already running
+    /// finish on the old pools.
+    fn pool(&self, stage: Stage) -> Option<Arc<ThreadPool>> {
+        let threads = thread_budget(self.settings_state.read().background_threads);
+
+        let mut pools = self.pools.lock().unwrap();
+        if pools.as_ref().is_none_or(|p| p.threads != threads) {
+            info!("Using {} background threads", threads);
+            *pools = Pools::build(threads);
+        }
+
+        pools.as_ref().map(|p| match stage {
+            Stage::Io => p.io.clone(),
+            Stage::Cpu => p.cpu.clone(),
+        })
+    }
+}
+
+/// Number of threads to use for background work. Zero means leave one CPU core free
+/// for the user interface.
+pub fn thread_budget(setting: u32) -> usize {
+    let cores = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+
+    budget_for_cores(setting, cores)
+}
+
+fn budget_for_cores(setting: u32, cores: usize) -> usize {
+    if setting == 0 {
+        cores.saturating_sub(1).max(1)
+    } else {
+        (setting as usize).min(cores)
+    }
+}
+
+/// Share of the thread budget for the I/O and CPU pools, so an I/O task and a CPU task
+/// running at the same time stay within the budget. With a budget of one thread, the
+/// CPU pool gets no threads and CPU work shares the I/O pool.
+fn split_budget(threads: usize) -> (usize, usize) {
+    let io = (threads / 2).clamp(1, MAX_IO_THREADS);
+    let cpu = threads.saturating_sub(io);
+    (io, cpu)
+}
+
+/// Running on battery if there is a system battery and no mains or USB power is connected.
+fn read_on_battery(power_supply_dir: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(power_supply_dir) else {
+        return false;
+    };
+
+    let read = |path: &Path, name: &str| {
+        fs::read_to_string(path.join(name))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let mut has_battery = false;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match read(&path, "type").as_str() {
+            "Mains" | "USB" if read(&path, "online") == "1" => return false,
+            // Peripherals, such as mice, also report batteries.
+            "Battery" if read(&path, "scope") != "Device" => has_battery = true,
+            _ => {}
+        }
+    }
+
+    has_battery
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Writes a fake power supply class directory. Each supply has attributes, such as
+    /// its type, as files.
+    fn power_supplies(supplies: &[(&str, &[(&str, &str)])]) -> TempDir {
+        let dir = tempfile::tempdir().unwrap();
+
+        for (supply, attributes) in supplies {
+            let supply_dir = dir.path().join(supply);
+            fs::create_dir_all(&supply_dir).unwrap();
+            for (attribute, value) in *attributes {
+                fs::write(supply_dir.join(attribute), format!("{}\n", value)).unwrap();
+            }
+        }
+
+        dir
+    }
+
+    #[test]
+    fn budget_leaves_a_core_free_by_default() {
+        assert_eq!(budget_for_cores(0, 8), 7);
+        assert_eq!(budget_for_cores(0, 1), 1);
+        assert_eq!(budget_for_cores(4, 8), 4);
+        assert_eq!(budget_for_cores(16, 8), 8);
+    }
+
+    #[test]
+    fn split_budget_between_pools() {
+        assert_eq!(split_budget(1), (1, 0));
+        assert_eq!(split_budget(2), (1, 1));
+        assert_eq!(split_budget(3), (1, 2));
+        assert_eq!(split_budget(8), (4, 4));
+        assert_eq!(split_budget(16), (MAX_IO_THREADS, 16 - MAX_IO_THREADS));
+    }
+
+    #[test]
+    fn on_battery_only_with_a_system_battery_and_no_external_power() {
+        let battery = power_supplies(&[
+            ("BAT0", &[("type", "Battery"), ("scope", "System")]),
+            ("AC", &[("type", "Mains"), ("online", "0")]),
+        ]);
+        assert!(read_on_battery(battery.path()));
+
+        let mains = power_supplies(&[
+            ("BAT0", &[("type", "Battery")]),
+            ("AC", &[("type", "Mains"), ("online", "1")]),
+        ]);
+        assert!(!read_on_battery(mains.path()));
+
+        let peripheral = power_supplies(&[(
+            "hid-mouse-battery",
+            &[("type", "Battery"), ("scope", "Device")],
+        )]);
+        assert!(!read_on_battery(peripheral.path()));
+
+        assert!(!read_on_battery(Path::new("/nonexistent/power_supply")));
+    }
+}
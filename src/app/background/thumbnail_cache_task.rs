@@ -0,0 +1,234 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use anyhow::Result;
+use relm4::Worker;
+use relm4::prelude::*;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tracing::{error, info};
+
+use fotema_core::FlatpakPathBuf;
+use fotema_core::jobs::{self, JobKind, Priority};
+use fotema_core::problems::{self, ProblemCategory};
+use fotema_core::thumbnailify::{Thumbnailer, maintenance};
+
+use crate::app::background::scheduler::{Scheduler, Stage};
+use crate::config::VERSION;
+
+#[derive(Debug, Clone)]
+pub enum ThumbnailCacheTaskInput {
+    /// Remove failure markers written by other versions of Fotema.
+    ExpireFailed,
+
+    /// Remove thumbnails of files that have left the library.
+    Sweep,
+
+    /// Remove thumbnails of all pictures and videos so they are generated again.
+    Regenerate,
+}
+
+#[derive(Debug)]
+pub enum ThumbnailCacheTaskOutput {
+    Started,
+
+    /// Completed. usize is the number of items that need new thumbnails.
+    Completed(usize),
+}
+
+pub struct ThumbnailCacheTask {
+    // Stop flag
+    stop: Arc<AtomicBool>,
+
+    scheduler: Scheduler,
+
+    thumbnailer: Thumbnailer,
+
+    library_base_dir: FlatpakPathBuf,
+
+    photo_repo: fotema_core::photo::Repository,
+
+    video_repo: fotema_core::video::Repository,
+
+    problems_repo: problems::Repository,
+
+    jobs_repo: jobs::Repository,
+}
+
+impl ThumbnailCacheTask {
+    fn library_paths(&self) -> Result<Vec<FlatpakPathBuf>> {
+        let mut paths = self.photo_repo.all_paths()?;
+        paths.extend(self.video_repo.all_paths()?);
+        Ok(paths)
+    }
+
+    /// Queue thumbnail jobs for all pictures and videos, newest first.
+    /// Jobs for items that still have thumbnails complete without any work.
+    fn queue_all(&mut self) -> Result<()> {
+        let picture_ids: Vec<i64> = self
+            .photo_repo
+            .all()?
+            .into_iter()
+            .rev()
+            .map(|pic| pic.picture_id.id())
+            .collect();
+        self.jobs_repo
+            .enqueue(JobKind::PhotoThumbnail, &picture_ids, Priority::Normal)?;
+
+        let video_ids: Vec<i64> = self
+            .video_repo
+            .all()?
+            .into_iter()
+            .rev()
+            .map(|vid| vid.video_id.id())
+            .collect();
+        self.jobs_repo
+            .enqueue(JobKind::VideoThumbnail, &video_ids, Priority::Normal)?;
+        Ok(())
+    }
+
+    fn expire_failed(&mut self) -> Result<usize> {
+        let count = maintenance::expire_failed(&self.thumbnailer, VERSION)?;
+        if count == 0 {
+            return Ok(0);
+        }
+
+        self.queue_all()?;
+
+        // Items that ran out of attempts are hidden until their broken marks are cleared.
+        let count = self
+            .problems_repo
+            .retry_category(ProblemCategory::Thumbnail)?;
+        info!("Retrying thumbnails for {} items", count);
+        Ok(count)
+    }
+
+    /// Thumbnail hashes of pictures and videos with edits or orientations chosen by the user.
+    fn edited_hashes(&self) -> Result<Vec<String>> {
+        let mut hashes: Vec<String> = self
+            .photo_repo
+            .all()?
+            .into_iter()
+            .filter(|pic| pic.has_own_thumbnails())
+            .flat_map(|pic| [pic.thumbnail_hash(), pic.unedited_thumbnail_hash()])
+            .collect();
+
+        hashes.extend(
+            self.video_repo
+                .all()?
+                .into_iter()
+                .filter(|vid| vid.orientation_override.is_some())
+                .map(|vid| vid.thumbnail_hash()),
+        );
+
+        hashes.sort();
+        hashes.dedup();
+        Ok(hashes)
+    }
+
+    fn sweep(&self) -> Result<()> {
+        let paths = self.library_paths()?;
+        let edited_hashes = self.edited_hashes()?;
+
+        maintenance::sweep_orphans(
+            &self.thumbnailer,
+            &self.library_base_dir,
+            &paths,
+            &edited_hashes,
+        )?;
+        Ok(())
+    }
+
+    fn regenerate(&mut self) -> Result<usize> {
+        let paths = self.library_paths()?;
+        maintenance::remove_thumbnails(&self.thumbnailer, &paths)?;
+        maintenance::remove_edited_thumbnails(&self.thumbnailer, &self.edited_hashes()?);
+        self.queue_all()?;
+        self.problems_repo
+            .retry_category(ProblemCategory::Thumbnail)?;
+        Ok(paths.len())
+    }
+}
+
+impl Worker for ThumbnailCacheTask {
+    type Init = (
+        Arc<AtomicBool>,
+        Scheduler,
+        Thumbnailer,
+        FlatpakPathBuf,
+        fotema_core::photo::Repository,
+        fotema_core::video::Repository,
+        problems::Repository,
+        jobs::Repository,
+    );
+    type Input = ThumbnailCacheTaskInput;
+    type Output = ThumbnailCacheTaskOutput;
+
+    fn init(
+        (
+            stop,
+            scheduler,
+            thumbnailer,
+            library_base_dir,
+            photo_repo,
+            video_repo,
+            problems_repo,
+            jobs_repo,
+        ): Self::Init,
+        _sender: ComponentSender<Self>,
+    ) -> Self {
+        Self {
+            stop,
+            scheduler,
+            thumbnailer,
+            library_base_dir,
+            photo_repo,
+            video_repo,
+            problems_repo,
+            jobs_repo,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        if self.stop.load(Ordering::Relaxed) {
+            let _ = sender.output(ThumbnailCacheTaskOutput::Completed(0));
+            return;
+        }
+
+        // Expiring and sweeping are quick, so don't flash the banner for them.
+        if matches!(msg, ThumbnailCacheTaskInput::Regenerate) {
+            let _ = sender.output(ThumbnailCacheTaskOutput::Started);
+        }
+
+        // Listing and deleting thumbnails mostly waits on the disk.
+        let scheduler = self.scheduler.clone();
+        let count = scheduler.install(Stage::Io, || match msg {
+            ThumbnailCacheTaskInput::ExpireFailed => {
+                info!("Expiring failed thumbnail markers...");
+                self.expire_failed().unwrap_or_else(|e| {
+                    error!("Failed to expire failed thumbnail markers: {}", e);
+                    0
+                })
+            }
+            ThumbnailCacheTaskInput::Sweep => {
+                info!("Sweeping thumbnail cache...");
+                if let Err(e) = self.sweep() {
+                    error!("Failed to sweep thumbnail cache: {}", e);
+                }
+                0
+            }
+            ThumbnailCacheTaskInput::Regenerate => {
+                info!("Removing thumbnails for regeneration...");
+                self.regenerate().unwrap_or_else(|e| {
+                    error!("Failed to remove thumbnails: {}", e);
+                    0
+                })
+            }
+        });
+
+        let _ = sender.output(ThumbnailCacheTaskOutput::Completed(count));
+    }
+}
@@ -0,0 +1,45 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use relm4::Worker;
+use relm4::prelude::*;
+use tracing::error;
+
+use fotema_core::jobs::{self, JobKind, Priority};
+
+#[derive(Debug)]
+pub enum ThumbnailPriorityTaskInput {
+    /// An item is on screen without a thumbnail, so generate its thumbnail next.
+    /// The i64 is the picture or video ID.
+    Prioritise(JobKind, i64),
+}
+
+/// Raises the priority of thumbnail jobs for items scrolled into view. Grid items are
+/// bound on the main thread, so the database is updated here instead.
+pub struct ThumbnailPriorityTask {
+    jobs_repo: jobs::Repository,
+}
+
+impl Worker for ThumbnailPriorityTask {
+    type Init = jobs::Repository;
+    type Input = ThumbnailPriorityTaskInput;
+    type Output = ();
+
+    fn init(jobs_repo: Self::Init, _sender: ComponentSender<Self>) -> Self {
+        Self { jobs_repo }
+    }
+
+    fn update(&mut self, msg: Self::Input, _sender: ComponentSender<Self>) {
+        match msg {
+            ThumbnailPriorityTaskInput::Prioritise(kind, target_id) => {
+                if let Err(e) = self
+                    .jobs_repo
+                    .prioritise(kind, target_id, Priority::Visible)
+                {
+                    error!("Failed prioritising thumbnail: {:?}", e);
+                }
+            }
+        }
+    }
+}
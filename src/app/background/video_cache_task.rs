@@ -0,0 +1,141 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use relm4::Worker;
+use relm4::prelude::*;
+
+use anyhow::*;
+
+use fotema_core::Visual;
+use fotema_core::photo::{self, MotionPhotoExtractor};
+use fotema_core::video::cache::{self, GIGABYTE};
+use fotema_core::video::{self, TranscodeProfile, Transcoder};
+use tracing::{error, info};
+
+use std::result::Result::Ok;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[derive(Debug)]
+pub enum VideoCacheTaskInput {
+    /// Evict least recently viewed videos until the cache is within a limit in gigabytes.
+    Evict(u32),
+
+    /// Transcode or extract a video again after it was evicted.
+    Restore(Arc<Visual>, TranscodeProfile),
+}
+
+#[derive(Debug)]
+pub enum VideoCacheTaskOutput {
+    // Video cache maintenance has started
+    Started,
+
+    // Video cache maintenance has completed.
+    // Count of videos evicted or restored.
+    Completed(usize),
+}
+
+pub struct VideoCacheTask {
+    // Stop flag
+    stop: Arc<AtomicBool>,
+
+    cache_repo: cache::Repository,
+
+    video_repo: video::Repository,
+
+    photo_repo: photo::Repository,
+
+    transcoder: Transcoder,
+
+    extractor: MotionPhotoExtractor,
+}
+
+impl VideoCacheTask {
+    fn evict(&self, limit_gb: u32) -> Result<usize> {
+        if limit_gb == 0 {
+            info!("Video cache is unlimited");
+            return Ok(0);
+        }
+
+        let count = cache::evict(&self.cache_repo, limit_gb as u64 * GIGABYTE)?;
+        info!("Evicted {} videos from video cache", count);
+        Ok(count)
+    }
+
+    fn restore(&mut self, visual: &Visual, profile: TranscodeProfile) -> Result<usize> {
+        if let (Some(picture_id), Some(picture_path)) = (visual.picture_id, &visual.picture_path)
+            && visual.motion_photo_video_path.is_some()
+        {
+            info!("Extracting evicted motion photo video for {}", picture_id);
+            let video = self
+                .extractor
+                .extract(&picture_id, &picture_path.sandbox_path, profile)?;
+            self.photo_repo.add_motion_photo_video(&picture_id, video)?;
+            return Ok(1);
+        }
+
+        if let (Some(video_id), Some(video_path)) = (visual.video_id, &visual.video_path) {
+            info!("Transcoding evicted video {}", video_id);
+            let transcoded_path = self.transcoder.transcode(
+                video_id,
+                &video_path.sandbox_path,
+                profile,
+                &self.stop,
+                |_| {},
+            )?;
+            self.video_repo.add_transcode(video_id, &transcoded_path)?;
+            return Ok(1);
+        }
+
+        Ok(0)
+    }
+}
+
+impl Worker for VideoCacheTask {
+    type Init = (
+        Arc<AtomicBool>,
+        cache::Repository,
+        video::Repository,
+        photo::Repository,
+        Transcoder,
+        MotionPhotoExtractor,
+    );
+    type Input = VideoCacheTaskInput;
+    type Output = VideoCacheTaskOutput;
+
+    fn init(
+        (stop, cache_repo, video_repo, photo_repo, transcoder, extractor): Self::Init,
+        _sender: ComponentSender<Self>,
+    ) -> Self {
+        Self {
+            stop,
+            cache_repo,
+            video_repo,
+            photo_repo,
+            transcoder,
+            extractor,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        if self.stop.load(Ordering::Relaxed) {
+            let _ = sender.output(VideoCacheTaskOutput::Completed(0));
+            return;
+        }
+
+        let _ = sender.output(VideoCacheTaskOutput::Started);
+
+        let result = match msg {
+            VideoCacheTaskInput::Evict(limit_gb) => self.evict(limit_gb),
+            VideoCacheTaskInput::Restore(visual, profile) => self.restore(&visual, profile),
+        };
+
+        if let Err(ref e) = result {
+            error!("Failed maintaining video cache: {:?}", e);
+        }
+
+        let count = result.unwrap_or(0);
+        let _ = sender.output(VideoCacheTaskOutput::Completed(count));
+    }
+}
@@ -12,6 +12,8 @@ use std::sync::atomic::{AtomicBool, Ordering};
 
 use tracing::{debug, error, info};
 
+use crate::app::background::scheduler::{Scheduler, Stage};
+
 #[derive(Debug)]
 pub enum VideoCleanTaskInput {
     Start,
@@ -30,6 +32,8 @@ pub struct VideoCleanTask {
     // Stop flag
     stop: Arc<AtomicBool>,
 
+    scheduler: Scheduler,
+
     // Danger! Don't hold the repo mutex for too long as it blocks viewing images.
     repo: fotema_core::video::Repository,
 }
@@ -97,12 +101,16 @@ impl VideoCleanTask {
 }
 
 impl Worker for VideoCleanTask {
-    type Init = (Arc<AtomicBool>, fotema_core::video::Repository);
+    type Init = (Arc<AtomicBool>, Scheduler, fotema_core::video::Repository);
     type Input = VideoCleanTaskInput;
     type Output = VideoCleanTaskOutput;
 
-    fn init((stop, repo): Self::Init, _sender: ComponentSender<Self>) -> Self {
-        Self { stop, repo }
+    fn init((stop, scheduler, repo): Self::Init, _sender: ComponentSender<Self>) -> Self {
+        Self {
+            stop,
+            scheduler,
+            repo,
+        }
     }
 
     fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
@@ -110,7 +118,9 @@ impl Worker for VideoCleanTask {
             VideoCleanTaskInput::Start => {
                 info!("Cleaning videos...");
 
-                if let Err(e) = self.cleanup(&sender) {
+                // Checking files exist mostly waits on the disk.
+                let scheduler = self.scheduler.clone();
+                if let Err(e) = scheduler.install(Stage::Io, || self.cleanup(&sender)) {
                     error!("Failed to clean videos: {}", e);
                 }
             }
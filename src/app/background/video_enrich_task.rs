@@ -14,6 +14,7 @@ use tracing::{error, info};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
+use crate::app::background::scheduler::{Scheduler, Stage};
 use crate::app::components::progress_monitor::{
     MediaType, ProgressMonitor, ProgressMonitorInput, TaskName,
 };
@@ -35,6 +36,8 @@ pub enum VideoEnrichTaskOutput {
 pub struct VideoEnrichTask {
     // Stop flag
     stop: Arc<AtomicBool>,
+
+    scheduler: Scheduler,
     repo: fotema_core::video::Repository,
     progress_monitor: Arc<Reducer<ProgressMonitor>>,
 }
@@ -42,6 +45,7 @@ pub struct VideoEnrichTask {
 impl VideoEnrichTask {
     fn enrich(
         stop: Arc<AtomicBool>,
+        scheduler: Scheduler,
         mut repo: fotema_core::video::Repository,
         progress_monitor: Arc<Reducer<ProgressMonitor>>,
         sender: &ComponentSender<VideoEnrichTask>,
@@ -71,6 +75,8 @@ impl VideoEnrichTask {
             .par_iter()
             .take_any_while(|_| !stop.load(Ordering::Relaxed))
             .flat_map(|vid| {
+                scheduler.wait_while_paused(&stop);
+
                 let result = metadata::from_path(&vid.sandbox_path());
                 progress_monitor.emit(ProgressMonitorInput::Advance);
                 result.map(|m| (vid.video_id, m))
@@ -98,15 +104,20 @@ impl VideoEnrichTask {
 impl Worker for VideoEnrichTask {
     type Init = (
         Arc<AtomicBool>,
+        Scheduler,
         fotema_core::video::Repository,
         Arc<Reducer<ProgressMonitor>>,
     );
     type Input = VideoEnrichTaskInput;
     type Output = VideoEnrichTaskOutput;
 
-    fn init((stop, repo, progress_monitor): Self::Init, _sender: ComponentSender<Self>) -> Self {
+    fn init(
+        (stop, scheduler, repo, progress_monitor): Self::Init,
+        _sender: ComponentSender<Self>,
+    ) -> Self {
         VideoEnrichTask {
             stop,
+            scheduler,
             repo,
             progress_monitor,
         }
@@ -117,12 +128,15 @@ impl Worker for VideoEnrichTask {
             VideoEnrichTaskInput::Start => {
                 info!("Enriching videos...");
                 let stop = self.stop.clone();
+                let scheduler = self.scheduler.clone();
                 let repo = self.repo.clone();
                 let progress_monitor = self.progress_monitor.clone();
 
                 // Avoid runtime panic from calling block_on
-                rayon::spawn(move || {
-                    if let Err(e) = VideoEnrichTask::enrich(stop, repo, progress_monitor, &sender) {
+                self.scheduler.spawn(Stage::Io, move || {
+                    if let Err(e) =
+                        VideoEnrichTask::enrich(stop, scheduler, repo, progress_monitor, &sender)
+                    {
                         error!("Failed to enrich videos: {}", e);
                     }
                 });
@@ -7,24 +7,34 @@ use rayon::prelude::*;
 use relm4::Reducer;
 use relm4::Worker;
 use relm4::prelude::*;
+use std::collections::HashMap;
 use std::panic;
-use std::path::{Path, PathBuf};
 use std::result::Result::Ok;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use tracing::{error, info};
 
-use fotema_core::thumbnailify;
-use fotema_core::thumbnailify::ThumbnailSize;
-use fotema_core::video::{Repository, Video, VideoThumbnailer};
+use fotema_core::jobs::{self, JobKind, Priority};
+use fotema_core::problems::ProblemCategory;
+use fotema_core::thumbnailify::{ThumbnailSize, Thumbnailer};
+use fotema_core::video::{Repository, Storyboarder, Video, VideoThumbnailer};
 
+use crate::app::background::scheduler::{Scheduler, Stage};
 use crate::app::components::progress_monitor::{
     ProgressMonitor, ProgressMonitorInput, TaskName, ThumbnailType,
 };
 
-#[derive(Debug)]
+/// Number of jobs to claim at a time.
+const BATCH_SIZE: usize = 64;
+
+#[derive(Debug, Clone)]
 pub enum VideoThumbnailTaskInput {
+    /// Generate thumbnails and storyboards for queued jobs.
     Start,
+
+    /// Queue jobs for all videos missing a thumbnail or storyboard, then generate them.
+    /// For when thumbnails have changed without knowing which videos are affected.
+    Sweep,
 }
 
 #[derive(Debug)]
@@ -40,45 +50,69 @@ pub struct VideoThumbnailTask {
     // Stop flag
     stop: Arc<AtomicBool>,
 
-    thumbnails_path: PathBuf,
+    scheduler: Scheduler,
+
+    thumbnail_cache: Thumbnailer,
     thumbnailer: VideoThumbnailer,
+    storyboarder: Storyboarder,
 
     // Danger! Don't hold the repo mutex for too long as it blocks viewing images.
     repo: Repository,
 
+    jobs_repo: jobs::Repository,
+
     progress_monitor: Arc<Reducer<ProgressMonitor>>,
 }
 
 impl VideoThumbnailTask {
+    fn has_thumbnail(thumbnail_cache: &Thumbnailer, vid: &Video) -> bool {
+        let thumb_hash = vid.thumbnail_hash();
+        let large_path =
+            thumbnail_cache.get_thumbnail_hash_output(&thumb_hash, ThumbnailSize::Large);
+        large_path.exists()
+    }
+
+    fn needs_work(thumbnail_cache: &Thumbnailer, storyboarder: &Storyboarder, vid: &Video) -> bool {
+        !Self::has_thumbnail(thumbnail_cache, vid)
+            || !storyboarder.storyboard_path(&vid.path).exists()
+    }
+
     fn enrich(
         stop: Arc<AtomicBool>,
+        scheduler: Scheduler,
         repo: Repository,
-        thumbnails_path: &Path,
+        mut jobs_repo: jobs::Repository,
+        thumbnail_cache: &Thumbnailer,
         thumbnailer: VideoThumbnailer,
+        storyboarder: Storyboarder,
         progress_monitor: Arc<Reducer<ProgressMonitor>>,
         sender: ComponentSender<VideoThumbnailTask>,
+        sweep: bool,
     ) -> Result<()> {
         let start = std::time::Instant::now();
 
-        let mut unprocessed: Vec<Video> = repo
-            .all()?
-            .into_iter()
-            .filter(|vid| vid.path.exists())
-            .filter(|vid| {
-                let thumb_hash = vid.thumbnail_hash();
-                let large_path = thumbnailify::get_thumbnail_hash_output(
-                    thumbnails_path,
-                    &thumb_hash,
-                    ThumbnailSize::Large,
-                );
-                !large_path.exists()
-            })
-            .collect();
+        // Jobs left running when Fotema last quit must be resumed.
+        let recovered = jobs_repo.recover(JobKind::VideoThumbnail)?;
+        if recovered > 0 {
+            info!("Resuming {} interrupted video thumbnail jobs", recovered);
+        }
 
-        // should be ascending time order from database, so reverse to process newest items first
-        unprocessed.reverse();
+        if sweep {
+            // should be ascending time order from database, so reverse to queue newest items first
+            let unprocessed: Vec<i64> = repo
+                .all()?
+                .into_iter()
+                .rev()
+                .filter(|vid| {
+                    vid.path.exists() && Self::needs_work(thumbnail_cache, &storyboarder, vid)
+                })
+                .map(|vid| vid.video_id.id())
+                .collect();
+
+            jobs_repo.enqueue(JobKind::VideoThumbnail, &unprocessed, Priority::Normal)?;
+        }
 
-        let count = unprocessed.len();
+        let count = jobs_repo.count_ready(JobKind::VideoThumbnail)?;
         info!("Found {} videos to generate thumbnails for", count);
 
         // Short-circuit before sending progress messages to stop
@@ -90,38 +124,117 @@ impl VideoThumbnailTask {
 
         let _ = sender.output(VideoThumbnailTaskOutput::Started);
 
+        let videos: HashMap<i64, Video> = repo
+            .all()?
+            .into_iter()
+            .map(|vid| (vid.video_id.id(), vid))
+            .collect();
+
         progress_monitor.emit(ProgressMonitorInput::Start(
             TaskName::Thumbnail(ThumbnailType::Video),
             count,
         ));
 
-        unprocessed
-            .par_iter()
-            .take_any_while(|_| !stop.load(Ordering::Relaxed))
-            .for_each(|vid| {
-                // Careful! panic::catch_unwind returns Ok(Err) if the evaluated expression returns
-                // an error but doesn't panic.
-                let result = panic::catch_unwind(|| thumbnailer.thumbnail(&vid.path));
-
-                // If we got an err, then there was a panic.
-                // If we got Ok(Err(e)) there wasn't a panic, but we still failed.
-                if let Ok(Err(e)) = result {
-                    error!(
-                        "Failed generate or add thumbnail: {:?}: Video path: {:?}",
-                        e.root_cause(),
-                        vid.path
-                    );
-                    let _ = repo.clone().mark_broken(&vid.video_id);
-                } else if result.is_err() {
-                    error!(
-                        "Panicked generate or add thumbnail: Video path: {:?}",
-                        vid.path
-                    );
-                    let _ = repo.clone().mark_broken(&vid.video_id);
-                }
-
-                progress_monitor.emit(ProgressMonitorInput::Advance);
-            });
+        // Claim jobs in batches so items that become visible while we work
+        // are picked up by the next batch.
+        let mut count = 0;
+        while !stop.load(Ordering::Relaxed) {
+            let batch = jobs_repo.claim(JobKind::VideoThumbnail, BATCH_SIZE)?;
+            if batch.is_empty() {
+                break;
+            }
+
+            count += batch.len();
+
+            batch
+                .par_iter()
+                .take_any_while(|_| !stop.load(Ordering::Relaxed))
+                .for_each(|job| {
+                    scheduler.wait_while_paused(&stop);
+
+                    let mut jobs_repo = jobs_repo.clone();
+
+                    // Video removed from library, or thumbnail generated elsewhere.
+                    let Some(vid) = videos.get(&job.target_id).filter(|vid| {
+                        vid.path.exists() && Self::needs_work(thumbnail_cache, &storyboarder, vid)
+                    }) else {
+                        let _ = jobs_repo.complete(job.job_id);
+                        progress_monitor.emit(ProgressMonitorInput::Advance);
+                        return;
+                    };
+
+                    let mut error = None;
+
+                    if !Self::has_thumbnail(thumbnail_cache, vid) {
+                        // Careful! panic::catch_unwind returns Ok(Err) if the evaluated expression returns
+                        // an error but doesn't panic.
+                        // Regenerate from the previously chosen frame, if any, so thumbnails
+                        // don't change when the thumbnail cache is cleared.
+                        let result = panic::catch_unwind(|| {
+                            thumbnailer.thumbnail(
+                                &vid.path,
+                                vid.thumbnail_ts,
+                                vid.orientation_override,
+                            )
+                        });
+
+                        // If we got an err, then there was a panic.
+                        // If we got Ok(Err(e)) there wasn't a panic, but we still failed.
+                        if let Ok(Ok(timestamp)) = result {
+                            if vid.thumbnail_ts.is_none()
+                                && let Err(e) = repo
+                                    .clone()
+                                    .add_thumbnail_timestamp(vid.video_id, timestamp)
+                            {
+                                error!("Failed saving thumbnail timestamp: {:?}", e);
+                            }
+                        } else if let Ok(Err(e)) = result {
+                            error!(
+                                "Failed generate or add thumbnail: {:?}: Video path: {:?}",
+                                e.root_cause(),
+                                vid.path
+                            );
+                            error = Some(format!("{:#}", e));
+                        } else if result.is_err() {
+                            error!(
+                                "Panicked generate or add thumbnail: Video path: {:?}",
+                                vid.path
+                            );
+                            error = Some(String::from("Panicked generating thumbnail"));
+                        }
+                    }
+
+                    // Storyboards are only for previews, so failing to generate one
+                    // doesn't make a video broken.
+                    if error.is_none() && !storyboarder.storyboard_path(&vid.path).exists() {
+                        let result = panic::catch_unwind(|| storyboarder.storyboard(&vid.path));
+                        if let Ok(Err(e)) = result {
+                            error!(
+                                "Failed generating storyboard: {:?}: Video path: {:?}",
+                                e.root_cause(),
+                                vid.path
+                            );
+                        } else if result.is_err() {
+                            error!("Panicked generating storyboard: Video path: {:?}", vid.path);
+                        }
+                    }
+
+                    if let Some(error) = error {
+                        // Only give up on the video once it has run out of retries.
+                        if let Ok(true) = jobs_repo.fail(job, &error) {
+                            let _ = repo.clone().mark_broken(
+                                &vid.video_id,
+                                ProblemCategory::Thumbnail,
+                                &error,
+                            );
+                        }
+                    } else {
+                        let _ = jobs_repo.complete(job.job_id);
+                    }
+
+                    progress_monitor.emit(ProgressMonitorInput::Advance);
+                });
+        }
 
         info!(
             "Generated {} video thumbnails in {} seconds.",
@@ -140,46 +253,69 @@ impl VideoThumbnailTask {
 impl Worker for VideoThumbnailTask {
     type Init = (
         Arc<AtomicBool>,
-        PathBuf,
+        Scheduler,
+        Thumbnailer,
         VideoThumbnailer,
+        Storyboarder,
         Repository,
+        jobs::Repository,
         Arc<Reducer<ProgressMonitor>>,
     );
     type Input = VideoThumbnailTaskInput;
     type Output = VideoThumbnailTaskOutput;
 
     fn init(
-        (stop, thumbnails_path, thumbnailer, repo, progress_monitor): Self::Init,
+        (
+            stop,
+            scheduler,
+            thumbnail_cache,
+            thumbnailer,
+            storyboarder,
+            repo,
+            jobs_repo,
+            progress_monitor,
+        ): Self::Init,
         _sender: ComponentSender<Self>,
     ) -> Self {
         Self {
             stop,
-            thumbnails_path: thumbnails_path.into(),
+            scheduler,
+            thumbnail_cache,
             thumbnailer,
+            storyboarder,
             repo,
+            jobs_repo,
             progress_monitor,
         }
     }
 
     fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
         match msg {
-            VideoThumbnailTaskInput::Start => {
+            VideoThumbnailTaskInput::Start | VideoThumbnailTaskInput::Sweep => {
                 info!("Generating video thumbnails...");
+                let sweep = matches!(msg, VideoThumbnailTaskInput::Sweep);
                 let stop = self.stop.clone();
+                let scheduler = self.scheduler.clone();
                 let repo = self.repo.clone();
-                let thumbnails_path = self.thumbnails_path.clone();
+                let jobs_repo = self.jobs_repo.clone();
+                let thumbnail_cache = self.thumbnail_cache.clone();
                 let thumbnailer = self.thumbnailer.clone();
+                let storyboarder = self.storyboarder.clone();
                 let progress_monitor = self.progress_monitor.clone();
 
                 // Avoid runtime panic from calling block_on
-                rayon::spawn(move || {
+                self.scheduler.spawn(Stage::Cpu, move || {
                     if let Err(e) = VideoThumbnailTask::enrich(
                         stop,
+                        scheduler,
                         repo,
-                        &thumbnails_path,
+                        jobs_repo,
+                        &thumbnail_cache,
                         thumbnailer,
+                        storyboarder,
                         progress_monitor,
                         sender,
+                        sweep,
                     ) {
                         error!("Failed to update video thumbnails: {}", e);
                     }
@@ -9,21 +9,29 @@ use relm4::prelude::*;
 use anyhow::*;
 
 use fotema_core::Visual;
+use fotema_core::problems::ProblemCategory;
 use fotema_core::video::Repository;
-use fotema_core::video::Transcoder;
+use fotema_core::video::cache::{self, GIGABYTE};
+use fotema_core::video::{TranscodeError, TranscodeProfile, Transcoder};
 use tracing::{error, info};
 
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
+use crate::app::background::scheduler::Scheduler;
 use crate::app::components::progress_monitor::{ProgressMonitor, ProgressMonitorInput, TaskName};
 
 use crate::app::SharedState;
 
+/// Each video is divided into this many steps of progress so that
+/// progress is shown while a long video is transcoding.
+const PROGRESS_STEPS: usize = 100;
+
 #[derive(Debug)]
 pub enum VideoTranscodeTaskInput {
-    /// Transcode all videos
-    Start,
+    /// Transcode all videos with a profile, while the video cache is within a limit
+    /// in gigabytes. Zero is unlimited.
+    Start(TranscodeProfile, u32),
 }
 
 #[derive(Debug)]
@@ -31,16 +39,21 @@ pub enum VideoTranscodeTaskOutput {
     // Video transcoding has started
     Started,
 
-    // Video transcoding has completed
-    Completed,
+    // Video transcoding has completed.
+    // Count of videos transcoded or marked as broken.
+    Completed(usize),
 }
 
 pub struct VideoTranscodeTask {
     // Stop flag
     stop: Arc<AtomicBool>,
 
+    scheduler: Scheduler,
+
     repo: Repository,
 
+    cache_repo: cache::Repository,
+
     transcoder: Transcoder,
 
     state: SharedState,
@@ -49,63 +62,125 @@ pub struct VideoTranscodeTask {
 }
 
 impl VideoTranscodeTask {
-    fn transcode_all(&mut self, sender: &ComponentSender<Self>) -> Result<()> {
+    fn transcode_all(
+        &mut self,
+        profile: TranscodeProfile,
+        limit_gb: u32,
+        sender: &ComponentSender<Self>,
+    ) -> Result<usize> {
+        // Evicted videos keep their transcoded path and are restored when viewed,
+        // so only videos that have never been transcoded are transcoded here.
         let unprocessed: Vec<Arc<Visual>> = {
             let data = self.state.read();
             data.iter()
                 .filter(|&x| x.video_id.is_some())
                 .filter(|&x| x.is_transcode_required.is_some_and(|y| y))
                 .filter(|&x| x.video_path.as_ref().is_some_and(|y| y.exists()))
-                .filter(|&x| !x.video_transcoded_path.as_ref().is_some_and(|y| y.exists()))
+                .filter(|&x| x.video_transcoded_path.is_none())
                 .cloned()
                 .collect()
         };
 
+        let budget_bytes = (limit_gb > 0).then_some(limit_gb as u64 * GIGABYTE);
+        let mut used_bytes = if budget_bytes.is_some() {
+            cache::usage(&self.cache_repo)?
+        } else {
+            0
+        };
+
         info!("Found {} videos to transcode", unprocessed.len());
 
         self.progress_monitor.emit(ProgressMonitorInput::Start(
             TaskName::Transcode,
-            unprocessed.len(),
+            unprocessed.len() * PROGRESS_STEPS,
         ));
 
         let _ = sender.output(VideoTranscodeTaskOutput::Started);
 
-        unprocessed
-            .iter()
-            .take_while(|_| !self.stop.load(Ordering::Relaxed))
-            .for_each(|visual| {
-                let video_id = visual.video_id.expect("Must have video_id");
-                let video_path = visual.video_path.as_ref().expect("Must have video_path");
+        let mut count = 0;
 
-                let result = self
-                    .transcoder
-                    .transcode(video_id, &video_path.sandbox_path)
-                    .with_context(|| format!("Video path: {:?}", video_path));
+        for (index, visual) in unprocessed.iter().enumerate() {
+            self.scheduler.wait_while_paused(&self.stop);
+            if self.stop.load(Ordering::Relaxed) {
+                break;
+            }
+
+            // Transcoding past the limit would only evict other videos on the next run.
+            if budget_bytes.is_some_and(|budget| used_bytes >= budget) {
+                info!(
+                    "Video cache is full, so skipping {} videos",
+                    unprocessed.len() - index
+                );
+                break;
+            }
 
-                if let std::result::Result::Ok(ref transcode_path) = result {
+            let video_id = visual.video_id.expect("Must have video_id");
+            let video_path = visual.video_path.as_ref().expect("Must have video_path");
+
+            // Progress steps reported for this video so far
+            let mut steps = 0;
+
+            let result = self.transcoder.transcode(
+                video_id,
+                &video_path.sandbox_path,
+                profile,
+                &self.stop,
+                |fraction| {
+                    let done = (fraction * PROGRESS_STEPS as f64) as usize;
+                    if done > steps {
+                        self.progress_monitor
+                            .emit(ProgressMonitorInput::AdvanceBy(done - steps));
+                        steps = done;
+                    }
+                },
+            );
+
+            match result {
+                std::result::Result::Ok(ref transcode_path) => {
+                    used_bytes += std::fs::metadata(transcode_path).map_or(0, |m| m.len());
                     if let Err(e) = self.repo.add_transcode(video_id, transcode_path) {
                         error!("Failed adding transcode path: {:?}", e);
+                    } else {
+                        count += 1;
+                    }
+                }
+                Err(TranscodeError::Cancelled) => {
+                    info!("Stopped transcoding {:?}", video_path);
+                    break;
+                }
+                Err(ref e) => {
+                    error!("Failed transcoding {:?}: {}", video_path, e);
+                    if e.is_broken_video() {
+                        if let Err(e) = self.repo.mark_broken(
+                            &video_id,
+                            ProblemCategory::Transcode,
+                            &e.to_string(),
+                        ) {
+                            error!("Failed marking video as broken: {:?}", e);
+                        } else {
+                            count += 1;
+                        }
                     }
-                } else if let Err(ref e) = result {
-                    error!("Failed transcoding: {:?}", e);
                 }
+            }
 
-                self.progress_monitor.emit(ProgressMonitorInput::Advance);
-            });
+            self.progress_monitor
+                .emit(ProgressMonitorInput::AdvanceBy(PROGRESS_STEPS - steps));
+        }
 
         self.progress_monitor.emit(ProgressMonitorInput::Complete);
 
-        let _ = sender.output(VideoTranscodeTaskOutput::Completed);
-
-        Ok(())
+        Ok(count)
     }
 }
 
 impl Worker for VideoTranscodeTask {
     type Init = (
         Arc<AtomicBool>,
+        Scheduler,
         SharedState,
         Repository,
+        cache::Repository,
         Transcoder,
         Arc<Reducer<ProgressMonitor>>,
     );
@@ -113,13 +188,15 @@ impl Worker for VideoTranscodeTask {
     type Output = VideoTranscodeTaskOutput;
 
     fn init(
-        (stop, state, repo, transcoder, progress_monitor): Self::Init,
+        (stop, scheduler, state, repo, cache_repo, transcoder, progress_monitor): Self::Init,
         _sender: ComponentSender<Self>,
     ) -> Self {
         Self {
             stop,
+            scheduler,
             state,
             repo,
+            cache_repo,
             transcoder,
             progress_monitor,
         }
@@ -127,12 +204,16 @@ impl Worker for VideoTranscodeTask {
 
     fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
         match msg {
-            VideoTranscodeTaskInput::Start => {
-                info!("Transcoding all incompatible videos");
+            VideoTranscodeTaskInput::Start(profile, limit_gb) => {
+                info!("Transcoding all incompatible videos with {:?}", profile);
 
-                if let Err(e) = self.transcode_all(&sender) {
-                    error!("Failed to transcode photo: {}", e);
+                let result = self.transcode_all(profile, limit_gb, &sender);
+                if let Err(ref e) = result {
+                    error!("Failed to transcode videos: {}", e);
                 }
+
+                let count = result.unwrap_or(0);
+                let _ = sender.output(VideoTranscodeTaskOutput::Completed(count));
             }
         };
     }
@@ -46,6 +46,7 @@ impl SimpleComponent for AboutDialog {
                 "libheif https://github.com/strukturag/libheif",
                 "libde265 https://github.com/strukturag/libde265",
                 "OpenStreetMap https://www.openstreetmap.org",
+                "GeoNames https://www.geonames.org/",
                 "Shumate https://gitlab.gnome.org/GNOME/libshumate",
             ],
         );
@@ -68,6 +69,12 @@ impl SimpleComponent for AboutDialog {
             gtk::License::Lgpl30,
             None,
         );
+        about.add_legal_section(
+            "GeoNames",
+            Some("GeoNames https://www.geonames.org/"),
+            gtk::License::Custom,
+            Some("Place names are from GeoNames and licensed under the Creative Commons Attribution 4.0 License."),
+        );
 
         about
     }
@@ -2,20 +2,27 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use fotema_core::Visual;
 use fotema_core::VisualId;
 use fotema_core::YearMonth;
+use fotema_core::jobs::JobKind;
+use fotema_core::orientation::Turn;
 use fotema_core::thumbnailify::{ThumbnailSize, Thumbnailer};
 
 use gtk::prelude::OrientableExt;
+use relm4::actions::{RelmAction, RelmActionGroup};
 use relm4::binding::*;
 use relm4::gtk;
 use relm4::gtk::gdk;
 use relm4::gtk::gdk_pixbuf;
+use relm4::gtk::glib;
 use relm4::gtk::prelude::AdjustmentExt;
 use relm4::gtk::prelude::*;
 use relm4::typed_view::grid::{RelmGridItem, TypedGridView};
 use relm4::*;
-use std::path::Path;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::Arc;
 
@@ -25,12 +32,55 @@ use crate::app::ActiveView;
 use crate::app::SharedState;
 use crate::app::ViewName;
 use crate::app::adaptive;
+use crate::app::background::thumbnail_priority_task::ThumbnailPriorityTaskInput;
+use crate::fl;
 
-use tracing::{debug, info};
+use tracing::{debug, error, info};
 
 const NARROW_EDGE_LENGTH: i32 = 112;
 const WIDE_EDGE_LENGTH: i32 = 200;
 
+relm4::new_action_group!(AlbumActionGroup, "album");
+
+// Start selecting items from the item that was long pressed or right clicked.
+relm4::new_stateless_action!(SelectItemAction, AlbumActionGroup, "select_item");
+
+relm4::new_stateless_action!(SelectAllAction, AlbumActionGroup, "select_all");
+relm4::new_stateless_action!(SelectMonthAction, AlbumActionGroup, "select_month");
+relm4::new_stateless_action!(SelectYearAction, AlbumActionGroup, "select_year");
+relm4::new_stateless_action!(MoveToFolderAction, AlbumActionGroup, "move_to_folder");
+relm4::new_stateless_action!(CopyToFolderAction, AlbumActionGroup, "copy_to_folder");
+relm4::new_stateless_action!(AssignPersonAction, AlbumActionGroup, "assign_person");
+relm4::new_stateless_action!(SetDateAction, AlbumActionGroup, "set_date");
+
+/// Action on all selected items.
+#[derive(Debug, Clone, Copy)]
+pub enum BatchAction {
+    /// Turn items a quarter turn.
+    Rotate(Turn),
+
+    /// Set the location of items by dropping a pin on a map.
+    SetLocation,
+
+    /// Set the date items were taken, keeping their time of day.
+    SetDate,
+
+    /// Mark the only unnamed face in each item as a person.
+    AssignPerson,
+
+    /// Move the files of items to another folder.
+    MoveToFolder,
+
+    /// Copy the files of items to another folder.
+    CopyToFolder,
+
+    /// Move the files of items to the trash.
+    Trash,
+
+    /// Export copies of items, or share them with another app.
+    Share,
+}
+
 #[derive(Debug)]
 pub enum AlbumInput {
     /// Album is visible
@@ -62,6 +112,27 @@ pub enum AlbumInput {
 
     // Scroll to top of photo grid, regardless of sort order
     ScrollToTop,
+
+    // Enter or leave selection mode. Entering starts with nothing selected.
+    SelectionMode(bool),
+
+    // Enter selection mode keeping the item that was just selected.
+    SelectItem,
+
+    // Selected items have changed.
+    SelectionChanged,
+
+    // Select all items in the album.
+    SelectAll,
+
+    // Select all items in the same months as the selected items.
+    SelectMonth,
+
+    // Select all items in the same years as the selected items.
+    SelectYear,
+
+    // Apply an action to the selected items, then leave selection mode.
+    Batch(BatchAction),
 }
 
 #[derive(Debug)]
@@ -71,6 +142,9 @@ pub enum AlbumOutput {
 
     // Scroll offset, in pixels.
     ScrollOffset(f64),
+
+    /// Apply an action to the selected items.
+    Batch(BatchAction, Vec<Arc<Visual>>),
 }
 
 #[derive(Debug)]
@@ -80,16 +154,70 @@ struct PhotoGridItem {
     // Length of thumbnail edge to allow for resizing when layout changes.
     edge_length: I32Binding,
 
+    // Is the album in selection mode?
+    selection_mode: BoolBinding,
+
     thumbnailer: Rc<Thumbnailer>,
+
+    priority_sender: relm4::Sender<ThumbnailPriorityTaskInput>,
+}
+
+/// Storyboard state for scrubbing through a video when hovering over it.
+#[derive(Default)]
+struct Scrub {
+    /// Storyboard sprite sheet for the bound video, if one has been generated.
+    storyboard_path: Option<PathBuf>,
+
+    /// Thumbnail to restore when the pointer leaves.
+    thumbnail_path: Option<PathBuf>,
+
+    /// Sprite sheet, loaded lazily on first hover.
+    sheet: Option<gdk_pixbuf::Pixbuf>,
+}
+
+impl Scrub {
+    fn enter(&mut self) {
+        if self.sheet.is_none() {
+            self.sheet = self
+                .storyboard_path
+                .as_ref()
+                .and_then(|path| gdk_pixbuf::Pixbuf::from_file(path).ok());
+        }
+    }
+
+    fn motion(&self, picture: &gtk::Picture, x: f64) {
+        let Some(ref sheet) = self.sheet else {
+            return;
+        };
+        let width = picture.width();
+        if width <= 0 {
+            return;
+        }
+        let position = x / f64::from(width);
+        let (x, y, w, h) =
+            fotema_core::video::storyboard::frame_rect(sheet.width(), sheet.height(), position);
+        let frame = sheet.new_subpixbuf(x, y, w, h);
+        picture.set_paintable(Some(&gdk::Texture::for_pixbuf(&frame)));
+    }
+
+    fn leave(&self, picture: &gtk::Picture) {
+        if self.sheet.is_some() {
+            picture.set_filename(self.thumbnail_path.as_ref());
+        }
+    }
 }
 
 struct PhotoGridItemWidgets {
     picture: gtk::Picture,
+    scrub: Rc<RefCell<Scrub>>,
     status_overlay: gtk::Frame,
     motion_type_icon: gtk::Image,
     duration_overlay: gtk::Frame,
     duration_label: gtk::Label,
 
+    // Shows if the item is selected when in selection mode.
+    selected_check: gtk::CheckButton,
+
     // If the gtk::Picture has been bound to edge_length.
     is_bound: bool,
 }
@@ -98,10 +226,22 @@ impl RelmGridItem for PhotoGridItem {
     type Root = gtk::Frame;
     type Widgets = PhotoGridItemWidgets;
 
-    fn setup(_item: &gtk::ListItem) -> (Self::Root, Self::Widgets) {
+    fn setup(item: &gtk::ListItem) -> (Self::Root, Self::Widgets) {
         relm4::view! {
             root = gtk::Frame {
                 gtk::Overlay {
+                    #[name(selected_check)]
+                    add_overlay = &gtk::CheckButton {
+                        set_halign: gtk::Align::Start,
+                        set_valign: gtk::Align::Start,
+                        set_margin_all: 8,
+                        set_visible: false,
+
+                        // Clicks select the whole item.
+                        set_can_target: false,
+                        add_css_class: "selection-mode",
+                    },
+
                     #[name(status_overlay)]
                     add_overlay =  &gtk::Frame {
                         set_halign: gtk::Align::End,
@@ -143,12 +283,90 @@ impl RelmGridItem for PhotoGridItem {
             }
         }
 
+        let scrub = Rc::new(RefCell::new(Scrub::default()));
+
+        let motion = gtk::EventControllerMotion::new();
+        {
+            let scrub = scrub.clone();
+            let picture = picture.clone();
+            motion.connect_enter(move |_, x, _| {
+                let mut scrub = scrub.borrow_mut();
+                scrub.enter();
+                scrub.motion(&picture, x);
+            });
+        }
+        {
+            let scrub = scrub.clone();
+            let picture = picture.clone();
+            motion.connect_motion(move |_, x, _| scrub.borrow().motion(&picture, x));
+        }
+        {
+            let scrub = scrub.clone();
+            let picture = picture.clone();
+            motion.connect_leave(move |_| scrub.borrow().leave(&picture));
+        }
+        picture.add_controller(motion);
+
+        item.bind_property("selected", &selected_check, "active")
+            .sync_create()
+            .build();
+
+        // In selection mode a click adds or removes an item, instead of selecting only
+        // that item. Shift-click selects a range.
+        let click = gtk::GestureClick::new();
+        click.set_propagation_phase(gtk::PropagationPhase::Capture);
+        {
+            let root = root.clone();
+            let selected_check = selected_check.clone();
+            click.connect_pressed(move |gesture, _, _, _| {
+                if !selected_check.is_visible() {
+                    return;
+                }
+                gesture.set_state(gtk::EventSequenceState::Claimed);
+                let extend = gesture
+                    .current_event_state()
+                    .contains(gdk::ModifierType::SHIFT_MASK);
+                let _ = root.activate_action("listitem.select", Some(&(true, extend).to_variant()));
+            });
+        }
+        root.add_controller(click);
+
+        // Long press or right click to start selecting items.
+        let start_selection = {
+            let root = root.clone();
+            move || {
+                let _ = root.activate_action("listitem.select", Some(&(false, false).to_variant()));
+                let _ = root.activate_action("album.select_item", None);
+            }
+        };
+
+        let long_press = gtk::GestureLongPress::new();
+        {
+            let start_selection = start_selection.clone();
+            long_press.connect_pressed(move |gesture, _, _| {
+                gesture.set_state(gtk::EventSequenceState::Claimed);
+                start_selection();
+            });
+        }
+        root.add_controller(long_press);
+
+        let right_click = gtk::GestureClick::builder()
+            .button(gdk::BUTTON_SECONDARY)
+            .build();
+        right_click.connect_pressed(move |gesture, _, _, _| {
+            gesture.set_state(gtk::EventSequenceState::Claimed);
+            start_selection();
+        });
+        root.add_controller(right_click);
+
         let widgets = PhotoGridItemWidgets {
             picture,
+            scrub,
             status_overlay,
             motion_type_icon,
             duration_overlay,
             duration_label,
+            selected_check,
             is_bound: false,
         };
 
@@ -171,6 +389,9 @@ impl RelmGridItem for PhotoGridItem {
             widgets
                 .picture
                 .add_write_only_binding(&self.edge_length, "height-request");
+            widgets
+                .selected_check
+                .add_write_only_binding(&self.selection_mode, "visible");
             widgets.is_bound = true;
         }
 
@@ -184,6 +405,24 @@ impl RelmGridItem for PhotoGridItem {
             .thumbnailer
             .nearest_thumbnail(&self.visual.thumbnail_hash(), thumbnail_size);
 
+        {
+            let mut scrub = widgets.scrub.borrow_mut();
+            *scrub = Scrub::default();
+            if self.visual.is_video_only() && thumbnail_path.is_some() {
+                let storyboard_path = self
+                    .thumbnailer
+                    .get_storyboard_path(&self.visual.thumbnail_hash());
+                if storyboard_path.exists() {
+                    scrub.storyboard_path = Some(storyboard_path);
+                    scrub.thumbnail_path = thumbnail_path.clone();
+                }
+            }
+        }
+
+        if thumbnail_path.is_none() {
+            self.prioritise_thumbnail();
+        }
+
         if thumbnail_path.is_some() {
             widgets.picture.set_filename(thumbnail_path);
 
@@ -206,6 +445,13 @@ impl RelmGridItem for PhotoGridItem {
             widgets.duration_overlay.set_visible(false);
             widgets.duration_label.set_label("");
             widgets.motion_type_icon.set_icon_name(Some("cd-symbolic"));
+        } else if self.visual.is_animated() {
+            widgets.status_overlay.set_visible(true);
+            widgets.duration_overlay.set_visible(false);
+            widgets.duration_label.set_label("");
+            widgets
+                .motion_type_icon
+                .set_icon_name(Some("playlist-infinite-symbolic"));
         } else if self.visual.is_video_only() && self.visual.video_duration.is_some() {
             widgets.status_overlay.set_visible(false);
             widgets.duration_overlay.set_visible(true);
@@ -233,6 +479,7 @@ impl RelmGridItem for PhotoGridItem {
     }
 
     fn unbind(&mut self, widgets: &mut Self::Widgets, _root: &mut Self::Root) {
+        *widgets.scrub.borrow_mut() = Scrub::default();
         widgets.picture.set_filename(None::<&Path>);
         widgets.motion_type_icon.set_icon_name(None);
         widgets.status_overlay.set_visible(false);
@@ -241,15 +488,38 @@ impl RelmGridItem for PhotoGridItem {
     }
 }
 
+impl PhotoGridItem {
+    /// Item is on screen without a thumbnail, so ask for it to be generated next.
+    fn prioritise_thumbnail(&self) {
+        let (kind, target_id) = if let Some(picture_id) = self.visual.picture_id {
+            (JobKind::PhotoThumbnail, picture_id.id())
+        } else if let Some(video_id) = self.visual.video_id {
+            (JobKind::VideoThumbnail, video_id.id())
+        } else {
+            return;
+        };
+
+        self.priority_sender
+            .emit(ThumbnailPriorityTaskInput::Prioritise(kind, target_id));
+    }
+}
+
 pub struct Album {
     state: SharedState,
     active_view: ActiveView,
     view_name: ViewName,
-    photo_grid: TypedGridView<PhotoGridItem, gtk::SingleSelection>,
+    photo_grid: TypedGridView<PhotoGridItem, gtk::MultiSelection>,
     filter: AlbumFilter,
     sort: AlbumSort,
     edge_length: I32Binding,
     thumbnailer: Rc<Thumbnailer>,
+    priority_sender: relm4::Sender<ThumbnailPriorityTaskInput>,
+
+    /// Is the album in selection mode? Outside of selection mode, clicking an item views it.
+    selection_mode: BoolBinding,
+
+    /// Count of selected items.
+    selected_count: u64,
 }
 
 #[relm4::component(pub)]
@@ -260,43 +530,172 @@ impl SimpleComponent for Album {
         ViewName,
         AlbumFilter,
         Rc<Thumbnailer>,
+        relm4::Sender<ThumbnailPriorityTaskInput>,
     );
     type Input = AlbumInput;
     type Output = AlbumOutput;
 
+    menu! {
+        select_menu: {
+            section! {
+                &fl!("album-select-menu", "all") => SelectAllAction,
+                &fl!("album-select-menu", "month") => SelectMonthAction,
+                &fl!("album-select-menu", "year") => SelectYearAction,
+            }
+        },
+        file_menu: {
+            section! {
+                &fl!("album-selection-file-menu", "move") => MoveToFolderAction,
+                &fl!("album-selection-file-menu", "copy") => CopyToFolderAction,
+            }
+        },
+        more_menu: {
+            section! {
+                &fl!("album-selection-more-menu", "assign-person") => AssignPersonAction,
+                &fl!("album-selection-more-menu", "set-date") => SetDateAction,
+            }
+        }
+    }
+
     view! {
-        gtk::ScrolledWindow {
-            set_vexpand: true,
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+
+            gtk::ScrolledWindow {
+                set_vexpand: true,
+
+                #[local_ref]
+                grid_view -> gtk::GridView {
+                    set_orientation: gtk::Orientation::Vertical,
 
-            #[local_ref]
-            grid_view -> gtk::GridView {
-                set_orientation: gtk::Orientation::Vertical,
-                set_single_click_activate: true,
+                    #[watch]
+                    set_single_click_activate: !model.selection_mode.value(),
 
-                connect_activate[sender] => move |_, idx| {
-                    sender.input(AlbumInput::Selected(idx))
+                    #[watch]
+                    set_enable_rubberband: model.selection_mode.value(),
+
+                    connect_activate[sender] => move |_, idx| {
+                        sender.input(AlbumInput::Selected(idx))
+                    },
                 },
-            },
 
-            #[wrap(Some)]
-            set_vadjustment = &gtk::Adjustment {
-                // Emit scroll events so PersonAlbum can determine when to hide avatar.
-                // FIXME maybe just emit one event at a boundary, instead of emitting an
-                // event for every scroll?
-                connect_value_changed[sender] => move |v| sender.input(AlbumInput::ScrollOffset(v.value())),
+                #[wrap(Some)]
+                set_vadjustment = &gtk::Adjustment {
+                    // Emit scroll events so PersonAlbum can determine when to hide avatar.
+                    // FIXME maybe just emit one event at a boundary, instead of emitting an
+                    // event for every scroll?
+                    connect_value_changed[sender] => move |v| sender.input(AlbumInput::ScrollOffset(v.value())),
+                },
             },
 
+            gtk::Revealer {
+                set_transition_type: gtk::RevealerTransitionType::SlideUp,
+
+                #[watch]
+                set_reveal_child: model.selection_mode.value(),
+
+                gtk::ActionBar {
+                    pack_start = &gtk::Button {
+                        set_label: &fl!("album-selection-cancel"),
+                        connect_clicked => AlbumInput::SelectionMode(false),
+                    },
+
+                    pack_start = &gtk::MenuButton {
+                        set_icon_name: "selection-mode-symbolic",
+                        set_tooltip_text: Some(&fl!("album-select-menu", "tooltip")),
+                        set_menu_model: Some(&select_menu),
+                    },
+
+                    #[wrap(Some)]
+                    set_center_widget = &gtk::Label {
+                        #[watch]
+                        set_label: &fl!("album-selection-count", count = model.selected_count),
+                    },
+
+                    pack_end = &gtk::MenuButton {
+                        set_icon_name: "view-more-symbolic",
+                        set_tooltip_text: Some(&fl!("album-selection-more-menu", "tooltip")),
+                        set_menu_model: Some(&more_menu),
+
+                        #[watch]
+                        set_sensitive: model.selected_count > 0,
+                    },
+
+                    pack_end = &gtk::Button {
+                        set_icon_name: "user-trash-symbolic",
+                        set_tooltip_text: Some(&fl!("album-selection-trash-tooltip")),
+                        connect_clicked => AlbumInput::Batch(BatchAction::Trash),
+
+                        #[watch]
+                        set_sensitive: model.selected_count > 0,
+                    },
+
+                    pack_end = &gtk::Button {
+                        set_icon_name: "document-send-symbolic",
+                        set_tooltip_text: Some(&fl!("album-selection-share-tooltip")),
+                        connect_clicked => AlbumInput::Batch(BatchAction::Share),
+
+                        #[watch]
+                        set_sensitive: model.selected_count > 0,
+                    },
+
+                    pack_end = &gtk::MenuButton {
+                        set_icon_name: "folder-symbolic",
+                        set_tooltip_text: Some(&fl!("album-selection-file-menu", "tooltip")),
+                        set_menu_model: Some(&file_menu),
+
+                        #[watch]
+                        set_sensitive: model.selected_count > 0,
+                    },
+
+                    pack_end = &gtk::Button {
+                        set_icon_name: "mark-location-symbolic",
+                        set_tooltip_text: Some(&fl!("viewer-set-location-tooltip")),
+                        connect_clicked => AlbumInput::Batch(BatchAction::SetLocation),
+
+                        #[watch]
+                        set_sensitive: model.selected_count > 0,
+                    },
+
+                    pack_end = &gtk::Button {
+                        set_icon_name: "object-rotate-right-symbolic",
+                        set_tooltip_text: Some(&fl!("viewer-rotate-right-tooltip")),
+                        connect_clicked => AlbumInput::Batch(BatchAction::Rotate(Turn::Right)),
+
+                        #[watch]
+                        set_sensitive: model.selected_count > 0,
+                    },
+
+                    pack_end = &gtk::Button {
+                        set_icon_name: "object-rotate-left-symbolic",
+                        set_tooltip_text: Some(&fl!("viewer-rotate-left-tooltip")),
+                        connect_clicked => AlbumInput::Batch(BatchAction::Rotate(Turn::Left)),
+
+                        #[watch]
+                        set_sensitive: model.selected_count > 0,
+                    },
+                },
+            },
         }
     }
 
     fn init(
-        (state, active_view, view_name, filter, thumbnailer): Self::Init,
+        (state, active_view, view_name, filter, thumbnailer, priority_sender): Self::Init,
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
         let photo_grid = TypedGridView::new();
         let grid_view = &photo_grid.view.clone();
 
+        {
+            let sender = sender.clone();
+            photo_grid
+                .selection_model
+                .connect_selection_changed(move |_, _, _| {
+                    sender.input(AlbumInput::SelectionChanged);
+                });
+        }
+
         let mut model = Album {
             state,
             active_view,
@@ -306,11 +705,101 @@ impl SimpleComponent for Album {
             sort: AlbumSort::default(),
             edge_length: I32Binding::new(NARROW_EDGE_LENGTH),
             thumbnailer,
+            priority_sender,
+            selection_mode: BoolBinding::new(false),
+            selected_count: 0,
         };
 
         model.update_filter();
 
         let widgets = view_output!();
+
+        let mut actions = RelmActionGroup::<AlbumActionGroup>::new();
+
+        let select_item_action = {
+            let sender = sender.clone();
+            RelmAction::<SelectItemAction>::new_stateless(move |_| {
+                sender.input(AlbumInput::SelectItem);
+            })
+        };
+
+        let select_all_action = {
+            let sender = sender.clone();
+            RelmAction::<SelectAllAction>::new_stateless(move |_| {
+                sender.input(AlbumInput::SelectAll);
+            })
+        };
+
+        let select_month_action = {
+            let sender = sender.clone();
+            RelmAction::<SelectMonthAction>::new_stateless(move |_| {
+                sender.input(AlbumInput::SelectMonth);
+            })
+        };
+
+        let select_year_action = {
+            let sender = sender.clone();
+            RelmAction::<SelectYearAction>::new_stateless(move |_| {
+                sender.input(AlbumInput::SelectYear);
+            })
+        };
+
+        let move_to_folder_action = {
+            let sender = sender.clone();
+            RelmAction::<MoveToFolderAction>::new_stateless(move |_| {
+                sender.input(AlbumInput::Batch(BatchAction::MoveToFolder));
+            })
+        };
+
+        let copy_to_folder_action = {
+            let sender = sender.clone();
+            RelmAction::<CopyToFolderAction>::new_stateless(move |_| {
+                sender.input(AlbumInput::Batch(BatchAction::CopyToFolder));
+            })
+        };
+
+        let assign_person_action = {
+            let sender = sender.clone();
+            RelmAction::<AssignPersonAction>::new_stateless(move |_| {
+                sender.input(AlbumInput::Batch(BatchAction::AssignPerson));
+            })
+        };
+
+        let set_date_action = {
+            let sender = sender.clone();
+            RelmAction::<SetDateAction>::new_stateless(move |_| {
+                sender.input(AlbumInput::Batch(BatchAction::SetDate));
+            })
+        };
+
+        actions.add_action(select_item_action);
+        actions.add_action(select_all_action);
+        actions.add_action(select_month_action);
+        actions.add_action(select_year_action);
+        actions.add_action(move_to_folder_action);
+        actions.add_action(copy_to_folder_action);
+        actions.add_action(assign_person_action);
+        actions.add_action(set_date_action);
+        actions.register_for_widget(&root);
+
+        let keys = gtk::EventControllerKey::new();
+        {
+            let sender = sender.clone();
+            let selection_mode = model.selection_mode.clone();
+            keys.connect_key_pressed(move |_, key, _, _| match key {
+                gdk::Key::Escape if selection_mode.value() => {
+                    sender.input(AlbumInput::SelectionMode(false));
+                    glib::Propagation::Stop
+                }
+                gdk::Key::Delete | gdk::Key::KP_Delete if selection_mode.value() => {
+                    sender.input(AlbumInput::Batch(BatchAction::Trash));
+                    glib::Propagation::Stop
+                }
+                _ => glib::Propagation::Proceed,
+            });
+        }
+        root.add_controller(keys);
+
         ComponentParts { model, widgets }
     }
 
@@ -344,6 +833,10 @@ impl SimpleComponent for Album {
                 }
             }
             AlbumInput::Selected(index) => {
+                if self.selection_mode.value() {
+                    return;
+                }
+
                 // Albums are filters so must use get_visible(...) over get(...), otherwise
                 // wrong photo is displayed.
                 if let Some(item) = self.photo_grid.get_visible(index) {
@@ -378,19 +871,69 @@ impl SimpleComponent for Album {
             AlbumInput::ScrollOffset(offset) => {
                 let _ = sender.output(AlbumOutput::ScrollOffset(offset));
             }
+            AlbumInput::SelectionMode(selection_mode) => {
+                // Outside of selection mode, the item under the pointer is selected,
+                // so that isn't a selection made by the user.
+                self.photo_grid.selection_model.unselect_all();
+                self.selection_mode.set_value(selection_mode);
+            }
+            AlbumInput::SelectItem => {
+                self.selection_mode.set_value(true);
+            }
+            AlbumInput::SelectionChanged => {
+                self.selected_count = self.photo_grid.selection_model.selection().size();
+            }
+            AlbumInput::SelectAll => {
+                self.photo_grid.selection_model.select_all();
+            }
+            AlbumInput::SelectMonth => {
+                let months: HashSet<YearMonth> = self
+                    .selected_visuals()
+                    .iter()
+                    .map(|v| v.year_month())
+                    .collect();
+                self.select_where(|v| months.contains(&v.year_month()));
+            }
+            AlbumInput::SelectYear => {
+                let years: HashSet<u32> =
+                    self.selected_visuals().iter().map(|v| v.year()).collect();
+                self.select_where(|v| years.contains(&v.year()));
+            }
+            AlbumInput::Batch(action) => {
+                let visuals = self.selected_visuals();
+                if visuals.is_empty() {
+                    return;
+                }
+
+                info!("{:?} for {} selected items", action, visuals.len());
+                let _ = sender.output(AlbumOutput::Batch(action, visuals));
+                sender.input(AlbumInput::SelectionMode(false));
+            }
         }
     }
 }
 
 impl Album {
     fn refresh(&mut self) {
+        // Keep the selection when the library is reloaded while selecting items.
+        let selected_ids: HashSet<VisualId> = if self.selection_mode.value() {
+            self.selected_visuals()
+                .iter()
+                .map(|v| v.visual_id.clone())
+                .collect()
+        } else {
+            HashSet::new()
+        };
+
         let mut all = {
             let data = self.state.read();
             data.iter()
                 .map(|visual| PhotoGridItem {
                     visual: visual.clone(),
                     edge_length: self.edge_length.clone(),
+                    selection_mode: self.selection_mode.clone(),
                     thumbnailer: self.thumbnailer.clone(),
+                    priority_sender: self.priority_sender.clone(),
                 })
                 .collect::<Vec<PhotoGridItem>>()
         };
@@ -405,11 +948,39 @@ impl Album {
 
         info!("{} items added to album", self.photo_grid.len());
 
+        if self.selection_mode.value() {
+            self.photo_grid.selection_model.unselect_all();
+            self.select_where(|v| selected_ids.contains(&v.visual_id));
+            return;
+        }
+
         // NOTE person album will in effect overide scrolling to the end
         // by sending a ScrollToTop command.
         self.sort.scroll_to_end(&mut self.photo_grid);
     }
 
+    /// Selected items, in the order shown.
+    fn selected_visuals(&self) -> Vec<Arc<Visual>> {
+        let selection = &self.photo_grid.selection_model;
+        (0..selection.n_items())
+            .filter(|index| selection.is_selected(*index))
+            .filter_map(|index| self.photo_grid.get_visible(index))
+            .map(|item| item.borrow().visual.clone())
+            .collect()
+    }
+
+    /// Add items to the selection.
+    fn select_where(&self, predicate: impl Fn(&Visual) -> bool) {
+        let selection = &self.photo_grid.selection_model;
+        for index in 0..selection.n_items() {
+            if let Some(item) = self.photo_grid.get_visible(index)
+                && predicate(&item.borrow().visual)
+            {
+                selection.select_item(index, false);
+            }
+        }
+    }
+
     fn update_filter(&mut self) {
         self.photo_grid.clear_filters();
         let filter = self.filter.clone();
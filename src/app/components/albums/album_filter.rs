@@ -7,6 +7,7 @@ use std::path::PathBuf;
 use fotema_core::PictureId;
 use fotema_core::Visual;
 use fotema_core::VisualId;
+use fotema_core::places::Place;
 use h3o::CellIndex;
 
 // An album is a view applied over the whole collection of messages.
@@ -37,6 +38,15 @@ pub enum AlbumFilter {
     // Show photos in a geographic area
     GeographicArea(CellIndex),
 
+    // Show photos taken in a country, by country code
+    Country(String),
+
+    // Show photos taken at a named place
+    Place(Place),
+
+    // Show photos that are part of an event or trip
+    Visuals(Vec<VisualId>),
+
     /// Show photos who's picture_id is in a set. Used for person filtering.
     /// FIXME should probably be a Set of some kind... but that mucks up PartialEq and Eq.
     Any(Vec<PictureId>),
@@ -49,7 +59,7 @@ impl AlbumFilter {
             AlbumFilter::One(visual_id) => v.visual_id == visual_id,
             AlbumFilter::All => true,
             AlbumFilter::Folder(path) => v.parent_path == path,
-            AlbumFilter::Motion => v.is_motion_photo(),
+            AlbumFilter::Motion => v.is_motion_photo() || v.is_animated(),
             AlbumFilter::Selfies => v.is_selfie(),
             AlbumFilter::Videos => v.is_video_only() && !v.is_motion_photo(),
             AlbumFilter::GeographicArea(cell_index) => {
@@ -60,6 +70,12 @@ impl AlbumFilter {
                     false
                 }
             }
+            AlbumFilter::Country(country_code) => v
+                .place
+                .as_ref()
+                .is_some_and(|place| place.country_code == country_code),
+            AlbumFilter::Place(place) => v.place.as_ref() == Some(&place),
+            AlbumFilter::Visuals(visual_ids) => visual_ids.contains(&v.visual_id),
             AlbumFilter::Any(picture_ids) => {
                 v.picture_id.is_some_and(|id| picture_ids.contains(&id))
             }
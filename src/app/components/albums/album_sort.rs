@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use relm4::gtk;
+use relm4::typed_view::RelmSelectionExt;
 use relm4::typed_view::grid::{RelmGridItem, TypedGridView};
 use strum::AsRefStr;
 use strum::EnumString;
@@ -27,9 +28,9 @@ impl AlbumSort {
         }
     }
 
-    pub fn scroll_to_end<T: RelmGridItem>(
+    pub fn scroll_to_end<T: RelmGridItem, S: RelmSelectionExt>(
         &self,
-        grid: &mut TypedGridView<T, gtk::SingleSelection>,
+        grid: &mut TypedGridView<T, S>,
     ) {
         if grid.is_empty() {
             return;
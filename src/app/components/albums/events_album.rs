@@ -0,0 +1,291 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use fotema_core::events::{self, Event, EventKind};
+use fotema_core::thumbnailify::{ThumbnailSize, Thumbnailer};
+use fotema_core::{Visual, VisualId};
+
+use relm4::adw;
+use relm4::adw::prelude::*;
+use relm4::gtk;
+use relm4::gtk::glib;
+use relm4::*;
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use tracing::{error, info};
+
+use crate::app::ActiveView;
+use crate::app::SharedState;
+use crate::app::ViewName;
+use crate::fl;
+
+const COVER_EDGE_LENGTH: i32 = 48;
+
+#[derive(Debug)]
+pub enum EventsAlbumInput {
+    Activate,
+
+    // Reload events from database
+    Refresh,
+
+    // An event or trip row has been activated
+    Selected(Vec<VisualId>),
+}
+
+#[derive(Debug)]
+pub enum EventsAlbumOutput {
+    Selected(Vec<VisualId>),
+}
+
+/// Trips and events, newest first. Events that are part of a trip are
+/// nested under the trip.
+pub struct EventsAlbum {
+    state: SharedState,
+    active_view: ActiveView,
+    repo: events::Repository,
+    thumbnailer: Rc<Thumbnailer>,
+
+    list_box: gtk::ListBox,
+    scrolled_window: gtk::ScrolledWindow,
+    status_page: adw::StatusPage,
+
+    need_refresh: bool,
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for EventsAlbum {
+    type Init = (SharedState, ActiveView, events::Repository, Rc<Thumbnailer>);
+    type Input = EventsAlbumInput;
+    type Output = EventsAlbumOutput;
+
+    view! {
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+
+            #[local_ref]
+            status_page -> adw::StatusPage {
+                set_vexpand: true,
+                set_visible: false,
+                set_icon_name: Some("x-office-calendar-symbolic"),
+                set_title: &fl!("events-empty", "title"),
+                set_description: Some(&fl!("events-empty", "description")),
+            },
+
+            #[local_ref]
+            scrolled_window -> gtk::ScrolledWindow {
+                set_vexpand: true,
+
+                adw::Clamp {
+                    set_margin_top: 12,
+                    set_margin_bottom: 12,
+                    set_margin_start: 12,
+                    set_margin_end: 12,
+
+                    #[local_ref]
+                    list_box -> gtk::ListBox {
+                        set_valign: gtk::Align::Start,
+                        set_selection_mode: gtk::SelectionMode::None,
+                        add_css_class: "boxed-list",
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        (state, active_view, repo, thumbnailer): Self::Init,
+        _root: Self::Root,
+        _sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let list_box = gtk::ListBox::new();
+        let scrolled_window = gtk::ScrolledWindow::new();
+        let status_page = adw::StatusPage::new();
+
+        let widgets = view_output!();
+
+        let model = EventsAlbum {
+            state,
+            active_view,
+            repo,
+            thumbnailer,
+            list_box,
+            scrolled_window,
+            status_page,
+            need_refresh: true,
+        };
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        match msg {
+            EventsAlbumInput::Activate => {
+                *self.active_view.write() = ViewName::Events;
+                if self.need_refresh {
+                    self.refresh(&sender);
+                }
+            }
+            EventsAlbumInput::Refresh => {
+                if *self.active_view.read() == ViewName::Events {
+                    info!("Events view is active so refreshing");
+                    self.refresh(&sender);
+                } else {
+                    info!("Events view is inactive so clearing");
+                    self.list_box.remove_all();
+                    self.need_refresh = true;
+                }
+            }
+            EventsAlbumInput::Selected(visual_ids) => {
+                let _ = sender.output(EventsAlbumOutput::Selected(visual_ids));
+            }
+        }
+    }
+}
+
+impl EventsAlbum {
+    fn refresh(&mut self, sender: &ComponentSender<Self>) {
+        self.need_refresh = false;
+        self.list_box.remove_all();
+
+        let events = match self.repo.all() {
+            Ok(events) => events,
+            Err(e) => {
+                error!("Failed to load events: {:?}", e);
+                vec![]
+            }
+        };
+
+        let visuals: HashMap<String, Arc<Visual>> = {
+            let data = self.state.read();
+            data.iter()
+                .map(|v| (v.visual_id.id().clone(), v.clone()))
+                .collect()
+        };
+
+        self.status_page.set_visible(events.is_empty());
+        self.scrolled_window.set_visible(!events.is_empty());
+
+        let mut trip_events: HashMap<_, Vec<&Event>> = HashMap::new();
+        for event in events.iter() {
+            if let Some(trip_id) = event.trip_id {
+                trip_events.entry(trip_id).or_default().push(event);
+            }
+        }
+
+        for event in events.iter().rev().filter(|e| e.trip_id.is_none()) {
+            match event.kind {
+                EventKind::Trip => {
+                    let trip_row = adw::ExpanderRow::builder()
+                        .title(glib::markup_escape_text(&Self::title(event)).as_str())
+                        .subtitle(Self::subtitle(event))
+                        .build();
+                    trip_row.add_prefix(&self.cover(event, &visuals));
+
+                    let all_row = adw::ActionRow::builder()
+                        .title(fl!("events-trip-all"))
+                        .activatable(true)
+                        .build();
+                    all_row.add_suffix(&Self::count_label(event.visual_ids.len()));
+                    all_row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+                    Self::connect_selected(&all_row, event, sender);
+                    trip_row.add_row(&all_row);
+
+                    for child in trip_events.get(&event.event_id).into_iter().flatten() {
+                        let row = self.event_row(child, &visuals, sender);
+                        trip_row.add_row(&row);
+                    }
+
+                    self.list_box.append(&trip_row);
+                }
+                EventKind::Event => {
+                    let row = self.event_row(event, &visuals, sender);
+                    self.list_box.append(&row);
+                }
+            }
+        }
+    }
+
+    fn event_row(
+        &self,
+        event: &Event,
+        visuals: &HashMap<String, Arc<Visual>>,
+        sender: &ComponentSender<Self>,
+    ) -> adw::ActionRow {
+        let row = adw::ActionRow::builder()
+            .title(glib::markup_escape_text(&Self::title(event)).as_str())
+            .subtitle(Self::subtitle(event))
+            .activatable(true)
+            .build();
+
+        row.add_prefix(&self.cover(event, visuals));
+        row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+        Self::connect_selected(&row, event, sender);
+        row
+    }
+
+    fn connect_selected(row: &adw::ActionRow, event: &Event, sender: &ComponentSender<Self>) {
+        let sender = sender.clone();
+        let visual_ids = event.visual_ids.clone();
+        row.connect_activated(move |_| {
+            sender.input(EventsAlbumInput::Selected(visual_ids.clone()));
+        });
+    }
+
+    fn cover(&self, event: &Event, visuals: &HashMap<String, Arc<Visual>>) -> gtk::Picture {
+        let picture = gtk::Picture::builder()
+            .content_fit(gtk::ContentFit::Cover)
+            .width_request(COVER_EDGE_LENGTH)
+            .height_request(COVER_EDGE_LENGTH)
+            .valign(gtk::Align::Center)
+            .build();
+
+        let thumbnail_path = event
+            .cover()
+            .and_then(|visual_id| visuals.get(visual_id.id()))
+            .and_then(|visual| {
+                self.thumbnailer
+                    .nearest_thumbnail(&visual.thumbnail_hash(), ThumbnailSize::Small)
+            });
+
+        picture.set_filename(thumbnail_path);
+        picture
+    }
+
+    fn title(event: &Event) -> String {
+        match (event.kind, event.place_name.as_ref()) {
+            (EventKind::Trip, Some(place)) => fl!("events-trip-title", place = place.clone()),
+            (EventKind::Trip, None) => fl!("events-trip-untitled"),
+            (EventKind::Event, Some(place)) => place.clone(),
+            (EventKind::Event, None) => event.local_start_ts.format("%Y-%m-%d").to_string(),
+        }
+    }
+
+    fn subtitle(event: &Event) -> String {
+        let dates = if event.is_multi_day() {
+            fl!(
+                "events-date-range",
+                start = event.local_start_ts.format("%Y-%m-%d").to_string(),
+                end = event.local_end_ts.format("%Y-%m-%d").to_string()
+            )
+        } else {
+            event.local_start_ts.format("%Y-%m-%d").to_string()
+        };
+
+        fl!(
+            "events-subtitle",
+            dates = dates,
+            count = event.visual_ids.len()
+        )
+    }
+
+    fn count_label(count: usize) -> gtk::Label {
+        gtk::Label::builder()
+            .label(count.to_string())
+            .css_classes(["dim-label", "numeric"])
+            .build()
+    }
+}
@@ -15,8 +15,9 @@ use crate::app::ActiveView;
 use crate::app::SharedState;
 use crate::app::ViewName;
 use crate::app::adaptive;
+use crate::app::background::thumbnail_priority_task::ThumbnailPriorityTaskInput;
 use crate::app::components::albums::{
-    album::{Album, AlbumInput, AlbumOutput},
+    album::{Album, AlbumInput, AlbumOutput, BatchAction},
     album_filter::AlbumFilter,
     album_sort::AlbumSort,
 };
@@ -24,6 +25,7 @@ use crate::app::components::albums::{
 use crate::fl;
 
 use fotema_core::PictureId;
+use fotema_core::Visual;
 use fotema_core::VisualId;
 use fotema_core::people;
 use fotema_core::thumbnailify::Thumbnailer;
@@ -31,6 +33,7 @@ use fotema_core::thumbnailify::Thumbnailer;
 use tracing::{error, info};
 
 use std::rc::Rc;
+use std::sync::Arc;
 
 const NARROW_EDGE_LENGTH: i32 = 50;
 const WIDE_EDGE_LENGTH: i32 = 200;
@@ -76,6 +79,15 @@ pub enum PersonAlbumInput {
     Delete,
 
     Sort(AlbumSort),
+
+    /// Play a slideshow of pictures of the person.
+    Slideshow,
+
+    /// Start selecting pictures of the person.
+    Select,
+
+    /// Apply an action to pictures selected in underlying album.
+    Batch(BatchAction, Vec<Arc<Visual>>),
 }
 
 #[derive(Debug)]
@@ -88,6 +100,12 @@ pub enum PersonAlbumOutput {
 
     /// Person renamed.
     Renamed,
+
+    /// Play a slideshow of an album.
+    Slideshow(AlbumFilter),
+
+    /// Apply an action to selected pictures.
+    Batch(BatchAction, Vec<Arc<Visual>>),
 }
 
 pub struct PersonAlbum {
@@ -103,7 +121,13 @@ pub struct PersonAlbum {
 
 #[relm4::component(pub)]
 impl SimpleComponent for PersonAlbum {
-    type Init = (SharedState, people::Repository, ActiveView, Rc<Thumbnailer>);
+    type Init = (
+        SharedState,
+        people::Repository,
+        ActiveView,
+        Rc<Thumbnailer>,
+        relm4::Sender<ThumbnailPriorityTaskInput>,
+    );
     type Input = PersonAlbumInput;
     type Output = PersonAlbumOutput;
 
@@ -130,6 +154,18 @@ impl SimpleComponent for PersonAlbum {
                     set_icon_name: "open-menu-symbolic",
                     set_menu_model: Some(&primary_menu),
                 },
+
+                pack_end = &gtk::Button {
+                    set_icon_name: "play-symbolic",
+                    set_tooltip_text: Some(&fl!("slideshow-start-tooltip")),
+                    set_action_name: Some("win.slideshow"),
+                },
+
+                pack_end = &gtk::Button {
+                    set_icon_name: "selection-mode-symbolic",
+                    set_tooltip_text: Some(&fl!("album-select-tooltip")),
+                    set_action_name: Some("win.select"),
+                },
             },
 
             #[wrap(Some)]
@@ -147,7 +183,7 @@ impl SimpleComponent for PersonAlbum {
     }
 
     fn init(
-        (state, repo, active_view, thumbnailer): Self::Init,
+        (state, repo, active_view, thumbnailer, priority_sender): Self::Init,
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
@@ -163,10 +199,12 @@ impl SimpleComponent for PersonAlbum {
                 ViewName::Person,
                 AlbumFilter::None,
                 thumbnailer,
+                priority_sender,
             ))
             .forward(sender.input_sender(), |msg| match msg {
                 AlbumOutput::Selected(id, _) => PersonAlbumInput::Selected(id),
                 AlbumOutput::ScrollOffset(offset) => PersonAlbumInput::ScrollOffset(offset),
+                AlbumOutput::Batch(action, visuals) => PersonAlbumInput::Batch(action, visuals),
             });
 
         let title = gtk::Label::builder().build();
@@ -270,6 +308,26 @@ impl SimpleComponent for PersonAlbum {
                     AlbumFilter::Any(self.picture_ids.clone()),
                 ));
             }
+            PersonAlbumInput::Slideshow => {
+                let Some(ref person) = self.person else {
+                    info!("Asked for slideshow, but no person for album");
+                    return;
+                };
+
+                // Faces may have been recognized since the album was opened.
+                let picture_ids = self
+                    .repo
+                    .find_pictures_for_person(person.person_id)
+                    .unwrap_or_default();
+
+                let _ = sender.output(PersonAlbumOutput::Slideshow(AlbumFilter::Any(picture_ids)));
+            }
+            PersonAlbumInput::Select => {
+                self.album.emit(AlbumInput::SelectionMode(true));
+            }
+            PersonAlbumInput::Batch(action, visuals) => {
+                let _ = sender.output(PersonAlbumOutput::Batch(action, visuals));
+            }
             PersonAlbumInput::Adapt(layout @ adaptive::Layout::Narrow) => {
                 self.edge_length.set_value(NARROW_EDGE_LENGTH);
                 // FIXME album should directly subscribe to layout state.
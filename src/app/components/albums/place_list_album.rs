@@ -0,0 +1,276 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use fotema_core::places::Place;
+
+use itertools::Itertools;
+
+use relm4::adw;
+use relm4::adw::prelude::*;
+use relm4::gtk;
+use relm4::gtk::glib;
+use relm4::*;
+
+use tracing::info;
+
+use crate::app::ActiveView;
+use crate::app::PrivacyState;
+use crate::app::SharedState;
+use crate::app::ViewName;
+use crate::fl;
+
+use super::album_filter::AlbumFilter;
+
+#[derive(Debug)]
+pub enum PlaceListAlbumInput {
+    Activate,
+
+    // Reload places from library
+    Refresh,
+
+    // A country or place row has been activated
+    Selected(AlbumFilter),
+
+    // Only show places with a name containing the search text
+    Search(String),
+}
+
+#[derive(Debug)]
+pub enum PlaceListAlbumOutput {
+    Selected(AlbumFilter),
+}
+
+/// Places grouped by country, then city, with a count of items for each.
+pub struct PlaceListAlbum {
+    state: SharedState,
+    active_view: ActiveView,
+    privacy: PrivacyState,
+
+    list_box: gtk::ListBox,
+    scrolled_window: gtk::ScrolledWindow,
+    status_page: adw::StatusPage,
+    search_entry: gtk::SearchEntry,
+
+    /// Text to search place names for.
+    search_text: String,
+
+    need_refresh: bool,
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for PlaceListAlbum {
+    type Init = (SharedState, ActiveView, PrivacyState);
+    type Input = PlaceListAlbumInput;
+    type Output = PlaceListAlbumOutput;
+
+    view! {
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+
+            adw::Clamp {
+                set_margin_top: 12,
+                set_margin_start: 12,
+                set_margin_end: 12,
+
+                #[local_ref]
+                search_entry -> gtk::SearchEntry {
+                    set_placeholder_text: Some(&fl!("places-list-search", "placeholder")),
+                    connect_search_changed[sender] => move |entry| {
+                        sender.input(PlaceListAlbumInput::Search(entry.text().to_string()));
+                    },
+                },
+            },
+
+            #[local_ref]
+            status_page -> adw::StatusPage {
+                set_vexpand: true,
+                set_visible: false,
+                set_icon_name: Some("mark-location-symbolic"),
+            },
+
+            #[local_ref]
+            scrolled_window -> gtk::ScrolledWindow {
+                set_vexpand: true,
+
+                adw::Clamp {
+                    set_margin_top: 12,
+                    set_margin_bottom: 12,
+                    set_margin_start: 12,
+                    set_margin_end: 12,
+
+                    #[local_ref]
+                    list_box -> gtk::ListBox {
+                        set_valign: gtk::Align::Start,
+                        set_selection_mode: gtk::SelectionMode::None,
+                        add_css_class: "boxed-list",
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        (state, active_view, privacy): Self::Init,
+        _root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let list_box = gtk::ListBox::new();
+        let scrolled_window = gtk::ScrolledWindow::new();
+        let status_page = adw::StatusPage::new();
+        let search_entry = gtk::SearchEntry::new();
+
+        let widgets = view_output!();
+
+        let model = PlaceListAlbum {
+            state,
+            active_view,
+            privacy,
+            list_box,
+            scrolled_window,
+            status_page,
+            search_entry,
+            search_text: String::new(),
+            need_refresh: true,
+        };
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        match msg {
+            PlaceListAlbumInput::Activate => {
+                *self.active_view.write() = ViewName::Places;
+                if self.need_refresh {
+                    self.refresh(&sender);
+                }
+            }
+            PlaceListAlbumInput::Refresh => {
+                if *self.active_view.read() == ViewName::Places {
+                    info!("Place list view is active so refreshing");
+                    self.refresh(&sender);
+                } else {
+                    info!("Place list view is inactive so clearing");
+                    self.list_box.remove_all();
+                    self.need_refresh = true;
+                }
+            }
+            PlaceListAlbumInput::Selected(filter) => {
+                let _ = sender.output(PlaceListAlbumOutput::Selected(filter));
+            }
+            PlaceListAlbumInput::Search(text) => {
+                self.search_text = text;
+                self.refresh(&sender);
+            }
+        }
+    }
+}
+
+impl PlaceListAlbum {
+    fn refresh(&mut self, sender: &ComponentSender<Self>) {
+        self.need_refresh = false;
+        self.list_box.remove_all();
+
+        let places: Vec<Place> = {
+            let data = self.state.read();
+            let privacy = self.privacy.read();
+            data.iter()
+                .filter(|v| privacy.visible_location(v.location).is_some())
+                .filter_map(|v| v.place.clone())
+                .collect()
+        };
+
+        self.search_entry.set_visible(!places.is_empty());
+
+        let is_library_empty = places.is_empty();
+
+        let places: Vec<Place> = places
+            .into_iter()
+            .filter(|place| place.matches(&self.search_text))
+            .collect();
+
+        if is_library_empty {
+            self.status_page
+                .set_title(&fl!("places-list-empty", "title"));
+            self.status_page
+                .set_description(Some(&fl!("places-list-empty", "description")));
+        } else {
+            self.status_page
+                .set_title(&fl!("places-list-search", "no-results"));
+            self.status_page.set_description(None);
+        }
+
+        self.status_page.set_visible(places.is_empty());
+        self.scrolled_window.set_visible(!places.is_empty());
+
+        let countries = places
+            .into_iter()
+            .sorted_by(|a, b| a.country.cmp(&b.country).then(a.city.cmp(&b.city)))
+            .chunk_by(|place| (place.country_code.clone(), place.country.clone()));
+
+        for ((country_code, country), group) in &countries {
+            let places = group.counts();
+            let total: usize = places.values().sum();
+
+            let country_row = adw::ExpanderRow::builder()
+                .title(glib::markup_escape_text(&country).as_str())
+                .subtitle(fl!("places-list-count", count = total))
+                .expanded(!self.search_text.trim().is_empty())
+                .build();
+
+            let all_row = Self::row(
+                &fl!("places-list-all"),
+                None,
+                total,
+                AlbumFilter::Country(country_code),
+                sender,
+            );
+            country_row.add_row(&all_row);
+
+            for (place, count) in places.into_iter().sorted() {
+                let region = place.region.clone().filter(|region| *region != place.city);
+                let row = Self::row(
+                    &place.city,
+                    region.as_deref(),
+                    count,
+                    AlbumFilter::Place(place.clone()),
+                    sender,
+                );
+                country_row.add_row(&row);
+            }
+
+            self.list_box.append(&country_row);
+        }
+    }
+
+    fn row(
+        title: &str,
+        subtitle: Option<&str>,
+        count: usize,
+        filter: AlbumFilter,
+        sender: &ComponentSender<Self>,
+    ) -> adw::ActionRow {
+        let row = adw::ActionRow::builder()
+            .title(glib::markup_escape_text(title).as_str())
+            .activatable(true)
+            .build();
+
+        if let Some(subtitle) = subtitle {
+            row.set_subtitle(glib::markup_escape_text(subtitle).as_str());
+        }
+
+        let count_label = gtk::Label::builder()
+            .label(count.to_string())
+            .css_classes(["dim-label", "numeric"])
+            .build();
+        row.add_suffix(&count_label);
+        row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+
+        let sender = sender.clone();
+        row.connect_activated(move |_| {
+            sender.input(PlaceListAlbumInput::Selected(filter.clone()));
+        });
+
+        row
+    }
+}
@@ -18,6 +18,7 @@ use tracing::{debug, error, info};
 
 use crate::adaptive;
 use crate::app::ActiveView;
+use crate::app::PrivacyState;
 use crate::app::SharedState;
 use crate::app::ViewName;
 
@@ -82,6 +83,10 @@ pub struct CellItem {
 pub struct PlacesAlbum {
     state: SharedState,
     active_view: ActiveView,
+
+    /// Items in private zones are never shown on the map.
+    privacy: PrivacyState,
+
     edge_length: I32Binding,
 
     /// Map of visual items
@@ -107,7 +112,7 @@ pub struct PlacesAlbum {
 
 #[relm4::component(pub)]
 impl SimpleComponent for PlacesAlbum {
-    type Init = (SharedState, ActiveView, Rc<Thumbnailer>);
+    type Init = (SharedState, ActiveView, PrivacyState, Rc<Thumbnailer>);
     type Input = PlacesAlbumInput;
     type Output = PlacesAlbumOutput;
 
@@ -122,7 +127,7 @@ impl SimpleComponent for PlacesAlbum {
     }
 
     fn init(
-        (state, active_view, thumbnailer): Self::Init,
+        (state, active_view, privacy, thumbnailer): Self::Init,
         _root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
@@ -173,6 +178,7 @@ impl SimpleComponent for PlacesAlbum {
         let model = PlacesAlbum {
             state,
             active_view,
+            privacy,
             need_refresh: true,
             edge_length: I32Binding::new(NARROW_EDGE_LENGTH),
             map: map_widget.clone(),
@@ -272,14 +278,19 @@ impl PlacesAlbum {
         );
 
         self.resolution = *resolution;
+        self.update_cells();
+    }
 
+    /// Build a map of cell indexes to cell items for current resolution
+    fn update_cells(&mut self) {
+        let resolution = &self.resolution;
         let data = self.state.read();
+        let privacy = self.privacy.read();
         self.cells.clear();
 
-        // Build a map of cell indexes to cell items for current resolution
         data.iter()
-            // only want visual items with location
-            .filter(|x| x.location.is_some())
+            // only want visual items with a location that isn't private
+            .filter(|x| privacy.visible_location(x.location).is_some())
             // make visual items in same cell adjacent
             .sorted_by_key(|x| x.location.map(|y| y.to_cell(*resolution)))
             // group visual items in same cell
@@ -331,10 +342,13 @@ impl PlacesAlbum {
         );
 
         self.centre_cell = centre_cell;
+        self.update_markers(sender);
+    }
 
+    fn update_markers(&mut self, sender: &ComponentSender<Self>) {
         // Get neighbouring cells. Hopefully enough to fully cover the map,
         // but not so many that the UI stutters.
-        let nearby = centre_cell.grid_disk::<Vec<_>>(5);
+        let nearby = self.centre_cell.grid_disk::<Vec<_>>(5);
         debug!("{} cells near centre", nearby.len());
 
         // WARNING reusing the marker layer by removing all markers and then adding new ones
@@ -387,7 +401,11 @@ impl PlacesAlbum {
 
     fn refresh(&mut self, sender: &ComponentSender<Self>) {
         let data = self.state.read().clone();
-        let data = data.iter().filter(|x| x.location.is_some()).collect_vec();
+        let privacy = self.privacy.read().clone();
+        let data = data
+            .iter()
+            .filter(|x| privacy.visible_location(x.location).is_some())
+            .collect_vec();
 
         info!("{} items with location data", data.len());
 
@@ -398,9 +416,16 @@ impl PlacesAlbum {
             map.center_on(location.lat(), location.lng());
         }
 
+        // Library or private zones might have changed, so always rebuild cells and markers.
         self.viewport.set_zoom_level(DEFAULT_ZOOM_LEVEL);
-        self.update_on_zoom(&PlacesAlbum::zoom_to_resolution(DEFAULT_ZOOM_LEVEL));
-        self.update_on_move(sender);
+        self.resolution = PlacesAlbum::zoom_to_resolution(DEFAULT_ZOOM_LEVEL);
+        self.update_cells();
+        if let Ok(centre_point) =
+            h3o::LatLng::new(self.viewport.latitude(), self.viewport.longitude())
+        {
+            self.centre_cell = centre_point.to_cell(self.resolution);
+        }
+        self.update_markers(sender);
         self.need_refresh = false;
     }
 
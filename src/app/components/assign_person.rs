@@ -0,0 +1,202 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use relm4::adw::prelude::*;
+use relm4::gtk::{self, gdk};
+use relm4::prelude::*;
+
+use tracing::{error, info};
+
+use crate::fl;
+use fotema_core::PersonId;
+use fotema_core::PictureId;
+use fotema_core::Visual;
+use fotema_core::people;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub enum AssignPersonInput {
+    /// Show the dialog to choose a person for items.
+    Present(Vec<Arc<Visual>>),
+
+    /// Mark the only unnamed face in each item as a person.
+    /// usize is index into vector of people.
+    Assign(usize),
+}
+
+#[derive(Debug)]
+pub enum AssignPersonOutput {
+    /// Faces have been marked as a person.
+    /// The first usize is the count of items assigned, the second of items chosen.
+    Assigned(usize, usize),
+}
+
+/// Dialog for choosing the person in many pictures at once. Only pictures with
+/// a single unnamed face are assigned, because otherwise which face is the person
+/// isn't known.
+pub struct AssignPersonDialog {
+    parent: adw::ApplicationWindow,
+    dialog: adw::Dialog,
+
+    people_repo: people::Repository,
+
+    /// List of avatars for people
+    people_list: gtk::ListBox,
+
+    /// List of person IDs of people.
+    /// MUST be in same order as people_list.
+    all_people: Vec<PersonId>,
+
+    /// Count of items chosen.
+    total: usize,
+
+    /// Pictures to assign a person to.
+    picture_ids: Vec<PictureId>,
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for AssignPersonDialog {
+    type Init = (people::Repository, adw::ApplicationWindow);
+    type Input = AssignPersonInput;
+    type Output = AssignPersonOutput;
+
+    view! {
+        adw::Dialog {
+            set_title: &fl!("assign-person-title"),
+            set_content_width: 360,
+            set_content_height: 480,
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {
+                    set_show_end_title_buttons: false,
+                    set_show_start_title_buttons: false,
+
+                    pack_start = &gtk::Button {
+                        set_label: &fl!("assign-person-cancel"),
+                        connect_clicked[dialog] => move |_| {
+                            dialog.close();
+                        },
+                    },
+                },
+
+                #[wrap(Some)]
+                set_content = &gtk::ScrolledWindow {
+                    set_vexpand: true,
+
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_spacing: 12,
+                        set_margin_all: 12,
+
+                        gtk::Label {
+                            set_label: &fl!("assign-person-description"),
+                            set_wrap: true,
+                            add_css_class: "dim-label",
+                        },
+
+                        #[local_ref]
+                        people_list -> gtk::ListBox,
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        (people_repo, parent): Self::Init,
+        dialog: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let people_list = gtk::ListBox::builder()
+            .css_classes(["boxed-list"])
+            .selection_mode(gtk::SelectionMode::None)
+            .activate_on_single_click(true)
+            .build();
+
+        {
+            let sender = sender.clone();
+            people_list.connect_row_activated(move |_, row| {
+                if let Ok(index) = usize::try_from(row.index()) {
+                    sender.input(AssignPersonInput::Assign(index));
+                }
+            });
+        }
+
+        let model = AssignPersonDialog {
+            parent,
+            dialog: dialog.clone(),
+            people_repo,
+            people_list: people_list.clone(),
+            all_people: vec![],
+            total: 0,
+            picture_ids: vec![],
+        };
+
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        match msg {
+            AssignPersonInput::Present(visuals) => {
+                self.total = visuals.len();
+                self.picture_ids = visuals.iter().filter_map(|v| v.picture_id).collect();
+
+                self.people_list.remove_all();
+                self.all_people.clear();
+
+                let people = self.people_repo.all_people().unwrap_or_default();
+
+                for person in people {
+                    let avatar = adw::Avatar::builder().size(50).name(&person.name).build();
+
+                    if let Some(thumbnail_path) = person.small_thumbnail_path {
+                        let img = gdk::Texture::from_filename(&thumbnail_path).ok();
+                        avatar.set_custom_image(img.as_ref());
+                    }
+
+                    let row = adw::ActionRow::builder()
+                        .title(person.name)
+                        .activatable(true)
+                        .build();
+
+                    row.add_prefix(&avatar);
+
+                    self.people_list.append(&row);
+                    self.all_people.push(person.person_id);
+                }
+
+                self.dialog.present(Some(&self.parent));
+            }
+            AssignPersonInput::Assign(index) => {
+                let Some(person_id) = self.all_people.get(index).copied() else {
+                    error!("Invalid person index: {}", index);
+                    return;
+                };
+
+                let picture_ids = std::mem::take(&mut self.picture_ids);
+                info!(
+                    "Assigning person {} to {} pictures",
+                    person_id,
+                    picture_ids.len()
+                );
+
+                match self
+                    .people_repo
+                    .mark_only_faces_as_person(&picture_ids, person_id)
+                {
+                    Ok(count) => {
+                        let _ = sender.output(AssignPersonOutput::Assigned(count, self.total));
+                    }
+                    Err(e) => {
+                        error!("Failed assigning person: {:?}", e);
+                    }
+                }
+
+                self.dialog.close();
+            }
+        }
+    }
+}
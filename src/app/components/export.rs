@@ -0,0 +1,538 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use ashpd::{
+    WindowIdentifier,
+    desktop::{email::EmailRequest, file_chooser::OpenFileRequest, open_uri},
+};
+
+use relm4::adw::prelude::*;
+use relm4::gtk;
+use relm4::prelude::*;
+
+use gtk::glib;
+
+use tracing::{error, info};
+
+use crate::app::{PrivacyState, SettingsState};
+use crate::config::APP_ID;
+use crate::fl;
+use anyhow::*;
+use fotema_core::Visual;
+use fotema_core::export::{self, ExportOptions, Exported, MetadataPolicy, PictureFormat};
+use futures::executor::block_on;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::result::Result::Ok;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Choices of maximum size of exported pictures, in the order shown.
+const MAX_DIMENSIONS: [Option<u32>; 4] = [None, Some(4096), Some(2048), Some(1024)];
+
+/// Choices of picture format, in the order shown.
+const FORMATS: [PictureFormat; 4] = [
+    PictureFormat::Original,
+    PictureFormat::Jpeg,
+    PictureFormat::WebP,
+    PictureFormat::Avif,
+];
+
+/// Choices of metadata to keep, in the order shown.
+const METADATA_POLICIES: [MetadataPolicy; 3] = [
+    MetadataPolicy::All,
+    MetadataPolicy::NoLocation,
+    MetadataPolicy::None,
+];
+
+#[derive(Debug)]
+pub enum ExportInput {
+    /// Show export options for items.
+    Present(Vec<Arc<Visual>>),
+
+    /// Choose a folder, then export items to it.
+    Export,
+
+    /// Export items, then attach them to a new email.
+    ShareByEmail,
+
+    /// Export items, then ask which app to open them with.
+    ShareWithApp,
+}
+
+/// Dialog for exporting copies of pictures and videos to a folder, or sharing them
+/// with other apps through the desktop portals.
+pub struct ExportDialog {
+    parent: adw::ApplicationWindow,
+    dialog: adw::Dialog,
+
+    format_row: adw::ComboRow,
+    quality_row: adw::SpinRow,
+    convert_heic_row: adw::SwitchRow,
+    size_row: adw::ComboRow,
+    metadata_row: adw::ComboRow,
+    template_row: adw::EntryRow,
+    zip_row: adw::SwitchRow,
+
+    toast_overlay: adw::ToastOverlay,
+
+    settings_state: SettingsState,
+
+    /// Locations in exported items must follow the privacy policy.
+    privacy_state: PrivacyState,
+
+    /// Items to export.
+    visuals: Vec<Arc<Visual>>,
+
+    /// Set to stop the export in progress.
+    stop: Arc<AtomicBool>,
+}
+
+#[relm4::component(pub async)]
+impl SimpleAsyncComponent for ExportDialog {
+    type Init = (
+        SettingsState,
+        PrivacyState,
+        adw::ToastOverlay,
+        adw::ApplicationWindow,
+    );
+    type Input = ExportInput;
+    type Output = ();
+
+    view! {
+        adw::Dialog {
+            set_content_width: 480,
+
+            #[watch]
+            set_title: &fl!("export-title", count = model.visuals.len()),
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar,
+
+                #[wrap(Some)]
+                set_content = &adw::PreferencesPage {
+                    add = &adw::PreferencesGroup {
+                        set_title: &fl!("export-pictures-section"),
+
+                        #[local_ref]
+                        format_row -> adw::ComboRow {
+                            set_title: &fl!("export-format"),
+                        },
+
+                        #[local_ref]
+                        quality_row -> adw::SpinRow {
+                            set_title: &fl!("export-quality"),
+                            set_subtitle: &fl!("export-quality", "subtitle"),
+                        },
+
+                        #[local_ref]
+                        convert_heic_row -> adw::SwitchRow {
+                            set_title: &fl!("export-convert-heic"),
+                            set_subtitle: &fl!("export-convert-heic", "subtitle"),
+                        },
+
+                        #[local_ref]
+                        size_row -> adw::ComboRow {
+                            set_title: &fl!("export-size"),
+                        },
+                    },
+
+                    add = &adw::PreferencesGroup {
+                        set_title: &fl!("export-metadata-section"),
+                        set_description: Some(&fl!("export-metadata-section", "description")),
+
+                        #[local_ref]
+                        metadata_row -> adw::ComboRow {
+                            set_title: &fl!("export-metadata"),
+                        },
+                    },
+
+                    add = &adw::PreferencesGroup {
+                        set_title: &fl!("export-files-section"),
+
+                        #[local_ref]
+                        template_row -> adw::EntryRow {
+                            set_title: &fl!("export-file-name-template"),
+                            set_tooltip_text: Some(&fl!("export-file-name-template", "tooltip")),
+                        },
+
+                        #[local_ref]
+                        zip_row -> adw::SwitchRow {
+                            set_title: &fl!("export-zip"),
+                            set_subtitle: &fl!("export-zip", "subtitle"),
+                        },
+                    },
+
+                    add = &adw::PreferencesGroup {
+                        gtk::Box {
+                            set_orientation: gtk::Orientation::Vertical,
+                            set_halign: gtk::Align::Center,
+                            set_spacing: 12,
+
+                            gtk::Button {
+                                set_label: &fl!("export-button"),
+                                add_css_class: "pill",
+                                add_css_class: "suggested-action",
+                                connect_clicked => ExportInput::Export,
+                            },
+
+                            gtk::Box {
+                                set_orientation: gtk::Orientation::Horizontal,
+                                set_spacing: 12,
+                                set_homogeneous: true,
+
+                                gtk::Button {
+                                    set_label: &fl!("export-share-email-button"),
+                                    add_css_class: "pill",
+                                    connect_clicked => ExportInput::ShareByEmail,
+                                },
+
+                                gtk::Button {
+                                    set_label: &fl!("export-share-app-button"),
+                                    add_css_class: "pill",
+                                    connect_clicked => ExportInput::ShareWithApp,
+                                },
+                            },
+                        },
+                    },
+                },
+            },
+        }
+    }
+
+    async fn init(
+        (settings_state, privacy_state, toast_overlay, parent): Self::Init,
+        dialog: Self::Root,
+        sender: AsyncComponentSender<Self>,
+    ) -> AsyncComponentParts<Self> {
+        let format_row = adw::ComboRow::new();
+        let list = gtk::StringList::new(&[
+            &fl!("export-format", "original"),
+            &fl!("export-format", "jpeg"),
+            &fl!("export-format", "webp"),
+            &fl!("export-format", "avif"),
+        ]);
+        format_row.set_model(Some(&list));
+
+        let quality_row = adw::SpinRow::with_range(1.0, 100.0, 1.0);
+
+        let convert_heic_row = adw::SwitchRow::new();
+
+        let size_row = adw::ComboRow::new();
+        let sizes: Vec<String> = MAX_DIMENSIONS
+            .iter()
+            .map(|max_dimension| match max_dimension {
+                Some(pixels) => fl!("export-size", "pixels", pixels = *pixels),
+                None => fl!("export-size", "original"),
+            })
+            .collect();
+        let sizes: Vec<&str> = sizes.iter().map(|size| size.as_str()).collect();
+        size_row.set_model(Some(&gtk::StringList::new(&sizes)));
+
+        let metadata_row = adw::ComboRow::new();
+        let list = gtk::StringList::new(&[
+            &fl!("export-metadata", "all"),
+            &fl!("export-metadata", "no-location"),
+            &fl!("export-metadata", "none"),
+        ]);
+        metadata_row.set_model(Some(&list));
+
+        let template_row = adw::EntryRow::new();
+
+        let zip_row = adw::SwitchRow::new();
+
+        let model = Self {
+            parent,
+            dialog: dialog.clone(),
+            format_row: format_row.clone(),
+            quality_row: quality_row.clone(),
+            convert_heic_row: convert_heic_row.clone(),
+            size_row: size_row.clone(),
+            metadata_row: metadata_row.clone(),
+            template_row: template_row.clone(),
+            zip_row: zip_row.clone(),
+            toast_overlay,
+            settings_state,
+            privacy_state,
+            visuals: Vec::new(),
+            stop: Arc::new(AtomicBool::new(false)),
+        };
+
+        let widgets = view_output!();
+
+        AsyncComponentParts { model, widgets }
+    }
+
+    async fn update(&mut self, msg: Self::Input, _sender: AsyncComponentSender<Self>) {
+        match msg {
+            ExportInput::Present(visuals) => {
+                if visuals.is_empty() {
+                    return;
+                }
+
+                let options = self.settings_state.read().export_options.clone();
+                self.show_options(&options);
+                self.visuals = visuals;
+                self.dialog.present(Some(&self.parent));
+            }
+            ExportInput::Export => {
+                let options = self.save_options();
+
+                let Some(dir) = self.choose_folder().await else {
+                    return;
+                };
+
+                self.dialog.close();
+                let visuals = std::mem::take(&mut self.visuals);
+                let count = visuals.len();
+
+                info!("Exporting {} items to {:?}", count, dir);
+
+                match self.export(visuals, options, dir).await {
+                    _ if self.is_stopped() => {
+                        self.toast(fl!("export-cancelled"));
+                    }
+                    Ok(exported) if exported.failures == 0 => {
+                        self.toast(fl!("export-done", count = count));
+                    }
+                    Ok(exported) => {
+                        self.toast(fl!("export-failed", count = exported.failures));
+                    }
+                    Err(e) => {
+                        error!("Failed exporting: {:?}", e);
+                        self.toast(fl!("export-failed", count = count));
+                    }
+                }
+            }
+            ExportInput::ShareByEmail => {
+                let options = self.save_options();
+                self.dialog.close();
+
+                let Some(files) = self.export_for_sharing(options).await else {
+                    return;
+                };
+
+                let identifier = WindowIdentifier::from_native(&self.parent).await;
+                let mut request = EmailRequest::default().identifier(identifier);
+                for file in files.iter() {
+                    request.add_attachment(file);
+                }
+
+                if let Err(e) = request.send().await {
+                    error!("Failed sharing by email: {e}");
+                    self.toast(fl!("export-share-failed"));
+                }
+            }
+            ExportInput::ShareWithApp => {
+                let mut options = self.save_options();
+                self.dialog.close();
+
+                // Apps are only asked to open one file.
+                let is_many_files = self.visuals.len() > 1
+                    || self
+                        .visuals
+                        .iter()
+                        .any(|v| v.picture_path.is_some() && v.video_path.is_some());
+                options.zip |= is_many_files;
+
+                let Some(files) = self.export_for_sharing(options).await else {
+                    return;
+                };
+
+                let Some(file) = files.first() else {
+                    return;
+                };
+
+                let identifier = WindowIdentifier::from_native(&self.parent).await;
+                let request = open_uri::OpenFileRequest::default()
+                    .identifier(identifier)
+                    .ask(true);
+
+                if let Err(e) = request.send_file(file).await {
+                    error!("Failed sharing with app: {e}");
+                    self.toast(fl!("export-share-failed"));
+                }
+            }
+        }
+    }
+}
+
+impl ExportDialog {
+    fn toast(&self, title: String) {
+        self.toast_overlay.add_toast(adw::Toast::new(&title));
+    }
+
+    fn show_options(&self, options: &ExportOptions) {
+        let format = FORMATS.iter().position(|x| *x == options.format);
+        self.format_row.set_selected(format.unwrap_or(0) as u32);
+
+        self.quality_row.set_value(f64::from(options.quality));
+        self.convert_heic_row.set_active(options.convert_heic);
+
+        let size = MAX_DIMENSIONS
+            .iter()
+            .position(|x| *x == options.max_dimension);
+        self.size_row.set_selected(size.unwrap_or(0) as u32);
+
+        let metadata = METADATA_POLICIES
+            .iter()
+            .position(|x| *x == options.metadata);
+        self.metadata_row.set_selected(metadata.unwrap_or(0) as u32);
+
+        self.template_row.set_text(&options.file_name_template);
+        self.zip_row.set_active(options.zip);
+    }
+
+    /// Options chosen in the dialog, which are remembered for next time.
+    fn save_options(&self) -> ExportOptions {
+        let options = ExportOptions {
+            max_dimension: MAX_DIMENSIONS
+                .get(self.size_row.selected() as usize)
+                .copied()
+                .flatten(),
+            format: FORMATS
+                .get(self.format_row.selected() as usize)
+                .copied()
+                .unwrap_or_default(),
+            quality: self.quality_row.value() as u8,
+            convert_heic: self.convert_heic_row.is_active(),
+            metadata: METADATA_POLICIES
+                .get(self.metadata_row.selected() as usize)
+                .copied()
+                .unwrap_or_default(),
+            file_name_template: self.template_row.text().to_string(),
+            zip: self.zip_row.is_active(),
+        };
+
+        if self.settings_state.read().export_options != options {
+            self.settings_state.write().export_options = options.clone();
+        }
+
+        options
+    }
+
+    /// Export items in the background.
+    async fn export(
+        &self,
+        visuals: Vec<Arc<Visual>>,
+        options: ExportOptions,
+        dir: PathBuf,
+    ) -> Result<Exported> {
+        let policy = self.privacy_state.read().clone();
+        let archive_name = fl!("export-archive-name");
+
+        self.stop.store(false, Ordering::Relaxed);
+
+        // The dialog is closed while exporting, so the progress toast is where to cancel.
+        let progress = adw::Toast::builder()
+            .title(fl!("export-started", count = visuals.len()))
+            .button_label(fl!("export-cancel"))
+            .timeout(0)
+            .build();
+        {
+            let stop = self.stop.clone();
+            progress.connect_button_clicked(move |_| {
+                info!("Cancelling export");
+                stop.store(true, Ordering::Relaxed);
+            });
+        }
+        self.toast_overlay.add_toast(progress.clone());
+
+        let stop = self.stop.clone();
+        let result = relm4::spawn_blocking(move || {
+            block_on(export::export_all(
+                &visuals,
+                &options,
+                &policy,
+                &dir,
+                &archive_name,
+                &stop,
+            ))
+        })
+        .await
+        .unwrap_or_else(|e| Err(anyhow!("Export task failed: {e}")));
+
+        progress.dismiss();
+        result
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+
+    /// Export items to a folder that other apps are given access to.
+    /// Returns the opened files, ready to hand to a portal.
+    async fn export_for_sharing(&mut self, options: ExportOptions) -> Option<Vec<File>> {
+        let visuals = std::mem::take(&mut self.visuals);
+        let count = visuals.len();
+
+        // Files from the previous share are no longer needed.
+        let dir = glib::user_cache_dir().join(APP_ID).join("share");
+        let _ = std::fs::remove_dir_all(&dir);
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            error!("Failed creating share folder {:?}: {}", dir, e);
+            self.toast(fl!("export-share-failed"));
+            return None;
+        }
+
+        info!("Exporting {} items for sharing", count);
+        let exported = match self.export(visuals, options, dir).await {
+            _ if self.is_stopped() => {
+                self.toast(fl!("export-cancelled"));
+                return None;
+            }
+            Ok(exported) if !exported.paths.is_empty() => exported,
+            Ok(_) => {
+                self.toast(fl!("export-failed", count = count));
+                return None;
+            }
+            Err(e) => {
+                error!("Failed exporting for sharing: {:?}", e);
+                self.toast(fl!("export-failed", count = count));
+                return None;
+            }
+        };
+
+        if exported.failures > 0 {
+            self.toast(fl!("export-failed", count = exported.failures));
+        }
+
+        let files: Vec<File> = exported
+            .paths
+            .iter()
+            .filter_map(|path| open(path))
+            .collect();
+
+        Some(files)
+    }
+
+    /// Ask the user for a folder to export to.
+    async fn choose_folder(&self) -> Option<PathBuf> {
+        let identifier = WindowIdentifier::from_native(&self.parent).await;
+        let request = OpenFileRequest::default()
+            .directory(true)
+            .identifier(identifier)
+            .modal(true)
+            .multiple(false);
+
+        match request.send().await.and_then(|r| r.response()) {
+            Ok(files) => files.uris().first().and_then(|uri| {
+                glib::Uri::parse(uri.as_str(), glib::UriFlags::NONE)
+                    .map(|glib_uri| PathBuf::from(glib_uri.path()))
+                    .ok()
+            }),
+            Err(err) => {
+                error!("Failed to choose export folder: {err}");
+                None
+            }
+        }
+    }
+}
+
+fn open(path: &Path) -> Option<File> {
+    File::open(path)
+        .map_err(|e| error!("Failed opening {:?}: {}", path, e))
+        .ok()
+}
@@ -0,0 +1,470 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use ashpd::{WindowIdentifier, desktop::file_chooser::OpenFileRequest};
+
+use relm4::adw::prelude::*;
+use relm4::gtk;
+use relm4::prelude::*;
+
+use gtk::glib;
+
+use tracing::{error, info, warn};
+
+use crate::app::{SettingsState, SharedState};
+use crate::fl;
+use crate::host_path;
+use fotema_core::Visual;
+use fotema_core::files::{self, ops};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub enum FileOperationsInput {
+    /// Ask for a new name for an item.
+    Rename(Arc<Visual>),
+
+    /// Rename the item being renamed.
+    RenameTo(String),
+
+    /// Choose a folder in the library, then move items to it.
+    MoveToFolder(Vec<Arc<Visual>>),
+
+    /// Choose a folder in the library, then copy items to it.
+    CopyToFolder(Vec<Arc<Visual>>),
+
+    /// Hide items straight away, then move their files to the trash
+    /// unless the move is undone.
+    Trash(Vec<Arc<Visual>>),
+
+    /// Show a batch of hidden items again.
+    UndoTrash(u64),
+
+    /// Move the files of a batch of hidden items to the trash.
+    CommitTrash(u64),
+}
+
+#[derive(Debug)]
+pub enum FileOperationsOutput {
+    /// Items have been renamed or moved.
+    Relocated(Vec<Arc<Visual>>),
+
+    /// Items have been hidden or shown again.
+    Refresh,
+
+    /// Files in the library have changed.
+    FilesChanged,
+}
+
+/// Renames, moves, copies, and trashes the files of items in the library.
+pub struct FileOperations {
+    parent: adw::ApplicationWindow,
+
+    /// Dialog for renaming an item.
+    dialog: adw::AlertDialog,
+
+    /// New name of the item being renamed.
+    name_entry: gtk::Entry,
+
+    /// Item being renamed.
+    renaming: Option<Arc<Visual>>,
+
+    toast_overlay: adw::ToastOverlay,
+
+    repo: files::Repository,
+
+    settings_state: SettingsState,
+
+    state: SharedState,
+
+    /// Batches of items that are hidden, but whose files are not yet in the trash.
+    pending_trash: Vec<(u64, Vec<Arc<Visual>>)>,
+
+    /// ID of the last batch of items moved to the trash.
+    trash_batch: u64,
+}
+
+#[relm4::component(pub async)]
+impl SimpleAsyncComponent for FileOperations {
+    type Init = (
+        files::Repository,
+        SettingsState,
+        SharedState,
+        adw::ToastOverlay,
+        adw::ApplicationWindow,
+    );
+    type Input = FileOperationsInput;
+    type Output = FileOperationsOutput;
+
+    view! {
+        adw::AlertDialog {
+            set_heading: Some(&fl!("files-rename-dialog", "heading")),
+
+            #[wrap(Some)]
+            #[local_ref]
+            set_extra_child = &name_entry -> gtk::Entry {
+                set_activates_default: true,
+            },
+        }
+    }
+
+    async fn init(
+        (mut repo, settings_state, state, toast_overlay, parent): Self::Init,
+        dialog: Self::Root,
+        sender: AsyncComponentSender<Self>,
+    ) -> AsyncComponentParts<Self> {
+        let name_entry = gtk::Entry::new();
+
+        // Items are only still hidden if Fotema exited before trashing their files.
+        match repo.unmark_all_trashed() {
+            Ok(count) if count > 0 => info!("Restored {} items not yet moved to trash", count),
+            Ok(_) => {}
+            Err(e) => error!("Failed restoring items not yet moved to trash: {:?}", e),
+        }
+
+        let widgets = view_output!();
+
+        dialog.add_response("cancel", &fl!("files-rename-dialog", "cancel-button"));
+        dialog.add_response("rename", &fl!("files-rename-dialog", "rename-button"));
+        dialog.set_response_appearance("rename", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("rename"));
+        dialog.set_close_response("cancel");
+
+        {
+            let name_entry = name_entry.clone();
+            dialog.connect_response(None, move |_, response| {
+                if response == "rename" {
+                    sender.input(FileOperationsInput::RenameTo(name_entry.text().into()));
+                }
+            });
+        }
+
+        let model = FileOperations {
+            parent,
+            dialog,
+            name_entry,
+            renaming: None,
+            toast_overlay,
+            repo,
+            settings_state,
+            state,
+            pending_trash: Vec::new(),
+            trash_batch: 0,
+        };
+
+        AsyncComponentParts { model, widgets }
+    }
+
+    async fn update(&mut self, msg: Self::Input, sender: AsyncComponentSender<Self>) {
+        match msg {
+            FileOperationsInput::Rename(visual) => {
+                let stem = visual
+                    .host_path()
+                    .file_stem()
+                    .map(|x| x.to_string_lossy().to_string())
+                    .unwrap_or_default();
+
+                self.name_entry.set_text(&stem);
+                self.renaming = Some(visual);
+                self.dialog.present(Some(&self.parent));
+                self.name_entry.grab_focus();
+            }
+            FileOperationsInput::RenameTo(stem) => {
+                let Some(visual) = self.renaming.take() else {
+                    return;
+                };
+
+                let stem = stem.trim().to_string();
+                if let Err(e) = ops::validate_stem(&stem) {
+                    warn!("Invalid name {:?}: {}", stem, e);
+                    self.toast(fl!("files-rename-invalid"));
+                    return;
+                }
+
+                let Some(dir) = visual.sandbox_path().parent().map(PathBuf::from) else {
+                    return;
+                };
+
+                // Unlike moving, renaming never picks a different name than the one asked for.
+                let files = ops::files_of(&visual);
+                if ops::free_stem(&files, &dir, &stem) != stem {
+                    self.toast(fl!("files-rename-exists", name = stem.clone()));
+                    return;
+                }
+
+                info!("Renaming {} to {:?}", visual.visual_id, stem);
+                let library_base_dir = self.settings_state.read().library_base_dir.clone();
+                match ops::relocate(&mut self.repo, &library_base_dir, &visual, &dir, &stem) {
+                    Ok(visual) => {
+                        let visuals = vec![Arc::new(visual)];
+                        self.replace_in_state(&visuals);
+                        let _ = sender.output(FileOperationsOutput::Relocated(visuals));
+                        let _ = sender.output(FileOperationsOutput::FilesChanged);
+                    }
+                    Err(e) => {
+                        error!("Failed renaming {}: {:?}", visual.visual_id, e);
+                        self.toast(fl!("files-rename-failed"));
+                    }
+                }
+            }
+            FileOperationsInput::MoveToFolder(visuals) => {
+                let Some(dir) = self.choose_folder().await else {
+                    return;
+                };
+
+                info!("Moving {} items to {:?}", visuals.len(), dir);
+                let library_base_dir = self.settings_state.read().library_base_dir.clone();
+                let mut repo = self.repo.clone();
+                let folder = folder_name(&dir);
+                let count = visuals.len();
+
+                let result = relm4::spawn_blocking(move || {
+                    let mut moved = vec![];
+                    for visual in visuals {
+                        let files = ops::files_of(&visual);
+                        let stem = ops::free_stem(&files, &dir, &stem_of(&visual));
+                        match ops::relocate(&mut repo, &library_base_dir, &visual, &dir, &stem) {
+                            Ok(visual) => moved.push(Arc::new(visual)),
+                            Err(e) => error!("Failed moving {}: {:?}", visual.visual_id, e),
+                        }
+                    }
+                    moved
+                })
+                .await;
+
+                let moved = result.unwrap_or_default();
+                if moved.len() == count {
+                    self.toast(fl!("files-move-done", count = count, folder = folder));
+                } else {
+                    self.toast(fl!("files-move-failed", count = count - moved.len()));
+                }
+
+                if !moved.is_empty() {
+                    self.replace_in_state(&moved);
+                    let _ = sender.output(FileOperationsOutput::Relocated(moved));
+                    let _ = sender.output(FileOperationsOutput::FilesChanged);
+                }
+            }
+            FileOperationsInput::CopyToFolder(visuals) => {
+                let Some(dir) = self.choose_folder().await else {
+                    return;
+                };
+
+                info!("Copying {} items to {:?}", visuals.len(), dir);
+                let folder = folder_name(&dir);
+                let count = visuals.len();
+
+                let result = relm4::spawn_blocking(move || {
+                    let mut failed = 0;
+                    for visual in visuals {
+                        let files = ops::files_of(&visual);
+                        let stem = ops::free_stem(&files, &dir, &stem_of(&visual));
+                        if let Err(e) = ops::copy(&visual, &dir, &stem) {
+                            error!("Failed copying {}: {:?}", visual.visual_id, e);
+                            failed += 1;
+                        }
+                    }
+                    failed
+                })
+                .await;
+
+                let failed = result.unwrap_or(count);
+                if failed == 0 {
+                    self.toast(fl!("files-copy-done", count = count, folder = folder));
+                } else {
+                    self.toast(fl!("files-copy-failed", count = failed));
+                }
+
+                if failed < count {
+                    // Copies are added to the library by a library scan.
+                    let _ = sender.output(FileOperationsOutput::FilesChanged);
+                }
+            }
+            FileOperationsInput::Trash(visuals) => {
+                if visuals.is_empty() {
+                    return;
+                }
+
+                info!("Moving {} items to trash", visuals.len());
+                for visual in visuals.iter() {
+                    if let Err(e) = self.repo.mark_trashed(visual.picture_id, visual.video_id) {
+                        error!("Failed hiding {}: {:?}", visual.visual_id, e);
+                    }
+                }
+
+                self.state
+                    .write()
+                    .retain(|v| !visuals.iter().any(|t| t.visual_id == v.visual_id));
+
+                self.trash_batch += 1;
+                let batch = self.trash_batch;
+
+                let toast = adw::Toast::builder()
+                    .title(fl!("files-trash-done", count = visuals.len()))
+                    .button_label(fl!("files-trash-done", "undo-button"))
+                    .priority(adw::ToastPriority::High)
+                    .build();
+
+                {
+                    let sender = sender.clone();
+                    toast.connect_button_clicked(move |_| {
+                        sender.input(FileOperationsInput::UndoTrash(batch));
+                    });
+                }
+
+                {
+                    // Dismissed after the undo button is clicked too, but then the
+                    // batch is no longer pending.
+                    let sender = sender.clone();
+                    toast.connect_dismissed(move |_| {
+                        sender.input(FileOperationsInput::CommitTrash(batch));
+                    });
+                }
+
+                self.pending_trash.push((batch, visuals));
+                self.toast_overlay.add_toast(toast);
+
+                let _ = sender.output(FileOperationsOutput::Refresh);
+            }
+            FileOperationsInput::UndoTrash(batch) => {
+                let Some(visuals) = self.take_pending_trash(batch) else {
+                    return;
+                };
+
+                info!("Restoring {} items from trash", visuals.len());
+                self.restore(visuals);
+                let _ = sender.output(FileOperationsOutput::Refresh);
+            }
+            FileOperationsInput::CommitTrash(batch) => {
+                let Some(visuals) = self.take_pending_trash(batch) else {
+                    return;
+                };
+
+                let result = relm4::spawn_blocking(move || {
+                    visuals
+                        .into_iter()
+                        .filter(|visual| match ops::trash(visual) {
+                            Ok(()) => false,
+                            Err(e) => {
+                                error!("Failed moving {} to trash: {:?}", visual.visual_id, e);
+                                true
+                            }
+                        })
+                        .collect::<Vec<Arc<Visual>>>()
+                })
+                .await;
+
+                match result {
+                    Ok(failed) if !failed.is_empty() => {
+                        self.toast(fl!("files-trash-failed", count = failed.len()));
+                        self.restore(failed);
+                        let _ = sender.output(FileOperationsOutput::Refresh);
+                    }
+                    Ok(_) => {}
+                    Err(e) => error!("Failed moving items to trash: {:?}", e),
+                }
+
+                // Rows of trashed files are removed by the clean tasks.
+                let _ = sender.output(FileOperationsOutput::FilesChanged);
+            }
+        }
+    }
+
+    fn shutdown(&mut self, _widgets: &mut Self::Widgets, _output: relm4::Sender<Self::Output>) {
+        // Exiting can no longer be undone, so don't leave files that were
+        // moved to the trash in the library.
+        for (_, visuals) in self.pending_trash.drain(..) {
+            for visual in visuals {
+                if let Err(e) = ops::trash(&visual) {
+                    error!("Failed moving {} to trash: {:?}", visual.visual_id, e);
+                }
+            }
+        }
+    }
+}
+
+impl FileOperations {
+    fn toast(&self, title: String) {
+        self.toast_overlay.add_toast(adw::Toast::new(&title));
+    }
+
+    fn take_pending_trash(&mut self, batch: u64) -> Option<Vec<Arc<Visual>>> {
+        let index = self.pending_trash.iter().position(|(id, _)| *id == batch)?;
+        Some(self.pending_trash.remove(index).1)
+    }
+
+    /// Show hidden items again.
+    fn restore(&mut self, visuals: Vec<Arc<Visual>>) {
+        for visual in visuals.iter() {
+            if let Err(e) = self.repo.unmark_trashed(visual.picture_id, visual.video_id) {
+                error!("Failed showing {}: {:?}", visual.visual_id, e);
+            }
+        }
+
+        let mut items = self.state.write();
+        items.extend(visuals);
+        items.sort_by_key(|v| v.sort_key());
+    }
+
+    /// Replace items in the library with renamed or moved items.
+    fn replace_in_state(&self, visuals: &[Arc<Visual>]) {
+        let mut items = self.state.write();
+        for visual in visuals {
+            if let Some(item) = items.iter_mut().find(|v| v.visual_id == visual.visual_id) {
+                *item = visual.clone();
+            }
+        }
+    }
+
+    /// Ask the user for a folder in the library.
+    /// Returns the path of the folder inside the sandbox.
+    async fn choose_folder(&self) -> Option<PathBuf> {
+        let identifier = WindowIdentifier::from_native(&self.parent).await;
+        let request = OpenFileRequest::default()
+            .directory(true)
+            .identifier(identifier)
+            .modal(true)
+            .multiple(false);
+
+        let dir = match request.send().await.and_then(|r| r.response()) {
+            Ok(files) => files.uris().first().and_then(|uri| {
+                glib::Uri::parse(uri.as_str(), glib::UriFlags::NONE)
+                    .map(|glib_uri| PathBuf::from(glib_uri.path()))
+                    .ok()
+            })?,
+            Err(err) => {
+                error!("Failed to choose folder: {err}");
+                return None;
+            }
+        };
+
+        // The chosen folder is likely exported to the sandbox with a different path
+        // than the library, so compare host paths.
+        let dir = host_path::host_path(&dir).await?;
+        let library_base_dir = self.settings_state.read().library_base_dir.clone();
+        match dir.host_path.strip_prefix(&library_base_dir.host_path) {
+            Ok(relative_dir) => Some(library_base_dir.sandbox_path.join(relative_dir)),
+            Err(_) => {
+                warn!("{:?} is not in the library", dir.host_path);
+                self.toast(fl!("files-folder-outside-library"));
+                None
+            }
+        }
+    }
+}
+
+/// Name of an item without an extension.
+fn stem_of(visual: &Visual) -> String {
+    visual
+        .sandbox_path()
+        .file_stem()
+        .map(|x| x.to_string_lossy().to_string())
+        .unwrap_or_else(|| visual.visual_id.to_string())
+}
+
+fn folder_name(dir: &Path) -> String {
+    dir.file_name()
+        .map(|x| x.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
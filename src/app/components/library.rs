@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use fotema_core::{VisualId, YearMonth};
+use fotema_core::{Visual, VisualId, YearMonth};
 
 use relm4::adw;
 use relm4::*;
@@ -16,9 +16,10 @@ use crate::app::ActiveView;
 use crate::app::SharedState;
 use crate::app::ViewName;
 use crate::app::adaptive;
+use crate::app::background::thumbnail_priority_task::ThumbnailPriorityTaskInput;
 use crate::fl;
 
-use super::albums::album::{Album, AlbumInput, AlbumOutput};
+use super::albums::album::{Album, AlbumInput, AlbumOutput, BatchAction};
 use super::albums::album_filter::AlbumFilter;
 use super::albums::album_sort::AlbumSort;
 use super::albums::months_album::{MonthsAlbum, MonthsAlbumInput, MonthsAlbumOutput};
@@ -48,11 +49,19 @@ pub enum LibraryInput {
     View(VisualId),
 
     Sort(AlbumSort),
+
+    // Start selecting items
+    Select,
+
+    // Apply an action to selected items
+    Batch(BatchAction, Vec<Arc<Visual>>),
 }
 
 #[derive(Debug)]
 pub enum LibraryOutput {
     View(VisualId),
+
+    Batch(BatchAction, Vec<Arc<Visual>>),
 }
 
 pub struct Library {
@@ -80,6 +89,7 @@ impl SimpleComponent for Library {
         ActiveView,
         Arc<adaptive::LayoutState>,
         Rc<Thumbnailer>,
+        relm4::Sender<ThumbnailPriorityTaskInput>,
     );
     type Input = LibraryInput;
     type Output = LibraryOutput;
@@ -94,7 +104,7 @@ impl SimpleComponent for Library {
     }
 
     fn init(
-        (state, active_view, layout_state, thumbnailer): Self::Init,
+        (state, active_view, layout_state, thumbnailer, priority_sender): Self::Init,
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
@@ -105,10 +115,12 @@ impl SimpleComponent for Library {
                 ViewName::All,
                 AlbumFilter::All,
                 thumbnailer.clone(),
+                priority_sender,
             ))
             .forward(sender.input_sender(), |msg| match msg {
                 AlbumOutput::Selected(id, _) => LibraryInput::View(id),
                 AlbumOutput::ScrollOffset(_) => LibraryInput::Ignore,
+                AlbumOutput::Batch(action, visuals) => LibraryInput::Batch(action, visuals),
             });
 
         state.subscribe(all_album.sender(), |_| AlbumInput::Refresh);
@@ -184,6 +196,15 @@ impl SimpleComponent for Library {
             LibraryInput::View(id) => {
                 let _ = sender.output(LibraryOutput::View(id));
             }
+            LibraryInput::Select => {
+                // Only the all photos view has items to select.
+                self.stack
+                    .set_visible_child_name(LibraryViewName::All.as_ref());
+                self.all_album.emit(AlbumInput::SelectionMode(true));
+            }
+            LibraryInput::Batch(action, visuals) => {
+                let _ = sender.output(LibraryOutput::Batch(action, visuals));
+            }
             LibraryInput::Sort(sort) => {
                 self.all_album.emit(AlbumInput::Sort(sort));
                 self.months_album.emit(MonthsAlbumInput::Sort(sort));
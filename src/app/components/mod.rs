@@ -4,9 +4,19 @@
 
 pub mod about;
 pub mod albums;
+pub mod assign_person;
+pub mod export;
+pub mod file_operations;
 pub mod library;
 pub mod onboard;
+pub mod photo_edit;
+pub mod pin_location;
 pub mod preferences;
+pub mod private_zone;
+pub mod problems;
 pub mod progress_monitor;
 pub mod progress_panel;
+pub mod set_date;
+pub mod slideshow;
+pub mod track_import;
 pub mod viewer;
@@ -0,0 +1,617 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use ashpd::{WindowIdentifier, desktop::file_chooser::SaveFileRequest};
+use relm4::adw::prelude::*;
+use relm4::gtk;
+use relm4::gtk::gdk;
+use relm4::gtk::gio;
+use relm4::gtk::glib;
+use relm4::prelude::*;
+
+use tracing::{error, info};
+
+use crate::fl;
+use fotema_core::Visual;
+use fotema_core::edits::{self, Adjustments, AspectRatio, EditStack};
+use fotema_core::orientation;
+use fotema_core::thumbnailify::{ThumbnailSize, Thumbnailer};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const PREVIEW_SIZE: i32 = 360;
+
+#[derive(Debug)]
+pub enum PhotoEditInput {
+    /// Show the dialog to edit a picture.
+    Present(Arc<Visual>),
+
+    AspectRatio(u32),
+    RotateLeft,
+    RotateRight,
+    FlipHorizontal,
+    FlipVertical,
+    Straighten(f64),
+    Exposure(f64),
+    Contrast(f64),
+    Saturation(f64),
+    AutoEnhance(bool),
+
+    /// Save edits and close.
+    Save,
+
+    /// Ask before discarding all edits.
+    Revert,
+
+    /// Discard all edits and close.
+    ConfirmRevert,
+
+    /// Choose where to save a copy of the picture with edits applied, then save it.
+    Export,
+
+    /// Saving a copy of the picture with edits applied failed.
+    ExportFailed,
+}
+
+#[derive(Debug)]
+pub enum PhotoEditOutput {
+    /// Edits of a picture have been saved or reverted.
+    Edited(Arc<Visual>),
+}
+
+/// Dialog for cropping, rotating, and adjusting a picture. Edits are saved to the
+/// database and rendered when the picture is shown, so the original file is untouched.
+pub struct PhotoEditDialog {
+    parent: adw::ApplicationWindow,
+    dialog: adw::Dialog,
+
+    edits_repo: edits::Repository,
+
+    thumbnailer: Thumbnailer,
+
+    toast_overlay: adw::ToastOverlay,
+
+    /// Picture being edited.
+    visual: Option<Arc<Visual>>,
+
+    adjustments: Adjustments,
+
+    /// Unedited picture, scaled down, to render previews from.
+    preview_source: Option<gdk::Texture>,
+
+    preview: gtk::Picture,
+
+    aspect_ratio: adw::ComboRow,
+    straighten: gtk::Scale,
+    exposure: gtk::Scale,
+    contrast: gtk::Scale,
+    saturation: gtk::Scale,
+    auto_enhance: adw::SwitchRow,
+}
+
+#[relm4::component(pub async)]
+impl SimpleAsyncComponent for PhotoEditDialog {
+    type Init = (
+        edits::Repository,
+        Thumbnailer,
+        adw::ApplicationWindow,
+        adw::ToastOverlay,
+    );
+    type Input = PhotoEditInput;
+    type Output = PhotoEditOutput;
+
+    view! {
+        adw::Dialog {
+            set_title: &fl!("photo-edit-title"),
+            set_content_width: 480,
+            set_content_height: 720,
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {
+                    set_show_end_title_buttons: false,
+                    set_show_start_title_buttons: false,
+
+                    pack_start = &gtk::Button {
+                        set_label: &fl!("photo-edit-cancel"),
+                        connect_clicked[dialog] => move |_| {
+                            dialog.close();
+                        },
+                    },
+
+                    pack_end = &gtk::Button {
+                        set_label: &fl!("photo-edit-save"),
+                        add_css_class: "suggested-action",
+                        connect_clicked => PhotoEditInput::Save,
+                    },
+                },
+
+                #[wrap(Some)]
+                set_content = &gtk::ScrolledWindow {
+                    set_hscrollbar_policy: gtk::PolicyType::Never,
+
+                    #[wrap(Some)]
+                    set_child = &adw::Clamp {
+                        #[wrap(Some)]
+                        set_child = &gtk::Box {
+                            set_orientation: gtk::Orientation::Vertical,
+                            set_spacing: 18,
+                            set_margin_all: 12,
+
+                            #[local_ref]
+                            preview -> gtk::Picture {
+                                set_height_request: PREVIEW_SIZE,
+                                set_content_fit: gtk::ContentFit::Contain,
+                            },
+
+                            adw::PreferencesGroup {
+                                set_title: &fl!("photo-edit-geometry"),
+
+                                #[local_ref]
+                                aspect_ratio_row -> adw::ComboRow {
+                                    set_title: &fl!("photo-edit-crop"),
+
+                                    connect_selected_notify[sender] => move |row| {
+                                        sender.input(PhotoEditInput::AspectRatio(row.selected()));
+                                    },
+                                },
+
+                                adw::ActionRow {
+                                    set_title: &fl!("photo-edit-rotate"),
+
+                                    add_suffix = &gtk::Button {
+                                        set_icon_name: "object-rotate-left-symbolic",
+                                        set_tooltip_text: Some(&fl!("photo-edit-rotate", "left")),
+                                        set_valign: gtk::Align::Center,
+                                        add_css_class: "flat",
+                                        connect_clicked => PhotoEditInput::RotateLeft,
+                                    },
+
+                                    add_suffix = &gtk::Button {
+                                        set_icon_name: "object-rotate-right-symbolic",
+                                        set_tooltip_text: Some(&fl!("photo-edit-rotate", "right")),
+                                        set_valign: gtk::Align::Center,
+                                        add_css_class: "flat",
+                                        connect_clicked => PhotoEditInput::RotateRight,
+                                    },
+                                },
+
+                                adw::ActionRow {
+                                    set_title: &fl!("photo-edit-flip"),
+
+                                    add_suffix = &gtk::Button {
+                                        set_icon_name: "object-flip-horizontal-symbolic",
+                                        set_tooltip_text: Some(&fl!("photo-edit-flip", "horizontal")),
+                                        set_valign: gtk::Align::Center,
+                                        add_css_class: "flat",
+                                        connect_clicked => PhotoEditInput::FlipHorizontal,
+                                    },
+
+                                    add_suffix = &gtk::Button {
+                                        set_icon_name: "object-flip-vertical-symbolic",
+                                        set_tooltip_text: Some(&fl!("photo-edit-flip", "vertical")),
+                                        set_valign: gtk::Align::Center,
+                                        add_css_class: "flat",
+                                        connect_clicked => PhotoEditInput::FlipVertical,
+                                    },
+                                },
+
+                                adw::ActionRow {
+                                    set_title: &fl!("photo-edit-straighten"),
+
+                                    add_suffix: &straighten_scale,
+                                },
+                            },
+
+                            adw::PreferencesGroup {
+                                set_title: &fl!("photo-edit-light"),
+
+                                #[local_ref]
+                                auto_enhance_row -> adw::SwitchRow {
+                                    set_title: &fl!("photo-edit-auto-enhance"),
+                                    set_subtitle: &fl!("photo-edit-auto-enhance", "subtitle"),
+
+                                    connect_active_notify[sender] => move |row| {
+                                        sender.input(PhotoEditInput::AutoEnhance(row.is_active()));
+                                    },
+                                },
+
+                                adw::ActionRow {
+                                    set_title: &fl!("photo-edit-exposure"),
+
+                                    add_suffix: &exposure_scale,
+                                },
+
+                                adw::ActionRow {
+                                    set_title: &fl!("photo-edit-contrast"),
+
+                                    add_suffix: &contrast_scale,
+                                },
+
+                                adw::ActionRow {
+                                    set_title: &fl!("photo-edit-saturation"),
+
+                                    add_suffix: &saturation_scale,
+                                },
+                            },
+
+                            adw::PreferencesGroup {
+                                adw::ButtonRow {
+                                    set_title: &fl!("photo-edit-export"),
+                                    set_start_icon_name: Some("document-save-as-symbolic"),
+                                    connect_activated => PhotoEditInput::Export,
+                                },
+
+                                adw::ButtonRow {
+                                    set_title: &fl!("photo-edit-revert"),
+                                    add_css_class: "destructive-action",
+
+                                    #[watch]
+                                    set_sensitive: model.visual.as_ref().is_some_and(|v| v.is_edited()),
+
+                                    connect_activated => PhotoEditInput::Revert,
+                                },
+                            },
+                        },
+                    },
+                },
+            },
+        }
+    }
+
+    async fn init(
+        (edits_repo, thumbnailer, parent, toast_overlay): Self::Init,
+        dialog: Self::Root,
+        sender: AsyncComponentSender<Self>,
+    ) -> AsyncComponentParts<Self> {
+        let preview = gtk::Picture::new();
+
+        let aspect_ratio_row = adw::ComboRow::new();
+        let list = gtk::StringList::new(&[
+            &fl!("photo-edit-crop", "original"),
+            &fl!("photo-edit-crop", "square"),
+            &fl!("photo-edit-crop", "four-three"),
+            &fl!("photo-edit-crop", "three-two"),
+            &fl!("photo-edit-crop", "sixteen-nine"),
+        ]);
+        aspect_ratio_row.set_model(Some(&list));
+
+        // Straightening is for small corrections. Bigger turns are in quarters.
+        let straighten_scale = adjustment_scale(-45.0, 45.0, 0.5);
+        straighten_scale.set_draw_value(true);
+        {
+            let sender = sender.clone();
+            straighten_scale.connect_value_changed(move |scale| {
+                sender.input(PhotoEditInput::Straighten(scale.value()));
+            });
+        }
+
+        // Stops of exposure.
+        let exposure_scale = adjustment_scale(-2.0, 2.0, 0.1);
+        {
+            let sender = sender.clone();
+            exposure_scale.connect_value_changed(move |scale| {
+                sender.input(PhotoEditInput::Exposure(scale.value()));
+            });
+        }
+
+        let contrast_scale = adjustment_scale(-1.0, 1.0, 0.05);
+        {
+            let sender = sender.clone();
+            contrast_scale.connect_value_changed(move |scale| {
+                sender.input(PhotoEditInput::Contrast(scale.value()));
+            });
+        }
+
+        let saturation_scale = adjustment_scale(-1.0, 1.0, 0.05);
+        {
+            let sender = sender.clone();
+            saturation_scale.connect_value_changed(move |scale| {
+                sender.input(PhotoEditInput::Saturation(scale.value()));
+            });
+        }
+
+        let auto_enhance_row = adw::SwitchRow::new();
+
+        let model = PhotoEditDialog {
+            parent,
+            dialog: dialog.clone(),
+            edits_repo,
+            thumbnailer,
+            toast_overlay,
+            visual: None,
+            adjustments: Adjustments::default(),
+            preview_source: None,
+            preview: preview.clone(),
+            aspect_ratio: aspect_ratio_row.clone(),
+            straighten: straighten_scale.clone(),
+            exposure: exposure_scale.clone(),
+            contrast: contrast_scale.clone(),
+            saturation: saturation_scale.clone(),
+            auto_enhance: auto_enhance_row.clone(),
+        };
+
+        let widgets = view_output!();
+        AsyncComponentParts { model, widgets }
+    }
+
+    async fn update(&mut self, msg: Self::Input, sender: AsyncComponentSender<Self>) {
+        match msg {
+            PhotoEditInput::Present(visual) => {
+                let Some(source) = self.load_preview_source(&visual).await else {
+                    error!("Failed loading picture to edit: {:?}", visual.path());
+                    return;
+                };
+
+                let (width, height) = (source.width() as u32, source.height() as u32);
+                self.adjustments = visual
+                    .picture_edits
+                    .as_ref()
+                    .map(|stack| Adjustments::from_stack(stack, width, height))
+                    .unwrap_or_default();
+
+                self.preview_source = Some(source);
+                self.visual = Some(visual);
+
+                // Show the saved edits. Changing the controls renders the preview.
+                let adjustments = self.adjustments;
+                let aspect_ratio = AspectRatio::ALL
+                    .iter()
+                    .position(|a| *a == adjustments.aspect_ratio)
+                    .unwrap_or_default();
+                self.aspect_ratio.set_selected(aspect_ratio as u32);
+                self.straighten.set_value(adjustments.straighten.into());
+                self.exposure.set_value(adjustments.exposure.into());
+                self.contrast.set_value(adjustments.contrast.into());
+                self.saturation.set_value(adjustments.saturation.into());
+                self.auto_enhance.set_active(adjustments.auto_enhance);
+                self.render_preview();
+
+                self.dialog.present(Some(&self.parent));
+            }
+            PhotoEditInput::AspectRatio(index) => {
+                self.adjustments.aspect_ratio = AspectRatio::ALL
+                    .get(index as usize)
+                    .copied()
+                    .unwrap_or_default();
+                self.render_preview();
+            }
+            PhotoEditInput::RotateLeft => {
+                self.adjustments.quarter_turns = (self.adjustments.quarter_turns + 3) % 4;
+                self.render_preview();
+            }
+            PhotoEditInput::RotateRight => {
+                self.adjustments.quarter_turns = (self.adjustments.quarter_turns + 1) % 4;
+                self.render_preview();
+            }
+            PhotoEditInput::FlipHorizontal => {
+                self.adjustments.flip_horizontal = !self.adjustments.flip_horizontal;
+                self.render_preview();
+            }
+            PhotoEditInput::FlipVertical => {
+                self.adjustments.flip_vertical = !self.adjustments.flip_vertical;
+                self.render_preview();
+            }
+            PhotoEditInput::Straighten(degrees) => {
+                self.adjustments.straighten = degrees as f32;
+                self.render_preview();
+            }
+            PhotoEditInput::Exposure(stops) => {
+                self.adjustments.exposure = stops as f32;
+                self.render_preview();
+            }
+            PhotoEditInput::Contrast(amount) => {
+                self.adjustments.contrast = amount as f32;
+                self.render_preview();
+            }
+            PhotoEditInput::Saturation(amount) => {
+                self.adjustments.saturation = amount as f32;
+                self.render_preview();
+            }
+            PhotoEditInput::AutoEnhance(is_active) => {
+                self.adjustments.auto_enhance = is_active;
+                self.render_preview();
+            }
+            PhotoEditInput::Save => {
+                let (Some(visual), Some(stack)) = (self.visual.as_ref(), self.stack()) else {
+                    return;
+                };
+
+                let Some(picture_id) = visual.picture_id else {
+                    return;
+                };
+
+                info!("Saving edits of {:?}: {}", visual.path(), stack);
+                if let Err(e) = self.edits_repo.save(picture_id, &stack) {
+                    error!("Failed saving edits: {:?}", e);
+                    return;
+                }
+
+                let mut edited = Visual::clone(visual);
+                edited.picture_edits = Some(stack).filter(|stack| !stack.is_empty());
+                let _ = sender.output(PhotoEditOutput::Edited(Arc::new(edited)));
+
+                self.dialog.close();
+            }
+            PhotoEditInput::Revert => {
+                let dialog = adw::AlertDialog::builder()
+                    .heading(fl!("photo-edit-revert", "confirm-heading"))
+                    .body(fl!("photo-edit-revert", "confirm-body"))
+                    .build();
+
+                dialog.add_response("cancel", &fl!("photo-edit-revert", "cancel"));
+                dialog.set_default_response(Some("cancel"));
+                dialog.set_close_response("cancel");
+
+                dialog.add_response("revert", &fl!("photo-edit-revert", "revert"));
+                dialog.set_response_appearance("revert", adw::ResponseAppearance::Destructive);
+
+                let sender = sender.clone();
+                dialog.connect_response(None, move |_, response| {
+                    if response == "revert" {
+                        sender.input(PhotoEditInput::ConfirmRevert);
+                    }
+                });
+
+                dialog.present(Some(&self.dialog));
+            }
+            PhotoEditInput::ConfirmRevert => {
+                let Some(visual) = self.visual.as_ref() else {
+                    return;
+                };
+
+                let Some(picture_id) = visual.picture_id else {
+                    return;
+                };
+
+                info!("Reverting edits of {:?}", visual.path());
+                if let Err(e) = self.edits_repo.revert(picture_id) {
+                    error!("Failed reverting edits: {:?}", e);
+                    return;
+                }
+
+                let mut reverted = Visual::clone(visual);
+                reverted.picture_edits = None;
+                let _ = sender.output(PhotoEditOutput::Edited(Arc::new(reverted)));
+
+                self.dialog.close();
+            }
+            PhotoEditInput::Export => {
+                let (Some(visual), Some(stack)) = (self.visual.clone(), self.stack()) else {
+                    return;
+                };
+
+                let file_name = visual
+                    .host_path()
+                    .file_stem()
+                    .map(|x| x.to_string_lossy().to_string())
+                    .unwrap_or_else(|| visual.visual_id.to_string());
+                let file_name = format!("{}-{}.jpg", file_name, fl!("photo-edit-export", "suffix"));
+
+                let Some(output_path) = self.choose_export_path(&file_name).await else {
+                    return;
+                };
+
+                // Edits are rendered onto the full size picture, which must be upright.
+                let Some(image) = load_upright(&visual)
+                    .await
+                    .and_then(|texture| edits::texture::to_image(&texture))
+                else {
+                    error!("Failed loading {:?} to export", visual.path());
+                    sender.input(PhotoEditInput::ExportFailed);
+                    return;
+                };
+
+                info!(
+                    "Exporting edited copy of {:?} to {:?}",
+                    visual.path(),
+                    output_path
+                );
+
+                // Don't wait for the export so the dialog stays responsive.
+                relm4::spawn_blocking(move || {
+                    let image = edits::render(image, &stack);
+                    if let Err(e) = edits::save_copy(image, &output_path) {
+                        error!("Failed exporting edited copy: {:?}", e);
+                        sender.input(PhotoEditInput::ExportFailed);
+                    }
+                });
+            }
+            PhotoEditInput::ExportFailed => {
+                let toast = adw::Toast::new(&fl!("photo-edit-export-failed"));
+                self.toast_overlay.add_toast(toast);
+            }
+        }
+    }
+}
+
+impl PhotoEditDialog {
+    /// Edit stack for the current settings, relative to the unedited picture.
+    fn stack(&self) -> Option<EditStack> {
+        let source = self.preview_source.as_ref()?;
+        Some(
+            self.adjustments
+                .to_stack(source.width() as u32, source.height() as u32),
+        )
+    }
+
+    fn render_preview(&self) {
+        let (Some(source), Some(stack)) = (self.preview_source.as_ref(), self.stack()) else {
+            return;
+        };
+
+        // Previews are small enough to render without leaving the main thread.
+        let texture = edits::texture::to_image(source)
+            .map(|image| edits::texture::to_texture(edits::render(image, &stack)));
+        self.preview.set_paintable(texture.as_ref());
+    }
+
+    /// Largest thumbnail of the unedited picture, or the picture itself if there
+    /// is no thumbnail.
+    async fn load_preview_source(&self, visual: &Visual) -> Option<gdk::Texture> {
+        let hash = visual.unedited_thumbnail_hash();
+        if let Some(path) = self
+            .thumbnailer
+            .nearest_thumbnail(&hash, ThumbnailSize::XLarge)
+            && let Ok(texture) = gdk::Texture::from_filename(&path)
+        {
+            return Some(texture);
+        }
+
+        load_upright(visual).await
+    }
+
+    /// Ask the user where to save an edited copy.
+    async fn choose_export_path(&self, file_name: &str) -> Option<PathBuf> {
+        let identifier = WindowIdentifier::from_native(&self.parent).await;
+
+        let request = SaveFileRequest::default()
+            .identifier(identifier)
+            .modal(true)
+            .current_name(file_name);
+
+        match request.send().await.and_then(|r| r.response()) {
+            Ok(files) => files.uris().first().and_then(|uri| {
+                glib::Uri::parse(uri.as_str(), glib::UriFlags::NONE)
+                    .map(|glib_uri| PathBuf::from(glib_uri.path()))
+                    .ok()
+            }),
+            Err(err) => {
+                error!("Failed to choose export file: {err}");
+                None
+            }
+        }
+    }
+}
+
+/// Load a picture with the orientation chosen by the user, or else its EXIF orientation, applied.
+async fn load_upright(visual: &Visual) -> Option<gdk::Texture> {
+    let orientation = visual.picture_orientation_override;
+
+    let file = gio::File::for_path(visual.sandbox_path());
+    let mut loader = glycin::Loader::new(file);
+    loader.apply_transformations(orientation.is_none());
+
+    let image = loader.load().await.ok()?;
+    let frame = image.next_frame().await.ok()?;
+
+    let Some(orientation) = orientation else {
+        return Some(frame.texture());
+    };
+
+    let image = edits::texture::to_image(&frame.texture())?;
+    let image = relm4::spawn_blocking(move || orientation::transform(image, orientation))
+        .await
+        .ok()?;
+    Some(edits::texture::to_texture(image))
+}
+
+/// Slider for an adjustment, marked at zero for no change.
+fn adjustment_scale(min: f64, max: f64, step: f64) -> gtk::Scale {
+    let scale = gtk::Scale::with_range(gtk::Orientation::Horizontal, min, max, step);
+    scale.set_hexpand(true);
+    scale.set_value(0.0);
+    scale.add_mark(0.0, gtk::PositionType::Bottom, None);
+    scale
+}
@@ -0,0 +1,177 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use relm4::adw::prelude::*;
+use relm4::gtk;
+use relm4::prelude::*;
+
+use shumate::MAP_SOURCE_OSM_MAPNIK;
+use shumate::prelude::*;
+
+use tracing::{error, info};
+
+use crate::fl;
+use fotema_core::Visual;
+use fotema_core::places::GeoTarget;
+use h3o::LatLng;
+use std::sync::Arc;
+
+/// Zoom level when the map is centred on an existing location.
+const LOCATED_ZOOM_LEVEL: f64 = 14.0;
+
+/// Zoom level when there is no location to centre the map on.
+const DEFAULT_ZOOM_LEVEL: f64 = 3.0;
+
+const PIN_SIZE: i32 = 32;
+
+#[derive(Debug)]
+pub enum PinLocationInput {
+    /// Show the dialog to set the location of items.
+    Present(Vec<Arc<Visual>>),
+
+    /// Set location of items to the centre of the map.
+    Save,
+}
+
+#[derive(Debug)]
+pub enum PinLocationOutput {
+    /// Set the location of pictures and videos.
+    Pinned(Vec<GeoTarget>, LatLng),
+}
+
+/// Dialog for dropping a pin on a map to set the location of pictures and videos.
+/// The pin stays at the centre of the map and the user moves the map under it.
+pub struct PinLocationDialog {
+    parent: adw::ApplicationWindow,
+    dialog: adw::Dialog,
+    viewport: shumate::Viewport,
+
+    /// Items to set location of.
+    targets: Vec<GeoTarget>,
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for PinLocationDialog {
+    type Init = adw::ApplicationWindow;
+    type Input = PinLocationInput;
+    type Output = PinLocationOutput;
+
+    view! {
+        adw::Dialog {
+            set_title: &fl!("pin-location-title"),
+            set_content_width: 640,
+            set_content_height: 480,
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {
+                    set_show_end_title_buttons: false,
+                    set_show_start_title_buttons: false,
+
+                    pack_start = &gtk::Button {
+                        set_label: &fl!("pin-location-cancel"),
+                        connect_clicked[dialog] => move |_| {
+                            dialog.close();
+                        },
+                    },
+
+                    pack_end = &gtk::Button {
+                        set_label: &fl!("pin-location-save"),
+                        add_css_class: "suggested-action",
+                        connect_clicked => PinLocationInput::Save,
+                    },
+                },
+
+                #[wrap(Some)]
+                set_content = &gtk::Overlay {
+                    #[local_ref]
+                    map_widget -> shumate::SimpleMap {
+                        set_vexpand: true,
+                        set_hexpand: true,
+                    },
+
+                    // Tip of pin marks the centre of the map.
+                    add_overlay = &gtk::Image {
+                        set_icon_name: Some("mark-location-symbolic"),
+                        set_pixel_size: PIN_SIZE,
+                        set_halign: gtk::Align::Center,
+                        set_valign: gtk::Align::Center,
+                        set_margin_bottom: PIN_SIZE,
+                        set_can_target: false,
+                        add_css_class: "accent",
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        parent: Self::Init,
+        dialog: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let map_widget = shumate::SimpleMap::builder().build();
+
+        if let Some(scale) = map_widget.scale() {
+            scale.set_unit(shumate::Unit::Metric);
+        }
+
+        // Use OpenStreetMap as the source
+        let registry = shumate::MapSourceRegistry::with_defaults();
+        let map_source = registry.by_id(MAP_SOURCE_OSM_MAPNIK);
+        map_widget.set_map_source(map_source.as_ref());
+
+        let viewport = map_widget.viewport().unwrap();
+        viewport.set_reference_map_source(map_source.as_ref());
+        viewport.set_zoom_level(DEFAULT_ZOOM_LEVEL);
+
+        let model = PinLocationDialog {
+            parent,
+            dialog: dialog.clone(),
+            viewport,
+            targets: Vec::new(),
+        };
+
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        match msg {
+            PinLocationInput::Present(visuals) => {
+                // The picture part of a live photo is where its location is stored.
+                self.targets = visuals
+                    .iter()
+                    .filter_map(|v| {
+                        v.picture_id
+                            .map(GeoTarget::Picture)
+                            .or_else(|| v.video_id.map(GeoTarget::Video))
+                    })
+                    .collect();
+
+                // Start from an existing location if there is one.
+                if let Some(location) = visuals.iter().find_map(|v| v.location) {
+                    self.viewport.set_location(location.lat(), location.lng());
+                    self.viewport.set_zoom_level(LOCATED_ZOOM_LEVEL);
+                }
+
+                self.dialog.present(Some(&self.parent));
+            }
+            PinLocationInput::Save => {
+                let location = LatLng::new(self.viewport.latitude(), self.viewport.longitude());
+                match location {
+                    Ok(location) => {
+                        info!("Pinning {} items to {}", self.targets.len(), location);
+                        let targets = std::mem::take(&mut self.targets);
+                        let _ = sender.output(PinLocationOutput::Pinned(targets, location));
+                    }
+                    Err(e) => {
+                        error!("Invalid map location: {}", e);
+                    }
+                }
+                self.dialog.close();
+            }
+        }
+    }
+}
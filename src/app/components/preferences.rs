@@ -14,18 +14,44 @@ use tracing::{error, info};
 
 use crate::app::AlbumSort;
 use crate::app::FaceDetectionMode;
+use crate::app::PrivacyState;
 use crate::app::{Settings, SettingsState};
 use crate::fl;
 use crate::host_path;
 use fotema_core::FlatpakPathBuf;
+use fotema_core::privacy::{ExportLocation, ZoneId};
+use fotema_core::thumbnailify::Thumbnailer;
+use fotema_core::thumbnailify::maintenance::{self, AreaUsage, CacheArea};
+use fotema_core::video::TranscodeProfile;
 use std::path::PathBuf;
 
 pub struct PreferencesDialog {
     parent: adw::ApplicationWindow,
     dialog: adw::PreferencesDialog,
     album_sort: adw::ComboRow,
+    export_location: adw::ComboRow,
+    transcode_profile: adw::ComboRow,
+    video_cache_limit: adw::SpinRow,
+    background_threads: adw::SpinRow,
+    slideshow_interval: adw::SpinRow,
+
+    /// Group listing private zones
+    privacy_group: adw::PreferencesGroup,
+
+    /// One row per private zone
+    zone_rows: Vec<adw::ActionRow>,
+
+    /// For reporting thumbnail disk usage.
+    thumbnailer: Thumbnailer,
+
+    /// Total thumbnail disk usage, expanding to usage per area.
+    thumbnail_usage: adw::ExpanderRow,
+
+    /// One row per thumbnail cache area
+    thumbnail_usage_rows: Vec<adw::ActionRow>,
 
     settings_state: SettingsState,
+    privacy_state: PrivacyState,
 
     // Preference values
     settings: Settings,
@@ -43,6 +69,62 @@ impl PreferencesDialog {
             .to_string_lossy()
             .to_string()
     }
+
+    fn update_zone_rows(&mut self, sender: &AsyncComponentSender<Self>) {
+        for row in self.zone_rows.drain(..) {
+            self.privacy_group.remove(&row);
+        }
+
+        for zone in self.privacy_state.read().zones.iter() {
+            let row = adw::ActionRow::builder().title(&zone.name).build();
+
+            let zone_id = zone.zone_id;
+            let remove_button = gtk::Button::builder()
+                .valign(gtk::Align::Center)
+                .icon_name("user-trash-symbolic")
+                .tooltip_text(fl!("prefs-privacy-zone", "remove-tooltip"))
+                .css_classes(["flat"])
+                .build();
+
+            let sender = sender.clone();
+            remove_button.connect_clicked(move |_| {
+                let _ = sender.output(PreferencesOutput::RemovePrivateZone(zone_id));
+            });
+
+            row.add_suffix(&remove_button);
+            self.privacy_group.add(&row);
+            self.zone_rows.push(row);
+        }
+    }
+
+    fn area_label(area: CacheArea) -> String {
+        let area = match area {
+            CacheArea::Thumbnails(size) => size.to_string(),
+            CacheArea::Storyboards => String::from("storyboards"),
+            CacheArea::Failed => String::from("failed"),
+        };
+        fl!("prefs-thumbnails-area", area = area)
+    }
+
+    fn update_thumbnail_usage_rows(&mut self, usage: &[AreaUsage]) {
+        for row in self.thumbnail_usage_rows.drain(..) {
+            self.thumbnail_usage.remove(&row);
+        }
+
+        let total: u64 = usage.iter().map(|area| area.bytes).sum();
+        self.thumbnail_usage.set_subtitle(&glib::format_size(total));
+
+        for area in usage.iter().filter(|area| area.files > 0) {
+            let row = adw::ActionRow::builder()
+                .title(Self::area_label(area.area))
+                .subtitle(fl!("prefs-thumbnails-files", count = area.files))
+                .build();
+
+            row.add_suffix(&gtk::Label::new(Some(&glib::format_size(area.bytes))));
+            self.thumbnail_usage.add_row(&row);
+            self.thumbnail_usage_rows.push(row);
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -60,8 +142,34 @@ pub enum PreferencesInput {
 
     UpdateProcessMotionPhotos(bool),
 
+    UpdateWriteOrientationToExif(bool),
+
+    UpdateSlideshowInterval(u32),
+
+    UpdateSlideshowShuffle(bool),
+
+    UpdateTranscodeProfile(TranscodeProfile),
+
+    UpdateVideoCacheLimit(u32),
+
+    UpdateBackgroundThreads(u32),
+
+    UpdatePauseOnBattery(bool),
+
+    UpdatePauseWhileActive(bool),
+
+    UpdatePrivateThumbnails(bool),
+
+    /// Ask to delete and generate again all thumbnails.
+    RegenerateThumbnails,
+
     Sort(AlbumSort),
 
+    UpdateExportLocation(ExportLocation),
+
+    /// Private zones have changed.
+    PrivacyChanged,
+
     ChoosePicturesDir,
 }
 
@@ -74,11 +182,28 @@ pub enum PreferencesOutput {
 
     /// Process motion photos right now.
     ProcessMotionPhotos,
+
+    /// Video cache limit has changed, so evict videos right now.
+    EvictVideoCache,
+
+    /// Delete and generate again all thumbnails.
+    RegenerateThumbnails,
+
+    /// Choose a new private zone.
+    AddPrivateZone,
+
+    /// Stop hiding locations in a private zone.
+    RemovePrivateZone(ZoneId),
 }
 
 #[relm4::component(pub async)]
 impl SimpleAsyncComponent for PreferencesDialog {
-    type Init = (SettingsState, adw::ApplicationWindow);
+    type Init = (
+        SettingsState,
+        PrivacyState,
+        Thumbnailer,
+        adw::ApplicationWindow,
+    );
     type Input = PreferencesInput;
     type Output = PreferencesOutput;
 
@@ -133,6 +258,33 @@ impl SimpleAsyncComponent for PreferencesDialog {
                         }
                     }
                 },
+
+                add = &adw::PreferencesGroup {
+                    set_title: &fl!("prefs-slideshow-section"),
+
+                    #[local_ref]
+                    slideshow_interval_row -> adw::SpinRow {
+                        set_title: &fl!("prefs-slideshow-interval"),
+                        set_subtitle: &fl!("prefs-slideshow-interval", "subtitle"),
+
+                        connect_value_notify[sender] => move |row| {
+                            let secs = row.value() as u32;
+                            let _ = sender.input_sender().send(PreferencesInput::UpdateSlideshowInterval(secs));
+                        }
+                    },
+
+                    adw::SwitchRow {
+                        set_title: &fl!("prefs-slideshow-shuffle"),
+                        set_subtitle: &fl!("prefs-slideshow-shuffle", "subtitle"),
+
+                        #[watch]
+                        set_active: model.settings.slideshow_shuffle,
+
+                        connect_active_notify[sender] => move |switch| {
+                            let _ = sender.input_sender().send(PreferencesInput::UpdateSlideshowShuffle(switch.is_active()));
+                        },
+                    },
+                },
                 add = &adw::PreferencesGroup {
                     set_title: &fl!("prefs-processing-section"),
                     set_description: Some(&fl!("prefs-processing-section", "description")),
@@ -168,13 +320,161 @@ impl SimpleAsyncComponent for PreferencesDialog {
                         },
                     },
 
+                    adw::SwitchRow {
+                        set_title: &fl!("prefs-processing-write-orientation"),
+                        set_subtitle: &fl!("prefs-processing-write-orientation", "subtitle"),
+
+                        #[watch]
+                        set_active: model.settings.write_orientation_to_exif,
+
+                        connect_active_notify[sender] => move |switch| {
+                            let _ = sender.input_sender().send(PreferencesInput::UpdateWriteOrientationToExif(switch.is_active()));
+                        },
+                    },
+                },
+
+                add = &adw::PreferencesGroup {
+                    set_title: &fl!("prefs-background-section"),
+                    set_description: Some(&fl!("prefs-background-section", "description")),
+
+                    #[local_ref]
+                    background_threads_row -> adw::SpinRow {
+                        set_title: &fl!("prefs-background-threads"),
+                        set_subtitle: &fl!("prefs-background-threads", "subtitle"),
+
+                        connect_value_notify[sender] => move |row| {
+                            let threads = row.value() as u32;
+                            let _ = sender.input_sender().send(PreferencesInput::UpdateBackgroundThreads(threads));
+                        }
+                    },
+
+                    adw::SwitchRow {
+                        set_title: &fl!("prefs-background-pause-on-battery"),
+                        set_subtitle: &fl!("prefs-background-pause-on-battery", "subtitle"),
+
+                        #[watch]
+                        set_active: model.settings.pause_on_battery,
+
+                        connect_active_notify[sender] => move |switch| {
+                            let _ = sender.input_sender().send(PreferencesInput::UpdatePauseOnBattery(switch.is_active()));
+                        },
+                    },
+
+                    adw::SwitchRow {
+                        set_title: &fl!("prefs-background-pause-while-active"),
+                        set_subtitle: &fl!("prefs-background-pause-while-active", "subtitle"),
+
+                        #[watch]
+                        set_active: model.settings.pause_while_active,
+
+                        connect_active_notify[sender] => move |switch| {
+                            let _ = sender.input_sender().send(PreferencesInput::UpdatePauseWhileActive(switch.is_active()));
+                        },
+                    },
+                },
+
+                add = &adw::PreferencesGroup {
+                    set_title: &fl!("prefs-videos-section"),
+                    set_description: Some(&fl!("prefs-videos-section", "description")),
+
+                    #[local_ref]
+                    transcode_profile_row -> adw::ComboRow {
+                        set_title: &fl!("prefs-videos-transcode-profile"),
+                        set_subtitle: &fl!("prefs-videos-transcode-profile", "subtitle"),
+
+                        connect_selected_item_notify[sender] => move |row| {
+                            let profile = match row.selected() {
+                                0 => TranscodeProfile::Standard,
+                                1 => TranscodeProfile::Compact,
+                                _ => TranscodeProfile::Open,
+                            };
+                            let _ = sender.input_sender().send(PreferencesInput::UpdateTranscodeProfile(profile));
+                        }
+                    },
+
+                    #[local_ref]
+                    video_cache_limit_row -> adw::SpinRow {
+                        set_title: &fl!("prefs-videos-cache-limit"),
+                        set_subtitle: &fl!("prefs-videos-cache-limit", "subtitle"),
+
+                        connect_value_notify[sender] => move |row| {
+                            let limit = row.value() as u32;
+                            let _ = sender.input_sender().send(PreferencesInput::UpdateVideoCacheLimit(limit));
+                        }
+                    },
+                },
+
+                add = &adw::PreferencesGroup {
+                    set_title: &fl!("prefs-thumbnails-section"),
+                    set_description: Some(&fl!("prefs-thumbnails-section", "description")),
+
+                    #[local_ref]
+                    thumbnail_usage_row -> adw::ExpanderRow {
+                        set_title: &fl!("prefs-thumbnails-usage"),
+                    },
+
+                    adw::ActionRow {
+                        set_title: &fl!("prefs-thumbnails-regenerate"),
+                        set_subtitle: &fl!("prefs-thumbnails-regenerate", "subtitle"),
+
+                        add_suffix = &gtk::Button {
+                            set_valign: gtk::Align::Center,
+                            set_icon_name: "view-refresh-symbolic",
+                            set_tooltip_text: Some(&fl!("prefs-thumbnails-regenerate", "tooltip")),
+                            add_css_class: "flat",
+                            connect_clicked => PreferencesInput::RegenerateThumbnails,
+                        }
+                    },
+                },
+
+                #[local_ref]
+                privacy_group -> adw::PreferencesGroup {
+                    set_title: &fl!("prefs-privacy-section"),
+                    set_description: Some(&fl!("prefs-privacy-section", "description")),
+
+                    #[wrap(Some)]
+                    set_header_suffix = &gtk::Button {
+                        set_icon_name: "list-add-symbolic",
+                        set_tooltip_text: Some(&fl!("prefs-privacy-zone", "add-tooltip")),
+                        add_css_class: "flat",
+                        connect_clicked[sender] => move |_| {
+                            let _ = sender.output(PreferencesOutput::AddPrivateZone);
+                        },
+                    },
+
+                    #[local_ref]
+                    export_location_row -> adw::ComboRow {
+                        set_title: &fl!("prefs-privacy-export-location"),
+                        set_subtitle: &fl!("prefs-privacy-export-location", "subtitle"),
+
+                        connect_selected_item_notify[sender] => move |row| {
+                            let export = match row.selected() {
+                                0 => ExportLocation::Keep,
+                                1 => ExportLocation::Coarse,
+                                _ => ExportLocation::Strip,
+                            };
+                            let _ = sender.input_sender().send(PreferencesInput::UpdateExportLocation(export));
+                        }
+                    },
+
+                    adw::SwitchRow {
+                        set_title: &fl!("prefs-privacy-private-thumbnails"),
+                        set_subtitle: &fl!("prefs-privacy-private-thumbnails", "subtitle"),
+
+                        #[watch]
+                        set_active: model.settings.private_thumbnails,
+
+                        connect_active_notify[sender] => move |switch| {
+                            let _ = sender.input_sender().send(PreferencesInput::UpdatePrivateThumbnails(switch.is_active()));
+                        },
+                    },
                 },
             }
         }
     }
 
     async fn init(
-        (settings_state, parent): Self::Init,
+        (settings_state, privacy_state, thumbnailer, parent): Self::Init,
         dialog: Self::Root,
         sender: AsyncComponentSender<Self>,
     ) -> AsyncComponentParts<Self> {
@@ -182,6 +482,8 @@ impl SimpleAsyncComponent for PreferencesDialog {
             PreferencesInput::SettingsChanged(settings.clone())
         });
 
+        privacy_state.subscribe(sender.input_sender(), |_| PreferencesInput::PrivacyChanged);
+
         let face_detection_mode_row = adw::SwitchRow::builder()
             .active(settings_state.read().face_detection_mode == FaceDetectionMode::On)
             .build();
@@ -193,17 +495,60 @@ impl SimpleAsyncComponent for PreferencesDialog {
         ]);
         album_sort_row.set_model(Some(&list));
 
+        let export_location_row = adw::ComboRow::new();
+        let list = gtk::StringList::new(&[
+            &fl!("prefs-privacy-export-location", "keep"),
+            &fl!("prefs-privacy-export-location", "coarse"),
+            &fl!("prefs-privacy-export-location", "strip"),
+        ]);
+        export_location_row.set_model(Some(&list));
+
+        let transcode_profile_row = adw::ComboRow::new();
+        let list = gtk::StringList::new(&[
+            &fl!("prefs-videos-transcode-profile", "standard"),
+            &fl!("prefs-videos-transcode-profile", "compact"),
+            &fl!("prefs-videos-transcode-profile", "open"),
+        ]);
+        transcode_profile_row.set_model(Some(&list));
+
+        // Zero is unlimited.
+        let video_cache_limit_row = adw::SpinRow::with_range(0.0, 1000.0, 1.0);
+
+        // Zero is automatic. More threads than CPU cores wouldn't go any faster.
+        let cores = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        let background_threads_row = adw::SpinRow::with_range(0.0, cores as f64, 1.0);
+
+        let slideshow_interval_row = adw::SpinRow::with_range(1.0, 600.0, 1.0);
+
+        let privacy_group = adw::PreferencesGroup::new();
+
+        let thumbnail_usage_row = adw::ExpanderRow::new();
+
         let model = Self {
             settings_state: settings_state.clone(),
+            privacy_state,
             parent,
             dialog: dialog.clone(),
             settings: settings_state.read().clone(),
             album_sort: album_sort_row.clone(),
+            export_location: export_location_row.clone(),
+            transcode_profile: transcode_profile_row.clone(),
+            video_cache_limit: video_cache_limit_row.clone(),
+            background_threads: background_threads_row.clone(),
+            slideshow_interval: slideshow_interval_row.clone(),
+            privacy_group: privacy_group.clone(),
+            zone_rows: Vec::new(),
+            thumbnailer,
+            thumbnail_usage: thumbnail_usage_row.clone(),
+            thumbnail_usage_rows: Vec::new(),
         };
 
         let widgets = view_output!();
 
         sender.input(PreferencesInput::SettingsChanged(model.settings.clone()));
+        sender.input(PreferencesInput::PrivacyChanged);
 
         AsyncComponentParts { model, widgets }
     }
@@ -213,6 +558,13 @@ impl SimpleAsyncComponent for PreferencesDialog {
             PreferencesInput::Present => {
                 self.settings = self.settings_state.read().clone();
                 self.dialog.present(Some(&self.parent));
+
+                // Walking the thumbnail cache can take a moment, so show the dialog first.
+                let thumbnailer = self.thumbnailer.clone();
+                match relm4::spawn_blocking(move || maintenance::disk_usage(&thumbnailer)).await {
+                    Ok(usage) => self.update_thumbnail_usage_rows(&usage),
+                    Err(e) => error!("Failed measuring thumbnail disk usage: {}", e),
+                }
             }
             PreferencesInput::SettingsChanged(settings) => {
                 info!("Received update from settings shared state");
@@ -224,6 +576,34 @@ impl SimpleAsyncComponent for PreferencesDialog {
                 };
 
                 self.album_sort.set_selected(index);
+
+                let index = match self.settings.export_location {
+                    ExportLocation::Keep => 0,
+                    ExportLocation::Coarse => 1,
+                    ExportLocation::Strip => 2,
+                };
+
+                self.export_location.set_selected(index);
+
+                let index = match self.settings.transcode_profile {
+                    TranscodeProfile::Standard => 0,
+                    TranscodeProfile::Compact => 1,
+                    TranscodeProfile::Open => 2,
+                };
+
+                self.transcode_profile.set_selected(index);
+
+                self.video_cache_limit
+                    .set_value(self.settings.video_cache_limit_gb as f64);
+
+                self.background_threads
+                    .set_value(self.settings.background_threads as f64);
+
+                self.slideshow_interval
+                    .set_value(self.settings.slideshow_interval_secs as f64);
+            }
+            PreferencesInput::PrivacyChanged => {
+                self.update_zone_rows(&sender);
             }
             PreferencesInput::UpdateShowSelfies(show_selfies) => {
                 info!("Update show selfies: {}", show_selfies);
@@ -251,6 +631,97 @@ impl SimpleAsyncComponent for PreferencesDialog {
                 self.settings.album_sort = mode;
                 *self.settings_state.write() = self.settings.clone();
             }
+            PreferencesInput::UpdateExportLocation(export) => {
+                info!("Update export location: {:?}", export);
+                if self.settings.export_location != export {
+                    self.settings.export_location = export;
+                    *self.settings_state.write() = self.settings.clone();
+                }
+            }
+            PreferencesInput::UpdateTranscodeProfile(profile) => {
+                info!("Update transcode profile: {:?}", profile);
+                if self.settings.transcode_profile != profile {
+                    self.settings.transcode_profile = profile;
+                    *self.settings_state.write() = self.settings.clone();
+                }
+            }
+            PreferencesInput::UpdateVideoCacheLimit(limit) => {
+                info!("Update video cache limit: {} GB", limit);
+                if self.settings.video_cache_limit_gb != limit {
+                    self.settings.video_cache_limit_gb = limit;
+                    *self.settings_state.write() = self.settings.clone();
+                    if limit > 0 {
+                        let _ = sender.output(PreferencesOutput::EvictVideoCache);
+                    }
+                }
+            }
+            PreferencesInput::UpdateBackgroundThreads(threads) => {
+                info!("Update background threads: {}", threads);
+                if self.settings.background_threads != threads {
+                    self.settings.background_threads = threads;
+                    *self.settings_state.write() = self.settings.clone();
+                }
+            }
+            PreferencesInput::UpdatePauseOnBattery(enable) => {
+                info!("Update pause on battery: {}", enable);
+                self.settings.pause_on_battery = enable;
+                *self.settings_state.write() = self.settings.clone();
+            }
+            PreferencesInput::UpdatePauseWhileActive(enable) => {
+                info!("Update pause while active: {}", enable);
+                self.settings.pause_while_active = enable;
+                *self.settings_state.write() = self.settings.clone();
+            }
+            PreferencesInput::UpdateWriteOrientationToExif(enable) => {
+                info!("Update write orientation to EXIF: {}", enable);
+                self.settings.write_orientation_to_exif = enable;
+                *self.settings_state.write() = self.settings.clone();
+            }
+            PreferencesInput::UpdateSlideshowInterval(secs) => {
+                info!("Update slideshow interval: {} seconds", secs);
+                if self.settings.slideshow_interval_secs != secs {
+                    self.settings.slideshow_interval_secs = secs;
+                    *self.settings_state.write() = self.settings.clone();
+                }
+            }
+            PreferencesInput::UpdateSlideshowShuffle(enable) => {
+                info!("Update slideshow shuffle: {}", enable);
+                if self.settings.slideshow_shuffle != enable {
+                    self.settings.slideshow_shuffle = enable;
+                    *self.settings_state.write() = self.settings.clone();
+                }
+            }
+            PreferencesInput::UpdatePrivateThumbnails(enable) => {
+                info!("Update private thumbnails: {}", enable);
+                self.settings.private_thumbnails = enable;
+                *self.settings_state.write() = self.settings.clone();
+            }
+            PreferencesInput::RegenerateThumbnails => {
+                let dialog = adw::AlertDialog::builder()
+                    .heading(fl!("prefs-thumbnails-regenerate", "confirm-heading"))
+                    .body(fl!("prefs-thumbnails-regenerate", "confirm-body"))
+                    .build();
+
+                dialog.add_response("cancel", &fl!("prefs-thumbnails-regenerate", "cancel"));
+                dialog.set_default_response(Some("cancel"));
+                dialog.set_close_response("cancel");
+
+                dialog.add_response(
+                    "regenerate",
+                    &fl!("prefs-thumbnails-regenerate", "regenerate"),
+                );
+                dialog.set_response_appearance("regenerate", adw::ResponseAppearance::Destructive);
+
+                let sender = sender.clone();
+                dialog.connect_response(None, move |_, response| {
+                    if response == "regenerate" {
+                        info!("Regenerating all thumbnails");
+                        let _ = sender.output(PreferencesOutput::RegenerateThumbnails);
+                    }
+                });
+
+                dialog.present(Some(&self.dialog));
+            }
             PreferencesInput::ChoosePicturesDir => {
                 info!("Presenting select pictures directory file chooser");
                 if let Some(root) = gtk::Widget::root(self.parent.widget_ref()) {
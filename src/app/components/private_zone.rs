@@ -0,0 +1,194 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use relm4::adw::prelude::*;
+use relm4::gtk;
+use relm4::prelude::*;
+
+use shumate::MAP_SOURCE_OSM_MAPNIK;
+use shumate::prelude::*;
+
+use tracing::{error, info};
+
+use crate::fl;
+use fotema_core::privacy::ZoneArea;
+use h3o::LatLng;
+
+/// Zoom level of map when dialog is shown.
+const DEFAULT_ZOOM_LEVEL: f64 = 3.0;
+
+/// Default radius of a private zone. Large enough to cover a home and the
+/// streets around it.
+const DEFAULT_RADIUS_METRES: f64 = 500.0;
+
+const PIN_SIZE: i32 = 32;
+
+#[derive(Debug)]
+pub enum PrivateZoneInput {
+    /// Show the dialog to choose a private zone.
+    Present,
+
+    /// Add a private zone around the centre of the map.
+    Save,
+}
+
+#[derive(Debug)]
+pub enum PrivateZoneOutput {
+    /// Hide locations in a named area.
+    Add(String, ZoneArea),
+}
+
+/// Dialog for choosing an area, such as home, where locations should be hidden.
+/// The zone is centred on the middle of the map and the user moves the map under it.
+pub struct PrivateZoneDialog {
+    parent: adw::ApplicationWindow,
+    dialog: adw::Dialog,
+    viewport: shumate::Viewport,
+
+    name_row: adw::EntryRow,
+    radius_row: adw::SpinRow,
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for PrivateZoneDialog {
+    type Init = adw::ApplicationWindow;
+    type Input = PrivateZoneInput;
+    type Output = PrivateZoneOutput;
+
+    view! {
+        adw::Dialog {
+            set_title: &fl!("private-zone-title"),
+            set_content_width: 640,
+            set_content_height: 640,
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {
+                    set_show_end_title_buttons: false,
+                    set_show_start_title_buttons: false,
+
+                    pack_start = &gtk::Button {
+                        set_label: &fl!("private-zone-cancel"),
+                        connect_clicked[dialog] => move |_| {
+                            dialog.close();
+                        },
+                    },
+
+                    pack_end = &gtk::Button {
+                        set_label: &fl!("private-zone-save"),
+                        add_css_class: "suggested-action",
+                        connect_clicked => PrivateZoneInput::Save,
+                    },
+                },
+
+                add_top_bar = &adw::PreferencesGroup {
+                    set_margin_all: 12,
+
+                    #[local_ref]
+                    name_row -> adw::EntryRow {
+                        set_title: &fl!("private-zone-name"),
+                    },
+
+                    #[local_ref]
+                    radius_row -> adw::SpinRow {
+                        set_title: &fl!("private-zone-radius"),
+                        set_subtitle: &fl!("private-zone-radius", "subtitle"),
+                    },
+                },
+
+                #[wrap(Some)]
+                set_content = &gtk::Overlay {
+                    #[local_ref]
+                    map_widget -> shumate::SimpleMap {
+                        set_vexpand: true,
+                        set_hexpand: true,
+                    },
+
+                    // Tip of pin marks the centre of the zone.
+                    add_overlay = &gtk::Image {
+                        set_icon_name: Some("mark-location-symbolic"),
+                        set_pixel_size: PIN_SIZE,
+                        set_halign: gtk::Align::Center,
+                        set_valign: gtk::Align::Center,
+                        set_margin_bottom: PIN_SIZE,
+                        set_can_target: false,
+                        add_css_class: "accent",
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        parent: Self::Init,
+        dialog: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let map_widget = shumate::SimpleMap::builder().build();
+
+        if let Some(scale) = map_widget.scale() {
+            scale.set_unit(shumate::Unit::Metric);
+        }
+
+        // Use OpenStreetMap as the source
+        let registry = shumate::MapSourceRegistry::with_defaults();
+        let map_source = registry.by_id(MAP_SOURCE_OSM_MAPNIK);
+        map_widget.set_map_source(map_source.as_ref());
+
+        let viewport = map_widget.viewport().unwrap();
+        viewport.set_reference_map_source(map_source.as_ref());
+        viewport.set_zoom_level(DEFAULT_ZOOM_LEVEL);
+
+        let name_row = adw::EntryRow::new();
+
+        let radius_row = adw::SpinRow::with_range(100.0, 5000.0, 50.0);
+        radius_row.set_value(DEFAULT_RADIUS_METRES);
+
+        let model = PrivateZoneDialog {
+            parent,
+            dialog: dialog.clone(),
+            viewport,
+            name_row: name_row.clone(),
+            radius_row: radius_row.clone(),
+        };
+
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        match msg {
+            PrivateZoneInput::Present => {
+                self.name_row.set_text("");
+                self.radius_row.set_value(DEFAULT_RADIUS_METRES);
+                self.dialog.present(Some(&self.parent));
+            }
+            PrivateZoneInput::Save => {
+                let centre = LatLng::new(self.viewport.latitude(), self.viewport.longitude());
+                match centre {
+                    Ok(centre) => {
+                        let name = self.name_row.text().trim().to_string();
+                        let name = if name.is_empty() {
+                            fl!("private-zone-name", "default")
+                        } else {
+                            name
+                        };
+
+                        let area = ZoneArea::Circle {
+                            centre,
+                            radius_metres: self.radius_row.value(),
+                        };
+
+                        info!("Adding private zone {} at {:?}", name, area);
+                        let _ = sender.output(PrivateZoneOutput::Add(name, area));
+                    }
+                    Err(e) => {
+                        error!("Invalid map location: {}", e);
+                    }
+                }
+                self.dialog.close();
+            }
+        }
+    }
+}
@@ -0,0 +1,258 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use relm4::adw::prelude::*;
+use relm4::gtk;
+use relm4::prelude::*;
+
+use tracing::{error, info};
+
+use crate::app::SettingsState;
+use crate::fl;
+use fotema_core::FlatpakPathBuf;
+use fotema_core::problems::{self, Problem, ProblemCategory, ProblemTarget};
+use fotema_core::thumbnailify::{Thumbnailer, maintenance};
+
+#[derive(Debug)]
+pub enum ProblemsInput {
+    /// Show the problems dialog.
+    Present,
+
+    /// Clear the broken mark of one item.
+    Retry(Problem),
+
+    /// Clear the broken marks of all items.
+    RetryAll,
+}
+
+#[derive(Debug)]
+pub enum ProblemsOutput {
+    /// Broken marks have been cleared, so the items should be processed again.
+    /// bool is true if videos that couldn't be transcoded are among them.
+    Retry(bool),
+}
+
+/// Dialog listing pictures and videos that couldn't be processed, and why.
+pub struct ProblemsDialog {
+    parent: adw::ApplicationWindow,
+    dialog: adw::Dialog,
+    repo: problems::Repository,
+
+    /// For removing failed thumbnail markers.
+    thumbnailer: Thumbnailer,
+
+    settings_state: SettingsState,
+
+    /// Shown when there are no problems.
+    status_page: adw::StatusPage,
+
+    /// Scrollable list of problems.
+    scrolled_window: gtk::ScrolledWindow,
+
+    /// One row per problem.
+    list_box: gtk::ListBox,
+
+    retry_all_button: gtk::Button,
+}
+
+impl ProblemsDialog {
+    fn category_label(category: Option<ProblemCategory>) -> String {
+        match category {
+            Some(ProblemCategory::Thumbnail) => fl!("problems-category", "thumbnail"),
+            Some(ProblemCategory::MotionPhoto) => fl!("problems-category", "motion-photo"),
+            Some(ProblemCategory::Transcode) => fl!("problems-category", "transcode"),
+            Some(ProblemCategory::FaceScan) => fl!("problems-category", "face-scan"),
+            None => fl!("problems-category", "unknown"),
+        }
+    }
+
+    /// Thumbnail generation isn't attempted while a failed thumbnail marker exists,
+    /// so remove the marker as well as the broken mark.
+    fn remove_failed_thumbnail(&self, problem: &Problem) {
+        if matches!(problem.target, ProblemTarget::FaceScan(_)) {
+            return;
+        }
+
+        let library_base_dir = self.settings_state.read().library_base_dir.clone();
+        let path = FlatpakPathBuf::build(
+            library_base_dir.host_path.join(&problem.path),
+            library_base_dir.sandbox_path.join(&problem.path),
+        );
+
+        if let Err(e) = maintenance::remove_failed(&self.thumbnailer, &path) {
+            error!(
+                "Failed removing failed thumbnail marker for {:?}: {}",
+                path, e
+            );
+        }
+    }
+
+    fn update_rows(&mut self, sender: &ComponentSender<Self>) {
+        self.list_box.remove_all();
+
+        let problems: Vec<Problem> = match self.repo.all() {
+            Ok(problems) => problems,
+            Err(e) => {
+                error!("Failed loading problems: {:?}", e);
+                vec![]
+            }
+        };
+
+        self.status_page.set_visible(problems.is_empty());
+        self.scrolled_window.set_visible(!problems.is_empty());
+        self.retry_all_button.set_sensitive(!problems.is_empty());
+
+        for problem in problems {
+            let subtitle = match problem.message {
+                Some(ref message) => {
+                    format!("{}: {}", Self::category_label(problem.category), message)
+                }
+                None => Self::category_label(problem.category),
+            };
+
+            // File names and error messages aren't Pango markup.
+            let row = adw::ActionRow::builder()
+                .title(problem.path.to_string_lossy())
+                .subtitle(subtitle)
+                .subtitle_lines(3)
+                .use_markup(false)
+                .build();
+
+            let retry_button = gtk::Button::builder()
+                .valign(gtk::Align::Center)
+                .icon_name("view-refresh-symbolic")
+                .tooltip_text(fl!("problems-retry", "tooltip"))
+                .css_classes(["flat"])
+                .build();
+
+            let sender = sender.clone();
+            retry_button.connect_clicked(move |_| {
+                sender.input(ProblemsInput::Retry(problem.clone()));
+            });
+
+            row.add_suffix(&retry_button);
+            self.list_box.append(&row);
+        }
+    }
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for ProblemsDialog {
+    type Init = (
+        problems::Repository,
+        Thumbnailer,
+        SettingsState,
+        adw::ApplicationWindow,
+    );
+    type Input = ProblemsInput;
+    type Output = ProblemsOutput;
+
+    view! {
+        adw::Dialog {
+            set_title: &fl!("problems-title"),
+            set_content_width: 560,
+            set_content_height: 560,
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {
+                    #[local_ref]
+                    pack_start = &retry_all_button -> gtk::Button {
+                        set_label: &fl!("problems-retry-all"),
+                        connect_clicked => ProblemsInput::RetryAll,
+                    },
+                },
+
+                #[wrap(Some)]
+                set_content = &gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+
+                    #[local_ref]
+                    status_page -> adw::StatusPage {
+                        set_icon_name: Some("emblem-ok-symbolic"),
+                        set_title: &fl!("problems-empty"),
+                        set_description: Some(&fl!("problems-empty", "description")),
+                        set_vexpand: true,
+                    },
+
+                    #[local_ref]
+                    scrolled_window -> gtk::ScrolledWindow {
+                        set_vexpand: true,
+
+                        adw::Clamp {
+                            set_margin_all: 12,
+
+                            #[local_ref]
+                            list_box -> gtk::ListBox {
+                                set_valign: gtk::Align::Start,
+                                set_selection_mode: gtk::SelectionMode::None,
+                                add_css_class: "boxed-list",
+                            },
+                        },
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        (repo, thumbnailer, settings_state, parent): Self::Init,
+        dialog: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let status_page = adw::StatusPage::new();
+        let scrolled_window = gtk::ScrolledWindow::new();
+        let list_box = gtk::ListBox::new();
+        let retry_all_button = gtk::Button::new();
+
+        let model = ProblemsDialog {
+            parent,
+            dialog: dialog.clone(),
+            repo,
+            thumbnailer,
+            settings_state,
+            status_page: status_page.clone(),
+            scrolled_window: scrolled_window.clone(),
+            list_box: list_box.clone(),
+            retry_all_button: retry_all_button.clone(),
+        };
+
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        match msg {
+            ProblemsInput::Present => {
+                self.update_rows(&sender);
+                self.dialog.present(Some(&self.parent));
+            }
+            ProblemsInput::Retry(problem) => {
+                info!("Retrying {:?}", problem.target);
+                let transcode = problem.category == Some(ProblemCategory::Transcode);
+                self.remove_failed_thumbnail(&problem);
+                if let Err(e) = self.repo.retry(problem.target) {
+                    error!("Failed retrying {:?}: {:?}", problem.target, e);
+                }
+                self.update_rows(&sender);
+                let _ = sender.output(ProblemsOutput::Retry(transcode));
+            }
+            ProblemsInput::RetryAll => {
+                let problems = self.repo.all().unwrap_or_default();
+                let transcode = problems
+                    .iter()
+                    .any(|problem| problem.category == Some(ProblemCategory::Transcode));
+                for problem in problems {
+                    self.remove_failed_thumbnail(&problem);
+                }
+                match self.repo.retry_all() {
+                    Ok(count) => info!("Retrying {} problems", count),
+                    Err(e) => error!("Failed retrying all problems: {:?}", e),
+                }
+                self.update_rows(&sender);
+                let _ = sender.output(ProblemsOutput::Retry(transcode));
+            }
+        }
+    }
+}
@@ -26,6 +26,8 @@ pub enum TaskName {
     Thumbnail(ThumbnailType),
     Transcode,
     MotionPhoto,
+    Geocode,
+    Geotag,
     DetectFaces,
     RecognizeFaces,
 
@@ -37,6 +39,7 @@ pub enum TaskName {
 pub enum ProgressMonitorInput {
     Start(TaskName, usize),
     Advance,
+    AdvanceBy(usize),
     Complete,
 }
 
@@ -89,6 +92,9 @@ impl Reducible for ProgressMonitor {
                     self.current_count += 1;
                 }
             }
+            ProgressMonitorInput::AdvanceBy(count) => {
+                self.current_count = (self.current_count + count).min(self.end_count);
+            }
             ProgressMonitorInput::Complete => {
                 self.current_count = self.end_count;
             }
@@ -94,6 +94,12 @@ impl SimpleComponent for ProgressPanel {
                             self.progress_bar
                                 .set_text(Some(&fl!("progress-motion-photo")));
                         }
+                        TaskName::Geocode => {
+                            self.progress_bar.set_text(Some(&fl!("progress-geocode")));
+                        }
+                        TaskName::Geotag => {
+                            self.progress_bar.set_text(Some(&fl!("progress-geotag")));
+                        }
                         TaskName::DetectFaces => {
                             self.progress_bar
                                 .set_text(Some(&fl!("progress-detect-faces-photos")));
@@ -0,0 +1,155 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use relm4::adw::prelude::*;
+use relm4::gtk::{self, glib};
+use relm4::prelude::*;
+
+use tracing::{error, info};
+
+use crate::fl;
+use chrono::{Datelike, NaiveDate};
+use fotema_core::Visual;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub enum SetDateInput {
+    /// Show the dialog to set the date of items.
+    Present(Vec<Arc<Visual>>),
+
+    /// Set the date of items to the day chosen on the calendar.
+    Save,
+}
+
+#[derive(Debug)]
+pub enum SetDateOutput {
+    /// Set the date of pictures and videos, keeping their time of day.
+    Dated(Vec<Arc<Visual>>, NaiveDate),
+}
+
+/// Dialog for choosing the day pictures and videos were taken, for when the
+/// date in their metadata is wrong or missing.
+pub struct SetDateDialog {
+    parent: adw::ApplicationWindow,
+    dialog: adw::Dialog,
+    calendar: gtk::Calendar,
+
+    /// Items to set date of.
+    visuals: Vec<Arc<Visual>>,
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for SetDateDialog {
+    type Init = adw::ApplicationWindow;
+    type Input = SetDateInput;
+    type Output = SetDateOutput;
+
+    view! {
+        adw::Dialog {
+            set_title: &fl!("set-date-title"),
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {
+                    set_show_end_title_buttons: false,
+                    set_show_start_title_buttons: false,
+
+                    pack_start = &gtk::Button {
+                        set_label: &fl!("set-date-cancel"),
+                        connect_clicked[dialog] => move |_| {
+                            dialog.close();
+                        },
+                    },
+
+                    pack_end = &gtk::Button {
+                        set_label: &fl!("set-date-save"),
+                        add_css_class: "suggested-action",
+                        connect_clicked => SetDateInput::Save,
+                    },
+                },
+
+                #[wrap(Some)]
+                set_content = &gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+                    set_spacing: 12,
+                    set_margin_all: 12,
+
+                    #[local_ref]
+                    calendar -> gtk::Calendar,
+
+                    gtk::Label {
+                        set_label: &fl!("set-date-description"),
+                        set_wrap: true,
+                        set_max_width_chars: 30,
+                        add_css_class: "dim-label",
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        parent: Self::Init,
+        dialog: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let calendar = gtk::Calendar::new();
+
+        let model = SetDateDialog {
+            parent,
+            dialog: dialog.clone(),
+            calendar: calendar.clone(),
+            visuals: Vec::new(),
+        };
+
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        match msg {
+            SetDateInput::Present(visuals) => {
+                // Start from the date of the first item.
+                if let Some(date) = visuals.first().and_then(|v| to_day(v.local_ts.date())) {
+                    self.calendar.select_day(&date);
+                }
+
+                self.visuals = visuals;
+                self.dialog.present(Some(&self.parent));
+            }
+            SetDateInput::Save => {
+                let date = self.calendar.date();
+                let date = NaiveDate::from_ymd_opt(
+                    date.year(),
+                    date.month() as u32,
+                    date.day_of_month() as u32,
+                );
+                match date {
+                    Some(date) => {
+                        info!("Setting date of {} items to {}", self.visuals.len(), date);
+                        let visuals = std::mem::take(&mut self.visuals);
+                        let _ = sender.output(SetDateOutput::Dated(visuals, date));
+                    }
+                    None => {
+                        error!("Invalid calendar date");
+                    }
+                }
+                self.dialog.close();
+            }
+        }
+    }
+}
+
+/// Start of a day in the local time zone, for choosing it on a calendar.
+fn to_day(date: NaiveDate) -> Option<glib::DateTime> {
+    glib::DateTime::from_local(
+        date.year(),
+        date.month() as i32,
+        date.day() as i32,
+        0,
+        0,
+        0.0,
+    )
+    .ok()
+}
@@ -0,0 +1,759 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use fotema_core::Visual;
+use fotema_core::VisualId;
+use fotema_core::visual::model::PictureOrientation;
+
+use relm4::adw::gdk;
+use relm4::gtk;
+use relm4::gtk::gio;
+use relm4::gtk::glib;
+use relm4::gtk::prelude::*;
+use relm4::*;
+use strum::IntoEnumIterator;
+
+use crate::app::components::albums::album_filter::AlbumFilter;
+use crate::app::components::viewer::view_one::{
+    LARGE_IMAGE_PIXELS, preview_size, render_edits, upright_texture,
+};
+use crate::app::{SettingsState, SharedState};
+use crate::fl;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{debug, error, info};
+
+/// Length of the crossfade from one item to the next, in milliseconds.
+const TRANSITION_MILLIS: u32 = 800;
+
+/// Controls are hidden once the pointer has been still for this long.
+const CONTROLS_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Loaded item ready to be shown.
+#[derive(Debug)]
+pub enum Slide {
+    Picture(gdk::Texture),
+
+    /// Video or live photo, with any orientation chosen by the user.
+    Video(gtk::MediaFile, Option<PictureOrientation>),
+}
+
+#[derive(Debug)]
+pub enum SlideshowInput {
+    /// Play the items of an album, starting from the first.
+    Start(AlbumFilter),
+
+    /// Slideshow is no longer visible.
+    Stop,
+
+    /// Show next item.
+    Next,
+
+    /// Show previous item.
+    Previous,
+
+    /// Pause or resume playing.
+    PlayToggle,
+
+    /// Switch between shuffled and sorted order.
+    ShuffleToggle,
+
+    /// Item has loaded, or failed to load. The u64 is the generation it was loaded for.
+    Loaded(u64, VisualId, Option<Slide>),
+
+    /// Time to move on from the item shown. The u64 is the generation of that item.
+    Advance(u64),
+
+    /// Pointer moved, so show the controls for a while.
+    PointerMoved,
+
+    HideControls,
+}
+
+#[derive(Debug)]
+pub enum SlideshowOutput {
+    /// User wants to leave the slideshow, or there was nothing to show.
+    Close,
+}
+
+pub struct Slideshow {
+    state: SharedState,
+    settings_state: SettingsState,
+
+    /// Items of the album in sort order.
+    ordered: Vec<Arc<Visual>>,
+
+    /// Items in the order they are shown. Either the same as `ordered`, or shuffled.
+    playlist: Vec<Arc<Visual>>,
+
+    /// Position in the playlist of the item shown.
+    index: usize,
+
+    /// Has the item at `index` finished loading and been shown.
+    is_shown: bool,
+
+    is_playing: bool,
+    is_shuffled: bool,
+
+    /// Changes whenever a different item is shown, so that loads and timers
+    /// for items that are no longer shown can be ignored.
+    generation: u64,
+
+    /// Next item, loaded ahead of time so it can be shown without a pause.
+    preloaded: Option<(VisualId, Slide)>,
+
+    root: gtk::Overlay,
+
+    /// Crossfades between the two pictures.
+    stack: gtk::Stack,
+
+    /// Pictures take turns showing the current item, so one can fade into the other.
+    pictures: [gtk::Picture; 2],
+
+    /// Which of the pictures is showing the current item.
+    front: usize,
+
+    /// Video of the current item.
+    video: Option<gtk::MediaFile>,
+
+    /// Advances to the next picture when the interval has passed.
+    timer: Option<glib::JoinHandle<()>>,
+
+    is_controls_revealed: bool,
+    controls_timer: Option<glib::JoinHandle<()>>,
+
+    /// Was the window already fullscreen before the slideshow started.
+    was_fullscreen: bool,
+
+    /// Stops the screen from blanking while playing.
+    inhibit_cookie: Option<u32>,
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for Slideshow {
+    type Init = (SharedState, SettingsState);
+    type Input = SlideshowInput;
+    type Output = SlideshowOutput;
+
+    view! {
+        gtk::Overlay {
+            add_css_class: "slideshow",
+            set_focusable: true,
+
+            #[local_ref]
+            stack -> gtk::Stack {
+                set_transition_type: gtk::StackTransitionType::Crossfade,
+                set_transition_duration: TRANSITION_MILLIS,
+                set_hexpand: true,
+                set_vexpand: true,
+            },
+
+            add_overlay = &gtk::Revealer {
+                set_transition_type: gtk::RevealerTransitionType::Crossfade,
+                set_halign: gtk::Align::Center,
+                set_valign: gtk::Align::End,
+                set_margin_bottom: 24,
+
+                #[watch]
+                set_reveal_child: model.is_controls_revealed,
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 12,
+
+                    gtk::Button {
+                        set_icon_name: "left-symbolic",
+                        add_css_class: "circular",
+                        add_css_class: "osd",
+                        set_tooltip_text: Some(&fl!("viewer-previous", "tooltip")),
+                        connect_clicked => SlideshowInput::Previous,
+                    },
+
+                    gtk::Button {
+                        add_css_class: "circular",
+                        add_css_class: "osd",
+                        set_tooltip_text: Some(&fl!("viewer-play", "tooltip")),
+
+                        #[watch]
+                        set_icon_name: if model.is_playing {
+                            "pause-symbolic"
+                        } else {
+                            "play-symbolic"
+                        },
+
+                        connect_clicked => SlideshowInput::PlayToggle,
+                    },
+
+                    gtk::Button {
+                        set_icon_name: "right-symbolic",
+                        add_css_class: "circular",
+                        add_css_class: "osd",
+                        set_tooltip_text: Some(&fl!("viewer-next", "tooltip")),
+                        connect_clicked => SlideshowInput::Next,
+                    },
+
+                    gtk::Label {
+                        add_css_class: "osd",
+                        add_css_class: "photo-grid-month-label",
+                        set_width_chars: 9,
+
+                        #[watch]
+                        set_label: &fl!(
+                            "slideshow-position",
+                            position = (model.index + 1).min(model.playlist.len()),
+                            count = model.playlist.len()
+                        ),
+                    },
+
+                    gtk::Button {
+                        add_css_class: "circular",
+                        add_css_class: "osd",
+
+                        #[watch]
+                        set_icon_name: if model.is_shuffled {
+                            "media-playlist-shuffle-symbolic"
+                        } else {
+                            "media-playlist-consecutive-symbolic"
+                        },
+
+                        #[watch]
+                        set_tooltip_text: Some(&if model.is_shuffled {
+                            fl!("slideshow-shuffle", "on")
+                        } else {
+                            fl!("slideshow-shuffle", "off")
+                        }),
+
+                        connect_clicked => SlideshowInput::ShuffleToggle,
+                    },
+
+                    gtk::Button {
+                        set_icon_name: "window-close-symbolic",
+                        add_css_class: "circular",
+                        add_css_class: "osd",
+                        set_tooltip_text: Some(&fl!("slideshow-exit", "tooltip")),
+                        connect_clicked[sender] => move |_| {
+                            let _ = sender.output(SlideshowOutput::Close);
+                        },
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        (state, settings_state): Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let stack = gtk::Stack::new();
+
+        let pictures = [gtk::Picture::new(), gtk::Picture::new()];
+        for picture in &pictures {
+            picture.set_content_fit(gtk::ContentFit::Contain);
+            stack.add_child(picture);
+        }
+
+        let model = Slideshow {
+            state,
+            settings_state,
+            ordered: Vec::new(),
+            playlist: Vec::new(),
+            index: 0,
+            is_shown: false,
+            is_playing: false,
+            is_shuffled: false,
+            generation: 0,
+            preloaded: None,
+            root: root.clone(),
+            stack: stack.clone(),
+            pictures,
+            front: 0,
+            video: None,
+            timer: None,
+            is_controls_revealed: false,
+            controls_timer: None,
+            was_fullscreen: false,
+            inhibit_cookie: None,
+        };
+
+        let widgets = view_output!();
+
+        let keys = gtk::EventControllerKey::new();
+        {
+            let sender = sender.clone();
+            keys.connect_key_pressed(move |_, key, _, _| match key {
+                gdk::Key::space => {
+                    sender.input(SlideshowInput::PlayToggle);
+                    glib::Propagation::Stop
+                }
+                gdk::Key::Left | gdk::Key::Page_Up => {
+                    sender.input(SlideshowInput::Previous);
+                    glib::Propagation::Stop
+                }
+                gdk::Key::Right | gdk::Key::Page_Down => {
+                    sender.input(SlideshowInput::Next);
+                    glib::Propagation::Stop
+                }
+                gdk::Key::s => {
+                    sender.input(SlideshowInput::ShuffleToggle);
+                    glib::Propagation::Stop
+                }
+                _ => glib::Propagation::Proceed,
+            });
+        }
+        root.add_controller(keys);
+
+        let motion = gtk::EventControllerMotion::new();
+        {
+            let sender = sender.clone();
+            motion.connect_motion(move |_, _, _| sender.input(SlideshowInput::PointerMoved));
+        }
+        root.add_controller(motion);
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        match msg {
+            SlideshowInput::Start(filter) => {
+                let settings = self.settings_state.read().clone();
+
+                self.ordered = self
+                    .state
+                    .read()
+                    .iter()
+                    .filter(|v| filter.clone().filter(v))
+                    .cloned()
+                    .collect();
+                settings.album_sort.sort(&mut self.ordered);
+
+                self.is_shuffled = settings.slideshow_shuffle;
+                self.playlist = self.ordered.clone();
+                if self.is_shuffled {
+                    shuffle(&mut self.playlist);
+                }
+
+                info!("Starting slideshow of {} items", self.playlist.len());
+                if self.playlist.is_empty() {
+                    let _ = sender.output(SlideshowOutput::Close);
+                    return;
+                }
+
+                self.preloaded = None;
+                for picture in &self.pictures {
+                    picture.set_paintable(None::<&gdk::Paintable>);
+                }
+
+                self.enter_fullscreen();
+                self.root.grab_focus();
+                self.is_playing = true;
+                self.show_controls(&sender);
+                self.go_to(0, &sender);
+            }
+            SlideshowInput::Stop => {
+                info!("Stopping slideshow");
+                self.stop_item();
+                self.generation += 1;
+                self.is_playing = false;
+                self.preloaded = None;
+                self.playlist.clear();
+                self.ordered.clear();
+
+                for picture in &self.pictures {
+                    picture.set_paintable(None::<&gdk::Paintable>);
+                }
+
+                if let Some(timer) = self.controls_timer.take() {
+                    timer.abort();
+                }
+                self.root.set_cursor(None);
+
+                self.leave_fullscreen();
+            }
+            SlideshowInput::Next => {
+                if !self.playlist.is_empty() {
+                    self.go_to(self.next_index(), &sender);
+                }
+            }
+            SlideshowInput::Previous => {
+                if !self.playlist.is_empty() {
+                    let index = (self.index + self.playlist.len() - 1) % self.playlist.len();
+                    self.go_to(index, &sender);
+                }
+            }
+            SlideshowInput::PlayToggle => {
+                self.is_playing = !self.is_playing;
+                if self.is_playing {
+                    if let Some(ref video) = self.video {
+                        video.play();
+                    } else if self.is_shown {
+                        self.start_timer(&sender);
+                    }
+                } else {
+                    if let Some(timer) = self.timer.take() {
+                        timer.abort();
+                    }
+                    if let Some(ref video) = self.video {
+                        video.pause();
+                    }
+                }
+            }
+            SlideshowInput::ShuffleToggle => {
+                self.is_shuffled = !self.is_shuffled;
+                self.settings_state.write().slideshow_shuffle = self.is_shuffled;
+
+                let Some(current) = self.playlist.get(self.index).cloned() else {
+                    return;
+                };
+
+                // Keep showing the current item and reorder what comes after it.
+                if self.is_shuffled {
+                    let mut rest: Vec<Arc<Visual>> = self
+                        .ordered
+                        .iter()
+                        .filter(|v| v.visual_id != current.visual_id)
+                        .cloned()
+                        .collect();
+                    shuffle(&mut rest);
+                    self.playlist = std::iter::once(current).chain(rest).collect();
+                    self.index = 0;
+                } else {
+                    self.playlist = self.ordered.clone();
+                    self.index = self
+                        .playlist
+                        .iter()
+                        .position(|v| v.visual_id == current.visual_id)
+                        .unwrap_or(0);
+                }
+
+                self.preloaded = None;
+                if self.is_shown {
+                    self.preload(&sender);
+                }
+            }
+            SlideshowInput::Loaded(generation, visual_id, slide) => {
+                if generation != self.generation {
+                    debug!("Ignoring load of {} for an item no longer shown", visual_id);
+                    return;
+                }
+
+                let is_current = self
+                    .playlist
+                    .get(self.index)
+                    .is_some_and(|v| v.visual_id == visual_id);
+
+                if is_current && !self.is_shown {
+                    match slide {
+                        Some(slide) => self.show(slide, &sender),
+                        None => {
+                            // Skip items that can't be shown.
+                            let next = self.next_index();
+                            if next != self.index {
+                                self.go_to(next, &sender);
+                            }
+                        }
+                    }
+                } else if let Some(slide) = slide {
+                    let is_next = self
+                        .playlist
+                        .get(self.next_index())
+                        .is_some_and(|v| v.visual_id == visual_id);
+                    if is_next {
+                        self.preloaded = Some((visual_id, slide));
+                    }
+                }
+            }
+            SlideshowInput::Advance(generation) => {
+                if generation == self.generation && self.is_playing && !self.playlist.is_empty() {
+                    self.go_to(self.next_index(), &sender);
+                }
+            }
+            SlideshowInput::PointerMoved => {
+                self.show_controls(&sender);
+            }
+            SlideshowInput::HideControls => {
+                self.is_controls_revealed = false;
+                self.root.set_cursor_from_name(Some("none"));
+            }
+        }
+    }
+}
+
+impl Slideshow {
+    /// Position in the playlist of the item after the current one, wrapping around at the end.
+    fn next_index(&self) -> usize {
+        if self.playlist.is_empty() {
+            0
+        } else {
+            (self.index + 1) % self.playlist.len()
+        }
+    }
+
+    /// Move to an item in the playlist, showing it once loaded.
+    fn go_to(&mut self, index: usize, sender: &ComponentSender<Self>) {
+        self.stop_item();
+        self.generation += 1;
+        self.index = index;
+        self.is_shown = false;
+
+        let Some(visual) = self.playlist.get(index).cloned() else {
+            return;
+        };
+
+        match self.preloaded.take() {
+            Some((visual_id, slide)) if visual_id == visual.visual_id => {
+                self.show(slide, sender);
+            }
+            _ => self.load(visual, sender),
+        }
+    }
+
+    /// Fade in a loaded item, then load the next one ahead of time.
+    fn show(&mut self, slide: Slide, sender: &ComponentSender<Self>) {
+        let back = 1 - self.front;
+        let picture = self.pictures[back].clone();
+
+        for orientation in PictureOrientation::iter() {
+            picture.remove_css_class(orientation.as_ref());
+        }
+
+        match slide {
+            Slide::Picture(texture) => {
+                picture.set_paintable(Some(&texture));
+                if self.is_playing {
+                    self.start_timer(sender);
+                }
+            }
+            Slide::Video(video, orientation) => {
+                // The rotation of the video stream is already applied, so only
+                // turn by the orientation chosen by the user.
+                if let Some(orientation) = orientation {
+                    picture.add_css_class(orientation.as_ref());
+                }
+
+                let generation = self.generation;
+                {
+                    let sender = sender.clone();
+                    video.connect_ended_notify(move |video| {
+                        if video.is_ended() {
+                            sender.input(SlideshowInput::Advance(generation));
+                        }
+                    });
+                }
+                {
+                    let sender = sender.clone();
+                    video.connect_error_notify(move |video| {
+                        if let Some(e) = video.error() {
+                            error!("Failed playing video in slideshow: {}", e);
+                            sender.input(SlideshowInput::Advance(generation));
+                        }
+                    });
+                }
+
+                picture.set_paintable(Some(&video));
+                if self.is_playing {
+                    video.play();
+                }
+                self.video = Some(video);
+            }
+        }
+
+        self.stack.set_visible_child(&picture);
+        self.front = back;
+        self.is_shown = true;
+
+        self.preload(sender);
+    }
+
+    /// Load the next item so it is ready when needed.
+    fn preload(&mut self, sender: &ComponentSender<Self>) {
+        let next = self.next_index();
+        if next == self.index {
+            return;
+        }
+        if let Some(visual) = self.playlist.get(next).cloned() {
+            self.load(visual, sender);
+        }
+    }
+
+    fn load(&self, visual: Arc<Visual>, sender: &ComponentSender<Self>) {
+        let generation = self.generation;
+        let sender = sender.clone();
+        relm4::spawn_local(async move {
+            let slide = load_slide(&visual).await;
+            sender.input(SlideshowInput::Loaded(
+                generation,
+                visual.visual_id.clone(),
+                slide,
+            ));
+        });
+    }
+
+    /// Move on from the picture shown once the interval has passed.
+    fn start_timer(&mut self, sender: &ComponentSender<Self>) {
+        if let Some(timer) = self.timer.take() {
+            timer.abort();
+        }
+
+        let interval = self.settings_state.read().slideshow_interval_secs.max(1);
+        let interval = Duration::from_secs(interval as u64);
+        let generation = self.generation;
+        let sender = sender.clone();
+
+        self.timer = Some(relm4::spawn_local(async move {
+            glib::timeout_future(interval).await;
+            sender.input(SlideshowInput::Advance(generation));
+        }));
+    }
+
+    /// Stop the timer and video of the item shown.
+    fn stop_item(&mut self) {
+        if let Some(timer) = self.timer.take() {
+            timer.abort();
+        }
+        if let Some(video) = self.video.take() {
+            video.pause();
+        }
+    }
+
+    fn show_controls(&mut self, sender: &ComponentSender<Self>) {
+        self.is_controls_revealed = true;
+        self.root.set_cursor(None);
+
+        if let Some(timer) = self.controls_timer.take() {
+            timer.abort();
+        }
+
+        let sender = sender.clone();
+        self.controls_timer = Some(relm4::spawn_local(async move {
+            glib::timeout_future(CONTROLS_TIMEOUT).await;
+            sender.input(SlideshowInput::HideControls);
+        }));
+    }
+
+    fn enter_fullscreen(&mut self) {
+        let Some(window) = self.root.root().and_downcast::<gtk::Window>() else {
+            return;
+        };
+
+        self.was_fullscreen = window.is_fullscreen();
+        if !self.was_fullscreen {
+            window.fullscreen();
+        }
+
+        if self.inhibit_cookie.is_none() {
+            let cookie = relm4::main_application().inhibit(
+                Some(&window),
+                gtk::ApplicationInhibitFlags::IDLE,
+                Some(&fl!("slideshow-inhibit-reason")),
+            );
+            self.inhibit_cookie = Some(cookie);
+        }
+    }
+
+    fn leave_fullscreen(&mut self) {
+        if let Some(cookie) = self.inhibit_cookie.take() {
+            relm4::main_application().uninhibit(cookie);
+        }
+
+        if !self.was_fullscreen
+            && let Some(window) = self.root.root().and_downcast::<gtk::Window>()
+        {
+            window.unfullscreen();
+        }
+    }
+}
+
+/// Video to play for an item, if it has one that can be played without converting it first.
+fn video_path(visual: &Visual) -> Option<PathBuf> {
+    if visual.is_photo_only() {
+        return None;
+    }
+
+    let transcoded = visual.video_transcoded_path.clone().filter(|x| x.exists());
+
+    if transcoded.is_none() && visual.is_transcode_required.is_some_and(|x| x) {
+        return None;
+    }
+
+    transcoded
+        .or_else(|| visual.video_path.clone().map(|p| p.sandbox_path))
+        .filter(|x| x.exists())
+        .or_else(|| visual.motion_photo_video_path.clone())
+        .filter(|x| x.exists())
+}
+
+/// Load an item so it is ready to show.
+/// Live photos play their video. Animated pictures show their first frame.
+async fn load_slide(visual: &Visual) -> Option<Slide> {
+    if let Some(path) = video_path(visual) {
+        let video = gtk::MediaFile::for_filename(path);
+        video.set_loop(false);
+        video.set_muted(visual.is_motion_photo());
+        return Some(Slide::Video(video, visual.video_orientation_override));
+    }
+
+    // Videos that must be converted first can't be shown.
+    let path = visual.picture_path.as_ref()?.sandbox_path.clone();
+
+    let mut loader = glycin::Loader::new(gio::File::for_path(&path));
+    loader.apply_transformations(false);
+
+    let image = match loader.load().await {
+        Ok(image) => image,
+        Err(e) => {
+            error!("Failed loading {:?} for slideshow: {:?}", path, e);
+            return None;
+        }
+    };
+
+    let edits = visual
+        .picture_edits
+        .clone()
+        .filter(|_| visual.is_edited() && !visual.is_animated());
+
+    // Large pictures are loaded at a reduced size, which is still more than a display can show.
+    let details = image.details();
+    let is_large =
+        edits.is_none() && details.width() as u64 * details.height() as u64 > LARGE_IMAGE_PIXELS;
+
+    let frame = if is_large {
+        let (width, height) = preview_size(details.width(), details.height());
+        image
+            .specific_frame(glycin::FrameRequest::new().scale(width, height))
+            .await
+    } else {
+        image.next_frame().await
+    };
+
+    let frame = match frame {
+        Ok(frame) => frame,
+        Err(e) => {
+            error!("Failed getting frame of {:?} for slideshow: {:?}", path, e);
+            return None;
+        }
+    };
+
+    let orientation = visual
+        .picture_orientation
+        .unwrap_or(PictureOrientation::North);
+
+    let texture = match edits {
+        Some(edits) => render_edits(&frame.texture(), Some(orientation), edits).await?,
+        None => upright_texture(&frame.texture(), orientation).await?,
+    };
+
+    Some(Slide::Picture(texture))
+}
+
+/// Shuffle items into a random order.
+fn shuffle<T>(items: &mut [T]) {
+    for i in (1..items.len()).rev() {
+        let j = glib::random_int_range(0, i as i32 + 1) as usize;
+        items.swap(i, j);
+    }
+}
@@ -0,0 +1,211 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use ashpd::{
+    WindowIdentifier,
+    desktop::file_chooser::{FileFilter, OpenFileRequest},
+};
+
+use relm4::adw::prelude::*;
+use relm4::gtk;
+use relm4::prelude::*;
+
+use gtk::glib;
+
+use tracing::{error, info};
+
+use crate::fl;
+use chrono::TimeDelta;
+use fotema_core::geotag::Correlator;
+use std::path::PathBuf;
+
+/// Default maximum gap between a capture time and a track point.
+const DEFAULT_TOLERANCE_MINUTES: f64 = 5.0;
+
+#[derive(Debug)]
+pub enum TrackImportInput {
+    /// Show the track import dialog.
+    Present,
+
+    /// Choose a GPX, KML, or GeoJSON file.
+    ChooseFile,
+
+    /// Match items to the chosen track log.
+    Import,
+}
+
+#[derive(Debug)]
+pub enum TrackImportOutput {
+    /// Match items without a location to a track log.
+    Import(PathBuf, Correlator),
+}
+
+/// Dialog for importing a GPS track log and matching it to pictures and videos
+/// that don't have a location.
+pub struct TrackImportDialog {
+    parent: adw::ApplicationWindow,
+    dialog: adw::Dialog,
+
+    tolerance_row: adw::SpinRow,
+    clock_offset_row: adw::SpinRow,
+
+    /// Track log to import
+    track_path: Option<PathBuf>,
+}
+
+impl TrackImportDialog {
+    fn track_file_name(&self) -> String {
+        self.track_path
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| fl!("track-import-file", "none"))
+    }
+}
+
+#[relm4::component(pub async)]
+impl SimpleAsyncComponent for TrackImportDialog {
+    type Init = adw::ApplicationWindow;
+    type Input = TrackImportInput;
+    type Output = TrackImportOutput;
+
+    view! {
+        adw::Dialog {
+            set_title: &fl!("track-import-title"),
+            set_content_width: 480,
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar,
+
+                #[wrap(Some)]
+                set_content = &adw::PreferencesPage {
+                    add = &adw::PreferencesGroup {
+                        set_description: Some(&fl!("track-import-description")),
+
+                        adw::ActionRow {
+                            set_title: &fl!("track-import-file"),
+
+                            #[watch]
+                            set_subtitle: &model.track_file_name(),
+
+                            add_suffix = &gtk::Button {
+                                set_valign: gtk::Align::Center,
+                                set_icon_name: "document-open-symbolic",
+                                set_tooltip_text: Some(&fl!("track-import-file", "tooltip")),
+                                connect_clicked => TrackImportInput::ChooseFile,
+                            }
+                        },
+
+                        #[local_ref]
+                        tolerance_row -> adw::SpinRow {
+                            set_title: &fl!("track-import-tolerance"),
+                            set_subtitle: &fl!("track-import-tolerance", "subtitle"),
+                        },
+
+                        #[local_ref]
+                        clock_offset_row -> adw::SpinRow {
+                            set_title: &fl!("track-import-clock-offset"),
+                            set_subtitle: &fl!("track-import-clock-offset", "subtitle"),
+                        },
+                    },
+
+                    add = &adw::PreferencesGroup {
+                        gtk::Button {
+                            set_label: &fl!("track-import-button"),
+                            set_halign: gtk::Align::Center,
+                            add_css_class: "pill",
+                            add_css_class: "suggested-action",
+
+                            #[watch]
+                            set_sensitive: model.track_path.is_some(),
+
+                            connect_clicked => TrackImportInput::Import,
+                        },
+                    },
+                },
+            },
+        }
+    }
+
+    async fn init(
+        parent: Self::Init,
+        dialog: Self::Root,
+        sender: AsyncComponentSender<Self>,
+    ) -> AsyncComponentParts<Self> {
+        let tolerance_row = adw::SpinRow::with_range(1.0, 120.0, 1.0);
+        tolerance_row.set_value(DEFAULT_TOLERANCE_MINUTES);
+
+        // A day either way covers cameras set to the wrong time zone.
+        let clock_offset_row = adw::SpinRow::with_range(-1440.0, 1440.0, 1.0);
+        clock_offset_row.set_value(0.0);
+
+        let model = Self {
+            parent,
+            dialog: dialog.clone(),
+            tolerance_row: tolerance_row.clone(),
+            clock_offset_row: clock_offset_row.clone(),
+            track_path: None,
+        };
+
+        let widgets = view_output!();
+
+        AsyncComponentParts { model, widgets }
+    }
+
+    async fn update(&mut self, msg: Self::Input, sender: AsyncComponentSender<Self>) {
+        match msg {
+            TrackImportInput::Present => {
+                self.dialog.present(Some(&self.parent));
+            }
+            TrackImportInput::ChooseFile => {
+                info!("Presenting track log file chooser");
+                let Some(root) = gtk::Widget::root(self.parent.widget_ref()) else {
+                    return;
+                };
+
+                let identifier = WindowIdentifier::from_native(&root).await;
+                let filter = FileFilter::new(&fl!("track-import-file", "filter"))
+                    .glob("*.gpx")
+                    .glob("*.kml")
+                    .glob("*.geojson")
+                    .glob("*.json");
+
+                let request = OpenFileRequest::default()
+                    .identifier(identifier)
+                    .modal(true)
+                    .multiple(false)
+                    .filter(filter);
+
+                match request.send().await.and_then(|r| r.response()) {
+                    Ok(files) => {
+                        self.track_path = files.uris().first().and_then(|uri| {
+                            glib::Uri::parse(uri.as_str(), glib::UriFlags::NONE)
+                                .map(|glib_uri| PathBuf::from(glib_uri.path()))
+                                .ok()
+                        });
+                        info!("User has chosen track log: {:?}", self.track_path);
+                    }
+                    Err(err) => {
+                        error!("Failed to choose a track log: {err}");
+                    }
+                }
+            }
+            TrackImportInput::Import => {
+                let Some(track_path) = self.track_path.take() else {
+                    return;
+                };
+
+                let correlator = Correlator {
+                    tolerance: TimeDelta::minutes(self.tolerance_row.value() as i64),
+                    clock_offset: TimeDelta::minutes(self.clock_offset_row.value() as i64),
+                };
+
+                info!("Importing track log {:?} with {:?}", track_path, correlator);
+                self.dialog.close();
+                let _ = sender.output(TrackImportOutput::Import(track_path, correlator));
+            }
+        }
+    }
+}
@@ -7,3 +7,4 @@ pub mod person_select;
 pub mod view_info;
 pub mod view_nav;
 pub mod view_one;
+pub mod zoom;
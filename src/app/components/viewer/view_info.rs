@@ -50,6 +50,7 @@ pub struct ViewInfo {
 
     folder: adw::ActionRow,
     file_name: adw::ActionRow,
+    place: adw::ActionRow,
 
     // FIXME what timestamps to show for live photos that have an image an a video?
     date_time_details: adw::PreferencesGroup,
@@ -124,6 +125,17 @@ impl SimpleComponent for ViewInfo {
                             set_icon_name: Some("image-alt-symbolic"),
                         }
                     },
+
+                    #[local_ref]
+                    place -> adw::ActionRow {
+                        set_title: &fl!("infobar-place"),
+                        add_css_class: "property",
+                        set_subtitle_selectable: true,
+
+                        add_prefix = &gtk::Image {
+                            set_icon_name: Some("mark-location-symbolic"),
+                        }
+                    },
                 },
 
                 #[local_ref]
@@ -303,6 +315,7 @@ impl SimpleComponent for ViewInfo {
     ) -> ComponentParts<Self> {
         let folder = adw::ActionRow::new();
         let file_name = adw::ActionRow::new();
+        let place = adw::ActionRow::new();
 
         let date_time_details = adw::PreferencesGroup::new();
         let created_at = adw::ActionRow::new();
@@ -334,6 +347,7 @@ impl SimpleComponent for ViewInfo {
 
             folder: folder.clone(),
             file_name: file_name.clone(),
+            place: place.clone(),
             path: None,
 
             date_time_details: date_time_details.clone(),
@@ -470,6 +484,7 @@ impl ViewInfo {
             &self.file_name,
             vis.host_path().file_name().map(|p| p.to_string_lossy()),
         );
+        Self::update_row(&self.place, vis.place.as_ref().map(|p| p.to_string()));
 
         // FIXME duplicated from Scanner
         let file = fs::File::open(vis.sandbox_path()).map_err(|e| e.to_string())?;
@@ -2,6 +2,7 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use ashpd::{WindowIdentifier, desktop::file_chooser::SaveFileRequest};
 use relm4::actions::{RelmAction, RelmActionGroup};
 use relm4::binding::*;
 use relm4::gtk;
@@ -11,20 +12,30 @@ use relm4::gtk::prelude::*;
 use relm4::prelude::*;
 use relm4::*;
 
+use chrono::TimeDelta;
+
 use super::view_info::{ViewInfo, ViewInfoInput};
 use super::view_one::{ViewOne, ViewOneInput, ViewOneOutput};
+use super::zoom::ZoomRequest;
 use crate::app::components::albums::album_filter::AlbumFilter;
 use crate::app::components::albums::album_sort::AlbumSort;
 
 use crate::adaptive;
+use crate::app::PrivacyState;
 use crate::app::SharedState;
 use crate::app::components::progress_monitor::ProgressMonitor;
 use crate::fl;
 
 use fotema_core::Visual;
 use fotema_core::VisualId;
+use fotema_core::export::MetadataPolicy;
+use fotema_core::orientation::Turn;
 use fotema_core::people;
+use fotema_core::video::cache::{self, CacheOwner};
+use fotema_core::video::export::{self, ExportFormat};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 
 use tracing::{debug, error, info};
 
@@ -46,6 +57,17 @@ relm4::new_stateless_action!(
     "ignore_unknown_faces"
 );
 
+relm4::new_stateless_action!(ExportVideoAction, ViewNavActionGroup, "export_video");
+relm4::new_stateless_action!(ExportGifAction, ViewNavActionGroup, "export_gif");
+relm4::new_stateless_action!(ExportWebPAction, ViewNavActionGroup, "export_webp");
+relm4::new_stateless_action!(ExportStillAction, ViewNavActionGroup, "export_still");
+
+relm4::new_stateless_action!(RenameAction, ViewNavActionGroup, "rename");
+relm4::new_stateless_action!(MoveToFolderAction, ViewNavActionGroup, "move_to_folder");
+relm4::new_stateless_action!(CopyToFolderAction, ViewNavActionGroup, "copy_to_folder");
+relm4::new_stateless_action!(TrashAction, ViewNavActionGroup, "trash");
+relm4::new_stateless_action!(ShareAction, ViewNavActionGroup, "share");
+
 #[derive(Debug)]
 pub enum ViewNavInput {
     /// View an item after applying an album filter.
@@ -78,6 +100,12 @@ pub enum ViewNavInput {
     /// Go to the next photo
     GoRight,
 
+    /// Zoom the item being viewed.
+    Zoom(ZoomRequest),
+
+    /// Item being viewed has been zoomed in, or fitted to the view again.
+    Zoomed(bool),
+
     /// How much of the bottom of the view that the bottom sheet obscures.
     SheetHeight(i32),
 
@@ -92,18 +120,99 @@ pub enum ViewNavInput {
 
     // Sort
     Sort(AlbumSort),
+
+    /// Set the location of the item being viewed.
+    SetLocation,
+
+    /// Transcode or extract a video again after it was evicted from the video cache.
+    RestoreCache(Arc<Visual>),
+
+    /// Edit the picture being viewed.
+    Edit,
+
+    /// Turn the item being viewed a quarter turn, because its orientation is wrong.
+    Rotate(Turn),
+
+    /// Edits or orientation of an item have changed, so show it again.
+    Edited(Arc<Visual>),
+
+    /// Export the video of the live or motion photo being viewed.
+    Export(ExportFormat),
+
+    /// Choose where to export the video of a live or motion photo, then export it.
+    ExportVisual(VisualId, ExportFormat),
+
+    /// Exporting the video of a live or motion photo failed.
+    ExportFailed,
+
+    /// Rename the files of the item being viewed.
+    Rename,
+
+    /// Move the files of the item being viewed to another folder.
+    MoveToFolder,
+
+    /// Copy the files of the item being viewed to another folder.
+    CopyToFolder,
+
+    /// Move the files of the item being viewed to the trash.
+    Trash,
+
+    /// Export a copy of the item being viewed, or share it with another app.
+    Share,
+
+    /// Items have been added to or removed from the library, so filter the album again.
+    /// If the item being viewed has gone, then view its neighbour instead.
+    Refresh,
 }
 
 #[derive(Debug)]
 pub enum ViewNavOutput {
     TranscodeAll,
+
+    /// Set the location of items by dropping a pin on a map.
+    SetLocation(Vec<Arc<Visual>>),
+
+    /// Transcode or extract a video again after it was evicted from the video cache.
+    RestoreCache(Arc<Visual>),
+
+    /// Edit a picture.
+    Edit(Arc<Visual>),
+
+    /// Turn an item a quarter turn.
+    Rotate(Arc<Visual>, Turn),
+
+    /// Rename the files of an item.
+    Rename(Arc<Visual>),
+
+    /// Move the files of items to another folder.
+    MoveToFolder(Vec<Arc<Visual>>),
+
+    /// Copy the files of items to another folder.
+    CopyToFolder(Vec<Arc<Visual>>),
+
+    /// Move the files of items to the trash.
+    Trash(Vec<Arc<Visual>>),
+
+    /// Export copies of items, or share them with another app.
+    Share(Vec<Arc<Visual>>),
+
+    /// No items are left to view.
+    Empty,
 }
 
 pub struct ViewNav {
     state: SharedState,
 
+    /// Decides whether an exported video may keep its location.
+    privacy_state: PrivacyState,
+
+    toast_overlay: adw::ToastOverlay,
+
     people_repo: people::Repository,
 
+    /// Records when videos are viewed so least recently viewed videos are evicted first.
+    cache_repo: cache::Repository,
+
     /// Carousel for swiping through items
     carousel: adw::Carousel,
 
@@ -160,6 +269,9 @@ impl SimpleAsyncComponent for ViewNav {
         Arc<Reducer<ProgressMonitor>>,
         Arc<adaptive::LayoutState>,
         people::Repository,
+        cache::Repository,
+        PrivacyState,
+        adw::ToastOverlay,
     );
     type Input = ViewNavInput;
     type Output = ViewNavOutput;
@@ -170,6 +282,27 @@ impl SimpleAsyncComponent for ViewNav {
                 &fl!("viewer-faces-menu", "restore-ignored") => RestoreIgnoredFacesAction,
                 &fl!("viewer-faces-menu", "ignore-unknown") => IgnoreUnknownFacesAction,
             }
+        },
+        export_menu: {
+            section! {
+                &fl!("viewer-export-menu", "video") => ExportVideoAction,
+                &fl!("viewer-export-menu", "gif") => ExportGifAction,
+                &fl!("viewer-export-menu", "webp") => ExportWebPAction,
+                &fl!("viewer-export-menu", "still") => ExportStillAction,
+            }
+        },
+        file_menu: {
+            section! {
+                &fl!("viewer-file-menu", "rename") => RenameAction,
+                &fl!("viewer-file-menu", "move") => MoveToFolderAction,
+                &fl!("viewer-file-menu", "copy") => CopyToFolderAction,
+            },
+            section! {
+                &fl!("viewer-file-menu", "share") => ShareAction,
+            },
+            section! {
+                &fl!("viewer-file-menu", "trash") => TrashAction,
+            }
         }
     }
 
@@ -179,11 +312,60 @@ impl SimpleAsyncComponent for ViewNav {
                 pack_end = &gtk::Box {
                     set_orientation: gtk::Orientation::Horizontal,
 
+                    // Edited pictures are rotated in the edit dialog instead.
+                    gtk::Button {
+                        set_icon_name: "object-rotate-left-symbolic",
+                        set_tooltip_text: Some(&fl!("viewer-rotate-left-tooltip")),
+                        connect_clicked => ViewNavInput::Rotate(Turn::Left),
+
+                        #[watch]
+                        set_visible: model.current_visual().is_some_and(|v| !v.is_edited()),
+                    },
+
+                    gtk::Button {
+                        set_icon_name: "object-rotate-right-symbolic",
+                        set_tooltip_text: Some(&fl!("viewer-rotate-right-tooltip")),
+                        connect_clicked => ViewNavInput::Rotate(Turn::Right),
+
+                        #[watch]
+                        set_visible: model.current_visual().is_some_and(|v| !v.is_edited()),
+                    },
+
+                    gtk::Button {
+                        set_icon_name: "document-edit-symbolic",
+                        set_tooltip_text: Some(&fl!("viewer-edit-tooltip")),
+                        connect_clicked => ViewNavInput::Edit,
+
+                        #[watch]
+                        set_visible: model.current_visual().is_some_and(|v| v.is_photo_only() && !v.is_animated()),
+                    },
+
+                    gtk::Button {
+                        set_icon_name: "mark-location-symbolic",
+                        set_tooltip_text: Some(&fl!("viewer-set-location-tooltip")),
+                        connect_clicked => ViewNavInput::SetLocation,
+                    },
+
+                    gtk::MenuButton {
+                        set_icon_name: "document-save-as-symbolic",
+                        set_tooltip_text: Some(&fl!("viewer-export-menu", "tooltip")),
+                        set_menu_model: Some(&export_menu),
+
+                        #[watch]
+                        set_visible: model.current_visual().is_some_and(|v| v.live_video_path().is_some()),
+                    },
+
                     gtk::MenuButton {
                         set_icon_name: "sentiment-very-satisfied-symbolic",
                         set_menu_model: Some(&viewnav_menu),
                     },
 
+                    gtk::MenuButton {
+                        set_icon_name: "folder-symbolic",
+                        set_tooltip_text: Some(&fl!("viewer-file-menu", "tooltip")),
+                        set_menu_model: Some(&file_menu),
+                    },
+
                     gtk::Button {
                         set_icon_name: "info-outline-symbolic",
                         set_tooltip_text: Some(&fl!("viewer-info-tooltip")),
@@ -219,7 +401,15 @@ impl SimpleAsyncComponent for ViewNav {
     }
 
     async fn init(
-        (state, transcode_progress_monitor, layout_state, people_repo): Self::Init,
+        (
+            state,
+            transcode_progress_monitor,
+            layout_state,
+            people_repo,
+            cache_repo,
+            privacy_state,
+            toast_overlay,
+        ): Self::Init,
         root: Self::Root,
         sender: AsyncComponentSender<Self>,
     ) -> AsyncComponentParts<Self> {
@@ -339,6 +529,11 @@ impl SimpleAsyncComponent for ViewNav {
                     ViewOneOutput::VideoShown(id) => ViewNavInput::ShowVideoInfo(id),
                     ViewOneOutput::ErrorShown(id) => ViewNavInput::ShowError(id),
                     ViewOneOutput::TranscodeShown(id) => ViewNavInput::ShowTranscode(id),
+                    ViewOneOutput::CacheEvicted(visual) => ViewNavInput::RestoreCache(visual),
+                    ViewOneOutput::Export(id, export_format) => {
+                        ViewNavInput::ExportVisual(id, export_format)
+                    }
+                    ViewOneOutput::Zoomed(is_zoomed) => ViewNavInput::Zoomed(is_zoomed),
                 }),
         );
 
@@ -351,6 +546,11 @@ impl SimpleAsyncComponent for ViewNav {
                     ViewOneOutput::VideoShown(id) => ViewNavInput::ShowVideoInfo(id),
                     ViewOneOutput::ErrorShown(id) => ViewNavInput::ShowError(id),
                     ViewOneOutput::TranscodeShown(id) => ViewNavInput::ShowTranscode(id),
+                    ViewOneOutput::CacheEvicted(visual) => ViewNavInput::RestoreCache(visual),
+                    ViewOneOutput::Export(id, export_format) => {
+                        ViewNavInput::ExportVisual(id, export_format)
+                    }
+                    ViewOneOutput::Zoomed(is_zoomed) => ViewNavInput::Zoomed(is_zoomed),
                 }),
         );
 
@@ -363,6 +563,11 @@ impl SimpleAsyncComponent for ViewNav {
                     ViewOneOutput::VideoShown(id) => ViewNavInput::ShowVideoInfo(id),
                     ViewOneOutput::ErrorShown(id) => ViewNavInput::ShowError(id),
                     ViewOneOutput::TranscodeShown(id) => ViewNavInput::ShowTranscode(id),
+                    ViewOneOutput::CacheEvicted(visual) => ViewNavInput::RestoreCache(visual),
+                    ViewOneOutput::Export(id, export_format) => {
+                        ViewNavInput::ExportVisual(id, export_format)
+                    }
+                    ViewOneOutput::Zoomed(is_zoomed) => ViewNavInput::Zoomed(is_zoomed),
                 }),
         );
 
@@ -374,7 +579,10 @@ impl SimpleAsyncComponent for ViewNav {
 
         let model = ViewNav {
             state,
+            privacy_state,
+            toast_overlay,
             people_repo,
+            cache_repo,
             carousel: carousel.clone(),
             carousel_pages,
             carousel_last_page_index: 0,
@@ -405,9 +613,82 @@ impl SimpleAsyncComponent for ViewNav {
             })
         };
 
+        let export_video_action = {
+            let sender = sender.clone();
+            RelmAction::<ExportVideoAction>::new_stateless(move |_| {
+                sender.input(ViewNavInput::Export(ExportFormat::Mp4));
+            })
+        };
+
+        let export_gif_action = {
+            let sender = sender.clone();
+            RelmAction::<ExportGifAction>::new_stateless(move |_| {
+                sender.input(ViewNavInput::Export(ExportFormat::Gif));
+            })
+        };
+
+        let export_webp_action = {
+            let sender = sender.clone();
+            RelmAction::<ExportWebPAction>::new_stateless(move |_| {
+                sender.input(ViewNavInput::Export(ExportFormat::WebP));
+            })
+        };
+
+        let export_still_action = {
+            let sender = sender.clone();
+            RelmAction::<ExportStillAction>::new_stateless(move |_| {
+                // Timestamp is replaced with that of the frame being shown.
+                sender.input(ViewNavInput::Export(ExportFormat::Jpeg(TimeDelta::zero())));
+            })
+        };
+
+        let rename_action = {
+            let sender = sender.clone();
+            RelmAction::<RenameAction>::new_stateless(move |_| {
+                sender.input(ViewNavInput::Rename);
+            })
+        };
+
+        let move_to_folder_action = {
+            let sender = sender.clone();
+            RelmAction::<MoveToFolderAction>::new_stateless(move |_| {
+                sender.input(ViewNavInput::MoveToFolder);
+            })
+        };
+
+        let copy_to_folder_action = {
+            let sender = sender.clone();
+            RelmAction::<CopyToFolderAction>::new_stateless(move |_| {
+                sender.input(ViewNavInput::CopyToFolder);
+            })
+        };
+
+        let trash_action = {
+            let sender = sender.clone();
+            RelmAction::<TrashAction>::new_stateless(move |_| {
+                sender.input(ViewNavInput::Trash);
+            })
+        };
+
+        let share_action = {
+            let sender = sender.clone();
+            RelmAction::<ShareAction>::new_stateless(move |_| {
+                sender.input(ViewNavInput::Share);
+            })
+        };
+
         let mut actions = RelmActionGroup::<ViewNavActionGroup>::new();
         actions.add_action(restore_action);
         actions.add_action(ignore_unknown_faces_action);
+        actions.add_action(export_video_action);
+        actions.add_action(export_gif_action);
+        actions.add_action(export_webp_action);
+        actions.add_action(export_still_action);
+        actions.add_action(rename_action);
+        actions.add_action(move_to_folder_action);
+        actions.add_action(copy_to_folder_action);
+        actions.add_action(trash_action);
+        actions.add_action(share_action);
         actions.register_for_widget(&root);
 
         let keys = gtk::EventControllerKey::new();
@@ -422,6 +703,26 @@ impl SimpleAsyncComponent for ViewNav {
                     sender.input(ViewNavInput::GoRight);
                     glib::Propagation::Stop
                 }
+                gdk::Key::plus | gdk::Key::equal | gdk::Key::KP_Add => {
+                    sender.input(ViewNavInput::Zoom(ZoomRequest::In));
+                    glib::Propagation::Stop
+                }
+                gdk::Key::minus | gdk::Key::KP_Subtract => {
+                    sender.input(ViewNavInput::Zoom(ZoomRequest::Out));
+                    glib::Propagation::Stop
+                }
+                gdk::Key::_0 | gdk::Key::KP_0 => {
+                    sender.input(ViewNavInput::Zoom(ZoomRequest::Fit));
+                    glib::Propagation::Stop
+                }
+                gdk::Key::_1 | gdk::Key::KP_1 => {
+                    sender.input(ViewNavInput::Zoom(ZoomRequest::Actual));
+                    glib::Propagation::Stop
+                }
+                gdk::Key::Delete | gdk::Key::KP_Delete => {
+                    sender.input(ViewNavInput::Trash);
+                    glib::Propagation::Stop
+                }
                 _ => glib::Propagation::Proceed,
             });
         }
@@ -669,6 +970,7 @@ impl SimpleAsyncComponent for ViewNav {
                     .emit(ViewInfoInput::Photo(visual_id, image_info));
             }
             ViewNavInput::ShowVideoInfo(visual_id) => {
+                self.touch_cache(&visual_id);
                 self.view_info.emit(ViewInfoInput::Video(visual_id));
             }
             ViewNavInput::ShowTranscode(visual_id) => {
@@ -677,6 +979,9 @@ impl SimpleAsyncComponent for ViewNav {
             ViewNavInput::ShowError(visual_id) => {
                 self.view_info.emit(ViewInfoInput::FileOnly(visual_id));
             }
+            ViewNavInput::RestoreCache(visual) => {
+                let _ = sender.output(ViewNavOutput::RestoreCache(visual));
+            }
             ViewNavInput::TranscodeAll => {
                 info!("Transcode all");
                 // FIXME refactor to remove message forwarding.
@@ -700,6 +1005,18 @@ impl SimpleAsyncComponent for ViewNav {
                     }
                 }
             }
+            ViewNavInput::Zoom(request) => {
+                let position = self.carousel.position().round() as usize;
+                if let Some(page) = self.carousel_pages.get(position)
+                    && position < self.carousel.n_pages() as usize
+                {
+                    page.emit(ViewOneInput::Zoom(request));
+                }
+            }
+            ViewNavInput::Zoomed(is_zoomed) => {
+                // Swiping would fight with panning a zoomed picture.
+                self.carousel.set_interactive(!is_zoomed);
+            }
             ViewNavInput::Adapt(adaptive::Layout::Narrow) => {
                 self.is_narrow = true;
             }
@@ -755,6 +1072,164 @@ impl SimpleAsyncComponent for ViewNav {
                 self.album_filter = AlbumFilter::None;
                 self.album.clear();
             }
+            ViewNavInput::SetLocation => {
+                let Some(visual) = self.album_index.and_then(|index| self.album.get(index)) else {
+                    return;
+                };
+
+                let _ = sender.output(ViewNavOutput::SetLocation(vec![visual.clone()]));
+            }
+            ViewNavInput::Edit => {
+                if let Some(visual) = self.current_visual() {
+                    let _ = sender.output(ViewNavOutput::Edit(visual.clone()));
+                }
+            }
+            ViewNavInput::Rotate(turn) => {
+                if let Some(visual) = self.current_visual() {
+                    let _ = sender.output(ViewNavOutput::Rotate(visual.clone(), turn));
+                }
+            }
+            ViewNavInput::Edited(visual) => {
+                let Some(index) = self
+                    .album
+                    .iter()
+                    .position(|v| v.visual_id == visual.visual_id)
+                else {
+                    return;
+                };
+
+                self.album[index] = visual.clone();
+
+                // Only the page being viewed can be edited.
+                if self.album_index == Some(index)
+                    && let Some(page) = self
+                        .carousel_pages
+                        .get(self.carousel_last_page_index as usize)
+                {
+                    page.emit(ViewOneInput::Load(visual));
+                    page.emit(ViewOneInput::View);
+                }
+            }
+            ViewNavInput::Export(export_format) => {
+                // Current page knows the timestamp of the frame being shown.
+                if let Some(page) = self
+                    .carousel_pages
+                    .get(self.carousel_last_page_index as usize)
+                {
+                    page.emit(ViewOneInput::Export(export_format));
+                }
+            }
+            ViewNavInput::ExportVisual(visual_id, export_format) => {
+                let Some(visual) = self.album.iter().find(|v| v.visual_id == visual_id) else {
+                    return;
+                };
+
+                let Some(video_path) = visual.live_video_path().map(PathBuf::from) else {
+                    return;
+                };
+
+                let file_name = visual
+                    .host_path()
+                    .file_stem()
+                    .map(|x| x.to_string_lossy().to_string())
+                    .unwrap_or_else(|| visual_id.to_string());
+                let file_name = format!("{}.{}", file_name, export_format.extension());
+
+                let Some(output_path) = self.choose_export_path(&file_name).await else {
+                    return;
+                };
+
+                // Metadata can only be kept whole when it reveals nothing the privacy policy hides.
+                let metadata = if self.privacy_state.read().keeps_original_location() {
+                    MetadataPolicy::All
+                } else {
+                    MetadataPolicy::NoLocation
+                };
+
+                info!(
+                    "Exporting {:?} as {:?} to {:?}",
+                    video_path, export_format, output_path
+                );
+
+                // Don't wait for the export so the viewer stays responsive.
+                relm4::spawn_blocking(move || {
+                    let stop = AtomicBool::new(false);
+                    match export::export(&video_path, &output_path, export_format, metadata, &stop)
+                    {
+                        Ok(()) => info!("Exported {:?}", output_path),
+                        Err(e) => {
+                            error!("Failed exporting {:?}: {:?}", video_path, e);
+                            sender.input(ViewNavInput::ExportFailed);
+                        }
+                    }
+                });
+            }
+            ViewNavInput::ExportFailed => {
+                let toast = adw::Toast::new(&fl!("viewer-export-failed"));
+                self.toast_overlay.add_toast(toast);
+            }
+            ViewNavInput::Rename => {
+                if let Some(visual) = self.current_visual() {
+                    let _ = sender.output(ViewNavOutput::Rename(visual.clone()));
+                }
+            }
+            ViewNavInput::MoveToFolder => {
+                if let Some(visual) = self.current_visual() {
+                    let _ = sender.output(ViewNavOutput::MoveToFolder(vec![visual.clone()]));
+                }
+            }
+            ViewNavInput::CopyToFolder => {
+                if let Some(visual) = self.current_visual() {
+                    let _ = sender.output(ViewNavOutput::CopyToFolder(vec![visual.clone()]));
+                }
+            }
+            ViewNavInput::Trash => {
+                if let Some(visual) = self.current_visual() {
+                    let _ = sender.output(ViewNavOutput::Trash(vec![visual.clone()]));
+                }
+            }
+            ViewNavInput::Share => {
+                if let Some(visual) = self.current_visual() {
+                    let _ = sender.output(ViewNavOutput::Share(vec![visual.clone()]));
+                }
+            }
+            ViewNavInput::Refresh => {
+                let album_filter = std::mem::replace(&mut self.album_filter, AlbumFilter::None);
+
+                let Some(index) = self.album_index else {
+                    // Not viewing, so filter again when next viewing.
+                    self.album.clear();
+                    return;
+                };
+
+                let visual_id = self.album[index].visual_id.clone();
+
+                self.album = self
+                    .state
+                    .read()
+                    .iter()
+                    .filter(|v| album_filter.clone().filter(v))
+                    .cloned()
+                    .collect();
+                self.album_sort.sort(&mut self.album);
+                self.album_filter = album_filter.clone();
+
+                // Items after a removed item move up into its place.
+                let visual_id = if self.album.iter().any(|v| v.visual_id == visual_id) {
+                    Some(visual_id)
+                } else {
+                    self.album
+                        .get(index.min(self.album.len().saturating_sub(1)))
+                        .map(|v| v.visual_id.clone())
+                };
+
+                if let Some(visual_id) = visual_id {
+                    sender.input(ViewNavInput::View(visual_id, album_filter));
+                } else {
+                    self.album_index = None;
+                    let _ = sender.output(ViewNavOutput::Empty);
+                }
+            }
             ViewNavInput::SheetHeight(height) => {
                 let shift = (height as f32 * 0.60) as i32;
                 self.bottom_margin.set_value(shift);
@@ -770,6 +1245,33 @@ impl SimpleAsyncComponent for ViewNav {
 }
 
 impl ViewNav {
+    fn current_visual(&self) -> Option<&Arc<Visual>> {
+        self.album_index.and_then(|index| self.album.get(index))
+    }
+
+    /// Ask the user where to save an export.
+    async fn choose_export_path(&self, file_name: &str) -> Option<PathBuf> {
+        let root = self.carousel.root()?;
+        let identifier = WindowIdentifier::from_native(&root).await;
+
+        let request = SaveFileRequest::default()
+            .identifier(identifier)
+            .modal(true)
+            .current_name(file_name);
+
+        match request.send().await.and_then(|r| r.response()) {
+            Ok(files) => files.uris().first().and_then(|uri| {
+                glib::Uri::parse(uri.as_str(), glib::UriFlags::NONE)
+                    .map(|glib_uri| PathBuf::from(glib_uri.path()))
+                    .ok()
+            }),
+            Err(err) => {
+                error!("Failed to choose export file: {err}");
+                None
+            }
+        }
+    }
+
     fn is_left_button_sensitive(&self) -> bool {
         self.album_index.is_some_and(|index| index > 0)
     }
@@ -780,4 +1282,25 @@ impl ViewNav {
                 .album_index
                 .is_some_and(|index| index != self.album.len() - 1)
     }
+
+    /// Record that the video of an item has been viewed.
+    fn touch_cache(&mut self, visual_id: &VisualId) {
+        let Some(visual) = self.album.iter().find(|v| v.visual_id == *visual_id) else {
+            return;
+        };
+
+        let owner = if let Some(picture_id) = visual.picture_id
+            && visual.motion_photo_video_path.is_some()
+        {
+            CacheOwner::MotionPhoto(picture_id)
+        } else if let Some(video_id) = visual.video_id {
+            CacheOwner::Video(video_id)
+        } else {
+            return;
+        };
+
+        if let Err(e) = self.cache_repo.touch(owner) {
+            error!("Failed recording video access: {:?}", e);
+        }
+    }
 }
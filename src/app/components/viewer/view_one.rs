@@ -6,28 +6,56 @@ use chrono::TimeDelta;
 use fotema_core::FlatpakPathBuf;
 use fotema_core::Visual;
 use fotema_core::VisualId;
+use fotema_core::edits::{self, EditStack};
+use fotema_core::orientation;
+use fotema_core::orientation::transform::{self, Region};
+use fotema_core::video::export::ExportFormat;
 use fotema_core::visual::model::PictureOrientation;
 
 use glycin;
 use relm4::adw::gdk;
 use relm4::gtk;
 use relm4::gtk::gio;
+use relm4::gtk::glib;
 use relm4::gtk::prelude::*;
 use relm4::prelude::*;
 use relm4::*;
 use strum::IntoEnumIterator;
 
+use super::zoom::{ZOOM_STEP, Zoom, ZoomRequest};
 use crate::app::components::progress_monitor::ProgressMonitor;
 use crate::app::components::progress_panel::ProgressPanel;
 use crate::fl;
 
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Duration;
 
-use tracing::{Level, debug, event, info};
+use tracing::{Level, debug, error, event, info};
 
 const TEN_SECS_IN_MICROS: i64 = 10_000_000;
 const FIFTEEN_SECS_IN_MICROS: i64 = 15_000_000;
 
+// Browsers treat very short frame delays in animated images as 100 milliseconds,
+// and some images rely on that.
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(20);
+const DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(100);
+
+// Pictures with more pixels than this are first shown at a reduced size, and detail
+// is loaded in tiles when zooming in.
+pub const LARGE_IMAGE_PIXELS: u64 = 50_000_000;
+
+// Longest edge of the reduced size of a large picture.
+const LARGE_IMAGE_PREVIEW_EDGE: u32 = 4096;
+
+// Edge of a tile of a large picture, in picture pixels.
+const TILE_SIZE: u32 = 1024;
+
+// Wait for panning and zooming to settle before loading tiles.
+const TILE_DELAY: Duration = Duration::from_millis(150);
+
 #[derive(Debug, Eq, PartialEq)]
 pub enum Viewing {
     Photo,
@@ -95,6 +123,30 @@ pub enum ViewOneInput {
 
     // Video has been "prepared", so duration should be available
     VideoPrepared,
+
+    // Export the video of a live or motion photo.
+    Export(ExportFormat),
+
+    // Zoom from a keyboard shortcut or button.
+    Zoom(ZoomRequest),
+
+    // Zoom by a factor, keeping the point under the pointer in place.
+    ZoomBy(f64, f64, f64),
+
+    // Switch between fitting the picture and its actual size, at a point in the view.
+    ZoomToggle(f64, f64),
+
+    // A pinch to zoom has started.
+    PinchBegin,
+
+    // Pinch to zoom by a scale since the pinch started, centred on a point in the view.
+    Pinch(f64, f64, f64),
+
+    // The visible part of a zoomed picture has changed.
+    ViewportChanged,
+
+    // A tile of a large picture has loaded. u64 is the tile generation.
+    TileLoaded(u64, Region, gdk::Texture),
 }
 
 #[derive(Debug)]
@@ -113,9 +165,39 @@ pub enum ViewOneOutput {
 
     /// Showing transcode status.
     TranscodeShown(VisualId),
+
+    /// Transcoded or motion photo video has been evicted from the video cache.
+    CacheEvicted(Arc<Visual>),
+
+    /// Export the video of a live or motion photo.
+    Export(VisualId, ExportFormat),
+
+    /// Picture has been zoomed in, or is fitted to the view again.
+    Zoomed(bool),
     // TODO is a NothingShown value needed?
 }
 
+/// A large picture that is loaded in tiles when zoomed in.
+#[derive(Clone)]
+struct LargeImage {
+    image: Rc<glycin::Image>,
+
+    /// Size of the stored pixels, before orientation.
+    width: u32,
+    height: u32,
+
+    orientation: PictureOrientation,
+
+    /// Scale of the reduced picture first shown, relative to the upright picture.
+    preview_scale: f64,
+}
+
+/// A tile of a large picture. Picture is `None` while loading.
+struct Tile {
+    region: Region,
+    picture: Option<gtk::Picture>,
+}
+
 pub struct ViewOne {
     viewing: Viewing,
     audio: Audio,
@@ -124,8 +206,39 @@ pub struct ViewOne {
 
     picture: gtk::Picture,
 
+    /// Scrolled window for zooming and panning pictures.
+    scroller: gtk::ScrolledWindow,
+
+    zoom: Zoom,
+
+    /// Can the item being viewed be zoomed.
+    is_zoomable: bool,
+
+    /// Zoom when a pinch started.
+    pinch_scale: f64,
+
+    /// Orientation applied to the picture with CSS.
+    css_orientation: PictureOrientation,
+
+    large_image: Option<LargeImage>,
+
+    /// Tiles of a zoomed large picture, keyed by column and row.
+    tiles: HashMap<(u32, u32), Tile>,
+
+    /// Layer over the picture that shows tiles.
+    tile_layer: gtk::Fixed,
+
+    /// Changes when tiles that have been requested are no longer wanted.
+    tile_generation: u64,
+
+    /// Waits for the view to settle, and then loads tiles.
+    tile_loader: Option<glib::JoinHandle<()>>,
+
     video: Option<gtk::MediaFile>,
 
+    /// Plays the frames of an animated image.
+    animation: Option<glib::JoinHandle<()>>,
+
     /// Info for loaded image
     image_info: Option<glycin::ImageDetails>,
 
@@ -253,19 +366,58 @@ impl SimpleAsyncComponent for ViewOne {
                 }
             },
 
+            // zoom_controls
+            add_overlay = &gtk::Box {
+                set_orientation: gtk::Orientation::Horizontal,
+                set_halign: gtk::Align::End,
+                set_valign: gtk::Align::End,
+                set_margin_end: 18,
+                set_margin_bottom: 18,
+                add_css_class: "linked",
+
+                #[watch]
+                set_visible: model.is_zoomable,
+
+                gtk::Button {
+                    set_icon_name: "zoom-out-symbolic",
+                    add_css_class: "osd",
+                    set_tooltip_text: Some(&fl!("viewer-zoom-out", "tooltip")),
+
+                    #[watch]
+                    set_sensitive: model.zoom.is_zoomed(),
+
+                    connect_clicked => ViewOneInput::Zoom(ZoomRequest::Out),
+                },
+
+                gtk::Button {
+                    add_css_class: "osd",
+                    set_tooltip_text: Some(&fl!("viewer-zoom-toggle", "tooltip")),
+
+                    #[watch]
+                    set_label: &fl!("viewer-zoom-toggle", percent = model.zoom.percent()),
+
+                    connect_clicked => ViewOneInput::Zoom(ZoomRequest::Toggle),
+                },
+
+                gtk::Button {
+                    set_icon_name: "zoom-in-symbolic",
+                    add_css_class: "osd",
+                    set_tooltip_text: Some(&fl!("viewer-zoom-in", "tooltip")),
+                    connect_clicked => ViewOneInput::Zoom(ZoomRequest::In),
+                },
+            },
+
             #[wrap(Some)]
             set_child = &gtk::Box {
                 set_orientation: gtk::Orientation::Vertical,
 
-                gtk::Box {
+                #[local_ref]
+                scroller -> gtk::ScrolledWindow {
                     set_vexpand: true,
-                    set_halign: gtk::Align::Center,
+                    set_hexpand: true,
 
                     #[watch]
                     set_visible: model.viewing == Viewing::Photo || model.viewing == Viewing::MotionPhoto || model.viewing == Viewing::Video,
-
-                    #[local_ref]
-                    picture -> gtk::Picture {}
                 },
 
                 adw::StatusPage {
@@ -320,10 +472,89 @@ impl SimpleAsyncComponent for ViewOne {
     async fn init(
         transcode_progress_monitor: Self::Init,
         root: Self::Root,
-        _sender: AsyncComponentSender<Self>,
+        sender: AsyncComponentSender<Self>,
     ) -> AsyncComponentParts<Self> {
         let picture = gtk::Picture::new();
 
+        // Tiles of large pictures are laid over the picture.
+        let tile_layer = gtk::Fixed::new();
+        tile_layer.set_can_target(false);
+
+        let content = gtk::Overlay::new();
+        content.set_child(Some(&picture));
+        content.add_overlay(&tile_layer);
+
+        let scroller = gtk::ScrolledWindow::new();
+        scroller.set_child(Some(&content));
+
+        let zoom = Zoom::new(&scroller, &content);
+
+        let pinch = gtk::GestureZoom::new();
+        {
+            let sender = sender.clone();
+            pinch.connect_begin(move |_, _| sender.input(ViewOneInput::PinchBegin));
+        }
+        {
+            let sender = sender.clone();
+            pinch.connect_scale_changed(move |gesture, scale| {
+                if let Some((x, y)) = gesture.bounding_box_center() {
+                    sender.input(ViewOneInput::Pinch(scale, x, y));
+                }
+            });
+        }
+        scroller.add_controller(pinch);
+
+        // Scrolling with control held zooms around the pointer.
+        let pointer = Rc::new(Cell::new((0.0, 0.0)));
+        let motion = gtk::EventControllerMotion::new();
+        {
+            let pointer = pointer.clone();
+            motion.connect_motion(move |_, x, y| pointer.set((x, y)));
+        }
+        scroller.add_controller(motion);
+
+        let scroll = gtk::EventControllerScroll::new(gtk::EventControllerScrollFlags::VERTICAL);
+        scroll.set_propagation_phase(gtk::PropagationPhase::Capture);
+        {
+            let sender = sender.clone();
+            scroll.connect_scroll(move |controller, _, dy| {
+                if controller
+                    .current_event_state()
+                    .contains(gdk::ModifierType::CONTROL_MASK)
+                {
+                    let (x, y) = pointer.get();
+                    sender.input(ViewOneInput::ZoomBy(ZOOM_STEP.powf(-dy), x, y));
+                    glib::Propagation::Stop
+                } else {
+                    glib::Propagation::Proceed
+                }
+            });
+        }
+        scroller.add_controller(scroll);
+
+        let click = gtk::GestureClick::new();
+        {
+            let sender = sender.clone();
+            click.connect_pressed(move |_, n_press, x, y| {
+                if n_press == 2 {
+                    sender.input(ViewOneInput::ZoomToggle(x, y));
+                }
+            });
+        }
+        scroller.add_controller(click);
+
+        for adjustment in [scroller.hadjustment(), scroller.vadjustment()] {
+            {
+                let sender = sender.clone();
+                adjustment
+                    .connect_value_changed(move |_| sender.input(ViewOneInput::ViewportChanged));
+            }
+            {
+                let sender = sender.clone();
+                adjustment.connect_changed(move |_| sender.input(ViewOneInput::ViewportChanged));
+            }
+        }
+
         let transcode_progress = ProgressPanel::builder()
             .launch(transcode_progress_monitor.clone())
             .detach();
@@ -335,7 +566,18 @@ impl SimpleAsyncComponent for ViewOne {
             broken: Broken::None,
 
             picture: picture.clone(),
+            scroller: scroller.clone(),
+            zoom,
+            is_zoomable: false,
+            pinch_scale: 1.0,
+            css_orientation: PictureOrientation::North,
+            large_image: None,
+            tiles: HashMap::new(),
+            tile_layer,
+            tile_generation: 0,
+            tile_loader: None,
             video: None,
+            animation: None,
             image_info: None,
             visual_id: None,
             is_skipping_allowed: false,
@@ -353,6 +595,12 @@ impl SimpleAsyncComponent for ViewOne {
             ViewOneInput::Load(visual) => {
                 info!("Load visual {}", visual.visual_id);
 
+                self.stop_animation();
+                self.reset_zoom(&sender);
+                self.is_zoomable = false;
+                self.large_image = None;
+                self.css_orientation = PictureOrientation::North;
+
                 let visual_sandbox_path = visual.sandbox_path();
 
                 self.viewing = Viewing::None;
@@ -379,21 +627,51 @@ impl SimpleAsyncComponent for ViewOne {
                     self.picture.remove_css_class(orient.as_ref());
                 }
 
-                if visual.is_photo_only() {
+                // Evicted videos keep their paths but the files are gone.
+                let is_motion_photo_evicted = visual
+                    .motion_photo_video_path
+                    .as_ref()
+                    .is_some_and(|x| !x.exists());
+                let is_evicted = is_motion_photo_evicted
+                    || visual
+                        .video_transcoded_path
+                        .as_ref()
+                        .is_some_and(|x| !x.exists());
+
+                if is_evicted {
+                    info!("Video for {} evicted from cache", visual.visual_id);
+                    let _ = sender.output(ViewOneOutput::CacheEvicted(visual.clone()));
+                }
+
+                // Show a motion photo as a still photo until its video is restored.
+                if visual.is_photo_only() || is_motion_photo_evicted {
                     self.viewing = Viewing::Photo;
 
-                    // Apply a CSS transformation to respect the EXIF orientation
+                    // Edits are relative to the upright picture, so the orientation must
+                    // be applied to the pixels before edits can be rendered.
+                    let edits = visual
+                        .picture_edits
+                        .clone()
+                        .filter(|_| visual.is_edited() && !visual.is_animated());
+
+                    // An orientation chosen by the user replaces the EXIF orientation.
+                    let orientation_override = visual.picture_orientation_override;
+
+                    // Apply a CSS transformation to respect the orientation
                     // NOTE: don't use Glycin to apply the transformation here because it is
                     // too slow.
-                    let orientation = visual
-                        .picture_orientation
-                        .unwrap_or(PictureOrientation::North);
-                    self.picture.add_css_class(orientation.as_ref());
+                    if edits.is_none() {
+                        let orientation = visual
+                            .picture_orientation
+                            .unwrap_or(PictureOrientation::North);
+                        self.picture.add_css_class(orientation.as_ref());
+                        self.css_orientation = orientation;
+                    }
 
                     let file = gio::File::for_path(visual_sandbox_path);
 
                     let mut loader = glycin::Loader::new(file);
-                    loader.apply_transformations(false);
+                    loader.apply_transformations(edits.is_some() && orientation_override.is_none());
 
                     let image = loader.load().await;
 
@@ -404,7 +682,19 @@ impl SimpleAsyncComponent for ViewOne {
                         return;
                     };
 
-                    let frame = image.next_frame().await;
+                    let details = image.details().clone();
+
+                    let is_large = edits.is_none()
+                        && !visual.is_animated()
+                        && details.width() as u64 * details.height() as u64 > LARGE_IMAGE_PIXELS;
+
+                    let frame = if is_large {
+                        let (width, height) = preview_size(details.width(), details.height());
+                        let request = glycin::FrameRequest::new().scale(width, height);
+                        image.specific_frame(request).await
+                    } else {
+                        image.next_frame().await
+                    };
                     let Ok(frame) = frame else {
                         event!(Level::ERROR, "Failed getting image frame: {:?}", frame);
                         self.viewing = Viewing::Error;
@@ -412,10 +702,57 @@ impl SimpleAsyncComponent for ViewOne {
                         return;
                     };
 
-                    self.image_info = Some(image.details().clone());
+                    self.image_info = Some(details.clone());
 
                     let texture = frame.texture();
+                    let texture = match edits {
+                        Some(edits) => render_edits(&texture, orientation_override, edits)
+                            .await
+                            .unwrap_or(texture),
+                        None => texture,
+                    };
                     self.picture.set_paintable(Some(&texture));
+
+                    // Zoom is relative to the upright picture at its full size.
+                    let (width, height) = if is_large {
+                        (details.width(), details.height())
+                    } else {
+                        (texture.width() as u32, texture.height() as u32)
+                    };
+                    let (width, height) =
+                        transform::upright_size(self.css_orientation, width, height);
+                    self.zoom.set_image_size(width, height);
+                    self.is_zoomable = !visual.is_animated();
+
+                    if visual.is_animated() {
+                        let picture = self.picture.clone();
+                        let mut delay = frame.delay();
+
+                        // Glycin loops back to the first frame after the last frame.
+                        self.animation = Some(relm4::spawn_local(async move {
+                            loop {
+                                glib::timeout_future(frame_delay(delay)).await;
+                                let Ok(frame) = image.next_frame().await else {
+                                    break;
+                                };
+                                picture.set_paintable(Some(&frame.texture()));
+                                delay = frame.delay();
+                            }
+                        }));
+                    } else if is_large {
+                        let (preview_width, _) = transform::upright_size(
+                            self.css_orientation,
+                            texture.width() as u32,
+                            texture.height() as u32,
+                        );
+                        self.large_image = Some(LargeImage {
+                            image: Rc::new(image),
+                            width: details.width(),
+                            height: details.height(),
+                            orientation: self.css_orientation,
+                            preview_scale: preview_width as f64 / width as f64,
+                        });
+                    }
                 } else {
                     // video or motion photo
                     let is_transcoded = visual
@@ -482,6 +819,12 @@ impl SimpleAsyncComponent for ViewOne {
                             });
                         }
 
+                        // The rotation of the video stream is already applied, so only
+                        // turn by the orientation chosen by the user.
+                        if let Some(orientation) = visual.video_orientation_override {
+                            self.picture.add_css_class(orientation.as_ref());
+                        }
+
                         self.video = Some(video);
                         self.picture.set_paintable(self.video.as_ref());
                     }
@@ -521,6 +864,8 @@ impl SimpleAsyncComponent for ViewOne {
             }
             ViewOneInput::Hidden => {
                 info!("Hide");
+                self.stop_animation();
+                self.reset_zoom(&sender);
                 if let Some(video) = self.video.as_ref() {
                     debug!("Pausing video");
                     if video.is_ended() {
@@ -629,15 +974,248 @@ impl SimpleAsyncComponent for ViewOne {
                     self.video_timestamp = format!("{}/{}", current_ts, total_ts).into();
                 }
             }
+            ViewOneInput::Export(export_format) => {
+                let Some(visual_id) = self.visual_id.as_ref() else {
+                    return;
+                };
+
+                // A still is of the frame currently shown.
+                let export_format = match (export_format, self.video.as_ref()) {
+                    (ExportFormat::Jpeg(_), Some(video)) => {
+                        ExportFormat::Jpeg(TimeDelta::microseconds(video.timestamp()))
+                    }
+                    _ => export_format,
+                };
+
+                let _ = sender.output(ViewOneOutput::Export(visual_id.clone(), export_format));
+            }
             ViewOneInput::TranscodeAll => {
                 event!(Level::INFO, "Transcode all");
                 let _ = sender.output(ViewOneOutput::TranscodeAll);
             }
+            ViewOneInput::Zoom(request) => {
+                let centre = self.zoom.centre();
+                let scale = match request {
+                    ZoomRequest::In => self.zoom.scale() * ZOOM_STEP,
+                    ZoomRequest::Out => self.zoom.scale() / ZOOM_STEP,
+                    ZoomRequest::Fit => self.zoom.fit_scale(),
+                    ZoomRequest::Actual => 1.0,
+                    ZoomRequest::Toggle => self.toggled_scale(),
+                };
+                self.zoom_to(scale, centre, &sender).await;
+            }
+            ViewOneInput::ZoomBy(factor, x, y) => {
+                self.zoom_to(self.zoom.scale() * factor, (x, y), &sender)
+                    .await;
+            }
+            ViewOneInput::ZoomToggle(x, y) => {
+                self.zoom_to(self.toggled_scale(), (x, y), &sender).await;
+            }
+            ViewOneInput::PinchBegin => {
+                self.pinch_scale = self.zoom.scale();
+            }
+            ViewOneInput::Pinch(scale, x, y) => {
+                self.zoom_to(self.pinch_scale * scale, (x, y), &sender)
+                    .await;
+            }
+            ViewOneInput::ViewportChanged => {
+                self.schedule_tiles(&sender);
+            }
+            ViewOneInput::TileLoaded(generation, region, texture) => {
+                if generation != self.tile_generation {
+                    return;
+                }
+                let key = (region.x / TILE_SIZE, region.y / TILE_SIZE);
+                let Some(tile) = self.tiles.get_mut(&key) else {
+                    return;
+                };
+                if tile.picture.is_some() {
+                    return;
+                }
+
+                let picture = gtk::Picture::new();
+                picture.set_content_fit(gtk::ContentFit::Fill);
+                picture.set_paintable(Some(&texture));
+                self.tile_layer.put(&picture, 0.0, 0.0);
+                self.zoom.place(&self.tile_layer, &picture, region);
+                tile.picture = Some(picture);
+            }
         }
     }
 }
 
 impl ViewOne {
+    fn stop_animation(&mut self) {
+        if let Some(animation) = self.animation.take() {
+            animation.abort();
+        }
+    }
+
+    /// Scale for switching between fitting the picture and showing its actual size.
+    fn toggled_scale(&self) -> f64 {
+        if self.zoom.is_zoomed() {
+            self.zoom.fit_scale()
+        } else {
+            1.0
+        }
+    }
+
+    async fn zoom_to(
+        &mut self,
+        scale: f64,
+        anchor: (f64, f64),
+        sender: &AsyncComponentSender<Self>,
+    ) {
+        if !self.is_zoomable {
+            return;
+        }
+
+        let was_zoomed = self.zoom.is_zoomed();
+        if !was_zoomed {
+            if self.zoom.is_fit_scale(scale) {
+                return;
+            }
+            self.make_upright().await;
+        }
+
+        self.zoom.zoom_to(scale, anchor);
+
+        if self.zoom.is_zoomed() {
+            for tile in self.tiles.values() {
+                if let Some(ref picture) = tile.picture {
+                    self.zoom.place(&self.tile_layer, picture, tile.region);
+                }
+            }
+            self.schedule_tiles(sender);
+        } else {
+            self.clear_tiles();
+        }
+
+        if was_zoomed != self.zoom.is_zoomed() {
+            let _ = sender.output(ViewOneOutput::Zoomed(self.zoom.is_zoomed()));
+        }
+    }
+
+    fn reset_zoom(&mut self, sender: &AsyncComponentSender<Self>) {
+        self.clear_tiles();
+        if self.zoom.is_zoomed() {
+            self.zoom.fit();
+            let _ = sender.output(ViewOneOutput::Zoomed(false));
+        }
+    }
+
+    /// Replace the CSS orientation with upright pixels, because zooming and panning
+    /// need the size of the picture widget to match the picture shown.
+    async fn make_upright(&mut self) {
+        if self.css_orientation == PictureOrientation::North {
+            return;
+        }
+
+        let Some(texture) = self.picture.paintable().and_downcast::<gdk::Texture>() else {
+            return;
+        };
+
+        if let Some(upright) = upright_texture(&texture, self.css_orientation).await {
+            self.picture.remove_css_class(self.css_orientation.as_ref());
+            self.picture.set_paintable(Some(&upright));
+            self.css_orientation = PictureOrientation::North;
+        }
+    }
+
+    /// Load the tiles of a large picture that are in view, once the view stops moving.
+    fn schedule_tiles(&mut self, sender: &AsyncComponentSender<Self>) {
+        let Some(large) = self.large_image.clone() else {
+            return;
+        };
+
+        if let Some(loader) = self.tile_loader.take() {
+            loader.abort();
+        }
+        self.tiles.retain(|_, tile| tile.picture.is_some());
+
+        // The reduced picture has enough detail until zoomed in past its own size.
+        let Some(visible) = self
+            .zoom
+            .visible_region()
+            .filter(|_| self.zoom.scale() > large.preview_scale)
+        else {
+            self.clear_tiles();
+            return;
+        };
+
+        let (width, height) = transform::upright_size(large.orientation, large.width, large.height);
+        let wanted = tiles_covering(visible, width, height);
+
+        self.tiles.retain(|key, tile| {
+            let is_wanted = wanted.iter().any(|(k, _)| k == key);
+            if !is_wanted && let Some(ref picture) = tile.picture {
+                self.tile_layer.remove(picture);
+            }
+            is_wanted
+        });
+
+        let mut missing = Vec::new();
+        for (key, region) in wanted {
+            if !self.tiles.contains_key(&key) {
+                self.tiles.insert(
+                    key,
+                    Tile {
+                        region,
+                        picture: None,
+                    },
+                );
+                missing.push(region);
+            }
+        }
+
+        if missing.is_empty() {
+            return;
+        }
+
+        let generation = self.tile_generation;
+        let sender = sender.clone();
+
+        self.tile_loader = Some(relm4::spawn_local(async move {
+            glib::timeout_future(TILE_DELAY).await;
+
+            for region in missing {
+                let source =
+                    transform::source_region(large.orientation, region, large.width, large.height);
+                let request = glycin::FrameRequest::new().clip(
+                    source.x,
+                    source.y,
+                    source.width,
+                    source.height,
+                );
+
+                let frame = match large.image.specific_frame(request).await {
+                    Ok(frame) => frame,
+                    Err(e) => {
+                        error!("Failed loading tile {:?}: {:?}", source, e);
+                        continue;
+                    }
+                };
+
+                let texture = upright_texture(&frame.texture(), large.orientation).await;
+                if let Some(texture) = texture {
+                    sender.input(ViewOneInput::TileLoaded(generation, region, texture));
+                }
+            }
+        }));
+    }
+
+    fn clear_tiles(&mut self) {
+        if let Some(loader) = self.tile_loader.take() {
+            loader.abort();
+        }
+        self.tile_generation += 1;
+        for (_, tile) in self.tiles.drain() {
+            if let Some(picture) = tile.picture {
+                self.tile_layer.remove(&picture);
+            }
+        }
+    }
+
     fn play_button_icon_name(&self) -> &str {
         match self.playback {
             Playback::Playing => "pause-symbolic",
@@ -674,3 +1252,83 @@ impl ViewOne {
         }
     }
 }
+
+/// Render edits onto a loaded picture without blocking the main thread.
+pub async fn render_edits(
+    texture: &gdk::Texture,
+    orientation: Option<PictureOrientation>,
+    edits: EditStack,
+) -> Option<gdk::Texture> {
+    let Some(image) = edits::texture::to_image(texture) else {
+        error!("Failed downloading texture to render edits");
+        return None;
+    };
+
+    let image = relm4::spawn_blocking(move || {
+        let image = match orientation {
+            Some(orientation) => orientation::transform(image, orientation),
+            None => image,
+        };
+        edits::render(image, &edits)
+    })
+    .await
+    .ok()?;
+
+    Some(edits::texture::to_texture(image))
+}
+
+/// Rotate and mirror a loaded picture so it is upright, without blocking the main thread.
+pub async fn upright_texture(
+    texture: &gdk::Texture,
+    orientation: PictureOrientation,
+) -> Option<gdk::Texture> {
+    if orientation == PictureOrientation::North {
+        return Some(texture.clone());
+    }
+
+    let Some(image) = edits::texture::to_image(texture) else {
+        error!("Failed downloading texture to make it upright");
+        return None;
+    };
+
+    let image = relm4::spawn_blocking(move || orientation::transform(image, orientation))
+        .await
+        .ok()?;
+
+    Some(edits::texture::to_texture(image))
+}
+
+/// Size of the reduced picture first shown for a large picture.
+pub fn preview_size(width: u32, height: u32) -> (u32, u32) {
+    let scale = LARGE_IMAGE_PREVIEW_EDGE as f64 / u32::max(width, height) as f64;
+    let width = (width as f64 * scale).round().max(1.0) as u32;
+    let height = (height as f64 * scale).round().max(1.0) as u32;
+    (width, height)
+}
+
+/// Tiles of the upright picture that cover a region, keyed by column and row.
+fn tiles_covering(region: Region, width: u32, height: u32) -> Vec<((u32, u32), Region)> {
+    let columns = region.x / TILE_SIZE..=(region.x + region.width).saturating_sub(1) / TILE_SIZE;
+    let rows = region.y / TILE_SIZE..=(region.y + region.height).saturating_sub(1) / TILE_SIZE;
+
+    rows.flat_map(|row| {
+        columns.clone().map(move |column| {
+            let x = column * TILE_SIZE;
+            let y = row * TILE_SIZE;
+            let tile = Region {
+                x,
+                y,
+                width: u32::min(TILE_SIZE, width - x),
+                height: u32::min(TILE_SIZE, height - y),
+            };
+            ((column, row), tile)
+        })
+    })
+    .collect()
+}
+
+fn frame_delay(delay: Option<Duration>) -> Duration {
+    delay
+        .filter(|x| *x >= MIN_FRAME_DELAY)
+        .unwrap_or(DEFAULT_FRAME_DELAY)
+}
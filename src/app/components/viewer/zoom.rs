@@ -0,0 +1,343 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use fotema_core::orientation::transform::Region;
+
+use relm4::gtk;
+use relm4::gtk::glib;
+use relm4::gtk::prelude::*;
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+/// Largest zoom, as a multiple of the size of the picture.
+pub const MAX_ZOOM: f64 = 8.0;
+
+/// Change of zoom for a key press, button click, or one step of a scroll wheel.
+pub const ZOOM_STEP: f64 = 1.25;
+
+/// Zooms closer than this to fitting the picture in the view are treated as fitting it.
+const FIT_TOLERANCE: f64 = 0.001;
+
+/// Time between frames when panning after a fling.
+const FLING_FRAME: Duration = Duration::from_millis(16);
+
+/// How much of the fling speed is kept after each frame.
+const FLING_FRICTION: f64 = 0.94;
+
+/// Flings stop when slower than this, in pixels per second.
+const FLING_MIN_SPEED: f64 = 20.0;
+
+/// Zoom requested by keyboard shortcut or button.
+/// Zooms in and out are centred on the middle of the view.
+#[derive(Debug, Clone, Copy)]
+pub enum ZoomRequest {
+    In,
+    Out,
+
+    /// Fit the picture to the view.
+    Fit,
+
+    /// Show one pixel of the picture per pixel of the display.
+    Actual,
+
+    /// Switch between fitting the picture and showing its actual size.
+    Toggle,
+}
+
+/// Zoom and pan of a picture shown in a scrolled window.
+///
+/// The content widget is sized to the zoomed picture, and the scrolled window pans over it.
+/// When not zoomed, the content fills the view and the picture fits inside.
+#[derive(Debug)]
+pub struct Zoom {
+    scroller: gtk::ScrolledWindow,
+    content: gtk::Widget,
+
+    /// Size of the upright picture in pixels.
+    image_size: (f64, f64),
+
+    /// Scale of display pixels to picture pixels, or `None` when fitting the picture to the view.
+    scale: Rc<Cell<Option<f64>>>,
+
+    /// Scroll position to restore once the scrolled window has been resized.
+    pending_scroll: Rc<Cell<Option<(f64, f64)>>>,
+
+    /// Pans the picture after a fling.
+    fling: Rc<RefCell<Option<glib::JoinHandle<()>>>>,
+}
+
+impl Zoom {
+    /// Zoom content in a scrolled window.
+    /// Dragging with a mouse or touch pans zoomed content, and keeps moving after a fling.
+    pub fn new(scroller: &gtk::ScrolledWindow, content: &impl IsA<gtk::Widget>) -> Self {
+        // Flings are handled below for both mouse and touch.
+        scroller.set_kinetic_scrolling(false);
+
+        let zoom = Zoom {
+            scroller: scroller.clone(),
+            content: content.clone().upcast(),
+            image_size: (0.0, 0.0),
+            scale: Rc::new(Cell::new(None)),
+            pending_scroll: Rc::new(Cell::new(None)),
+            fling: Rc::new(RefCell::new(None)),
+        };
+
+        zoom.fit();
+
+        // Adjustments only grow to the zoomed size after the next layout, so
+        // scroll positions that are out of range are set again once they can be.
+        {
+            let pending_scroll = zoom.pending_scroll.clone();
+            let hadjustment = scroller.hadjustment();
+            let vadjustment = scroller.vadjustment();
+            let restore = move |_: &gtk::Adjustment| {
+                if let Some((x, y)) = pending_scroll.get() {
+                    hadjustment.set_value(x);
+                    vadjustment.set_value(y);
+                    if hadjustment.value() == x && vadjustment.value() == y {
+                        pending_scroll.set(None);
+                    }
+                }
+            };
+            scroller.hadjustment().connect_changed(restore.clone());
+            scroller.vadjustment().connect_changed(restore);
+        }
+
+        let drag = gtk::GestureDrag::new();
+        {
+            let scale = zoom.scale.clone();
+            let fling = zoom.fling.clone();
+            let scroller = scroller.clone();
+            let start = Rc::new(Cell::new((0.0, 0.0)));
+
+            {
+                let start = start.clone();
+                let scroller = scroller.clone();
+                drag.connect_drag_begin(move |gesture, _, _| {
+                    // Leave unzoomed swipes to the carousel.
+                    if scale.get().is_none() {
+                        gesture.set_state(gtk::EventSequenceState::Denied);
+                        return;
+                    }
+                    stop_fling(&fling);
+                    start.set((
+                        scroller.hadjustment().value(),
+                        scroller.vadjustment().value(),
+                    ));
+                });
+            }
+
+            drag.connect_drag_update(move |gesture, offset_x, offset_y| {
+                gesture.set_state(gtk::EventSequenceState::Claimed);
+                let (x, y) = start.get();
+                scroller.hadjustment().set_value(x - offset_x);
+                scroller.vadjustment().set_value(y - offset_y);
+            });
+        }
+        scroller.add_controller(drag);
+
+        let swipe = gtk::GestureSwipe::new();
+        {
+            let scale = zoom.scale.clone();
+            let fling = zoom.fling.clone();
+            let scroller = scroller.clone();
+            swipe.connect_swipe(move |_, velocity_x, velocity_y| {
+                if scale.get().is_some() {
+                    start_fling(&fling, &scroller, velocity_x, velocity_y);
+                }
+            });
+        }
+        scroller.add_controller(swipe);
+
+        zoom
+    }
+
+    /// Show a picture of a new size, fitted to the view.
+    pub fn set_image_size(&mut self, width: u32, height: u32) {
+        self.image_size = (width as f64, height as f64);
+        self.fit();
+    }
+
+    pub fn is_zoomed(&self) -> bool {
+        self.scale.get().is_some()
+    }
+
+    /// Current scale of display pixels to picture pixels.
+    pub fn scale(&self) -> f64 {
+        self.scale.get().unwrap_or_else(|| self.fit_scale())
+    }
+
+    /// Scale that fits the whole picture in the view.
+    pub fn fit_scale(&self) -> f64 {
+        let (view_width, view_height) = self.view_size();
+        let (width, height) = self.image_size;
+        if width <= 0.0 || height <= 0.0 || view_width <= 0.0 || view_height <= 0.0 {
+            return 1.0;
+        }
+        f64::min(view_width / width, view_height / height)
+    }
+
+    /// Would zooming to a scale fit the picture to the view.
+    pub fn is_fit_scale(&self, scale: f64) -> bool {
+        (scale - self.fit_scale()).abs() < FIT_TOLERANCE
+    }
+
+    /// Zoom as a percentage for showing to the user.
+    pub fn percent(&self) -> u32 {
+        (self.scale() * 100.0).round() as u32
+    }
+
+    /// Fit the picture to the view.
+    pub fn fit(&self) {
+        stop_fling(&self.fling);
+        self.scale.set(None);
+        self.pending_scroll.set(None);
+
+        self.scroller
+            .set_policy(gtk::PolicyType::Never, gtk::PolicyType::Never);
+        self.content.set_size_request(-1, -1);
+        self.content.set_halign(gtk::Align::Fill);
+        self.content.set_valign(gtk::Align::Fill);
+    }
+
+    /// Zoom to a scale, keeping the point of the picture under the anchor in place.
+    /// Anchor is in the coordinates of the view.
+    pub fn zoom_to(&self, scale: f64, anchor: (f64, f64)) {
+        let (view_width, view_height) = self.view_size();
+        if view_width <= 0.0 || view_height <= 0.0 {
+            return;
+        }
+
+        // Small pictures fill the view when fitted, so can be zoomed out to their own size.
+        let fit_scale = self.fit_scale();
+        let scale = scale.clamp(f64::min(fit_scale, 1.0), MAX_ZOOM);
+        if self.is_fit_scale(scale) {
+            self.fit();
+            return;
+        }
+
+        let (picture_x, picture_y) = self.to_picture(anchor);
+
+        stop_fling(&self.fling);
+        self.scale.set(Some(scale));
+
+        let width = self.image_size.0 * scale;
+        let height = self.image_size.1 * scale;
+        self.content
+            .set_size_request(width.round() as i32, height.round() as i32);
+        self.content.set_halign(gtk::Align::Center);
+        self.content.set_valign(gtk::Align::Center);
+        self.scroller
+            .set_policy(gtk::PolicyType::Automatic, gtk::PolicyType::Automatic);
+
+        let x = picture_x * scale + padding(view_width, width) - anchor.0;
+        let y = picture_y * scale + padding(view_height, height) - anchor.1;
+        self.scroll_to(x, y);
+    }
+
+    /// Middle of the view, for centring zooms.
+    pub fn centre(&self) -> (f64, f64) {
+        let (view_width, view_height) = self.view_size();
+        (view_width / 2.0, view_height / 2.0)
+    }
+
+    /// Region of the upright picture that is visible, in picture pixels.
+    pub fn visible_region(&self) -> Option<Region> {
+        let scale = self.scale.get()?;
+        let (view_width, view_height) = self.view_size();
+        let (x, y) = self.to_picture((0.0, 0.0));
+        let (x, y) = (x.max(0.0), y.max(0.0));
+        let width = f64::min(view_width / scale, self.image_size.0 - x);
+        let height = f64::min(view_height / scale, self.image_size.1 - y);
+        if width <= 0.0 || height <= 0.0 {
+            return None;
+        }
+
+        Some(Region {
+            x: x.floor() as u32,
+            y: y.floor() as u32,
+            width: width.ceil() as u32,
+            height: height.ceil() as u32,
+        })
+    }
+
+    /// Position and size a widget over a region of the zoomed picture.
+    /// The widget must be a child of a `gtk::Fixed` covering the content.
+    pub fn place(&self, fixed: &gtk::Fixed, widget: &impl IsA<gtk::Widget>, region: Region) {
+        let scale = self.scale();
+        let x = (region.x as f64 * scale).floor();
+        let y = (region.y as f64 * scale).floor();
+        let right = ((region.x + region.width) as f64 * scale).ceil();
+        let bottom = ((region.y + region.height) as f64 * scale).ceil();
+        widget.set_size_request((right - x) as i32, (bottom - y) as i32);
+        fixed.move_(widget, x, y);
+    }
+
+    fn view_size(&self) -> (f64, f64) {
+        (self.scroller.width() as f64, self.scroller.height() as f64)
+    }
+
+    /// Convert a point in the view to a point in the picture.
+    fn to_picture(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        let (view_width, view_height) = self.view_size();
+        let scale = self.scale();
+        let width = self.image_size.0 * scale;
+        let height = self.image_size.1 * scale;
+        let x = self.scroller.hadjustment().value() + x - padding(view_width, width);
+        let y = self.scroller.vadjustment().value() + y - padding(view_height, height);
+        (x / scale, y / scale)
+    }
+
+    fn scroll_to(&self, x: f64, y: f64) {
+        self.pending_scroll.set(Some((x, y)));
+        self.scroller.hadjustment().set_value(x);
+        self.scroller.vadjustment().set_value(y);
+    }
+}
+
+impl Drop for Zoom {
+    fn drop(&mut self) {
+        stop_fling(&self.fling);
+    }
+}
+
+/// Space either side of content that is centred in the view.
+fn padding(view: f64, content: f64) -> f64 {
+    f64::max(0.0, (view - content) / 2.0)
+}
+
+fn stop_fling(fling: &RefCell<Option<glib::JoinHandle<()>>>) {
+    if let Some(fling) = fling.borrow_mut().take() {
+        fling.abort();
+    }
+}
+
+/// Keep panning in the direction of a fling, slowing down until stopped.
+fn start_fling(
+    fling: &RefCell<Option<glib::JoinHandle<()>>>,
+    scroller: &gtk::ScrolledWindow,
+    velocity_x: f64,
+    velocity_y: f64,
+) {
+    stop_fling(fling);
+
+    let hadjustment = scroller.hadjustment();
+    let vadjustment = scroller.vadjustment();
+
+    let handle = relm4::spawn_local(async move {
+        let (mut velocity_x, mut velocity_y) = (velocity_x, velocity_y);
+        let seconds = FLING_FRAME.as_secs_f64();
+        while velocity_x.hypot(velocity_y) > FLING_MIN_SPEED {
+            glib::timeout_future(FLING_FRAME).await;
+            hadjustment.set_value(hadjustment.value() - velocity_x * seconds);
+            vadjustment.set_value(vadjustment.value() - velocity_y * seconds);
+            velocity_x *= FLING_FRICTION;
+            velocity_y *= FLING_FRICTION;
+        }
+    });
+
+    *fling.borrow_mut() = Some(handle);
+}
@@ -8,8 +8,9 @@ mod adaptive;
 mod config;
 mod host_path;
 mod languages;
+mod thumbnails;
 
-use app::App;
+use app::{App, SlideshowAction};
 
 use config::{APP_ID, GETTEXT_PACKAGE, LOCALEDIR, RESOURCES_FILE};
 use gettextrs::{LocaleCategory, gettext};
@@ -79,6 +80,7 @@ fn main() {
     actions.register_for_main_application();
 
     app.set_accelerators_for_action::<QuitAction>(&["<Control>q"]);
+    app.set_accelerators_for_action::<SlideshowAction>(&["F5"]);
 
     let app = RelmApp::from_app(app);
 
@@ -0,0 +1,76 @@
+// SPDX-FileCopyrightText: © 2026 David Bliss
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::config::APP_ID;
+use fotema_core::thumbnailify::Thumbnailer;
+use relm4::gtk::glib;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+const FLATPAK_INFO: &str = "/.flatpak-info";
+
+/// Build a thumbnailer that shares thumbnails with file managers and other apps,
+/// or that keeps them private to Fotema.
+pub fn build_thumbnailer(private_thumbnails: bool) -> Thumbnailer {
+    // Storyboards, and all thumbnails if private, live in Fotema's own cache.
+    let private_dir = glib::user_cache_dir().join(APP_ID).join("thumbnails");
+
+    let private_thumbnails = private_thumbnails || {
+        let can_share = can_share_thumbnails();
+        if !can_share {
+            warn!("No access to the shared thumbnail cache, so keeping thumbnails private");
+        }
+        !can_share
+    };
+
+    if private_thumbnails {
+        info!("Private thumbnail directory is {:?}", private_dir);
+        Thumbnailer::build(&private_dir)
+    } else {
+        let shared_dir = shared_thumbnail_dir();
+        info!("Shared thumbnail directory is {:?}", shared_dir);
+        Thumbnailer::build_shared(&shared_dir, &private_dir)
+    }
+}
+
+/// The XDG thumbnail cache.
+fn shared_thumbnail_dir() -> PathBuf {
+    // Inside Flatpak the user cache directory belongs to the app, but the
+    // `xdg-cache/thumbnails` permission mounts the host's thumbnail cache
+    // at its usual place in the home directory.
+    if Path::new(FLATPAK_INFO).exists() {
+        glib::home_dir().join(".cache").join("thumbnails")
+    } else {
+        glib::user_cache_dir().join("thumbnails")
+    }
+}
+
+/// Can the shared thumbnail cache be used? Inside Flatpak this needs a filesystem
+/// permission that a build of Fotema might not have been granted.
+fn can_share_thumbnails() -> bool {
+    let flatpak_info = Path::new(FLATPAK_INFO);
+    if !flatpak_info.exists() {
+        return true;
+    }
+
+    let key_file = glib::KeyFile::new();
+    if let Err(e) = key_file.load_from_file(flatpak_info, glib::KeyFileFlags::NONE) {
+        warn!("Failed reading {:?}: {}", flatpak_info, e);
+        return false;
+    }
+
+    let Ok(filesystems) = key_file.string("Context", "filesystems") else {
+        return false;
+    };
+
+    filesystems.split(';').any(|filesystem| {
+        // Permissions can have a suffix, such as ':create' or ':ro'.
+        let (filesystem, mode) = filesystem.split_once(':').unwrap_or((filesystem, "rw"));
+        mode != "ro"
+            && matches!(
+                filesystem,
+                "xdg-cache/thumbnails" | "xdg-cache" | "home" | "host"
+            )
+    })
+}